@@ -0,0 +1,85 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use tui_textarea::{CursorMove, Input, Key, TextArea};
+use tui_textarea_bench::LOREM;
+
+#[derive(Clone, Copy)]
+enum Kind {
+    Type,
+    Delete,
+    TypeAndDelete,
+}
+
+// Single line of about 1MB
+fn lorem_line() -> String {
+    let mut line = String::new();
+    while line.len() < 1024 * 1024 {
+        for s in LOREM {
+            line.push_str(s);
+            line.push(' ');
+        }
+    }
+    line
+}
+
+fn key(key: Key) -> Input {
+    Input {
+        key,
+        ctrl: false,
+        alt: false,
+        shift: false,
+    }
+}
+
+// The terminal is not drawn since rendering the long line dominates the time of each edit
+#[inline]
+fn run(textarea: &TextArea<'_>, kind: Kind) -> usize {
+    let mut t = textarea.clone();
+    for c in LOREM[0].chars().take(50) {
+        match kind {
+            Kind::Type => {
+                t.input(key(Key::Char(c)));
+            }
+            Kind::Delete => {
+                t.input(key(Key::Backspace));
+            }
+            Kind::TypeAndDelete => {
+                t.input(key(Key::Char(c)));
+                t.input(key(Key::Char(c)));
+                t.input(key(Key::Backspace));
+            }
+        }
+    }
+    t.lines()[0].len()
+}
+
+fn bench(c: &mut Criterion) {
+    let line = lorem_line();
+    let (head, tail) = line.split_at(line.len() / 2);
+
+    let mut end = TextArea::from([line.as_str()]);
+    end.move_cursor(CursorMove::End);
+
+    // Put the cursor at the middle of the line
+    let mut middle = TextArea::from([head, tail]);
+    middle.move_cursor(CursorMove::Down);
+    middle.delete_newline();
+
+    c.bench_function("long_line::end::type", |b| {
+        b.iter(|| black_box(run(&end, Kind::Type)))
+    });
+    c.bench_function("long_line::end::delete", |b| {
+        b.iter(|| black_box(run(&end, Kind::Delete)))
+    });
+    c.bench_function("long_line::middle::type", |b| {
+        b.iter(|| black_box(run(&middle, Kind::Type)))
+    });
+    c.bench_function("long_line::middle::delete", |b| {
+        b.iter(|| black_box(run(&middle, Kind::Delete)))
+    });
+    c.bench_function("long_line::middle::type_and_delete", |b| {
+        b.iter(|| black_box(run(&middle, Kind::TypeAndDelete)))
+    });
+}
+
+criterion_group!(long_line, bench);
+criterion_main!(long_line);
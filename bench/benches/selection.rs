@@ -0,0 +1,63 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use tui_textarea::{CursorMove, TextArea};
+use tui_textarea_bench::{dummy_terminal, TerminalExt, LOREM};
+
+// Chunk of 200k lines
+fn lorem_lines() -> Vec<String> {
+    LOREM
+        .iter()
+        .cycle()
+        .take(200_000)
+        .map(|s| s.to_string())
+        .collect()
+}
+
+// Move the cursor up and down while the whole text is selected. The cursor move changes the selection so that the
+// last frame is not reused
+#[inline]
+fn render(textarea: &mut TextArea<'_>, repeat: usize) -> (usize, usize) {
+    let mut term = dummy_terminal();
+    for _ in 0..repeat {
+        textarea.move_cursor(CursorMove::Up);
+        term.draw_textarea(textarea);
+        textarea.move_cursor(CursorMove::Down);
+        term.draw_textarea(textarea);
+    }
+    textarea.cursor()
+}
+
+#[inline]
+fn copy(textarea: &mut TextArea<'_>) -> (usize, usize) {
+    textarea.select_all();
+    textarea.copy();
+    textarea.cursor()
+}
+
+fn bench(c: &mut Criterion) {
+    // Select all from the bottom to the top so that the cursor is at the top of the viewport
+    let mut textarea = TextArea::new(lorem_lines());
+    textarea.move_cursor(CursorMove::Bottom);
+    textarea.move_cursor(CursorMove::End);
+    textarea.start_selection();
+    textarea.move_cursor(CursorMove::Top);
+    textarea.move_cursor(CursorMove::Down);
+    c.bench_function("selection::render::200k_lines", |b| {
+        b.iter(|| black_box(render(&mut textarea, 100)))
+    });
+
+    // One secondary selection per line
+    let mut textarea = TextArea::new(lorem_lines());
+    textarea.select_all();
+    textarea.split_selection_on_newlines();
+    c.bench_function("selection::render::200k_selections", |b| {
+        b.iter(|| black_box(render(&mut textarea, 100)))
+    });
+
+    let mut textarea = TextArea::new(lorem_lines());
+    c.bench_function("selection::copy::200k_lines", |b| {
+        b.iter(|| black_box(copy(&mut textarea)))
+    });
+}
+
+criterion_group!(selection, bench);
+criterion_main!(selection);
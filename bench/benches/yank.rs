@@ -0,0 +1,86 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use tui_textarea::{CursorMove, TextArea};
+use tui_textarea_bench::LOREM;
+
+// Chunk of 100k lines
+fn lorem_lines() -> Vec<String> {
+    LOREM
+        .iter()
+        .cycle()
+        .take(100_000)
+        .map(|s| s.to_string())
+        .collect()
+}
+
+#[inline]
+fn paste(textarea: &TextArea<'_>, times: usize) -> usize {
+    let mut t = textarea.clone();
+    for _ in 0..times {
+        t.paste();
+    }
+    t.lines().len()
+}
+
+#[inline]
+fn paste_with(textarea: &TextArea<'_>, f: impl FnOnce(&mut TextArea<'_>)) -> usize {
+    let mut t = textarea.clone();
+    f(&mut t);
+    t.lines().len()
+}
+
+#[inline]
+fn cut_and_paste(textarea: &TextArea<'_>) -> usize {
+    let mut t = textarea.clone();
+    t.select_all();
+    t.cut();
+    t.paste();
+    t.lines().len()
+}
+
+fn bench(c: &mut Criterion) {
+    let mut textarea = TextArea::default();
+    textarea.set_yank_text(lorem_lines().join("\n"));
+    c.bench_function("yank::paste::100k_lines", |b| {
+        b.iter(|| black_box(paste(&textarea, 1)))
+    });
+    c.bench_function("yank::paste::100k_lines_3_times", |b| {
+        b.iter(|| black_box(paste(&textarea, 3)))
+    });
+
+    // Pasting copies the chunk only once into the lines. The edit log stores the pasted text as one string so it
+    // makes one more copy of the chunk. Pasting with the history or without it should stay below that run.
+    // Otherwise following the edit by the history, the jumps, or the anchors started to copy the chunk again.
+    let mut group = c.benchmark_group("yank::paste::100k_lines_copies");
+    group.bench_function("default", |b| {
+        b.iter(|| {
+            black_box(paste_with(&textarea, |t| {
+                t.paste();
+            }))
+        })
+    });
+    group.bench_function("without_history", |b| {
+        b.iter(|| {
+            black_box(paste_with(&textarea, |t| {
+                t.with_history_disabled(|t| t.paste());
+            }))
+        })
+    });
+    group.bench_function("with_edit_log", |b| {
+        b.iter(|| {
+            black_box(paste_with(&textarea, |t| {
+                t.set_edit_log(true);
+                t.paste();
+            }))
+        })
+    });
+    group.finish();
+
+    let mut textarea = TextArea::new(lorem_lines());
+    textarea.move_cursor(CursorMove::Top);
+    c.bench_function("yank::cut_and_paste::100k_lines", |b| {
+        b.iter(|| black_box(cut_and_paste(&textarea)))
+    });
+}
+
+criterion_group!(yank, bench);
+criterion_main!(yank);
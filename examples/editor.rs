@@ -11,11 +11,9 @@ use ratatui::Terminal;
 use std::borrow::Cow;
 use std::env;
 use std::fmt::Display;
-use std::fs;
 use std::io;
-use std::io::{BufRead, Write};
 use std::path::PathBuf;
-use tui_textarea::{CursorMove, Input, Key, TextArea};
+use tui_textarea::{CursorMove, Input, Key, TextArea, TextAreaSet};
 
 macro_rules! error {
     ($fmt: expr $(, $args:tt)*) => {{
@@ -90,54 +88,21 @@ impl SearchBox<'_> {
     }
 }
 
-struct Buffer<'a> {
-    textarea: TextArea<'a>,
-    path: PathBuf,
-    modified: bool,
-}
-
-impl Buffer<'_> {
-    fn new(path: PathBuf) -> io::Result<Self> {
-        let mut textarea = if let Ok(md) = path.metadata() {
-            if md.is_file() {
-                let mut textarea: TextArea = io::BufReader::new(fs::File::open(&path)?)
-                    .lines()
-                    .collect::<io::Result<_>>()?;
-                if textarea.lines().iter().any(|l| l.starts_with('\t')) {
-                    textarea.set_hard_tab_indent(true);
-                }
-                textarea
-            } else {
-                return error!("{:?} is not a file", path);
-            }
-        } else {
-            TextArea::default() // File does not exist
-        };
-        textarea.set_line_number_style(Style::default().fg(Color::DarkGray));
-        Ok(Self {
-            textarea,
-            path,
-            modified: false,
-        })
+fn open_buffer(buffers: &mut TextAreaSet<'_>, path: PathBuf) -> io::Result<()> {
+    if path.exists() && !path.is_file() {
+        return error!("{:?} is not a file", path);
     }
-
-    fn save(&mut self) -> io::Result<()> {
-        if !self.modified {
-            return Ok(());
-        }
-        let mut f = io::BufWriter::new(fs::File::create(&self.path)?);
-        for line in self.textarea.lines() {
-            f.write_all(line.as_bytes())?;
-            f.write_all(b"\n")?;
-        }
-        self.modified = false;
-        Ok(())
+    let index = buffers.open_file(path)?; // Empty buffer is created when the file does not exist
+    let textarea = buffers.get_mut(index).unwrap();
+    if textarea.lines().iter().any(|l| l.starts_with('\t')) {
+        textarea.set_hard_tab_indent(true);
     }
+    textarea.set_line_number_style(Style::default().fg(Color::DarkGray));
+    Ok(())
 }
 
 struct Editor<'a> {
-    current: usize,
-    buffers: Vec<Buffer<'a>>,
+    buffers: TextAreaSet<'a>,
     term: Terminal<CrosstermBackend<io::Stdout>>,
     message: Option<Cow<'static, str>>,
     search: SearchBox<'a>,
@@ -149,9 +114,10 @@ impl Editor<'_> {
         I: Iterator,
         I::Item: Into<PathBuf>,
     {
-        let buffers = paths
-            .map(|p| Buffer::new(p.into()))
-            .collect::<io::Result<Vec<_>>>()?;
+        let mut buffers = TextAreaSet::default();
+        for path in paths {
+            open_buffer(&mut buffers, path.into())?;
+        }
         if buffers.is_empty() {
             return error!("USAGE: cargo run --example editor FILE1 [FILE2...]");
         }
@@ -160,8 +126,8 @@ impl Editor<'_> {
         crossterm::execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
         let backend = CrosstermBackend::new(stdout);
         let term = Terminal::new(backend)?;
+        buffers.switch_to(0);
         Ok(Self {
-            current: 0,
             buffers,
             term,
             message: None,
@@ -191,14 +157,20 @@ impl Editor<'_> {
                     f.render_widget(&self.search.textarea, chunks[0]);
                 }
 
-                let buffer = &self.buffers[self.current];
-                let textarea = &buffer.textarea;
-                f.render_widget(textarea, chunks[1]);
+                // Render the tab bar and the active buffer
+                f.render_widget(&self.buffers, chunks[1]);
 
                 // Render status line
-                let modified = if buffer.modified { " [modified]" } else { "" };
-                let slot = format!("[{}/{}]", self.current + 1, self.buffers.len());
-                let path = format!(" {}{} ", buffer.path.display(), modified);
+                let current = self.buffers.active_index().unwrap();
+                let textarea = self.buffers.active().unwrap();
+                let modified = if textarea.is_modified() {
+                    " [modified]"
+                } else {
+                    ""
+                };
+                let slot = format!("[{}/{}]", current + 1, self.buffers.len());
+                let path = self.buffers.path(current).unwrap();
+                let path = format!(" {}{} ", path.display(), modified);
                 let (row, col) = textarea.cursor();
                 let cursor = format!("({},{})", row + 1, col + 1);
                 let status_chunks = Layout::default()
@@ -255,7 +227,7 @@ impl Editor<'_> {
             })?;
 
             if search_height > 0 {
-                let textarea = &mut self.buffers[self.current].textarea;
+                let textarea = self.buffers.active_mut().unwrap();
                 match crossterm::event::read()?.into() {
                     Input {
                         key: Key::Char('g' | 'n'),
@@ -317,16 +289,19 @@ impl Editor<'_> {
                         ctrl: true,
                         ..
                     } => {
-                        self.current = (self.current + 1) % self.buffers.len();
-                        self.message =
-                            Some(format!("Switched to buffer #{}", self.current + 1).into());
+                        self.buffers.switch_next();
+                        let current = self.buffers.active_index().unwrap();
+                        self.message = Some(format!("Switched to buffer #{}", current + 1).into());
                     }
                     Input {
                         key: Key::Char('s'),
                         ctrl: true,
                         ..
                     } => {
-                        self.buffers[self.current].save()?;
+                        let current = self.buffers.active_index().unwrap();
+                        if self.buffers.is_modified(current) {
+                            self.buffers.save(current)?;
+                        }
                         self.message = Some("Saved!".into());
                     }
                     Input {
@@ -337,8 +312,7 @@ impl Editor<'_> {
                         self.search.open();
                     }
                     input => {
-                        let buffer = &mut self.buffers[self.current];
-                        buffer.modified = buffer.textarea.input(input);
+                        self.buffers.input(input);
                     }
                 }
             }
@@ -8,10 +8,12 @@ use ratatui::style::{Color, Style};
 use ratatui::widgets::{Block, Borders};
 use ratatui::Terminal;
 use std::io;
-use tui_textarea::{Input, Key, TextArea};
+use tui_textarea::{Input, Key, TextInput};
 
-fn validate(textarea: &mut TextArea) -> bool {
-    if let Err(err) = textarea.lines()[0].parse::<f64>() {
+fn validate(input: &mut TextInput) -> bool {
+    let valid = input.text().parse::<f64>();
+    let textarea = input.textarea_mut();
+    if let Err(err) = valid {
         textarea.set_style(Style::default().fg(Color::LightRed));
         textarea.set_block(
             Block::default()
@@ -41,8 +43,7 @@ fn main() -> io::Result<()> {
     let backend = CrosstermBackend::new(stdout);
     let mut term = Terminal::new(backend)?;
 
-    let mut textarea = TextArea::default();
-    textarea.set_cursor_line_style(Style::default());
+    let mut textarea = TextInput::default();
     textarea.set_placeholder_text("Enter a valid float (e.g. 1.56)");
     let layout = Layout::default().constraints([Constraint::Length(3), Constraint::Min(1)]);
     let mut is_valid = validate(&mut textarea);
@@ -58,16 +59,8 @@ fn main() -> io::Result<()> {
             Input {
                 key: Key::Enter, ..
             } if is_valid => break,
-            Input {
-                key: Key::Char('m'),
-                ctrl: true,
-                ..
-            }
-            | Input {
-                key: Key::Enter, ..
-            } => {}
             input => {
-                // TextArea::input returns if the input modified its text
+                // TextInput::input ignores Enter and returns if the input modified its text
                 if textarea.input(input) {
                     is_valid = validate(&mut textarea);
                 }
@@ -83,6 +76,6 @@ fn main() -> io::Result<()> {
     )?;
     term.show_cursor()?;
 
-    println!("Input: {:?}", textarea.lines()[0]);
+    println!("Input: {:?}", textarea.text());
     Ok(())
 }
@@ -0,0 +1,23 @@
+#![no_main]
+
+use arbitrary::{Arbitrary as _, Result, Unstructured};
+use libfuzzer_sys::fuzz_target;
+use tui_textarea::TextArea;
+use tui_textarea_bench::{dummy_terminal, TerminalExt};
+
+fn fuzz(data: &[u8]) -> Result<()> {
+    let mut term = dummy_terminal();
+    let mut data = Unstructured::new(data);
+    let text = <&str>::arbitrary(&mut data)?;
+    let mut textarea = TextArea::from(text.lines());
+    for _ in 0..100 {
+        textarea.apply_random_op(&mut data)?;
+        term.draw_textarea(&textarea);
+        textarea.check_invariants().unwrap();
+    }
+    Ok(())
+}
+
+fuzz_target!(|data: &[u8]| {
+    let _ = fuzz(data);
+});
@@ -0,0 +1,195 @@
+use crate::history::Change;
+use crate::util::Pos;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Handle of an anchored range created by [`TextArea::create_anchor`]. The current range can be retrieved by
+/// [`TextArea::resolve_anchor`].
+///
+/// [`TextArea::create_anchor`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.create_anchor
+/// [`TextArea::resolve_anchor`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.resolve_anchor
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AnchorId(u64);
+
+impl AnchorId {
+    // IDs are allocated sequentially from 0 so small numbers often point to existing anchors
+    #[cfg(feature = "arbitrary")]
+    pub(crate) fn new(id: u64) -> Self {
+        Self(id)
+    }
+}
+
+/// How an anchored range grows when text is inserted at its boundaries. Text inserted inside the range always grows
+/// the range and text inserted outside the range never does.
+///
+/// This type is marked as `#[non_exhaustive]` since more variations may be supported in the future. See also:
+/// [`TextArea::create_anchor`]
+///
+/// [`TextArea::create_anchor`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.create_anchor
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AnchorGrowth {
+    /// Text inserted at the start or the end is not included in the range. This is the default. This is suitable for
+    /// ranges such as diagnostics which should not be extended by typing around them.
+    Neither,
+    /// Text inserted at the start and the end is included in the range.
+    Both,
+    /// Only text inserted at the start is included in the range.
+    Start,
+    /// Only text inserted at the end is included in the range. This is suitable for ranges such as a word being
+    /// typed.
+    End,
+}
+
+impl Default for AnchorGrowth {
+    fn default() -> Self {
+        Self::Neither
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Anchor {
+    start: Pos,
+    end: Pos,
+    growth: AnchorGrowth,
+}
+
+impl Anchor {
+    fn follow(&mut self, change: &Change) {
+        let empty = (self.start.row, self.start.offset) == (self.end.row, self.end.offset);
+        let (grow_start, grow_end) = match self.growth {
+            AnchorGrowth::Neither => (false, false),
+            _ if empty => (true, true), // Both ends are at the insertion point
+            AnchorGrowth::Both => (true, true),
+            AnchorGrowth::Start => (true, false),
+            AnchorGrowth::End => (false, true),
+        };
+        // The start of the range which grows stays before the inserted text and vice versa
+        let start = if grow_start {
+            change.map_before_insertion(&self.start)
+        } else {
+            change.map(&self.start)
+        };
+        let end = if grow_end {
+            change.map(&self.end)
+        } else {
+            change.map_before_insertion(&self.end)
+        };
+        // Insertion into an empty range which does not grow
+        self.start = if (start.row, start.offset) > (end.row, end.offset) {
+            end.clone()
+        } else {
+            start
+        };
+        self.end = end;
+    }
+}
+
+// Ranges which follow edits of the text
+#[derive(Clone, Debug, Default)]
+pub struct Anchors {
+    next_id: u64,
+    anchors: BTreeMap<AnchorId, Anchor>,
+}
+
+impl Anchors {
+    pub fn create(&mut self, start: Pos, end: Pos, growth: AnchorGrowth) -> AnchorId {
+        let id = AnchorId(self.next_id);
+        self.next_id += 1;
+        self.anchors.insert(id, Anchor { start, end, growth });
+        id
+    }
+
+    pub fn get(&self, id: AnchorId) -> Option<(&Pos, &Pos)> {
+        self.anchors.get(&id).map(|a| (&a.start, &a.end))
+    }
+
+    pub fn remove(&mut self, id: AnchorId) -> bool {
+        self.anchors.remove(&id).is_some()
+    }
+
+    pub fn clear(&mut self) {
+        self.anchors.clear();
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (AnchorId, &Pos, &Pos)> {
+        self.anchors.iter().map(|(id, a)| (*id, &a.start, &a.end))
+    }
+
+    #[cfg(feature = "arbitrary")]
+    pub fn positions(&self) -> impl Iterator<Item = &Pos> {
+        self.anchors.values().flat_map(|a| [&a.start, &a.end])
+    }
+
+    pub fn follow(&mut self, change: &Change) {
+        for anchor in self.anchors.values_mut() {
+            anchor.follow(change);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::EditKind;
+    use std::sync::Arc;
+
+    fn pos(col: usize) -> Pos {
+        Pos::new(0, col, col)
+    }
+
+    fn insert(col: usize, s: &str) -> Change {
+        let kind = EditKind::InsertStr(Arc::new(s.to_string()));
+        Change::new(&kind, &pos(col), &pos(col + s.len()))
+    }
+
+    fn delete(start: usize, s: &str) -> Change {
+        let kind = EditKind::DeleteStr(Arc::new(s.to_string()));
+        Change::new(&kind, &pos(start + s.len()), &pos(start))
+    }
+
+    fn follow(growth: AnchorGrowth, range: (usize, usize), change: &Change) -> (usize, usize) {
+        let mut anchor = Anchor {
+            start: pos(range.0),
+            end: pos(range.1),
+            growth,
+        };
+        anchor.follow(change);
+        (anchor.start.col, anchor.end.col)
+    }
+
+    #[test]
+    fn growth() {
+        use AnchorGrowth::*;
+        let tests = [
+            (Neither, (2, 4), insert(2, "xx"), (4, 6)),
+            (Neither, (2, 4), insert(4, "xx"), (2, 4)),
+            (Neither, (2, 4), insert(3, "xx"), (2, 6)),
+            (Neither, (2, 4), insert(0, "xx"), (4, 6)),
+            (Neither, (2, 4), insert(5, "xx"), (2, 4)),
+            (Both, (2, 4), insert(2, "xx"), (2, 6)),
+            (Both, (2, 4), insert(4, "xx"), (2, 6)),
+            (Start, (2, 4), insert(2, "xx"), (2, 6)),
+            (Start, (2, 4), insert(4, "xx"), (2, 4)),
+            (End, (2, 4), insert(2, "xx"), (4, 6)),
+            (End, (2, 4), insert(4, "xx"), (2, 6)),
+            (Neither, (2, 2), insert(2, "xx"), (2, 2)),
+            (End, (2, 2), insert(2, "xx"), (2, 4)),
+            (Neither, (2, 4), delete(1, "xx"), (1, 2)),
+            (Neither, (2, 4), delete(0, "xxxxx"), (0, 0)),
+            (Both, (2, 4), delete(3, "xx"), (2, 3)),
+        ];
+        for (growth, range, change, expected) in tests {
+            assert_eq!(
+                follow(growth, range, &change),
+                expected,
+                "{:?} {:?}",
+                growth,
+                range,
+            );
+        }
+    }
+}
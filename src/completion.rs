@@ -0,0 +1,223 @@
+use crate::ratatui::buffer::Buffer;
+use crate::ratatui::layout::Rect;
+use crate::ratatui::style::{Color, Modifier, Style};
+use std::cmp;
+use unicode_width::UnicodeWidthStr as _;
+
+const MAX_HEIGHT: usize = 8;
+
+// Candidates of completion shown in a popup at the cursor
+#[derive(Clone, Debug)]
+pub struct Completion {
+    items: Vec<String>,
+    selected: usize,
+    pub style: Style,
+    pub selected_style: Style,
+}
+
+impl Default for Completion {
+    fn default() -> Self {
+        Self {
+            items: vec![],
+            selected: 0,
+            style: Style::default().bg(Color::DarkGray),
+            selected_style: Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::REVERSED),
+        }
+    }
+}
+
+impl Completion {
+    pub fn set_items(&mut self, items: Vec<String>) {
+        self.items = items;
+        self.selected = 0;
+    }
+
+    pub fn items(&self) -> &[String] {
+        &self.items
+    }
+
+    // Items which start with the prefix. The prefix itself is excluded since there is nothing to complete. An empty
+    // prefix matches nothing so that the popup is not opened at the head of a line or after whitespace.
+    pub fn matches<'a>(&'a self, prefix: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.items
+            .iter()
+            .map(String::as_str)
+            .filter(move |i| !prefix.is_empty() && i.len() > prefix.len() && i.starts_with(prefix))
+    }
+
+    pub fn selected<'a>(&'a self, prefix: &'a str) -> Option<&'a str> {
+        let len = self.matches(prefix).count();
+        if len == 0 {
+            return None;
+        }
+        self.matches(prefix).nth(self.selected % len)
+    }
+
+    pub fn select_next(&mut self, prefix: &str) -> bool {
+        let len = self.matches(prefix).count();
+        if len == 0 {
+            return false;
+        }
+        self.selected = (self.selected % len + 1) % len;
+        true
+    }
+
+    pub fn select_prev(&mut self, prefix: &str) -> bool {
+        let len = self.matches(prefix).count();
+        if len == 0 {
+            return false;
+        }
+        self.selected = (self.selected % len + len - 1) % len;
+        true
+    }
+
+    // Render the popup. `(x, y)` is the screen position where the completed word starts. The popup is rendered below
+    // the position when there is enough space, otherwise above it.
+    pub fn render(&self, prefix: &str, x: u16, y: u16, area: Rect, buf: &mut Buffer) {
+        let len = self.matches(prefix).count();
+        if len == 0 || area.width == 0 {
+            return;
+        }
+        let selected = self.selected % len;
+
+        let height = cmp::min(cmp::min(len, MAX_HEIGHT), area.height as usize);
+        if height == 0 {
+            return;
+        }
+        // Scroll the items so that the selected item is always visible
+        let skip = (selected + 1).saturating_sub(height);
+        let item_width = self
+            .matches(prefix)
+            .skip(skip)
+            .take(height)
+            .map(|i| i.width())
+            .max()
+            .unwrap_or(0);
+        let width = cmp::min(item_width + 2, area.width as usize) as u16; // `+ 2` for padding
+        let height = height as u16;
+
+        let below = y + 1;
+        let top = if below + height <= area.bottom() {
+            below
+        } else if y >= area.top() + height {
+            y - height
+        } else {
+            area.top()
+        };
+        // Align the text of items with the word. `- 1` for the padding
+        let left = cmp::min(x.saturating_sub(1), area.right().saturating_sub(width));
+        let left = cmp::max(left, area.left());
+
+        for (i, item) in self
+            .matches(prefix)
+            .skip(skip)
+            .take(height as usize)
+            .enumerate()
+        {
+            // Rows outside the buffer are skipped since a part of the textarea may be rendered
+            let row = top + i as u16;
+            if row < buf.area.top() || row >= buf.area.bottom() {
+                continue;
+            }
+            let style = if skip + i == selected {
+                self.selected_style
+            } else {
+                self.style
+            };
+            let pad = item_width - item.width();
+            let line = format!(" {}{} ", item, " ".repeat(pad));
+            buf.set_stringn(left, row, line, width as usize, style);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn completion(items: &[&str]) -> Completion {
+        let mut c = Completion::default();
+        c.set_items(items.iter().map(|s| s.to_string()).collect());
+        c
+    }
+
+    fn rendered(buf: &Buffer) -> Vec<String> {
+        let area = buf.area;
+        (area.top()..area.bottom())
+            .map(|y| {
+                (area.left()..area.right())
+                    .map(|x| {
+                        #[cfg(feature = "ratatui")]
+                        let symbol = buf[(x, y)].symbol();
+                        #[cfg(feature = "tuirs")]
+                        let symbol = buf.get(x, y).symbol.as_str();
+                        symbol
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn select_matches() {
+        let mut c = completion(&["foo", "bar", "foobar", "fo"]);
+        assert_eq!(c.matches("fo").collect::<Vec<_>>(), ["foo", "foobar"]);
+        assert_eq!(c.selected("fo"), Some("foo"));
+        assert!(c.select_next("fo"));
+        assert_eq!(c.selected("fo"), Some("foobar"));
+        assert!(c.select_next("fo"));
+        assert_eq!(c.selected("fo"), Some("foo"));
+        assert!(c.select_prev("fo"));
+        assert_eq!(c.selected("fo"), Some("foobar"));
+        assert_eq!(c.selected("x"), None);
+        assert!(!c.select_next("x"));
+
+        // Empty prefix matches nothing
+        assert_eq!(c.matches("").count(), 0);
+        assert_eq!(c.selected(""), None);
+        assert!(!c.select_next(""));
+    }
+
+    #[test]
+    fn render_popup() {
+        let c = completion(&["foo", "foobar", "bar"]);
+        let area = Rect::new(0, 0, 10, 4);
+
+        // Below the position
+        let mut buf = Buffer::empty(area);
+        c.render("f", 1, 0, area, &mut buf);
+        assert_eq!(
+            rendered(&buf),
+            ["          ", " foo      ", " foobar   ", "          "],
+        );
+
+        // Above the position and shifted left to fit in the area
+        let mut buf = Buffer::empty(area);
+        c.render("f", 8, 3, area, &mut buf);
+        assert_eq!(
+            rendered(&buf),
+            ["          ", "   foo    ", "   foobar ", "          "],
+        );
+
+        // Nothing is rendered when no item matches
+        let mut buf = Buffer::empty(area);
+        c.render("x", 0, 0, area, &mut buf);
+        assert_eq!(rendered(&buf), ["          "; 4]);
+    }
+
+    #[test]
+    fn render_popup_in_one_row() {
+        // The popup fits neither below nor above the position. Items are clipped to the height of the area
+        let c = completion(&["foo", "foobar"]);
+        let area = Rect::new(0, 0, 10, 1);
+        let mut buf = Buffer::empty(area);
+        c.render("f", 1, 0, area, &mut buf);
+        assert_eq!(rendered(&buf), [" foo      "]);
+
+        let area = Rect::new(0, 0, 10, 0);
+        let mut buf = Buffer::empty(area);
+        c.render("f", 1, 0, area, &mut buf);
+    }
+}
@@ -0,0 +1,63 @@
+use crate::anchor::{AnchorGrowth, AnchorId, Anchors};
+use crate::history::Change;
+use crate::util::Pos;
+use std::collections::BTreeMap;
+
+// Ranges of the text rendered as their replacements. The ranges are anchored so that they follow edits of the text
+#[derive(Clone, Debug, Default)]
+pub struct Conceals {
+    ranges: Anchors,
+    replacements: BTreeMap<AnchorId, String>,
+}
+
+impl Conceals {
+    pub fn create(&mut self, start: Pos, end: Pos, replacement: String) -> AnchorId {
+        let id = self.ranges.create(start, end, AnchorGrowth::Neither);
+        self.replacements.insert(id, replacement);
+        id
+    }
+
+    pub fn remove(&mut self, id: AnchorId) -> bool {
+        self.replacements.remove(&id);
+        self.ranges.remove(id)
+    }
+
+    pub fn clear(&mut self) {
+        self.ranges.clear();
+        self.replacements.clear();
+    }
+
+    pub fn follow(&mut self, change: &Change) {
+        self.ranges.follow(change);
+    }
+
+    #[cfg(feature = "arbitrary")]
+    pub fn positions(&self) -> impl Iterator<Item = &Pos> {
+        self.ranges.positions()
+    }
+
+    // Concealed parts of the line at the row as byte offsets. A range across multiple lines conceals the part in each
+    // line and its replacement is rendered only in the first line. Ranges emptied by deletions are ignored.
+    pub fn in_row(
+        &self,
+        row: usize,
+        line_len: usize,
+    ) -> impl Iterator<Item = (usize, usize, &str)> + '_ {
+        self.ranges.iter().filter_map(move |(id, start, end)| {
+            if row < start.row
+                || end.row < row
+                || (start.row, start.offset) == (end.row, end.offset)
+            {
+                return None;
+            }
+            let s = if start.row == row { start.offset } else { 0 };
+            let e = if end.row == row { end.offset } else { line_len };
+            let replacement = if start.row == row {
+                self.replacements.get(&id).map_or("", String::as_str)
+            } else {
+                ""
+            };
+            Some((s, e, replacement))
+        })
+    }
+}
@@ -0,0 +1,87 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+
+/// Specify how control characters in the text such as `\x1b` of ANSI escape sequences are rendered. Terminals don't
+/// render control characters as text and writing them as-is corrupts the screen. Tabs are not affected by this
+/// setting. The width of the text on screen is measured with the rendered form so the cursor and the horizontal
+/// scroll are kept consistent with it.
+///
+/// This type is marked as `#[non_exhaustive]` since more variations may be supported in the future. See also:
+/// [`TextArea::set_show_control_chars`]
+///
+/// [`TextArea::set_show_control_chars`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.set_show_control_chars
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ControlCharStyle {
+    /// Render control characters in caret notation like `^[` for `\x1b` and `^?` for `\x7f`. Control characters in
+    /// the C1 range are rendered with their code points like `<85>`. This is the default.
+    Caret,
+    /// Render each control character as the replacement character `\u{FFFD}`.
+    Replacement,
+    /// Render control characters as they are. This may break the rendering.
+    Raw,
+}
+
+impl Default for ControlCharStyle {
+    fn default() -> Self {
+        Self::Caret
+    }
+}
+
+impl ControlCharStyle {
+    // Check if the character is rendered in some notation instead of as it is
+    pub(crate) fn replaces(self, c: char) -> bool {
+        self != Self::Raw && c != '\t' && c.is_control()
+    }
+
+    // Push the text rendered instead of the character to the buffer. Nothing is pushed when the character is rendered
+    // as it is. This doesn't allocate a string for each character since it is called on every render
+    pub(crate) fn push_notation(self, c: char, buf: &mut String) {
+        if !self.replaces(c) {
+            return;
+        }
+        match self {
+            Self::Caret => match c as u32 {
+                0x7f => buf.push_str("^?"),
+                n if n < 0x20 => {
+                    buf.push('^');
+                    buf.push((n as u8 + 0x40) as char);
+                }
+                n => {
+                    let _ = write!(buf, "<{:02x}>", n);
+                }
+            },
+            Self::Replacement => buf.push('\u{FFFD}'),
+            Self::Raw => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notation() {
+        let tests = [
+            ('a', ControlCharStyle::Caret, None),
+            ('\t', ControlCharStyle::Caret, None),
+            ('\x1b', ControlCharStyle::Caret, Some("^[")),
+            ('\0', ControlCharStyle::Caret, Some("^@")),
+            ('\r', ControlCharStyle::Caret, Some("^M")),
+            ('\x7f', ControlCharStyle::Caret, Some("^?")),
+            ('\u{85}', ControlCharStyle::Caret, Some("<85>")),
+            ('\x1b', ControlCharStyle::Replacement, Some("\u{FFFD}")),
+            ('\t', ControlCharStyle::Replacement, None),
+            ('\x1b', ControlCharStyle::Raw, None),
+        ];
+        for (c, style, expected) in tests {
+            let mut buf = String::new();
+            style.push_notation(c, &mut buf);
+            assert_eq!(style.replaces(c), expected.is_some(), "{:?} {:?}", c, style);
+            assert_eq!(buf, expected.unwrap_or(""), "{:?} {:?}", c, style);
+        }
+    }
+}
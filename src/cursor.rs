@@ -1,6 +1,7 @@
 use crate::widget::Viewport;
 use crate::word::{
-    find_word_inclusive_end_forward, find_word_start_backward, find_word_start_forward,
+    find_word_inclusive_end_backward, find_word_inclusive_end_forward, find_word_start_backward,
+    find_word_start_forward, WordBoundary, Words,
 };
 #[cfg(feature = "arbitrary")]
 use arbitrary::Arbitrary;
@@ -109,9 +110,51 @@ pub enum CursorMove {
     /// assert_eq!(textarea.cursor(), (2, 0));
     /// ```
     Bottom,
+    /// Move cursor to the first non-space character of the next line. When the line consists of only spaces, it
+    /// moves to the head of the line. This is similar to the '+' mapping of Vim in normal mode.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["abc", "  def", "ghi"]);
+    ///
+    /// textarea.move_cursor(CursorMove::End);
+    /// textarea.move_cursor(CursorMove::DownToFirstNonSpace);
+    /// assert_eq!(textarea.cursor(), (1, 2));
+    /// textarea.move_cursor(CursorMove::DownToFirstNonSpace);
+    /// assert_eq!(textarea.cursor(), (2, 0));
+    /// ```
+    DownToFirstNonSpace,
+    /// Move cursor to the first non-space character of the previous line. When the line consists of only spaces, it
+    /// moves to the head of the line. This is similar to the '-' mapping of Vim in normal mode.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["  abc", "def"]);
+    ///
+    /// textarea.move_cursor(CursorMove::Bottom);
+    /// textarea.move_cursor(CursorMove::End);
+    /// textarea.move_cursor(CursorMove::UpToFirstNonSpace);
+    /// assert_eq!(textarea.cursor(), (0, 2));
+    /// ```
+    UpToFirstNonSpace,
+    /// Move cursor to the first non-space character of the line at the row. Note that the row is 0-based. When the
+    /// row points outside the text, the cursor moves to the last line. This is useful to implement commands like
+    /// `:{number}` of Vim. Like [`CursorMove::Jump`], the position before the move is recorded in the jumplist.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["aaa", "    bbb", "  ccc"]);
+    ///
+    /// textarea.move_cursor(CursorMove::LineStartNonSpaceOf(1));
+    /// assert_eq!(textarea.cursor(), (1, 4));
+    /// textarea.move_cursor(CursorMove::LineStartNonSpaceOf(10));
+    /// assert_eq!(textarea.cursor(), (2, 2));
+    /// ```
+    LineStartNonSpaceOf(u16),
     /// Move cursor forward by one word. Word boundary appears at spaces, punctuations, and others. For example
     /// `fn foo(a)` consists of words `fn`, `foo`, `(`, `a`, `)`. When the cursor is at the end of line, it moves to the
     /// head of next line.
+    /// Word boundaries can be changed with [`TextArea::set_word_boundary_mode`](crate::TextArea::set_word_boundary_mode).
     /// ```
     /// use tui_textarea::{TextArea, CursorMove};
     ///
@@ -126,6 +169,7 @@ pub enum CursorMove {
     /// Move cursor forward to the next end of word. Word boundary appears at spaces, punctuations, and others. For example
     /// `fn foo(a)` consists of words `fn`, `foo`, `(`, `a`, `)`. When the cursor is at the end of line, it moves to the
     /// end of the first word of the next line. This is similar to the 'e' mapping of Vim in normal mode.
+    /// Word boundaries can be changed with [`TextArea::set_word_boundary_mode`](crate::TextArea::set_word_boundary_mode).
     /// ```
     /// use tui_textarea::{TextArea, CursorMove};
     ///
@@ -153,6 +197,7 @@ pub enum CursorMove {
     /// Move cursor backward by one word.  Word boundary appears at spaces, punctuations, and others. For example
     /// `fn foo(a)` consists of words `fn`, `foo`, `(`, `a`, `)`.When the cursor is at the head of line, it moves to
     /// the end of previous line.
+    /// Word boundaries can be changed with [`TextArea::set_word_boundary_mode`](crate::TextArea::set_word_boundary_mode).
     /// ```
     /// use tui_textarea::{TextArea, CursorMove};
     ///
@@ -167,6 +212,38 @@ pub enum CursorMove {
     /// assert_eq!(textarea.cursor(), (0, 0));
     /// ```
     WordBack,
+    /// Move cursor backward to the previous end of word. Word boundary appears at spaces, punctuations, and others
+    /// like [`CursorMove::WordEnd`]. When no word ends before the cursor in the line, it moves to the end of the last
+    /// word of the previous lines. This is similar to the 'ge' mapping of Vim in normal mode.
+    /// Word boundaries can be changed with [`TextArea::set_word_boundary_mode`](crate::TextArea::set_word_boundary_mode).
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["aaa", "", "bbb [[[ccc"]);
+    ///
+    /// textarea.move_cursor(CursorMove::Jump(2, 9));
+    /// textarea.move_cursor(CursorMove::WordEndBack);
+    /// assert_eq!(textarea.cursor(), (2, 6));      // At the end of '[[['
+    /// textarea.move_cursor(CursorMove::WordEndBack);
+    /// assert_eq!(textarea.cursor(), (2, 2));      // At the end of 'bbb'
+    /// textarea.move_cursor(CursorMove::WordEndBack);
+    /// assert_eq!(textarea.cursor(), (0, 2));      // At the end of 'aaa'
+    /// ```
+    WordEndBack,
+    /// Move cursor backward to the previous end of a sequence of non-space characters. Unlike
+    /// [`CursorMove::WordEndBack`], word boundaries appear only at spaces regardless of
+    /// [`TextArea::set_word_boundary_mode`](crate::TextArea::set_word_boundary_mode). This is similar to the 'gE'
+    /// mapping of Vim in normal mode.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["foo(a) bar.baz"]);
+    ///
+    /// textarea.move_cursor(CursorMove::Jump(0, 13));
+    /// textarea.move_cursor(CursorMove::WordSpacingEndBack);
+    /// assert_eq!(textarea.cursor(), (0, 5));      // At the end of 'foo(a)'
+    /// ```
+    WordSpacingEndBack,
     /// Move cursor down by one paragraph. Paragraph is a chunk of non-empty lines. Cursor moves to the first line of paragraph.
     /// ```
     /// use tui_textarea::{TextArea, CursorMove};
@@ -223,6 +300,23 @@ pub enum CursorMove {
     /// assert_eq!(textarea.cursor(), (2, 4));
     /// ```
     Jump(u16, u16),
+    /// Move cursor to (row, col) position allowing the column beyond the end of the line. When virtual edit mode is
+    /// enabled by [`TextArea::set_virtual_edit`](crate::TextArea::set_virtual_edit), the cursor can be placed after
+    /// the end of the line and spaces are inserted to fill the gap when some text is inserted at the position. When
+    /// virtual edit mode is disabled, this is the same as [`CursorMove::Jump`].
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["ab", "cd"]);
+    /// textarea.set_virtual_edit(true);
+    ///
+    /// textarea.move_cursor(CursorMove::JumpVirtual(1, 5));
+    /// assert_eq!(textarea.cursor(), (1, 5));
+    ///
+    /// textarea.insert_char('x');
+    /// assert_eq!(textarea.lines(), ["ab", "cd   x"]);
+    /// ```
+    JumpVirtual(u16, u16),
     /// Move cursor to keep it within the viewport. For example, when a viewport displays line 8 to line 16:
     ///
     /// - cursor at line 4 is moved to line 8
@@ -257,12 +351,82 @@ pub enum CursorMove {
     InViewport,
 }
 
+/// Change of the cursor position and the text selection passed to the callback set by
+/// [`TextArea::set_on_cursor_change`].
+///
+/// Positions are 0-base character-wise (row, col) and selection ranges are in the same format as
+/// [`TextArea::selection_range`]. This type is marked as `#[non_exhaustive]` since more fields may be added in the
+/// future.
+///
+/// [`TextArea::set_on_cursor_change`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.set_on_cursor_change
+/// [`TextArea::selection_range`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.selection_range
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CursorChange {
+    /// Cursor position before the change.
+    pub old_cursor: (usize, usize),
+    /// Cursor position after the change.
+    pub new_cursor: (usize, usize),
+    /// Selection range before the change. `None` means nothing was selected.
+    pub old_selection: Option<((usize, usize), (usize, usize))>,
+    /// Selection range after the change. `None` means nothing is selected.
+    pub new_selection: Option<((usize, usize), (usize, usize))>,
+}
+
+// Column of the first non-space character. The head of line when the line has only spaces
+fn first_non_space(line: &str) -> usize {
+    line.chars().position(|c| !c.is_whitespace()).unwrap_or(0)
+}
+
+// Position of the end of the word before the cursor. Lines without words are skipped
+fn word_end_back(
+    row: usize,
+    col: usize,
+    lines: &[String],
+    words: &Words,
+) -> Option<(usize, usize)> {
+    if let Some(col) = find_word_inclusive_end_backward(&lines[row], col, words) {
+        return Some((row, col));
+    }
+    for row in (0..row).rev() {
+        let line = &lines[row];
+        if let Some(col) = find_word_inclusive_end_backward(line, usize::MAX, words) {
+            return Some((row, col));
+        }
+    }
+    Some((0, 0))
+}
+
 impl CursorMove {
+    // Motions which cover whole lines when they are used for operators like `dj` of Vim
+    pub(crate) fn linewise(self) -> bool {
+        matches!(
+            self,
+            Self::Up
+                | Self::Down
+                | Self::Top
+                | Self::Bottom
+                | Self::DownToFirstNonSpace
+                | Self::UpToFirstNonSpace
+                | Self::LineStartNonSpaceOf(_)
+        )
+    }
+
+    // Motions which include the character at the destination when they are used for operators like `de` of Vim
+    pub(crate) fn inclusive(self) -> bool {
+        matches!(
+            self,
+            Self::WordEnd | Self::WordEndBack | Self::WordSpacingEndBack
+        )
+    }
+
     pub(crate) fn next_cursor(
         &self,
         (row, col): (usize, usize),
         lines: &[String],
         viewport: &Viewport,
+        words: &Words,
     ) -> Option<(usize, usize)> {
         use CursorMove::*;
 
@@ -292,9 +456,18 @@ impl CursorMove {
                 let row = lines.len() - 1;
                 Some((row, fit_col(col, &lines[row])))
             }
+            DownToFirstNonSpace => Some((row + 1, first_non_space(lines.get(row + 1)?))),
+            UpToFirstNonSpace => {
+                let row = row.checked_sub(1)?;
+                Some((row, first_non_space(&lines[row])))
+            }
+            LineStartNonSpaceOf(row) => {
+                let row = cmp::min(*row as usize, lines.len() - 1);
+                Some((row, first_non_space(&lines[row])))
+            }
             WordEnd => {
                 // `+ 1` for not accepting the current cursor position
-                if let Some(col) = find_word_inclusive_end_forward(&lines[row], col + 1) {
+                if let Some(col) = find_word_inclusive_end_forward(&lines[row], col + 1, words) {
                     Some((row, col))
                 } else {
                     let mut row = row;
@@ -303,14 +476,14 @@ impl CursorMove {
                             break Some((row, lines[row].chars().count()));
                         }
                         row += 1;
-                        if let Some(col) = find_word_inclusive_end_forward(&lines[row], 0) {
+                        if let Some(col) = find_word_inclusive_end_forward(&lines[row], 0, words) {
                             break Some((row, col));
                         }
                     }
                 }
             }
             WordForward => {
-                if let Some(col) = find_word_start_forward(&lines[row], col) {
+                if let Some(col) = find_word_start_forward(&lines[row], col, words) {
                     Some((row, col))
                 } else if row + 1 < lines.len() {
                     Some((row + 1, 0))
@@ -319,7 +492,7 @@ impl CursorMove {
                 }
             }
             WordBack => {
-                if let Some(col) = find_word_start_backward(&lines[row], col) {
+                if let Some(col) = find_word_start_backward(&lines[row], col, words) {
                     Some((row, col))
                 } else if row > 0 {
                     Some((row - 1, lines[row - 1].chars().count()))
@@ -327,6 +500,14 @@ impl CursorMove {
                     Some((row, 0))
                 }
             }
+            WordEndBack => word_end_back(row, col, lines, words),
+            WordSpacingEndBack => {
+                let words = Words {
+                    boundary: WordBoundary::WhitespaceOnly,
+                    chars: None,
+                };
+                word_end_back(row, col, lines, &words)
+            }
             ParagraphForward => {
                 let mut prev_is_empty = lines[row].is_empty();
                 for row in row + 1..lines.len() {
@@ -352,7 +533,7 @@ impl CursorMove {
                 }
                 Some((0, fit_col(col, &lines[0])))
             }
-            Jump(row, col) => {
+            Jump(row, col) | JumpVirtual(row, col) => {
                 let row = cmp::min(*row as usize, lines.len() - 1);
                 let col = fit_col(*col as usize, &lines[row]);
                 Some((row, col))
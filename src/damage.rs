@@ -0,0 +1,170 @@
+use crate::history::Change;
+use std::cmp;
+use std::ops::{Bound, Range, RangeBounds};
+
+// Rows of the lines modified since they were cleared last time. Ranges are sorted and neither overlap nor touch each
+// other. Like anchors, the ranges follow edits so that they always point to the rows in the current text.
+#[derive(Clone, Debug, Default)]
+pub struct Damage {
+    ranges: Vec<Range<usize>>,
+}
+
+impl Damage {
+    pub fn ranges(&self) -> &[Range<usize>] {
+        &self.ranges
+    }
+
+    pub fn follow(&mut self, change: &Change) {
+        let (start, old_end, new_end) = change.rows();
+        // Rows after the replaced rows are shifted and rows in them are merged into the damaged range
+        let map_end = |end: usize| {
+            if end > old_end + 1 {
+                end - old_end + new_end
+            } else {
+                new_end + 1
+            }
+        };
+
+        let mut damaged = start..new_end + 1;
+        let mut ranges = Vec::with_capacity(self.ranges.len() + 1);
+        let mut inserted = false;
+        for r in self.ranges.drain(..) {
+            if r.end < start {
+                ranges.push(r);
+            } else if r.start > old_end + 1 {
+                if !inserted {
+                    ranges.push(damaged.clone());
+                    inserted = true;
+                }
+                let len = r.end - r.start;
+                let s = r.start - old_end + new_end;
+                ranges.push(s..s + len);
+            } else {
+                damaged = cmp::min(r.start, damaged.start)..cmp::max(map_end(r.end), damaged.end);
+            }
+        }
+        if !inserted {
+            ranges.push(damaged);
+        }
+        self.ranges = ranges;
+    }
+
+    pub fn clear(&mut self, rows: impl RangeBounds<usize>) {
+        let start = match rows.start_bound() {
+            Bound::Included(&s) => s,
+            Bound::Excluded(&s) => s + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match rows.end_bound() {
+            Bound::Included(&e) => e + 1,
+            Bound::Excluded(&e) => e,
+            Bound::Unbounded => usize::MAX,
+        };
+        if start >= end {
+            return;
+        }
+        let mut ranges = Vec::with_capacity(self.ranges.len() + 1);
+        for r in self.ranges.drain(..) {
+            if r.start < start {
+                ranges.push(r.start..cmp::min(r.end, start));
+            }
+            if end < r.end {
+                ranges.push(cmp::max(r.start, end)..r.end);
+            }
+        }
+        self.ranges = ranges;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::EditKind;
+    use crate::util::Pos;
+    use std::sync::Arc;
+
+    fn insert(row: usize, lines: usize) -> Change {
+        let text = vec![String::new(); lines + 1];
+        let kind = EditKind::InsertChunk(Arc::new(text));
+        Change::new(&kind, &Pos::new(row, 0, 0), &Pos::new(row + lines, 0, 0))
+    }
+
+    fn delete(row: usize, lines: usize) -> Change {
+        let text = vec![String::new(); lines + 1];
+        let kind = EditKind::DeleteChunk(Arc::new(text));
+        Change::new(&kind, &Pos::new(row + lines, 0, 0), &Pos::new(row, 0, 0))
+    }
+
+    fn rows(damage: &Damage) -> Vec<(usize, usize)> {
+        damage.ranges().iter().map(|r| (r.start, r.end)).collect()
+    }
+
+    fn edit_in_line(row: usize) -> Change {
+        let kind = EditKind::InsertChar('a');
+        Change::new(&kind, &Pos::new(row, 0, 0), &Pos::new(row, 1, 1))
+    }
+
+    #[test]
+    fn follow_changes() {
+        let tests = [
+            (vec![edit_in_line(3)], vec![(3, 4)]),
+            (vec![edit_in_line(3), edit_in_line(3)], vec![(3, 4)]),
+            (vec![edit_in_line(3), edit_in_line(4)], vec![(3, 5)]),
+            (vec![edit_in_line(3), edit_in_line(5)], vec![(3, 4), (5, 6)]),
+            (vec![edit_in_line(5), edit_in_line(3)], vec![(3, 4), (5, 6)]),
+            (vec![insert(3, 2)], vec![(3, 6)]),
+            (vec![delete(3, 2)], vec![(3, 4)]),
+            (vec![edit_in_line(8), insert(3, 2)], vec![(3, 6), (10, 11)]),
+            (vec![edit_in_line(8), delete(3, 2)], vec![(3, 4), (6, 7)]),
+            (vec![edit_in_line(5), delete(3, 3)], vec![(3, 4)]),
+            (vec![edit_in_line(7), delete(3, 3)], vec![(3, 5)]),
+            (vec![edit_in_line(1), insert(3, 1)], vec![(1, 2), (3, 5)]),
+            (
+                vec![edit_in_line(3), edit_in_line(9), delete(2, 5)],
+                vec![(2, 3), (4, 5)],
+            ),
+            (
+                vec![edit_in_line(2), edit_in_line(9), insert(5, 1)],
+                vec![(2, 3), (5, 7), (10, 11)],
+            ),
+        ];
+        for (changes, expected) in tests {
+            let mut damage = Damage::default();
+            for change in &changes {
+                damage.follow(change);
+            }
+            assert_eq!(rows(&damage), expected);
+        }
+    }
+
+    #[test]
+    fn clear_rows() {
+        let tests = [
+            ((Bound::Unbounded, Bound::Unbounded), vec![]),
+            (
+                (Bound::Included(3), Bound::Excluded(4)),
+                vec![(1, 3), (8, 10)],
+            ),
+            (
+                (Bound::Included(2), Bound::Included(8)),
+                vec![(1, 2), (9, 10)],
+            ),
+            ((Bound::Excluded(0), Bound::Unbounded), vec![]),
+            (
+                (Bound::Included(5), Bound::Excluded(7)),
+                vec![(1, 4), (8, 10)],
+            ),
+            (
+                (Bound::Included(4), Bound::Excluded(4)),
+                vec![(1, 4), (8, 10)],
+            ),
+        ];
+        for (bounds, expected) in tests {
+            let mut damage = Damage {
+                ranges: vec![1..4, 8..10],
+            };
+            damage.clear(bounds);
+            assert_eq!(rows(&damage), expected, "{:?}", bounds);
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use crate::ratatui::style::{Color, Style};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Kind of a change in a diff used to mark lines by [`TextArea::set_diff_marks`]. A marked line is rendered with the
+/// background color of its kind and the sign of its kind in the gutter.
+///
+/// This type is marked as `#[non_exhaustive]` since more variations may be supported in the future. See also:
+/// [`TextArea::set_diff_style`]
+///
+/// [`TextArea::set_diff_marks`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.set_diff_marks
+/// [`TextArea::set_diff_style`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.set_diff_style
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DiffKind {
+    /// The line was added. It is marked with `+` in dark green background by default.
+    Added,
+    /// The line was removed. It is marked with `-` in dark red background by default.
+    Removed,
+    /// The line was changed. It is marked with `~` in dark yellow background by default.
+    Changed,
+}
+
+impl DiffKind {
+    /// Sign of the kind rendered in the gutter.
+    /// ```
+    /// use tui_textarea::DiffKind;
+    ///
+    /// assert_eq!(DiffKind::Added.sign(), '+');
+    /// assert_eq!(DiffKind::Removed.sign(), '-');
+    /// assert_eq!(DiffKind::Changed.sign(), '~');
+    /// ```
+    pub fn sign(self) -> char {
+        match self {
+            Self::Added => '+',
+            Self::Removed => '-',
+            Self::Changed => '~',
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            Self::Added => 0,
+            Self::Removed => 1,
+            Self::Changed => 2,
+        }
+    }
+}
+
+// Kinds of the marked lines and the styles of the kinds
+#[derive(Clone, Debug)]
+pub struct DiffMarks {
+    marks: BTreeMap<usize, DiffKind>,
+    styles: [Style; 3],
+}
+
+impl Default for DiffMarks {
+    fn default() -> Self {
+        Self {
+            marks: BTreeMap::new(),
+            styles: [
+                Style::default().bg(Color::Indexed(22)),
+                Style::default().bg(Color::Indexed(52)),
+                Style::default().bg(Color::Indexed(58)),
+            ],
+        }
+    }
+}
+
+impl DiffMarks {
+    pub fn set(&mut self, marks: impl IntoIterator<Item = (usize, DiffKind)>) {
+        self.marks = marks.into_iter().collect();
+    }
+
+    pub fn get(&self, row: usize) -> Option<DiffKind> {
+        self.marks.get(&row).copied()
+    }
+
+    pub fn clear(&mut self) {
+        self.marks.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.marks.is_empty()
+    }
+
+    pub fn set_style(&mut self, kind: DiffKind, style: Style) {
+        self.styles[kind.index()] = style;
+    }
+
+    pub fn style(&self, kind: DiffKind) -> Style {
+        self.styles[kind.index()]
+    }
+}
@@ -0,0 +1,304 @@
+use crate::word::{find_word_range, Words};
+
+const BRACKETS: [(char, char); 4] = [('(', ')'), ('[', ']'), ('{', '}'), ('<', '>')];
+
+// Selection before it was expanded, and the range selected by the expansion as byte offsets in the text where lines
+// are joined with newlines. The range is used to check that the selection was not changed since the expansion.
+#[derive(Clone, Debug)]
+pub struct Expansion {
+    pub selection_start: Option<(usize, usize)>,
+    pub cursor: (usize, usize),
+    pub range: (usize, usize),
+}
+
+// Text where lines are joined with newlines, with the byte offsets of the heads of the lines
+struct Text<'a> {
+    lines: &'a [String],
+    heads: Vec<usize>,
+}
+
+impl<'a> Text<'a> {
+    fn new(lines: &'a [String]) -> Self {
+        let mut heads = Vec::with_capacity(lines.len());
+        let mut offset = 0;
+        for line in lines {
+            heads.push(offset);
+            offset += line.len() + 1;
+        }
+        Self { lines, heads }
+    }
+
+    fn len(&self) -> usize {
+        self.heads[self.heads.len() - 1] + self.lines[self.lines.len() - 1].len()
+    }
+
+    fn row(&self, offset: usize) -> usize {
+        match self.heads.binary_search(&offset) {
+            Ok(row) => row,
+            Err(row) => row - 1,
+        }
+    }
+
+    fn line_end(&self, row: usize) -> usize {
+        self.heads[row] + self.lines[row].len()
+    }
+
+    // Characters before the offset in reverse order with their offsets
+    fn chars_before(&self, offset: usize) -> impl Iterator<Item = (usize, char)> + '_ {
+        let row = self.row(offset);
+        let line = &self.lines[row][..offset - self.heads[row]];
+        let head = self.heads[row];
+        let current = line.char_indices().rev().map(move |(i, c)| (head + i, c));
+        let above = (0..row).rev().flat_map(move |r| {
+            let head = self.heads[r];
+            let newline = Some((head + self.lines[r].len(), '\n'));
+            newline.into_iter().chain(
+                self.lines[r]
+                    .char_indices()
+                    .rev()
+                    .map(move |(i, c)| (head + i, c)),
+            )
+        });
+        current.chain(above)
+    }
+
+    // Characters at and after the offset with their offsets
+    fn chars_after(&self, offset: usize) -> impl Iterator<Item = (usize, char)> + '_ {
+        let row = self.row(offset);
+        (row..self.lines.len()).flat_map(move |r| {
+            let head = self.heads[r];
+            let skip = if r == row { offset - head } else { 0 };
+            let newline = (r + 1 < self.lines.len()).then(|| (head + self.lines[r].len(), '\n'));
+            self.lines[r][skip..]
+                .char_indices()
+                .map(move |(i, c)| (head + skip + i, c))
+                .chain(newline)
+        })
+    }
+
+    // Range of the word containing the range in a line
+    fn word(&self, start: usize, end: usize, words: &Words) -> Option<(usize, usize)> {
+        let row = self.row(start);
+        if self.row(end) != row {
+            return None;
+        }
+        let (head, line) = (self.heads[row], self.lines[row].as_str());
+        let col = line[..start - head].chars().count();
+        let (s, e) = find_word_range(line, col, words)
+            .or_else(|| find_word_range(line, col.checked_sub(1)?, words))?;
+        let offset =
+            |col: usize| head + line.char_indices().nth(col).map_or(line.len(), |(i, _)| i);
+        let (s, e) = (offset(s), offset(e));
+        // Consecutive spaces are not a word to select
+        if self.lines[row][s - head..e - head].trim().is_empty() {
+            return None;
+        }
+        Some((s, e))
+    }
+
+    // Range extended to the whitespaces around it in a line, like WORD of Vim. Whitespaces in brackets don't split the
+    // range and the range does not go beyond the enclosing brackets.
+    fn non_blank(&self, start: usize, end: usize) -> (usize, usize) {
+        fn is_boundary(
+            c: char,
+            depth: &mut usize,
+            open: fn(char) -> bool,
+            close: fn(char) -> bool,
+        ) -> bool {
+            if c == '\n' {
+                return true;
+            }
+            if open(c) {
+                *depth += 1;
+            } else if close(c) {
+                if *depth == 0 {
+                    return true;
+                }
+                *depth -= 1;
+            }
+            *depth == 0 && c.is_whitespace()
+        }
+        let is_open = |c: char| BRACKETS.iter().any(|&(o, _)| o == c);
+        let is_close = |c: char| BRACKETS.iter().any(|&(_, e)| e == c);
+        let mut depth = 0;
+        let start = self
+            .chars_before(start)
+            .find(|&(_, c)| is_boundary(c, &mut depth, is_close, is_open))
+            .map_or(0, |(i, c)| i + c.len_utf8());
+        let mut depth = 0;
+        let end = self
+            .chars_after(end)
+            .find(|&(_, c)| is_boundary(c, &mut depth, is_open, is_close))
+            .map_or(self.len(), |(i, _)| i);
+        (start, end)
+    }
+
+    // Range of the lines containing the range without the indentation
+    fn trimmed_lines(&self, start: usize, end: usize) -> (usize, usize) {
+        let row = self.row(start);
+        let line = &self.lines[row];
+        let indent = line.len() - line.trim_start().len();
+        (self.heads[row] + indent, self.line_end(self.row(end)))
+    }
+
+    fn lines(&self, start: usize, end: usize) -> (usize, usize) {
+        let row = self.row(start);
+        (self.heads[row], self.line_end(self.row(end)))
+    }
+
+    // Range of the non-empty lines around the range
+    fn paragraph(&self, start: usize, end: usize) -> Option<(usize, usize)> {
+        let is_blank = |row: usize| self.lines[row].trim().is_empty();
+        let (mut top, mut bottom) = (self.row(start), self.row(end));
+        if is_blank(top) || is_blank(bottom) {
+            return None;
+        }
+        while top > 0 && !is_blank(top - 1) {
+            top -= 1;
+        }
+        while bottom + 1 < self.lines.len() && !is_blank(bottom + 1) {
+            bottom += 1;
+        }
+        Some((self.heads[top], self.line_end(bottom)))
+    }
+
+    // Offset of the closing bracket matching the opening bracket at the offset
+    fn matching_close(&self, open_offset: usize, open: char, close: char) -> Option<usize> {
+        let mut depth = 0;
+        for (i, c) in self.chars_after(open_offset + open.len_utf8()) {
+            if c == open {
+                depth += 1;
+            } else if c == close {
+                if depth == 0 {
+                    return Some(i);
+                }
+                depth -= 1;
+            }
+        }
+        None
+    }
+
+    // Offsets of the innermost pair of brackets surrounding the range
+    fn brackets(&self, start: usize, end: usize) -> Option<(usize, usize)> {
+        let mut depths = [0usize; BRACKETS.len()];
+        for (i, c) in self.chars_before(start) {
+            for (k, &(open, close)) in BRACKETS.iter().enumerate() {
+                if c == close {
+                    depths[k] += 1;
+                } else if c == open {
+                    if depths[k] > 0 {
+                        depths[k] -= 1;
+                        continue;
+                    }
+                    match self.matching_close(i, open, close) {
+                        Some(j) if j >= end => return Some((i, j)),
+                        _ => {}
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+// Expand the range to the smallest syntactic unit which contains it. Units are a word, a sequence of non-blank
+// characters, lines without the indentation, lines, a paragraph, the inside of brackets, brackets, and the whole text.
+// The range is byte offsets in the text where lines are joined with newlines.
+pub fn expand_range(
+    lines: &[String],
+    start: usize,
+    end: usize,
+    words: &Words,
+) -> Option<(usize, usize)> {
+    let text = Text::new(lines);
+    let mut candidates = vec![
+        text.non_blank(start, end),
+        text.trimmed_lines(start, end),
+        text.lines(start, end),
+        (0, text.len()),
+    ];
+    candidates.extend(text.word(start, end, words));
+    candidates.extend(text.paragraph(start, end));
+    if let Some((open, close)) = text.brackets(start, end) {
+        candidates.push((open + 1, close)); // Brackets are ASCII characters
+        candidates.push((open, close + 1));
+    }
+    candidates
+        .into_iter()
+        .filter(|&(s, e)| s <= start && end <= e && e - s > end - start)
+        .min_by_key(|&(s, e)| e - s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand_all(lines: &[&str], cursor: usize) -> Vec<String> {
+        let lines: Vec<_> = lines.iter().map(|l| l.to_string()).collect();
+        let text = lines.join("\n");
+        let words = Words::default();
+        let (mut start, mut end) = (cursor, cursor);
+        let mut expanded = vec![];
+        while let Some((s, e)) = expand_range(&lines, start, end, &words) {
+            expanded.push(text[s..e].to_string());
+            start = s;
+            end = e;
+        }
+        expanded
+    }
+
+    #[test]
+    fn expand_selection_ranges() {
+        let tests: &[(&[&str], usize, &[&str])] = &[
+            (&[""], 0, &[]),
+            (&["abc"], 1, &["abc"]),
+            (&["abc def"], 7, &["def", "abc def"]),
+            (
+                &["  foo.bar(x, y) z"],
+                3,
+                &[
+                    "foo",
+                    "foo.bar(x, y)",
+                    "foo.bar(x, y) z",
+                    "  foo.bar(x, y) z",
+                ],
+            ),
+            (
+                &["  f(foo.bar, y)"],
+                6,
+                &[
+                    "foo",
+                    "foo.bar,",
+                    "foo.bar, y",
+                    "(foo.bar, y)",
+                    "f(foo.bar, y)",
+                    "  f(foo.bar, y)",
+                ],
+            ),
+            (
+                &["a", "fn f() {", "  x", "}", "", "b"],
+                13,
+                &[
+                    "x",
+                    "  x",
+                    "\n  x\n",
+                    "{\n  x\n}",
+                    "fn f() {\n  x\n}",
+                    "a\nfn f() {\n  x\n}",
+                    "a\nfn f() {\n  x\n}\n\nb",
+                ],
+            ),
+            (&["(a) (b)"], 3, &["(a)", "(a) (b)"]),
+            (&["[(a])"], 2, &["a", "a]", "(a])", "[(a])"]),
+        ];
+        for (lines, cursor, expected) in tests {
+            assert_eq!(
+                &expand_all(lines, *cursor),
+                expected,
+                "{:?} at {}",
+                lines,
+                cursor
+            );
+        }
+    }
+}
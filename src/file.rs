@@ -0,0 +1,127 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::io::{self, Write};
+
+/// Encoding of a file read by [`TextArea::open_file`]. The encoding is detected from the byte order mark (BOM) at the
+/// head of the file. When no BOM is found, the file is read as UTF-8. The same encoding is used when writing the text
+/// by [`TextArea::save_file`].
+///
+/// This type is marked as `#[non_exhaustive]` since more variations may be supported in the future.
+///
+/// [`TextArea::open_file`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.open_file
+/// [`TextArea::save_file`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.save_file
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Encoding {
+    /// UTF-8 without BOM. This is the default.
+    Utf8,
+    /// UTF-8 with BOM.
+    Utf8Bom,
+    /// UTF-16 little endian with BOM.
+    Utf16Le,
+    /// UTF-16 big endian with BOM.
+    Utf16Be,
+}
+
+impl Default for Encoding {
+    fn default() -> Self {
+        Self::Utf8
+    }
+}
+
+const UTF8_BOM: &[u8] = b"\xef\xbb\xbf";
+const UTF16LE_BOM: &[u8] = b"\xff\xfe";
+const UTF16BE_BOM: &[u8] = b"\xfe\xff";
+
+fn invalid_data(msg: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn decode_utf16(bytes: &[u8], from_bytes: fn([u8; 2]) -> u16) -> io::Result<String> {
+    if bytes.len() % 2 != 0 {
+        return Err(invalid_data("odd number of bytes in UTF-16 text"));
+    }
+    let units: Vec<_> = bytes
+        .chunks_exact(2)
+        .map(|b| from_bytes([b[0], b[1]]))
+        .collect();
+    String::from_utf16(&units).map_err(|_| invalid_data("invalid UTF-16 text"))
+}
+
+pub fn decode(mut bytes: Vec<u8>) -> io::Result<(String, Encoding)> {
+    if bytes.starts_with(UTF8_BOM) {
+        bytes.drain(..UTF8_BOM.len());
+        let s = String::from_utf8(bytes).map_err(|_| invalid_data("invalid UTF-8 text"))?;
+        Ok((s, Encoding::Utf8Bom))
+    } else if bytes.starts_with(UTF16LE_BOM) {
+        let s = decode_utf16(&bytes[UTF16LE_BOM.len()..], u16::from_le_bytes)?;
+        Ok((s, Encoding::Utf16Le))
+    } else if bytes.starts_with(UTF16BE_BOM) {
+        let s = decode_utf16(&bytes[UTF16BE_BOM.len()..], u16::from_be_bytes)?;
+        Ok((s, Encoding::Utf16Be))
+    } else {
+        let s = String::from_utf8(bytes).map_err(|_| invalid_data("invalid UTF-8 text"))?;
+        Ok((s, Encoding::Utf8))
+    }
+}
+
+pub fn encode<W: Write>(mut w: W, text: &str, encoding: Encoding) -> io::Result<()> {
+    match encoding {
+        Encoding::Utf8 => w.write_all(text.as_bytes()),
+        Encoding::Utf8Bom => {
+            w.write_all(UTF8_BOM)?;
+            w.write_all(text.as_bytes())
+        }
+        Encoding::Utf16Le => {
+            w.write_all(UTF16LE_BOM)?;
+            for u in text.encode_utf16() {
+                w.write_all(&u.to_le_bytes())?;
+            }
+            Ok(())
+        }
+        Encoding::Utf16Be => {
+            w.write_all(UTF16BE_BOM)?;
+            for u in text.encode_utf16() {
+                w.write_all(&u.to_be_bytes())?;
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_encode_round_trip() {
+        let tests = [
+            (&b"abc\n"[..], Encoding::Utf8),
+            (&b"\xef\xbb\xbfabc\n"[..], Encoding::Utf8Bom),
+            (&b"\xff\xfea\x00b\x00\n\x00"[..], Encoding::Utf16Le),
+            (&b"\xfe\xff\x00a\x00b\x00\n"[..], Encoding::Utf16Be),
+            (&b"\xe3\x81\x82"[..], Encoding::Utf8),
+        ];
+        for (bytes, encoding) in tests {
+            let (text, detected) = decode(bytes.to_vec()).unwrap();
+            assert_eq!(detected, encoding, "{:?}", bytes);
+            let mut encoded = vec![];
+            encode(&mut encoded, &text, encoding).unwrap();
+            assert_eq!(encoded, bytes, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn decode_invalid_text() {
+        for bytes in [
+            &b"\xff"[..],
+            b"\xef\xbb\xbf\xff",
+            b"\xff\xfea",
+            b"\xff\xfe\x00\xd8",
+        ] {
+            let err = decode(bytes.to_vec()).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{:?}", bytes);
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use crate::input::{Input, Key};
+use crate::textarea::TextArea;
+use std::fmt;
+use std::sync::Arc;
+
+type ValidatorFn = dyn Fn(&TextArea<'_>) -> Result<(), String> + Send + Sync;
+
+#[derive(Clone)]
+struct Validator(Arc<ValidatorFn>);
+
+impl fmt::Debug for Validator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Validator")
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Field<'a> {
+    textarea: TextArea<'a>,
+    validator: Option<Validator>,
+}
+
+/// A set of [`TextArea`] fields with one focused field, like an input form of dialogs. Inputs are handled by the
+/// focused field and `Tab` / `Shift+Tab` move the focus to the next / previous field. The focus state of each field is
+/// updated with [`TextArea::set_focused`] so that the focused field is rendered differently. Each field can have a
+/// validator to check its text.
+///
+/// How the fields are laid out is up to the application. Render each field returned from [`Form::get`] or
+/// [`Form::iter`] in its own area.
+/// ```
+/// use tui_textarea::{Form, Input, Key, TextArea};
+///
+/// let mut form = Form::default();
+/// let name = form.push(TextArea::default());
+/// let age = form.push(TextArea::default());
+/// form.set_validator(age, |t| match t.lines()[0].parse::<u8>() {
+///     Ok(_) => Ok(()),
+///     Err(err) => Err(format!("invalid age: {}", err)),
+/// });
+///
+/// form.input(Input { key: Key::Char('a'), ..Default::default() });
+/// form.input(Input { key: Key::Tab, ..Default::default() });
+/// assert_eq!(form.focused_index(), Some(age));
+/// form.input(Input { key: Key::Char('x'), ..Default::default() });
+///
+/// assert_eq!(form.get(name).unwrap().lines(), ["a"]);
+/// assert!(!form.get(name).unwrap().focused());
+/// assert_eq!(form.validate(age), Err("invalid age: invalid digit found in string".to_string()));
+/// assert_eq!(form.first_invalid(), Some(age));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Form<'a> {
+    fields: Vec<Field<'a>>,
+    focused: usize,
+}
+
+impl<'a> Form<'a> {
+    /// Add the textarea as a new field at the end and return the index of the field. The first field is focused.
+    pub fn push(&mut self, mut textarea: TextArea<'a>) -> usize {
+        textarea.set_focused(self.fields.is_empty());
+        self.fields.push(Field {
+            textarea,
+            validator: None,
+        });
+        self.fields.len() - 1
+    }
+
+    /// Remove the field at the index and return its textarea. When the focused field is removed, the next field is
+    /// focused, or the previous field when it was the last one. The validator of the field is removed together.
+    /// `None` is returned when the index is out of range.
+    /// ```
+    /// use tui_textarea::{Form, TextArea};
+    ///
+    /// let mut form = Form::default();
+    /// form.push(TextArea::from(["a"]));
+    /// form.push(TextArea::from(["b"]));
+    ///
+    /// assert_eq!(form.remove(0).unwrap().lines(), ["a"]);
+    /// assert_eq!(form.focused_index(), Some(0));
+    /// assert!(form.focused().unwrap().focused());
+    /// ```
+    pub fn remove(&mut self, index: usize) -> Option<TextArea<'a>> {
+        if index >= self.fields.len() {
+            return None;
+        }
+        let field = self.fields.remove(index);
+        if index < self.focused || self.focused == self.fields.len() {
+            self.focused = self.focused.saturating_sub(1);
+        }
+        if let Some(f) = self.fields.get_mut(self.focused) {
+            f.textarea.set_focused(true);
+        }
+        Some(field.textarea)
+    }
+
+    /// Get the number of fields.
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    /// Return if this form has no field.
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// Get the index of the focused field. `None` is returned when this form is empty.
+    pub fn focused_index(&self) -> Option<usize> {
+        (!self.fields.is_empty()).then(|| self.focused)
+    }
+
+    /// Get the textarea of the focused field.
+    pub fn focused(&self) -> Option<&TextArea<'a>> {
+        self.get(self.focused)
+    }
+
+    /// Get the textarea of the focused field mutably.
+    pub fn focused_mut(&mut self) -> Option<&mut TextArea<'a>> {
+        self.get_mut(self.focused)
+    }
+
+    /// Get the textarea of the field at the index.
+    pub fn get(&self, index: usize) -> Option<&TextArea<'a>> {
+        self.fields.get(index).map(|f| &f.textarea)
+    }
+
+    /// Get the textarea of the field at the index mutably.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut TextArea<'a>> {
+        self.fields.get_mut(index).map(|f| &mut f.textarea)
+    }
+
+    /// Iterate the textareas of all fields.
+    pub fn iter(&self) -> impl Iterator<Item = &TextArea<'a>> {
+        self.fields.iter().map(|f| &f.textarea)
+    }
+
+    /// Convert this form into the textareas of all fields. This is useful to get the inputs after the form is
+    /// submitted.
+    pub fn into_textareas(self) -> Vec<TextArea<'a>> {
+        self.fields.into_iter().map(|f| f.textarea).collect()
+    }
+
+    /// Move the focus to the field at the index. This method returns `false` when the index is out of range.
+    pub fn focus(&mut self, index: usize) -> bool {
+        if index >= self.fields.len() {
+            return false;
+        }
+        self.fields[self.focused].textarea.set_focused(false);
+        self.fields[index].textarea.set_focused(true);
+        self.focused = index;
+        true
+    }
+
+    /// Move the focus to the next field. After the last field, the first field is focused. This method returns
+    /// `false` when this form is empty.
+    pub fn focus_next(&mut self) -> bool {
+        if self.fields.is_empty() {
+            return false;
+        }
+        self.focus((self.focused + 1) % self.fields.len())
+    }
+
+    /// Move the focus to the previous field. Before the first field, the last field is focused. This method returns
+    /// `false` when this form is empty.
+    pub fn focus_prev(&mut self) -> bool {
+        if self.fields.is_empty() {
+            return false;
+        }
+        let index = self.focused.checked_sub(1).unwrap_or(self.fields.len() - 1);
+        self.focus(index)
+    }
+
+    /// Handle a key input. `Tab` moves the focus to the next field and `Shift+Tab` moves it to the previous field.
+    /// Other inputs are handled by the focused field with [`TextArea::input`]. Note that this means `Tab` is not
+    /// inserted into the fields. This method returns if the input modified the text or not.
+    pub fn input(&mut self, input: impl Into<Input>) -> bool {
+        match input.into() {
+            Input {
+                key: Key::Tab,
+                ctrl: false,
+                alt: false,
+                shift,
+            } => {
+                if shift {
+                    self.focus_prev();
+                } else {
+                    self.focus_next();
+                }
+                false
+            }
+            input => match self.focused_mut() {
+                Some(textarea) => textarea.input(input),
+                None => false,
+            },
+        }
+    }
+
+    /// Set the function to validate the text of the field at the index. The function returns an error message when
+    /// the text is invalid. Setting a validator replaces the previous one. This method returns `false` when the
+    /// index is out of range.
+    pub fn set_validator<F>(&mut self, index: usize, f: F) -> bool
+    where
+        F: Fn(&TextArea<'_>) -> Result<(), String> + Send + Sync + 'static,
+    {
+        match self.fields.get_mut(index) {
+            Some(field) => {
+                field.validator = Some(Validator(Arc::new(f)));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove the validator of the field at the index set by [`Form::set_validator`].
+    pub fn remove_validator(&mut self, index: usize) -> bool {
+        self.fields
+            .get_mut(index)
+            .map_or(false, |f| f.validator.take().is_some())
+    }
+
+    /// Validate the text of the field at the index with its validator and return the result. A field without
+    /// validator or out of range is always valid. Applications can call this after each input to show the error
+    /// message next to the field.
+    pub fn validate(&self, index: usize) -> Result<(), String> {
+        match self.fields.get(index) {
+            Some(Field {
+                textarea,
+                validator: Some(validator),
+            }) => (validator.0)(textarea),
+            _ => Ok(()),
+        }
+    }
+
+    /// Get the index of the first field whose text is invalid. `None` is returned when all fields are valid. This is
+    /// useful to move the focus to the invalid field on submitting the form.
+    pub fn first_invalid(&self) -> Option<usize> {
+        (0..self.fields.len()).find(|&i| self.validate(i).is_err())
+    }
+
+    /// Return if the texts of all fields are valid.
+    pub fn is_valid(&self) -> bool {
+        self.first_invalid().is_none()
+    }
+}
@@ -0,0 +1,553 @@
+use crate::anchor::{AnchorGrowth, AnchorId};
+use crate::control::ControlCharStyle;
+use crate::cursor::CursorMove;
+use crate::indent::IndentPolicy;
+use crate::input::Input;
+use crate::line_ending::LineEnding;
+use crate::placeholder::PlaceholderCursor;
+use crate::ratatui::layout::Alignment;
+use crate::scroll::Scrolling;
+#[cfg(feature = "search")]
+use crate::search::{MatchAction, SearchDirection, SearchMode};
+use crate::state::Snapshot;
+use crate::textarea::TextArea;
+use crate::word::WordBoundary;
+use arbitrary::{Arbitrary, Result, Unstructured};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+// Positions are small so that they often point to the text
+type Pos = (u8, u8);
+
+fn pos((row, col): Pos) -> (usize, usize) {
+    (row as usize, col as usize)
+}
+
+// Operation applied to the textarea via its public API
+#[derive(Arbitrary, Debug)]
+enum Op {
+    Input(Input),
+    InputWithoutShortcuts(Input),
+    InputWithCount(Input, u8),
+    RepeatLastChange,
+    QueueInput(Input),
+    ProcessQueued(u8),
+    ClearQueuedInputs,
+    SetPasteFilter(bool),
+    BindKeys(Vec<Input>, bool),
+    ProcessPendingKeys,
+    ClearPendingKeys,
+    RemoveKeymap,
+    MoveCursor(CursorMove),
+    Scroll(i16, i16),
+    ScrollPages(i8, bool),
+    ScrollToRow(u8),
+    SetScrollOffset(u8, u8),
+    SetPageOverlap(u8),
+    GotoLine(u8, bool),
+    GotoPercentage(u8, bool),
+    InsertChar(char),
+    InsertStr(String),
+    InsertTab,
+    InsertNewline,
+    DeleteNewline,
+    DeleteChar,
+    DeleteNextChar,
+    ReplaceChar(char),
+    DeleteStr(u8),
+    DeleteLineByEnd,
+    DeleteLineByHead,
+    DeleteWord,
+    DeleteNextWord,
+    TrimTrailingWhitespace,
+    EnsureTrailingNewline,
+    CollapseMultipleBlankLines(u8),
+    SetText(String),
+    SetLines(Vec<String>),
+    ClearHistory,
+    Restore(String, Pos, Option<Pos>),
+    ConvertLineEndings(bool),
+    AppendLines(Vec<String>),
+    ReplaceRange(Pos, Pos, String),
+    InsertLinesAt(u8, Vec<String>),
+    DeleteLines(u8, u8),
+    ApplyRemoteEdit(Pos, Pos, String),
+    SetEditLog(bool),
+    DrainEdits,
+    StartSelection,
+    CancelSelection,
+    ReselectLast,
+    ExpandSelection,
+    ShrinkSelection,
+    SplitSelectionOnNewlines,
+    KeepPrimarySelection,
+    ReplaceSelections(String),
+    RotateSelectionContents,
+    AlignSelections,
+    SelectAll,
+    SetSelectionInclusive(bool),
+    Copy,
+    Cut,
+    Paste,
+    SetYankText(String),
+    SurroundSelection(String, String),
+    UnsurroundSelection,
+    Undo,
+    Redo,
+    UndoUntil(u16),
+    RedoUntil(u16),
+    SetMaxHistories(u8),
+    SetHistoryMergeInterval(Option<u8>),
+    SetMaxHistoryBytes(u16),
+    WithHistoryDisabled(Box<Op>),
+    MarkSaved,
+    JumpBack,
+    JumpForward,
+    PushJump,
+    CreateAnchor(Pos, Pos, u8),
+    RemoveAnchor(u8),
+    ClearAnchors,
+    SetLineMeta(u8, u8),
+    RemoveLineMeta(u8),
+    ConcealRange(Pos, Pos, String),
+    ClearConceals,
+    ClearDamagedLines(u8, u8),
+    SetVirtualEdit(bool),
+    SetFocused(bool),
+    SetCursorBlink(Option<u8>),
+    Tick(u16),
+    SetWordBoundary(u8),
+    SetWordChars(Option<Vec<char>>),
+    SetHardTabIndent(bool),
+    SetTabLength(u8),
+    SetLineNumberWidth(Option<u8>),
+    SetRowAlignment(u8, u8),
+    SetPlaceholder(String, u8, Option<u8>),
+    SetShowControlChars(u8),
+    SetWidthOverrides(Vec<(char, char, u8)>),
+    SetIndentPolicy(u8, bool, bool),
+    SetPromptMode(bool),
+    PushPromptHistory(String),
+    PrevPromptHistory,
+    NextPromptHistory,
+    SetCompletionItems(Vec<String>),
+    SelectNextCompletion,
+    AcceptCompletion,
+    SetPreedit(Option<(String, u8)>),
+    CommitPreedit,
+    CancelPreedit,
+    #[cfg(feature = "search")]
+    SetSearchPattern(String),
+    #[cfg(feature = "search")]
+    SetSearchMode(u8),
+    #[cfg(feature = "search")]
+    SearchForward(bool),
+    #[cfg(feature = "search")]
+    SearchBack(bool),
+    #[cfg(feature = "search")]
+    SetSearchDirection(bool),
+    #[cfg(feature = "search")]
+    SearchNext(bool),
+    #[cfg(feature = "search")]
+    SelectAllMatches(String),
+    #[cfg(feature = "search")]
+    ForEachMatch(Vec<Option<String>>),
+}
+
+impl Op {
+    fn apply(self, t: &mut TextArea<'_>) {
+        match self {
+            Self::Input(input) => {
+                t.input(input);
+            }
+            Self::InputWithoutShortcuts(input) => {
+                t.input_without_shortcuts(input);
+            }
+            Self::InputWithCount(input, count) => {
+                t.input_with_count(input, count as usize);
+            }
+            Self::RepeatLastChange => {
+                t.repeat_last_change();
+            }
+            Self::QueueInput(input) => {
+                t.queue_input(input);
+            }
+            Self::ProcessQueued(max) => {
+                t.process_queued(max as usize);
+            }
+            Self::ClearQueuedInputs => t.clear_queued_inputs(),
+            Self::SetPasteFilter(true) => t.set_paste_filter(|s| s.replace('\t', "  ")),
+            Self::SetPasteFilter(false) => t.remove_paste_filter(),
+            Self::BindKeys(keys, timeout) => {
+                let mut keymap = t.keymap().cloned().unwrap_or_default();
+                keymap.set_timeout(timeout.then(|| Duration::ZERO));
+                keymap.bind(keys, |t| t.delete_line_by_head());
+                t.set_keymap(keymap);
+            }
+            Self::ProcessPendingKeys => {
+                t.process_pending_keys();
+            }
+            Self::ClearPendingKeys => t.clear_pending_keys(),
+            Self::RemoveKeymap => {
+                t.remove_keymap();
+            }
+            Self::MoveCursor(m) => t.move_cursor(m),
+            Self::Scroll(rows, cols) => t.scroll((rows, cols)),
+            Self::ScrollPages(quarters, horizontal) => {
+                let pages = quarters as f32 / 4.0;
+                t.scroll(if horizontal {
+                    Scrolling::HorizontalPages(pages)
+                } else {
+                    Scrolling::Pages(pages)
+                });
+            }
+            Self::ScrollToRow(row) => t.scroll(Scrolling::ToRow(row as usize)),
+            Self::SetScrollOffset(row, col) => t.set_scroll_offset(row as usize, col as usize),
+            Self::SetPageOverlap(rows) => t.set_page_overlap(rows as u16),
+            Self::GotoLine(line, center) => t.goto_line(line as usize, center),
+            Self::GotoPercentage(percent, center) => t.goto_percentage(percent as usize, center),
+            Self::InsertChar(c) => t.insert_char(c),
+            Self::InsertStr(s) => {
+                t.insert_str(s);
+            }
+            Self::InsertTab => {
+                t.insert_tab();
+            }
+            Self::InsertNewline => t.insert_newline(),
+            Self::DeleteNewline => {
+                t.delete_newline();
+            }
+            Self::DeleteChar => {
+                t.delete_char();
+            }
+            Self::DeleteNextChar => {
+                t.delete_next_char();
+            }
+            Self::ReplaceChar(c) => {
+                t.replace_char(c);
+            }
+            Self::DeleteStr(n) => {
+                t.delete_str(n as usize);
+            }
+            Self::DeleteLineByEnd => {
+                t.delete_line_by_end();
+            }
+            Self::DeleteLineByHead => {
+                t.delete_line_by_head();
+            }
+            Self::DeleteWord => {
+                t.delete_word();
+            }
+            Self::DeleteNextWord => {
+                t.delete_next_word();
+            }
+            Self::TrimTrailingWhitespace => {
+                t.trim_trailing_whitespace();
+            }
+            Self::EnsureTrailingNewline => {
+                t.ensure_trailing_newline();
+            }
+            Self::CollapseMultipleBlankLines(max) => {
+                t.collapse_multiple_blank_lines(max as usize);
+            }
+            Self::SetText(s) => {
+                t.set_text(s);
+            }
+            Self::SetLines(lines) => {
+                t.set_lines(lines);
+            }
+            Self::ClearHistory => t.clear_history(),
+            Self::Restore(s, cursor, start) => {
+                t.restore(Snapshot {
+                    lines: Arc::new(s.split('\n').map(String::from).collect()),
+                    cursor: pos(cursor),
+                    selection_start: start.map(pos),
+                });
+            }
+            Self::ConvertLineEndings(crlf) => {
+                t.convert_line_endings(if crlf {
+                    LineEnding::CrLf
+                } else {
+                    LineEnding::Lf
+                });
+            }
+            Self::AppendLines(lines) => {
+                t.append_lines(lines);
+            }
+            Self::ReplaceRange(start, end, s) => {
+                t.replace_range(pos(start), pos(end), s);
+            }
+            Self::InsertLinesAt(row, lines) => {
+                t.insert_lines_at(row as usize, lines);
+            }
+            Self::DeleteLines(start, end) => {
+                t.delete_lines(start as usize..end as usize);
+            }
+            Self::ApplyRemoteEdit(start, end, s) => {
+                t.apply_remote_edit(pos(start), pos(end), s);
+            }
+            Self::SetEditLog(b) => t.set_edit_log(b),
+            Self::DrainEdits => {
+                t.drain_edits();
+            }
+            Self::StartSelection => t.start_selection(),
+            Self::CancelSelection => t.cancel_selection(),
+            Self::ReselectLast => {
+                t.reselect_last();
+            }
+            Self::ExpandSelection => {
+                t.expand_selection();
+            }
+            Self::ShrinkSelection => {
+                t.shrink_selection();
+            }
+            Self::SplitSelectionOnNewlines => {
+                t.split_selection_on_newlines();
+            }
+            Self::KeepPrimarySelection => {
+                t.keep_primary_selection();
+            }
+            Self::ReplaceSelections(s) => {
+                t.replace_selections(s);
+            }
+            Self::RotateSelectionContents => {
+                t.rotate_selection_contents();
+            }
+            Self::AlignSelections => {
+                t.align_selections();
+            }
+            Self::SelectAll => t.select_all(),
+            Self::SetSelectionInclusive(b) => t.set_selection_inclusive(b),
+            Self::Copy => t.copy(),
+            Self::Cut => {
+                t.cut();
+            }
+            Self::Paste => {
+                t.paste();
+            }
+            Self::SetYankText(s) => t.set_yank_text(s),
+            Self::SurroundSelection(open, close) => {
+                t.surround_selection(open, close);
+            }
+            Self::UnsurroundSelection => {
+                t.unsurround_selection();
+            }
+            Self::Undo => {
+                t.undo();
+            }
+            Self::Redo => {
+                t.redo();
+            }
+            Self::UndoUntil(ms) => {
+                if let Some(time) = Instant::now().checked_sub(Duration::from_millis(ms as u64)) {
+                    t.undo_until(time);
+                }
+            }
+            Self::RedoUntil(ms) => {
+                if let Some(time) = Instant::now().checked_sub(Duration::from_millis(ms as u64)) {
+                    t.redo_until(time);
+                }
+            }
+            Self::SetMaxHistories(n) => t.set_max_histories(n as usize),
+            Self::SetHistoryMergeInterval(ms) => {
+                t.set_history_merge_interval(ms.map(|ms| Duration::from_millis(ms as u64)))
+            }
+            Self::SetMaxHistoryBytes(n) => t.set_max_history_bytes(n as usize),
+            Self::WithHistoryDisabled(op) => t.with_history_disabled(|t| op.apply(t)),
+            Self::MarkSaved => t.mark_saved(),
+            Self::JumpBack => {
+                t.jump_back();
+            }
+            Self::JumpForward => {
+                t.jump_forward();
+            }
+            Self::PushJump => t.push_jump(),
+            Self::CreateAnchor(start, end, growth) => {
+                let growth = match growth % 4 {
+                    0 => AnchorGrowth::Neither,
+                    1 => AnchorGrowth::Both,
+                    2 => AnchorGrowth::Start,
+                    _ => AnchorGrowth::End,
+                };
+                t.create_anchor(pos(start), pos(end), growth);
+            }
+            Self::RemoveAnchor(id) => {
+                t.remove_anchor(AnchorId::new(id as u64));
+            }
+            Self::ClearAnchors => t.clear_anchors(),
+            Self::SetLineMeta(row, meta) => {
+                t.set_line_meta(row as usize, meta);
+            }
+            Self::RemoveLineMeta(row) => {
+                t.remove_line_meta(row as usize);
+            }
+            Self::ConcealRange(start, end, s) => {
+                t.conceal_range(pos(start), pos(end), s);
+            }
+            Self::ClearConceals => t.clear_conceals(),
+            Self::ClearDamagedLines(start, end) => {
+                t.clear_damaged_lines(start as usize..end as usize)
+            }
+            Self::SetVirtualEdit(b) => t.set_virtual_edit(b),
+            Self::SetFocused(b) => t.set_focused(b),
+            Self::SetCursorBlink(ms) => {
+                t.set_cursor_blink(ms.map(|ms| Duration::from_millis(ms as u64)));
+            }
+            Self::Tick(ms) => {
+                t.tick(Instant::now() + Duration::from_millis(ms as u64));
+            }
+            Self::SetWordBoundary(b) => t.set_word_boundary_mode(match b % 3 {
+                0 => WordBoundary::Default,
+                1 => WordBoundary::SubWord,
+                _ => WordBoundary::WhitespaceOnly,
+            }),
+            Self::SetWordChars(Some(chars)) => t.set_word_chars(move |c| chars.contains(&c)),
+            Self::SetWordChars(None) => t.clear_word_chars(),
+            Self::SetHardTabIndent(b) => t.set_hard_tab_indent(b),
+            Self::SetTabLength(n) => t.set_tab_length(n),
+            Self::SetLineNumberWidth(w) => t.set_line_number_width(w),
+            Self::SetShowControlChars(c) => t.set_show_control_chars(match c % 3 {
+                0 => ControlCharStyle::Caret,
+                1 => ControlCharStyle::Replacement,
+                _ => ControlCharStyle::Raw,
+            }),
+            Self::SetWidthOverrides(overrides) => t.set_width_overrides(
+                overrides
+                    .into_iter()
+                    .map(|(s, e, w)| (s..=e, w as usize % 4)),
+            ),
+            Self::SetRowAlignment(row, a) => t.set_row_alignment(
+                row as usize,
+                match a % 3 {
+                    0 => Alignment::Left,
+                    1 => Alignment::Center,
+                    _ => Alignment::Right,
+                },
+            ),
+            Self::SetPlaceholder(text, cursor, alignment) => {
+                t.set_placeholder_text(text);
+                t.set_placeholder_cursor(match cursor % 3 {
+                    0 => PlaceholderCursor::Before,
+                    1 => PlaceholderCursor::Over,
+                    _ => PlaceholderCursor::Hidden,
+                });
+                t.set_placeholder_alignment(alignment.map(|a| match a % 3 {
+                    0 => Alignment::Left,
+                    1 => Alignment::Center,
+                    _ => Alignment::Right,
+                }));
+            }
+            Self::SetIndentPolicy(width, hard_tab, keep_alignment) => {
+                let mut policy = if hard_tab {
+                    IndentPolicy::hard_tab(width as usize)
+                } else {
+                    IndentPolicy::spaces(width as usize)
+                };
+                policy.keep_alignment = keep_alignment;
+                t.set_indent_policy(policy);
+            }
+            Self::SetPromptMode(b) => t.set_prompt_mode(b),
+            Self::PushPromptHistory(s) => t.push_prompt_history(s),
+            Self::PrevPromptHistory => {
+                t.prev_prompt_history();
+            }
+            Self::NextPromptHistory => {
+                t.next_prompt_history();
+            }
+            Self::SetCompletionItems(items) => t.set_completion_items(items),
+            Self::SelectNextCompletion => {
+                t.select_next_completion();
+            }
+            Self::AcceptCompletion => {
+                t.accept_completion();
+            }
+            Self::SetPreedit(preedit) => {
+                t.set_preedit(preedit.map(|(s, c)| (s, c as usize)));
+            }
+            Self::CommitPreedit => {
+                t.commit_preedit();
+            }
+            Self::CancelPreedit => t.cancel_preedit(),
+            #[cfg(feature = "search")]
+            Self::SetSearchPattern(s) => {
+                let _ = t.set_search_pattern(s);
+            }
+            #[cfg(feature = "search")]
+            Self::SetSearchMode(m) => {
+                let _ = t.set_search_mode(match m % 3 {
+                    0 => SearchMode::Regex,
+                    1 => SearchMode::Literal,
+                    _ => SearchMode::Fuzzy,
+                });
+            }
+            #[cfg(feature = "search")]
+            Self::SearchForward(b) => {
+                t.search_forward(b);
+            }
+            #[cfg(feature = "search")]
+            Self::SearchBack(b) => {
+                t.search_back(b);
+            }
+            #[cfg(feature = "search")]
+            Self::SetSearchDirection(backward) => t.set_search_direction(if backward {
+                SearchDirection::Backward
+            } else {
+                SearchDirection::Forward
+            }),
+            #[cfg(feature = "search")]
+            Self::SearchNext(next) => {
+                if next {
+                    t.search_next();
+                } else {
+                    t.search_prev();
+                }
+            }
+            #[cfg(feature = "search")]
+            Self::SelectAllMatches(s) => {
+                let _ = t.select_all_matches(s);
+            }
+            #[cfg(feature = "search")]
+            Self::ForEachMatch(actions) => {
+                let mut actions = actions.into_iter();
+                t.for_each_match(|_| match actions.next() {
+                    Some(Some(s)) => MatchAction::Replace(s),
+                    Some(None) => MatchAction::Skip,
+                    None => MatchAction::Stop,
+                });
+            }
+        }
+    }
+}
+
+impl TextArea<'_> {
+    /// Apply one operation generated from the random data to the textarea. Operations cover the public methods which
+    /// modify the text, the cursor, the selection, the history and other states such as [`TextArea::input`],
+    /// [`TextArea::replace_range`], [`TextArea::undo`], and [`TextArea::apply_remote_edit`]. This method is available
+    /// when `arbitrary` feature is enabled. An error is returned when the data is not enough to generate an
+    /// operation. See [`TextArea::apply_random_ops`].
+    #[cfg_attr(docsrs, doc(cfg(feature = "arbitrary")))]
+    pub fn apply_random_op(&mut self, u: &mut Unstructured<'_>) -> Result<()> {
+        Op::arbitrary(u)?.apply(self);
+        Ok(())
+    }
+
+    /// Apply operations generated from the random data to the textarea until the data is exhausted. This is useful
+    /// for fuzzing and property-based tests with [`TextArea::check_invariants`]. This method is available when
+    /// `arbitrary` feature is enabled.
+    /// ```
+    /// use arbitrary::Unstructured;
+    /// use tui_textarea::TextArea;
+    ///
+    /// let data = [0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde, 0xf0, 0x0f, 0xed, 0xcb, 0xa9];
+    /// let mut textarea = TextArea::from(["hello", "world"]);
+    /// textarea.apply_random_ops(&mut Unstructured::new(&data)).unwrap();
+    /// textarea.check_invariants().unwrap();
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "arbitrary")))]
+    pub fn apply_random_ops(&mut self, u: &mut Unstructured<'_>) -> Result<()> {
+        while !u.is_empty() {
+            self.apply_random_op(u)?;
+        }
+        Ok(())
+    }
+}
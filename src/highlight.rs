@@ -1,88 +1,166 @@
+use crate::control::ControlCharStyle;
 use crate::ratatui::style::Style;
 use crate::ratatui::text::Span;
 use crate::util::{num_digits, spaces};
+use crate::whitespace::WhitespaceStyle;
+use crate::width::{CharWidth, UnicodeWidth};
 #[cfg(feature = "ratatui")]
 use ratatui::text::Line;
 use std::borrow::Cow;
-use std::cmp::Ordering;
 use std::iter;
+use std::mem;
 #[cfg(feature = "tuirs")]
 use tui::text::Spans as Line;
-use unicode_width::UnicodeWidthChar as _;
 
-enum Boundary {
-    Cursor(Style),
-    Select(Style),
+// Layers of highlighted regions. When regions overlap, their styles are patched from the lowest layer to the highest
+// one. Regions on the same layer are patched in the order they were added.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Layer {
+    Diff,        // Lines marked by `TextArea::set_diff_marks`
+    Trailing,    // Trailing whitespaces
+    Control,     // Control characters rendered in their notations
+    Link,        // URLs detected in the line
+    Custom(u16), // Highlights added by `TextArea::add_highlight` with their priorities
+    Select,
     #[cfg(feature = "search")]
-    Search(Style),
-    End,
+    Search,
+    Cursor,
 }
 
-impl Boundary {
-    fn cmp(&self, other: &Boundary) -> Ordering {
-        fn rank(b: &Boundary) -> u8 {
-            match b {
-                Boundary::Cursor(_) => 3,
-                #[cfg(feature = "search")]
-                Boundary::Search(_) => 2,
-                Boundary::Select(_) => 1,
-                Boundary::End => 0,
-            }
-        }
-        rank(self).cmp(&rank(other))
-    }
+struct Region {
+    layer: Layer,
+    style: Style,
+    start: usize,
+    end: usize,
+}
 
-    fn style(&self) -> Option<Style> {
-        match self {
-            Boundary::Cursor(s) => Some(*s),
-            Boundary::Select(s) => Some(*s),
-            #[cfg(feature = "search")]
-            Boundary::Search(s) => Some(*s),
-            Boundary::End => None,
+// Highlight added by `TextArea::add_highlight`. Positions are (row, col) and `end` is exclusive.
+#[derive(Clone, Debug)]
+pub struct Highlight {
+    pub id: usize,
+    pub start: (usize, usize),
+    pub end: (usize, usize),
+    pub style: Style,
+    pub priority: u16,
+}
+
+// Push the mask characters replacing characters whose display width is `width` in total. The masked text keeps the
+// same display width as the original text. Cells where the mask character does not fit, such as a single cell for a
+// wide mask character, are filled with spaces.
+fn push_mask(buf: &mut String, width: usize, mask: char, measure: &dyn CharWidth) {
+    let mask_width = measure.width(mask).max(1);
+    buf.extend(iter::repeat(mask).take(width / mask_width));
+    buf.extend(iter::repeat(' ').take(width % mask_width));
+}
+
+// Replace all the characters of the text with the mask character when it is set
+fn mask_str<'s>(s: &'s str, mask: Option<char>, measure: &dyn CharWidth) -> Cow<'s, str> {
+    match mask {
+        Some(ch) => {
+            let width = s.chars().map(|c| measure.width(c).max(1)).sum();
+            let mut masked = String::with_capacity(s.len());
+            push_mask(&mut masked, width, ch, measure);
+            Cow::Owned(masked)
         }
+        None => Cow::Borrowed(s),
     }
 }
 
-struct DisplayTextBuilder {
-    tab_len: u8,
+// Measurement used when no measurement is specified
+static UNICODE_WIDTH: UnicodeWidth = UnicodeWidth { cjk: false };
+
+struct DisplayTextBuilder<'m> {
+    measure: &'m dyn CharWidth,
+    tab_len: usize,
     width: usize,
     mask: Option<char>,
+    offset: usize,
+    reveal: Option<(usize, usize)>,
+    whitespace: WhitespaceStyle,
+    trailing_start: usize, // Byte offset where trailing whitespaces start in the line
+    control: ControlCharStyle,
 }
 
-impl DisplayTextBuilder {
-    fn new(tab_len: u8, mask: Option<char>) -> Self {
+impl<'m> DisplayTextBuilder<'m> {
+    fn new(tab_len: usize, mask: Option<char>) -> Self {
         Self {
+            measure: &UNICODE_WIDTH,
             tab_len,
             width: 0,
             mask,
+            offset: 0,
+            reveal: None,
+            whitespace: WhitespaceStyle::Hidden,
+            trailing_start: 0,
+            control: ControlCharStyle::Raw,
+        }
+    }
+
+    fn is_visible_whitespace(&self, offset: usize) -> bool {
+        match self.whitespace {
+            WhitespaceStyle::Hidden => false,
+            WhitespaceStyle::All => true,
+            WhitespaceStyle::Trailing => offset >= self.trailing_start,
         }
     }
 
     fn build<'s>(&mut self, s: &'s str) -> Cow<'s, str> {
+        let offset = self.offset;
+        self.offset += s.len();
+
         if let Some(ch) = self.mask {
             // Note: We don't need to track width on masking text since width of tab character is fixed
-            let masked = iter::repeat(ch).take(s.chars().count()).collect();
+            // Characters between revealed ones are masked together so that a wide mask character can cover
+            // narrow characters
+            let mut masked = String::with_capacity(s.len());
+            let mut width = 0;
+            for (i, c) in s.char_indices() {
+                let i = offset + i;
+                match self.reveal {
+                    Some((start, end)) if start <= i && i < end && c != '\t' => {
+                        push_mask(&mut masked, width, ch, self.measure);
+                        width = 0;
+                        masked.push(c);
+                    }
+                    _ => width += self.measure.width(c).max(1),
+                }
+            }
+            push_mask(&mut masked, width, ch, self.measure);
             return Cow::Owned(masked);
         }
 
         let tab = spaces(self.tab_len);
         let mut buf = String::new();
         for (i, c) in s.char_indices() {
-            if c == '\t' {
+            let visible = (c == '\t' || c == ' ') && self.is_visible_whitespace(offset + i);
+            let replaced = self.control.replaces(c);
+            if c == '\t' || visible || replaced {
                 if buf.is_empty() {
                     buf.reserve(s.len());
                     buf.push_str(&s[..i]);
                 }
-                if self.tab_len > 0 {
-                    let len = self.tab_len as usize - (self.width % self.tab_len as usize);
-                    buf.push_str(&tab[..len]);
+                if replaced {
+                    let start = buf.len();
+                    self.control.push_notation(c, &mut buf);
+                    self.width += self.measure.str_width(&buf[start..]);
+                } else if c == ' ' {
+                    buf.push('·');
+                    self.width += 1;
+                } else if self.tab_len > 0 {
+                    let len = self.tab_len - self.width % self.tab_len;
+                    if visible {
+                        buf.push('→');
+                        buf.push_str(&tab[..len - 1]);
+                    } else {
+                        buf.push_str(&tab[..len]);
+                    }
                     self.width += len;
                 }
             } else {
                 if !buf.is_empty() {
                     buf.push(c);
                 }
-                self.width += c.width().unwrap_or(0);
+                self.width += self.measure.width(c);
             }
         }
 
@@ -94,65 +172,349 @@ impl DisplayTextBuilder {
     }
 }
 
+pub fn trailing_whitespace_start(line: &str) -> usize {
+    line.trim_end_matches(&[' ', '\t'][..]).len()
+}
+
+// Width of the text on screen. Tabs are expanded, control characters are replaced, and masking is applied as rendered
+// by `LineHighlighter`.
+pub fn display_width(
+    s: &str,
+    tab_len: usize,
+    mask: Option<char>,
+    control: ControlCharStyle,
+    measure: &dyn CharWidth,
+) -> usize {
+    let mut builder = DisplayTextBuilder::new(tab_len, mask);
+    builder.measure = measure;
+    builder.control = control;
+    measure.str_width(&builder.build(s))
+}
+
+// Set of the ranges covering the current offset while sweeping a line from left to right. This finds the ranges
+// covering each segment without scanning all the ranges for every segment. Ranges are identified by their indices
+// and the indices of the covering ranges are sorted in ascending order
+#[derive(Default)]
+struct Sweep {
+    starts: Vec<(usize, usize)>, // Pairs of the start offset and the index sorted by the offsets
+    ends: Vec<(usize, usize)>,   // Pairs of the end offset and the index sorted by the offsets
+    next_start: usize,
+    next_end: usize,
+    covering: Vec<usize>,
+}
+
+impl Sweep {
+    // Start sweeping the ranges from the head of line
+    fn reset(&mut self, ranges: impl Iterator<Item = (usize, usize)>) {
+        self.starts.clear();
+        self.ends.clear();
+        for (i, (start, end)) in ranges.enumerate() {
+            self.starts.push((start, i));
+            self.ends.push((end, i));
+        }
+        self.starts.sort_unstable();
+        self.ends.sort_unstable();
+        self.next_start = 0;
+        self.next_end = 0;
+        self.covering.clear();
+    }
+
+    // Move to the offset and return the ranges covering the character at the offset. Offsets must not decrease
+    fn advance(&mut self, offset: usize) -> &[usize] {
+        while let Some(&(start, i)) = self.starts.get(self.next_start) {
+            if start > offset {
+                break;
+            }
+            if let Err(idx) = self.covering.binary_search(&i) {
+                self.covering.insert(idx, i);
+            }
+            self.next_start += 1;
+        }
+        while let Some(&(end, i)) = self.ends.get(self.next_end) {
+            if end > offset {
+                break;
+            }
+            if let Ok(idx) = self.covering.binary_search(&i) {
+                self.covering.remove(idx);
+            }
+            self.next_end += 1;
+        }
+        &self.covering
+    }
+}
+
+// Buffers shared by all lines rendered in one frame. They are passed from one line to the next so that rendering lines
+// doesn't allocate them again
+#[derive(Default)]
+pub struct Scratch {
+    regions: Vec<Region>,
+    points: Vec<usize>,
+    sweep: Sweep,
+}
+
 pub struct LineHighlighter<'a> {
     line: &'a str,
     spans: Vec<Span<'a>>,
-    boundaries: Vec<(Boundary, usize)>, // TODO: Consider smallvec
+    regions: Vec<Region>,
+    points: Vec<usize>, // Byte offsets where the line is split into segments
+    sweep: Sweep,       // Regions covering each segment
+    inserts: Vec<(usize, Vec<Span<'a>>)>, // Virtual texts which are not a part of the line
+    conceals: Vec<(usize, usize, &'a str)>, // Ranges of the line rendered as the replacements
+    measure: &'a dyn CharWidth,
     style_begin: Style,
     cursor_at_end: bool,
+    cursor_padding: usize, // Columns between the end of line and the cursor in virtual edit mode
     cursor_style: Style,
-    tab_len: u8,
+    tab_len: usize,
     mask: Option<char>,
+    mask_reveal: Option<(usize, usize)>,
     select_at_end: bool,
     select_style: Style,
+    whitespace: WhitespaceStyle,
+    control: ControlCharStyle,
+    merge_styles: bool,
+    fill: Option<(usize, Style)>,
+    cursor_hidden: bool,
+    annotation: Option<Span<'a>>,
 }
 
 impl<'a> LineHighlighter<'a> {
     pub fn new(
         line: &'a str,
         cursor_style: Style,
-        tab_len: u8,
+        tab_len: usize,
         mask: Option<char>,
         select_style: Style,
     ) -> Self {
         Self {
             line,
             spans: vec![],
-            boundaries: vec![],
+            regions: vec![],
+            points: vec![],
+            sweep: Sweep::default(),
+            inserts: vec![],
+            conceals: vec![],
+            measure: &UNICODE_WIDTH,
             style_begin: Style::default(),
             cursor_at_end: false,
+            cursor_padding: 0,
             cursor_style,
             tab_len,
             mask,
+            mask_reveal: None,
             select_at_end: false,
             select_style,
+            whitespace: WhitespaceStyle::Hidden,
+            control: ControlCharStyle::Raw,
+            merge_styles: true,
+            fill: None,
+            cursor_hidden: false,
+            annotation: None,
         }
     }
 
+    // Use the buffers of the scratch. Call this before adding any region and give the buffers back with
+    // `into_spans_reusing`
+    pub fn reuse(&mut self, scratch: &mut Scratch) {
+        self.regions = mem::take(&mut scratch.regions);
+        self.points = mem::take(&mut scratch.points);
+        self.sweep = mem::take(&mut scratch.sweep);
+    }
+
+    // When `false`, the style of the topmost region replaces the styles under it instead of being patched on them.
+    pub fn merge_styles(&mut self, enabled: bool) {
+        self.merge_styles = enabled;
+    }
+
+    // Virtual text rendered after the end of line
+    pub fn annotation(&mut self, span: Span<'a>) {
+        self.annotation = Some(span);
+    }
+
+    // Spaces rendered before the text to align the line. Call this after the gutter
+    pub fn padding(&mut self, width: usize) {
+        self.spans.push(Span::raw(spaces(width)));
+    }
+
+    // Pad the line with spaces in the style until its width reaches `width`
+    pub fn fill(&mut self, width: usize, style: Style) {
+        self.fill = Some((width, style));
+    }
+
+    pub fn char_width(&mut self, measure: &'a dyn CharWidth) {
+        self.measure = measure;
+    }
+
+    pub fn show_whitespace(&mut self, whitespace: WhitespaceStyle) {
+        self.whitespace = whitespace;
+    }
+
+    // Render control characters in the notation with the style
+    pub fn control_chars(&mut self, control: ControlCharStyle, style: Style) {
+        self.control = control;
+        if control == ControlCharStyle::Raw {
+            return;
+        }
+        for (i, c) in self.line.char_indices() {
+            if control.replaces(c) {
+                self.region(Layer::Control, style, i, i + c.len_utf8());
+            }
+        }
+    }
+
+    fn region(&mut self, layer: Layer, style: Style, start: usize, end: usize) {
+        if start < end {
+            self.regions.push(Region {
+                layer,
+                style,
+                start,
+                end,
+            });
+        }
+    }
+
+    pub fn trailing_whitespace(&mut self, style: Style) {
+        let start = trailing_whitespace_start(self.line);
+        self.region(Layer::Trailing, style, start, self.line.len());
+    }
+
+    pub fn link(&mut self, start: usize, end: usize, style: Style) {
+        self.region(Layer::Link, style, start, end);
+    }
+
+    pub fn custom(&mut self, start: usize, end: usize, style: Style, priority: u16) {
+        let end = end.min(self.line.len());
+        self.region(Layer::Custom(priority), style, start, end);
+    }
+
+    // Render the range of the line as the replacement. The styles of the range are applied to the replacement
+    pub fn conceal(&mut self, start: usize, end: usize, replacement: &'a str) {
+        let end = end.min(self.line.len());
+        if start < end {
+            self.conceals.push((start, end, replacement));
+        }
+    }
+
+    pub fn mask_reveal(&mut self, start: usize, end: usize) {
+        if start < end {
+            self.mask_reveal = Some((start, end));
+        }
+    }
+
+    // Line number is right-aligned in the width. When it is wider than the width, only the last digits are rendered
     pub fn line_number(&mut self, row: usize, lnum_len: u8, style: Style) {
-        let pad = spaces(lnum_len - num_digits(row + 1) + 1);
+        let digits = num_digits(row + 1);
+        if digits > lnum_len {
+            let lnum = (row + 1).to_string();
+            let lnum = &lnum[(digits - lnum_len) as usize..];
+            self.spans.push(Span::styled(format!(" {} ", lnum), style));
+            return;
+        }
+        let pad = spaces((lnum_len - digits + 1) as usize);
         self.spans
             .push(Span::styled(format!("{}{} ", pad, row + 1), style));
     }
 
+    // Sign of the diff mark in the gutter. The whole line is highlighted with the style. `None` renders a blank
+    // gutter for lines without marks
+    pub fn diff_mark(&mut self, mark: Option<(char, Style)>) {
+        match mark {
+            Some((sign, style)) => {
+                self.spans.push(Span::styled(format!("{} ", sign), style));
+                self.region(Layer::Diff, style, 0, self.line.len());
+            }
+            None => self.spans.push(Span::raw("  ")),
+        }
+    }
+
+    // Only the cursor line style is applied by `cursor_line` and `preedit` after calling this method
+    pub fn hide_cursor(&mut self) {
+        self.cursor_hidden = true;
+    }
+
     pub fn cursor_line(&mut self, cursor_col: usize, style: Style) {
+        self.style_begin = style;
+        if self.cursor_hidden {
+            return;
+        }
         if let Some((start, c)) = self.line.char_indices().nth(cursor_col) {
-            self.boundaries
-                .push((Boundary::Cursor(self.cursor_style), start));
-            self.boundaries.push((Boundary::End, start + c.len_utf8()));
+            let style = self.cursor_style;
+            self.region(Layer::Cursor, style, start, start + c.len_utf8());
         } else {
             self.cursor_at_end = true;
         }
-        self.style_begin = style;
     }
 
+    // Render the cursor at `padding` columns after the end of line. This must be called after `cursor_line`
+    pub fn cursor_padding(&mut self, padding: usize) {
+        self.cursor_padding = padding;
+    }
+
+    pub fn preedit(
+        &mut self,
+        cursor_col: usize,
+        text: &'a str,
+        cursor: usize,
+        style: Style,
+        line_style: Style,
+    ) {
+        let offset = self
+            .line
+            .char_indices()
+            .nth(cursor_col)
+            .map(|(i, _)| i)
+            .unwrap_or(self.line.len());
+
+        // The composing text is masked as well as the line so that it is not shown in clear in a password field
+        let (mask, measure) = (self.mask, self.measure);
+        let content = |s: &'a str| mask_str(s, mask, measure);
+
+        let mut spans = vec![];
+        let cursor = text
+            .char_indices()
+            .nth(cursor)
+            .filter(|_| !self.cursor_hidden);
+        if let Some((start, c)) = cursor {
+            // The cursor is rendered in the composing text instead of the line
+            let end = start + c.len_utf8();
+            if start > 0 {
+                spans.push(Span::styled(content(&text[..start]), style));
+            }
+            spans.push(Span::styled(
+                content(&text[start..end]),
+                style.patch(self.cursor_style),
+            ));
+            if end < text.len() {
+                spans.push(Span::styled(content(&text[end..]), style));
+            }
+            self.style_begin = line_style;
+        } else {
+            if !text.is_empty() {
+                spans.push(Span::styled(content(text), style));
+            }
+            self.cursor_line(cursor_col, line_style);
+        }
+
+        if !spans.is_empty() {
+            self.inserts.push((offset, spans));
+        }
+    }
+
+    // `current` is the match the cursor last jumped to with its style. It is highlighted only when it is still one of
+    // the matches
     #[cfg(feature = "search")]
-    pub fn search(&mut self, matches: impl Iterator<Item = (usize, usize)>, style: Style) {
+    pub fn search(
+        &mut self,
+        matches: impl Iterator<Item = (usize, usize)>,
+        style: Style,
+        current: Option<(usize, usize, Style)>,
+    ) {
         for (start, end) in matches {
-            if start != end {
-                self.boundaries.push((Boundary::Search(style), start));
-                self.boundaries.push((Boundary::End, end));
-            }
+            let style = match current {
+                Some((s, e, current_style)) if (s, e) == (start, end) => current_style,
+                _ => style,
+            };
+            self.region(Layer::Search, style, start, end);
         }
     }
 
@@ -179,75 +541,147 @@ impl<'a> LineHighlighter<'a> {
         } else {
             return;
         };
-        if start != end {
-            self.boundaries
-                .push((Boundary::Select(self.select_style), start));
-            self.boundaries.push((Boundary::End, end));
-        }
+        let style = self.select_style;
+        self.region(Layer::Select, style, start, end);
     }
 
+    #[cfg(all(test, feature = "ratatui"))]
     pub fn into_spans(self) -> Line<'a> {
+        self.build().0
+    }
+
+    // Build the spans and give the buffers back to the scratch for the next line
+    pub fn into_spans_reusing(self, scratch: &mut Scratch) -> Line<'a> {
+        let (line, buffers) = self.build();
+        *scratch = buffers;
+        line
+    }
+
+    fn build(self) -> (Line<'a>, Scratch) {
         let Self {
             line,
             mut spans,
-            mut boundaries,
+            mut regions,
+            mut points,
+            mut sweep,
+            mut inserts,
+            conceals,
+            measure,
             tab_len,
             style_begin,
             cursor_style,
             cursor_at_end,
+            cursor_padding,
             mask,
+            mask_reveal,
             select_at_end,
             select_style,
+            whitespace,
+            control,
+            merge_styles,
+            fill,
+            cursor_hidden: _,
+            annotation,
         } = self;
         let mut builder = DisplayTextBuilder::new(tab_len, mask);
+        builder.measure = measure;
+        builder.reveal = mask_reveal;
+        builder.whitespace = whitespace;
+        builder.trailing_start = trailing_whitespace_start(line);
+        builder.control = control;
 
-        if boundaries.is_empty() {
-            let built = builder.build(line);
-            if !built.is_empty() {
-                spans.push(Span::styled(built, style_begin));
-            }
-            if cursor_at_end {
-                spans.push(Span::styled(" ", cursor_style));
-            } else if select_at_end {
-                spans.push(Span::styled(" ", select_style));
-            }
-            return Line::from(spans);
-        }
-
-        boundaries.sort_unstable_by(|(l, i), (r, j)| match i.cmp(j) {
-            Ordering::Equal => l.cmp(r),
-            o => o,
-        });
-
-        let mut style = style_begin;
-        let mut start = 0;
-        let mut stack = vec![];
+        // Split the line at every edge of the regions and the inserted texts
+        points.clear();
+        points.extend([0, line.len()]);
+        points.extend(regions.iter().flat_map(|r| [r.start, r.end]));
+        points.extend(inserts.iter().map(|(i, _)| *i));
+        points.extend(conceals.iter().flat_map(|&(s, e, _)| [s, e]));
+        points.sort_unstable();
+        points.dedup();
+        // Stable sorts keep the order of regions on the same layer and texts inserted at the same offset
+        regions.sort_by_key(|r| r.layer);
+        inserts.sort_by_key(|(i, _)| *i);
+        let mut inserts = inserts.into_iter().peekable();
+        sweep.reset(regions.iter().map(|r| (r.start, r.end)));
+        // Conceals are rare. A new sweep does not allocate when there is no conceal
+        let mut concealing = Sweep::default();
+        concealing.reset(conceals.iter().map(|&(s, e, _)| (s, e)));
 
-        for (next_boundary, end) in boundaries {
-            if start < end {
-                spans.push(Span::styled(builder.build(&line[start..end]), style));
+        for (i, &start) in points.iter().enumerate() {
+            while let Some((_, inserted)) = inserts.next_if(|(o, _)| *o == start) {
+                for span in inserted {
+                    builder.width += measure.str_width(&span.content);
+                    spans.push(span);
+                }
             }
 
-            style = if let Some(s) = next_boundary.style() {
-                stack.push(style);
-                s
+            let end = match points.get(i + 1) {
+                Some(&end) => end,
+                None => break,
+            };
+            // Segments never cross the edges of regions. Merge styles of all the regions covering this segment
+            let mut covering = sweep.advance(start).iter().map(|&i| &regions[i]);
+            let style = if merge_styles {
+                covering.fold(style_begin, |s, r| s.patch(r.style))
             } else {
-                stack.pop().unwrap_or(style_begin)
+                covering.next_back().map_or(style_begin, |r| r.style)
             };
-            start = end;
-        }
-
-        if start != line.len() {
-            spans.push(Span::styled(builder.build(&line[start..]), style));
+            match concealing.advance(start).first().map(|&i| conceals[i]) {
+                Some((s, _, replacement)) => {
+                    // Only the first segment of the concealed range is replaced and the rest is hidden. The
+                    // replacement is masked as well as the text not to be shown in clear in a password field
+                    builder.offset += end - start;
+                    if s == start && !replacement.is_empty() {
+                        let replacement = mask_str(replacement, mask, measure);
+                        builder.width += measure.str_width(&replacement);
+                        spans.push(Span::styled(replacement, style));
+                    }
+                }
+                None => spans.push(Span::styled(builder.build(&line[start..end]), style)),
+            }
         }
 
+        let (cursor_style, select_style) = if merge_styles {
+            (
+                style_begin.patch(cursor_style),
+                style_begin.patch(select_style),
+            )
+        } else {
+            (cursor_style, select_style)
+        };
         if cursor_at_end {
+            if cursor_padding > 0 {
+                spans.push(Span::styled(" ".repeat(cursor_padding), style_begin));
+            }
             spans.push(Span::styled(" ", cursor_style));
         } else if select_at_end {
             spans.push(Span::styled(" ", select_style));
         }
 
-        Line::from(spans)
+        if let Some(span) = annotation {
+            spans.push(span);
+        }
+
+        if let Some((width, style)) = fill {
+            let len = spans
+                .iter()
+                .map(|s| measure.str_width(&s.content))
+                .sum::<usize>();
+            if len < width {
+                spans.push(Span::styled(" ".repeat(width - len), style));
+            }
+        }
+
+        regions.clear();
+        points.clear();
+        (
+            Line::from(spans),
+            Scratch {
+                regions,
+                points,
+                sweep,
+            },
+        )
     }
 }
 
@@ -255,16 +689,16 @@ impl<'a> LineHighlighter<'a> {
 #[cfg(all(test, feature = "ratatui"))]
 mod tests {
     use super::*;
-    use crate::ratatui::style::Color;
+    use crate::ratatui::style::{Color, Modifier};
     use std::fmt::Debug;
     use unicode_width::UnicodeWidthStr as _;
 
-    fn build(text: &'static str, tab: u8, mask: Option<char>) -> Cow<'static, str> {
+    fn build(text: &'static str, tab: usize, mask: Option<char>) -> Cow<'static, str> {
         DisplayTextBuilder::new(tab, mask).build(text)
     }
 
     #[track_caller]
-    fn build_with_offset(offset: usize, text: &'static str, tab: u8) -> Cow<'static, str> {
+    fn build_with_offset(offset: usize, text: &'static str, tab: usize) -> Cow<'static, str> {
         let mut b = DisplayTextBuilder::new(tab, None);
         b.width = offset;
         let built = b.build(text);
@@ -325,7 +759,27 @@ mod tests {
         assert_eq!(&build(  "あ\t",  0,      None),                "あ");
         assert_eq!(&build(  "あ\t",  4,      None),              "あ  ");
         assert_eq!(&build(  "🐶\t",  4,      None),              "🐶  ");
-        assert_eq!(&build(  "あ\t",  4, Some('x')),                "xx");
+        assert_eq!(&build(  "あ\t",  4, Some('x')),               "xxx");
+        assert_eq!(&build(    "あい",  4, Some('x')),              "xxxx");
+        assert_eq!(&build(   "a🐶b",  4, Some('x')),              "xxxx");
+        assert_eq!(&build(    "aあ",  4, Some('＊')),              "＊ ");
+        assert_eq!(&build(  "あい",  4, Some('＊')),               "＊＊");
+
+        // Visible whitespaces
+        let build_ws = |text, tab, ws| {
+            let mut b = DisplayTextBuilder::new(tab, None);
+            b.whitespace = ws;
+            b.trailing_start = trailing_whitespace_start(text);
+            b.build(text).into_owned()
+        };
+        assert_eq!(&build_ws(     "a b",  4, WhitespaceStyle::All),             "a·b");
+        assert_eq!(&build_ws(   "a\tb",  4, WhitespaceStyle::All),           "a→  b");
+        assert_eq!(&build_ws(  "\t\ta",  2, WhitespaceStyle::All),           "→ → a");
+        assert_eq!(&build_ws(    "a\t",  0, WhitespaceStyle::All),               "a");
+        assert_eq!(&build_ws(   "あ\t",  4, WhitespaceStyle::All),            "あ→ ");
+        assert_eq!(&build_ws(" a b \t", 4, WhitespaceStyle::Trailing),    " a b·→  ");
+        assert_eq!(&build_ws(   "a b",  4, WhitespaceStyle::Trailing),          "a b");
+        assert_eq!(&build_ws(  "a b ",  4, WhitespaceStyle::Hidden),           "a b ");
 
         // When the start position of the text is not start of the line (#43)
         assert_eq!(&build_with_offset(1,         "", 0),           "");
@@ -382,6 +836,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn into_spans_reusing_scratch() {
+        let mut scratch = Scratch::default();
+        let mut lh = LineHighlighter::new("abcd", CUR, 4, None, SEL);
+        lh.reuse(&mut scratch);
+        lh.selection(0, 0, 1, 0, 3);
+        let spans = lh.into_spans_reusing(&mut scratch).spans;
+        assert_eq!(
+            spans,
+            [
+                Span::styled("a", DEFAULT),
+                Span::styled("bc", SEL),
+                Span::styled("d", DEFAULT)
+            ]
+        );
+        assert!(scratch.regions.capacity() > 0);
+
+        // Regions of the previous line are not applied to the next line
+        let mut lh = LineHighlighter::new("efgh", CUR, 4, None, SEL);
+        lh.reuse(&mut scratch);
+        let spans = lh.into_spans_reusing(&mut scratch).spans;
+        assert_eq!(spans, [Span::styled("efgh", DEFAULT)]);
+    }
+
     #[test]
     fn into_spans_cursor_line() {
         let tests = [
@@ -402,12 +880,269 @@ mod tests {
         }
     }
 
+    #[test]
+    fn into_spans_cursor_padding() {
+        let tests = [
+            ("", 0, 2, &[("  ", LINE), (" ", CUR)][..]),
+            ("ab", 2, 1, &[("ab", LINE), (" ", LINE), (" ", CUR)][..]),
+            ("ab", 1, 0, &[("a", LINE), ("b", CUR)][..]),
+        ];
+
+        for test in tests {
+            let (line, col, padding, want) = test;
+            let mut lh = LineHighlighter::new(line, CUR, 4, None, SEL);
+            lh.cursor_line(col, LINE);
+            lh.cursor_padding(padding);
+            assert_spans(lh, want, test);
+        }
+    }
+
+    #[test]
+    fn into_spans_masked_wide_chars() {
+        let tests = [
+            ("あいう", 0, &[("xx", CUR), ("xxxx", LINE)][..]),
+            ("あいう", 1, &[("xx", LINE), ("xx", CUR), ("xx", LINE)][..]),
+            ("aいう", 3, &[("xxxxx", LINE), (" ", CUR)][..]),
+        ];
+
+        for test in tests {
+            let (line, col, want) = test;
+            let mut lh = LineHighlighter::new(line, CUR, 4, Some('x'), SEL);
+            lh.cursor_line(col, LINE);
+            assert_spans(lh, want, test);
+        }
+    }
+
+    #[test]
+    fn into_spans_mask_reveal() {
+        let tests = [
+            ("abcd", (3, 4), None, &[("xxxd", DEFAULT)][..]),
+            ("abcd", (0, 4), None, &[("abcd", DEFAULT)][..]),
+            ("abcd", (2, 2), None, &[("xxxx", DEFAULT)][..]),
+            ("aあいう", (4, 10), None, &[("xxxいう", DEFAULT)][..]),
+            ("a\tb", (0, 3), None, &[("axb", DEFAULT)][..]),
+            ("abcd", (2, 4), Some(3), &[("xxc", LINE), ("d", CUR)][..]),
+            (
+                "abcd",
+                (2, 4),
+                Some(1),
+                &[("x", LINE), ("x", CUR), ("cd", LINE)][..],
+            ),
+            ("abcd", (3, 4), Some(4), &[("xxxd", LINE), (" ", CUR)][..]),
+        ];
+
+        for test in tests {
+            let (line, (start, end), cursor, want) = test;
+            let mut lh = LineHighlighter::new(line, CUR, 4, Some('x'), SEL);
+            lh.mask_reveal(start, end);
+            if let Some(col) = cursor {
+                lh.cursor_line(col, LINE);
+            }
+            assert_spans(lh, want, test);
+        }
+    }
+
+    #[test]
+    fn into_spans_preedit() {
+        const PRE: Style = Style::new().bg(Color::Cyan);
+        const PRE_CUR: Style = Style::new().bg(Color::Red);
+        let tests = [
+            // (line, cursor col, preedit text, preedit cursor, want)
+            ("", 0, "あい", 2, &[("あい", PRE), (" ", CUR)][..]),
+            ("", 0, "あい", 0, &[("あ", PRE_CUR), ("い", PRE)][..]),
+            ("", 0, "あい", 1, &[("あ", PRE), ("い", PRE_CUR)][..]),
+            ("ab", 1, "x", 1, &[("a", LINE), ("x", PRE), ("b", CUR)][..]),
+            (
+                "abc",
+                1,
+                "xyz",
+                1,
+                &[
+                    ("a", LINE),
+                    ("x", PRE),
+                    ("y", PRE_CUR),
+                    ("z", PRE),
+                    ("bc", LINE),
+                ][..],
+            ),
+            ("ab", 2, "x", 1, &[("ab", LINE), ("x", PRE), (" ", CUR)][..]),
+            ("ab", 1, "", 0, &[("a", LINE), ("b", CUR)][..]),
+            (
+                "a\tb",
+                1,
+                "xy",
+                2,
+                &[("a", LINE), ("xy", PRE), (" ", CUR), ("b", LINE)][..],
+            ),
+        ];
+
+        for test in tests {
+            let (line, col, text, cursor, want) = test;
+            let mut lh = LineHighlighter::new(line, CUR, 4, None, SEL);
+            lh.preedit(col, text, cursor, PRE, LINE);
+            assert_spans(lh, want, test);
+        }
+    }
+
+    #[test]
+    fn into_spans_unordered_regions() {
+        const A: Style = Style::new().fg(Color::Red);
+        const B: Style = Style::new().fg(Color::Blue).bg(Color::Cyan);
+        const C: Style = Style::new().fg(Color::Green);
+        for (merge, want) in [
+            (
+                true,
+                &[
+                    ("a", A),
+                    ("b", A.patch(C)),
+                    ("c", A.patch(B).patch(C)),
+                    ("d", A.patch(B)),
+                    ("e", B),
+                    ("xyz", B),
+                    ("h", DEFAULT),
+                ][..],
+            ),
+            (
+                false,
+                &[
+                    ("a", A),
+                    ("b", C),
+                    ("c", C),
+                    ("d", B),
+                    ("e", B),
+                    ("xyz", B),
+                    ("h", DEFAULT),
+                ][..],
+            ),
+        ] {
+            // Regions are added in a different order from their positions
+            let mut lh = LineHighlighter::new("abcdefgh", CUR, 4, None, SEL);
+            lh.merge_styles(merge);
+            lh.custom(0, 4, A, 0);
+            lh.custom(2, 6, B, 0);
+            lh.custom(1, 3, C, 0);
+            lh.conceal(5, 7, "xyz");
+            assert_spans(lh, want, merge);
+        }
+    }
+
+    #[test]
+    fn into_spans_preedit_masked() {
+        const PRE: Style = Style::new().bg(Color::Cyan);
+        const PRE_CUR: Style = Style::new().bg(Color::Red);
+        let tests = [
+            // (line, cursor col, preedit text, preedit cursor, want)
+            (
+                "ab",
+                1,
+                "xy",
+                2,
+                &[("*", LINE), ("**", PRE), ("*", CUR)][..],
+            ),
+            ("", 0, "あい", 1, &[("**", PRE), ("**", PRE_CUR)][..]),
+        ];
+
+        for test in tests {
+            let (line, col, text, cursor, want) = test;
+            let mut lh = LineHighlighter::new(line, CUR, 4, Some('*'), SEL);
+            lh.preedit(col, text, cursor, PRE, LINE);
+            assert_spans(lh, want, test);
+        }
+    }
+
+    #[test]
+    fn into_spans_trailing_whitespace() {
+        const TRAIL: Style = Style::new().bg(Color::Magenta);
+        let tests = [
+            (
+                "abc",
+                WhitespaceStyle::Hidden,
+                None,
+                &[("abc", DEFAULT)][..],
+            ),
+            (
+                "abc  ",
+                WhitespaceStyle::Hidden,
+                None,
+                &[("abc", DEFAULT), ("  ", TRAIL)][..],
+            ),
+            ("   ", WhitespaceStyle::Hidden, None, &[("   ", TRAIL)][..]),
+            (
+                "a b ",
+                WhitespaceStyle::Trailing,
+                None,
+                &[("a b", DEFAULT), ("·", TRAIL)][..],
+            ),
+            (
+                "ab \t",
+                WhitespaceStyle::All,
+                Some(3),
+                &[("ab", DEFAULT), ("·", TRAIL), ("→", CUR)][..],
+            ),
+        ];
+        for (line, ws, cursor, want) in tests {
+            let mut lh = LineHighlighter::new(line, CUR, 4, None, SEL);
+            lh.show_whitespace(ws);
+            lh.trailing_whitespace(TRAIL);
+            if let Some(col) = cursor {
+                lh.cursor_line(col, DEFAULT);
+            }
+            assert_spans(lh, want, line);
+        }
+    }
+
+    #[test]
+    fn into_spans_control_chars() {
+        const CTRL: Style = Style::new().fg(Color::Blue);
+        let tests = [
+            (
+                "a\x1b[0m",
+                ControlCharStyle::Caret,
+                None,
+                &[("a", DEFAULT), ("^[", CTRL), ("[0m", DEFAULT)][..],
+            ),
+            (
+                "\0\tb",
+                ControlCharStyle::Caret,
+                Some(0),
+                &[("^@", CTRL.patch(CUR)), ("  b", DEFAULT)][..],
+            ),
+            (
+                "\x07\u{85}",
+                ControlCharStyle::Replacement,
+                None,
+                &[("\u{FFFD}", CTRL), ("\u{FFFD}", CTRL)][..],
+            ),
+            (
+                "a\x1b",
+                ControlCharStyle::Raw,
+                None,
+                &[("a\x1b", DEFAULT)][..],
+            ),
+        ];
+        for (line, control, cursor, want) in tests {
+            let mut lh = LineHighlighter::new(line, CUR, 4, None, SEL);
+            lh.control_chars(control, CTRL);
+            if let Some(col) = cursor {
+                lh.cursor_line(col, DEFAULT);
+            }
+            assert_spans(lh, want, line);
+        }
+
+        assert_eq!(
+            display_width("\x1b\x7f", 4, None, ControlCharStyle::Caret, &UNICODE_WIDTH),
+            4,
+        );
+    }
+
     #[test]
     fn into_spans_line_number() {
         let tests = [
             (0, 1, &[(" 1 ", LNUM)][..]),
             (123, 3, &[(" 124 ", LNUM)][..]),
             (123, 5, &[("   124 ", LNUM)][..]),
+            (123, 2, &[(" 24 ", LNUM)][..]),
+            (104, 2, &[(" 05 ", LNUM)][..]),
         ];
         for test in tests {
             let (row, len, want) = test;
@@ -477,7 +1212,31 @@ mod tests {
         for test in tests {
             let (line, matches, want) = test;
             let mut lh = LineHighlighter::new(line, CUR, 4, None, SEL);
-            lh.search(matches.iter().copied(), SEARCH);
+            lh.search(matches.iter().copied(), SEARCH, None);
+            assert_spans(lh, want, test);
+        }
+    }
+
+    #[cfg(feature = "search")]
+    #[test]
+    fn into_spans_current_search() {
+        const CURRENT: Style = Style::new().bg(Color::Yellow);
+        let tests = [
+            (
+                Some((2, 3, CURRENT)),
+                &[("ab", SEARCH), ("c", CURRENT), ("de", SEARCH)][..],
+            ),
+            // Stale range which is no longer a match
+            (
+                Some((2, 4, CURRENT)),
+                &[("ab", SEARCH), ("c", SEARCH), ("de", SEARCH)][..],
+            ),
+            (None, &[("ab", SEARCH), ("c", SEARCH), ("de", SEARCH)][..]),
+        ];
+        for test in tests {
+            let (current, want) = test;
+            let mut lh = LineHighlighter::new("abcde", CUR, 4, None, SEL);
+            lh.search([(0, 2), (2, 3), (3, 5)].into_iter(), SEARCH, current);
             assert_spans(lh, want, test);
         }
     }
@@ -522,6 +1281,335 @@ mod tests {
         }
     }
 
+    #[test]
+    fn into_spans_custom_highlights() {
+        const HL1: Style = Style::new().bg(Color::Magenta);
+        const HL2: Style = Style::new().bg(Color::Cyan);
+
+        let tests = [
+            (
+                "partially overlapping",
+                {
+                    let mut lh = LineHighlighter::new("abcdef", CUR, 4, None, SEL);
+                    lh.custom(1, 4, HL1, 0);
+                    lh.custom(2, 5, HL2, 0);
+                    lh
+                },
+                &[
+                    ("a", DEFAULT),
+                    ("b", HL1),
+                    ("cd", HL2),
+                    ("e", HL2),
+                    ("f", DEFAULT),
+                ][..],
+            ),
+            (
+                "priority",
+                {
+                    let mut lh = LineHighlighter::new("abcdef", CUR, 4, None, SEL);
+                    lh.custom(1, 4, HL1, 1);
+                    lh.custom(2, 5, HL2, 0);
+                    lh
+                },
+                &[
+                    ("a", DEFAULT),
+                    ("b", HL1),
+                    ("cd", HL1),
+                    ("e", HL2),
+                    ("f", DEFAULT),
+                ][..],
+            ),
+            (
+                "nested",
+                {
+                    let mut lh = LineHighlighter::new("abcdef", CUR, 4, None, SEL);
+                    lh.custom(0, 6, HL1, 0);
+                    lh.custom(2, 4, HL2, 0);
+                    lh
+                },
+                &[("ab", HL1), ("cd", HL2), ("ef", HL1)][..],
+            ),
+            (
+                "under selection and cursor",
+                {
+                    let mut lh = LineHighlighter::new("abcdef", CUR, 4, None, SEL);
+                    lh.cursor_line(2, LINE);
+                    lh.selection(0, 0, 3, 0, 5);
+                    lh.custom(1, 4, HL1, 10);
+                    lh
+                },
+                &[
+                    ("a", LINE),
+                    ("b", HL1),
+                    ("c", CUR),
+                    ("d", SEL),
+                    ("e", SEL),
+                    ("f", LINE),
+                ][..],
+            ),
+            (
+                "out of line",
+                {
+                    let mut lh = LineHighlighter::new("abc", CUR, 4, None, SEL);
+                    lh.custom(2, 10, HL1, 0);
+                    lh
+                },
+                &[("ab", DEFAULT), ("c", HL1)][..],
+            ),
+        ];
+
+        for (what, lh, want) in tests {
+            assert_spans(lh, want, what);
+        }
+    }
+
+    #[test]
+    fn into_spans_overlapping_regions() {
+        // Check all combinations of two regions in a line against styles computed character by character
+        const FG: Style = Style::new().fg(Color::Red);
+        const BOLD: Style = Style::new().add_modifier(Modifier::BOLD);
+        const BASE: Style = Style::new().add_modifier(Modifier::UNDERLINED);
+
+        let line = "abcde";
+        let mut ranges = vec![];
+        for start in 0..=line.len() {
+            for end in start..=line.len() {
+                ranges.push((start, end));
+            }
+        }
+
+        for &(s1, e1) in &ranges {
+            for &(s2, e2) in &ranges {
+                let mut lh = LineHighlighter::new(line, CUR, 4, None, SEL);
+                lh.style_begin = BASE;
+                lh.selection(0, 0, s1, 0, e1);
+                lh.custom(s2, e2, FG, 0);
+                lh.custom(s2, e2, BOLD, 1);
+
+                let mut have = vec![];
+                for span in lh.into_spans().spans {
+                    have.extend(span.content.chars().map(|c| (c, span.style)));
+                }
+
+                let want = line
+                    .chars()
+                    .enumerate()
+                    .map(|(i, c)| {
+                        let mut style = BASE;
+                        if s2 <= i && i < e2 {
+                            style = style.patch(FG).patch(BOLD);
+                        }
+                        if s1 <= i && i < e1 {
+                            style = style.patch(SEL);
+                        }
+                        (c, style)
+                    })
+                    .collect::<Vec<_>>();
+
+                assert_eq!(have, want, "selection={:?} custom={:?}", (s1, e1), (s2, e2));
+            }
+        }
+    }
+
+    #[test]
+    fn into_spans_merge_styles() {
+        let lh = {
+            let mut lh = LineHighlighter::new("abcd", CUR, 4, None, SEL);
+            lh.cursor_line(1, LNUM.add_modifier(Modifier::UNDERLINED));
+            lh.selection(0, 0, 0, 0, 3);
+            lh.custom(2, 4, Style::new().fg(Color::Red), 0);
+            lh
+        };
+        let line = LNUM.add_modifier(Modifier::UNDERLINED);
+        let want = [
+            ("a", line.patch(SEL)),
+            ("b", line.patch(SEL).patch(CUR)),
+            ("c", line.fg(Color::Red).patch(SEL)),
+            ("d", line.fg(Color::Red)),
+        ];
+        assert_spans(lh, &want, "merge");
+    }
+
+    #[test]
+    fn into_spans_replace_styles() {
+        let lh = {
+            let mut lh = LineHighlighter::new("abcd", CUR, 4, None, SEL);
+            lh.merge_styles(false);
+            lh.cursor_line(1, LNUM.add_modifier(Modifier::UNDERLINED));
+            lh.selection(0, 0, 0, 0, 3);
+            lh.custom(2, 4, Style::new().fg(Color::Red), 0);
+            lh
+        };
+        let want = [
+            ("a", SEL),
+            ("b", CUR),
+            ("c", SEL),
+            ("d", Style::new().fg(Color::Red)),
+        ];
+        assert_spans(lh, &want, "replace");
+    }
+
+    #[test]
+    fn into_spans_fill() {
+        let tests = [
+            (
+                "ab",
+                Some(1),
+                5,
+                &[("a", LINE), ("b", CUR), ("   ", LINE)][..],
+            ),
+            ("ab", None, 5, &[("ab", LINE), (" ", CUR), ("  ", LINE)][..]),
+            ("abcdef", Some(0), 3, &[("a", CUR), ("bcdef", LINE)][..]),
+        ];
+        for test in tests {
+            let (line, cursor, width, want) = test;
+            let mut lh = LineHighlighter::new(line, CUR, 4, None, SEL);
+            lh.cursor_line(cursor.unwrap_or(line.len()), LINE);
+            lh.fill(width, LINE);
+            assert_spans(lh, want, test);
+        }
+
+        // The filled width respects the measurement of characters
+        let wide = |c: char| if c == 'a' { 2 } else { 1 };
+        let mut lh = LineHighlighter::new("ab", CUR, 4, None, SEL);
+        lh.char_width(&wide);
+        lh.cursor_line(2, LINE);
+        lh.fill(5, LINE);
+        assert_spans(lh, &[("ab", LINE), (" ", CUR), (" ", LINE)], "wide");
+    }
+
+    #[test]
+    fn into_spans_diff_mark() {
+        const ADD: Style = Style::new().bg(Color::Green);
+        let tests = [
+            (
+                "abc",
+                Some(('+', ADD)),
+                &[("+ ", ADD), ("a", CUR), ("bc", ADD)][..],
+            ),
+            ("", Some(('+', ADD)), &[("+ ", ADD), (" ", CUR)][..]),
+            (
+                "abc",
+                None,
+                &[("  ", DEFAULT), ("a", CUR), ("bc", DEFAULT)][..],
+            ),
+        ];
+        for test in tests {
+            let (line, mark, want) = test;
+            let mut lh = LineHighlighter::new(line, CUR, 4, None, SEL);
+            lh.diff_mark(mark);
+            lh.cursor_line(0, DEFAULT);
+            assert_spans(lh, want, test);
+        }
+    }
+
+    #[test]
+    fn into_spans_conceal() {
+        let tests = [
+            (
+                "abcde",
+                (1, 3),
+                "X",
+                &[("a", CUR), ("X", DEFAULT), ("de", DEFAULT)][..],
+            ),
+            ("abcde", (1, 3), "", &[("a", CUR), ("de", DEFAULT)][..]),
+            ("abcde", (0, 5), "あ", &[("あ", CUR)][..]),
+            ("a\tb", (0, 1), "xyz", &[("xyz", CUR), (" b", DEFAULT)][..]),
+            ("abc", (2, 2), "X", &[("a", CUR), ("bc", DEFAULT)][..]),
+        ];
+        for test in tests {
+            let (line, (start, end), replacement, want) = test;
+            let mut lh = LineHighlighter::new(line, CUR, 4, None, SEL);
+            lh.cursor_line(0, DEFAULT);
+            lh.conceal(start, end, replacement);
+            assert_spans(lh, want, test);
+        }
+    }
+
+    #[test]
+    fn into_spans_masked_conceal() {
+        let tests = [
+            (
+                "abcde",
+                (1, 3),
+                "XY",
+                &[("*", CUR), ("**", DEFAULT), ("**", DEFAULT)][..],
+            ),
+            ("abcde", (0, 5), "あ", &[("**", CUR)][..]),
+        ];
+        for test in tests {
+            let (line, (start, end), replacement, want) = test;
+            let mut lh = LineHighlighter::new(line, CUR, 4, Some('*'), SEL);
+            lh.cursor_line(0, DEFAULT);
+            lh.conceal(start, end, replacement);
+            assert_spans(lh, want, test);
+        }
+    }
+
+    #[test]
+    fn into_spans_char_width() {
+        let cjk = UnicodeWidth::cjk();
+        let wide = |c: char| if c == 'a' { 2 } else { 1 };
+        let tests: [(&str, Option<char>, &dyn CharWidth, &str); 6] = [
+            ("○\tb", None, &UNICODE_WIDTH, "○   b"),
+            ("○\tb", None, &cjk, "○  b"),
+            ("a\tb", None, &wide, "a  b"),
+            ("ab", Some('*'), &wide, "***"),
+            // The wide mask character covers two narrow characters and the rest is padded
+            ("ab", Some('○'), &cjk, "○"),
+            ("aあ", Some('○'), &cjk, "○ "),
+        ];
+        for test in tests {
+            let (line, mask, measure, want) = test;
+            let mut lh = LineHighlighter::new(line, CUR, 4, mask, SEL);
+            lh.char_width(measure);
+            let have: String = lh
+                .into_spans()
+                .spans
+                .iter()
+                .map(|s| s.content.as_ref())
+                .collect();
+            assert_eq!(have, want, "{:?}", (line, mask));
+        }
+    }
+
+    #[test]
+    fn into_spans_hidden_cursor() {
+        let mut lh = LineHighlighter::new("abc", CUR, 4, None, SEL);
+        lh.hide_cursor();
+        lh.cursor_line(1, LINE);
+        assert_spans(lh, &[("abc", LINE)], "cursor in line");
+
+        let mut lh = LineHighlighter::new("abc", CUR, 4, None, SEL);
+        lh.hide_cursor();
+        lh.cursor_line(3, LINE);
+        assert_spans(lh, &[("abc", LINE)], "cursor at end");
+
+        let mut lh = LineHighlighter::new("ab", CUR, 4, None, SEL);
+        lh.hide_cursor();
+        lh.preedit(1, "xy", 0, SEARCH, LINE);
+        assert_spans(lh, &[("a", LINE), ("xy", SEARCH), ("b", LINE)], "preedit");
+    }
+
+    #[test]
+    fn into_spans_annotation() {
+        let tests = [
+            (None, &[("ab", LINE), (" x", SEARCH)][..]),
+            (Some(2), &[("ab", LINE), (" ", CUR), (" x", SEARCH)][..]),
+            (Some(1), &[("a", LINE), ("b", CUR), (" x", SEARCH)][..]),
+        ];
+        for test in tests {
+            let (cursor, want) = test;
+            let mut lh = LineHighlighter::new("ab", CUR, 4, None, SEL);
+            match cursor {
+                Some(col) => lh.cursor_line(col, LINE),
+                None => lh.style_begin = LINE,
+            }
+            lh.annotation(Span::styled(" x", SEARCH));
+            assert_spans(lh, want, test);
+        }
+    }
+
     #[test]
     fn into_spans_mixed_highlights() {
         let tests = [
@@ -542,7 +1630,7 @@ mod tests {
                     let mut lh = LineHighlighter::new("abcdefg", CUR, 4, None, SEL);
                     lh.cursor_line(3, LINE);
                     lh.selection(0, 0, 2, 0, 5);
-                    lh.search([(1, 2), (5, 6)].into_iter(), SEARCH);
+                    lh.search([(1, 2), (5, 6)].into_iter(), SEARCH, None);
                     lh
                 },
                 &[
@@ -555,6 +1643,23 @@ mod tests {
                     ("g", LINE),
                 ][..],
             ),
+            #[cfg(feature = "search")]
+            (
+                "search partially overlaps selection",
+                {
+                    let mut lh = LineHighlighter::new("abcdef", CUR, 4, None, SEL);
+                    lh.search([(1, 4)].into_iter(), SEARCH, None);
+                    lh.selection(0, 0, 2, 0, 5);
+                    lh
+                },
+                &[
+                    ("a", DEFAULT),
+                    ("b", SEARCH),
+                    ("cd", SEARCH),
+                    ("e", SEL),
+                    ("f", DEFAULT),
+                ][..],
+            ),
             (
                 "selection + cursor at end",
                 {
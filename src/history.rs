@@ -1,16 +1,28 @@
-use crate::util::Pos;
+use crate::anchor::Anchors;
+use crate::conceal::Conceals;
+use crate::damage::Damage;
+use crate::line_meta::LineMeta;
+use crate::metrics::Metrics;
+use crate::util::{self, Pos};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
+use std::mem;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+// Text in edits is wrapped with `Arc` so that the same text can be shared with the yank buffer and inverted edits
+// without copying it. This matters when cutting or pasting a large text.
 #[derive(Clone, Debug)]
 pub enum EditKind {
     InsertChar(char),
     DeleteChar(char),
     InsertNewline,
     DeleteNewline,
-    InsertStr(String),
-    DeleteStr(String),
-    InsertChunk(Vec<String>),
-    DeleteChunk(Vec<String>),
+    InsertStr(Arc<String>),
+    DeleteStr(Arc<String>),
+    InsertChunk(Arc<Vec<String>>),
+    DeleteChunk(Arc<Vec<String>>),
 }
 
 impl EditKind {
@@ -61,7 +73,7 @@ impl EditKind {
                 // Remove middle lines of chunk
                 let mut last_line = lines
                     .drain(after.row + 1..after.row + c.len())
-                    .last()
+                    .next_back()
                     .unwrap();
                 // Remove last line of chunk
                 last_line.drain(..c[c.len() - 1].len());
@@ -74,6 +86,59 @@ impl EditKind {
         }
     }
 
+    // Kind of the edit and the inserted or deleted text. Lines are joined with `\n`
+    fn text(&self) -> (EditRecordKind, String) {
+        use EditKind::*;
+        match self {
+            InsertChar(c) => (EditRecordKind::Insert, c.to_string()),
+            InsertNewline => (EditRecordKind::Insert, "\n".to_string()),
+            InsertStr(s) => (EditRecordKind::Insert, s.to_string()),
+            InsertChunk(c) => (EditRecordKind::Insert, c.join("\n")),
+            DeleteChar(c) => (EditRecordKind::Delete, c.to_string()),
+            DeleteNewline => (EditRecordKind::Delete, "\n".to_string()),
+            DeleteStr(s) => (EditRecordKind::Delete, s.to_string()),
+            DeleteChunk(c) => (EditRecordKind::Delete, c.join("\n")),
+        }
+    }
+
+    fn record_kind(&self) -> EditRecordKind {
+        use EditKind::*;
+        match self {
+            InsertChar(_) | InsertNewline | InsertStr(_) | InsertChunk(_) => EditRecordKind::Insert,
+            DeleteChar(_) | DeleteNewline | DeleteStr(_) | DeleteChunk(_) => EditRecordKind::Delete,
+        }
+    }
+
+    // Position at the end of the inserted or deleted text placed at the `start` position. Unlike `text`, this does
+    // not allocate so that a large chunk is not copied on each edit
+    fn end_pos(&self, start: &Pos) -> Pos {
+        use EditKind::*;
+        match self {
+            InsertChar(c) | DeleteChar(c) => {
+                Pos::new(start.row, start.col + 1, start.offset + c.len_utf8())
+            }
+            InsertNewline | DeleteNewline => Pos::new(start.row + 1, 0, 0),
+            InsertStr(s) | DeleteStr(s) => Pos::new(
+                start.row,
+                start.col + s.chars().count(),
+                start.offset + s.len(),
+            ),
+            InsertChunk(c) | DeleteChunk(c) => {
+                let last = c.last().map_or("", String::as_str);
+                Pos::new(start.row + c.len() - 1, last.chars().count(), last.len())
+            }
+        }
+    }
+
+    // Start position of the edited range. `before` and `after` are the positions passed to `EditKind::apply`
+    fn start<'p>(&self, before: &'p Pos, after: &'p Pos) -> &'p Pos {
+        use EditKind::*;
+        match self {
+            InsertChar(_) | InsertNewline | InsertStr(_) | InsertChunk(_) => before,
+            DeleteChar(_) | DeleteNewline | DeleteStr(_) | DeleteChunk(_) => after,
+        }
+    }
+
     fn invert(&self) -> Self {
         use EditKind::*;
         match self.clone() {
@@ -89,11 +154,254 @@ impl EditKind {
     }
 }
 
+/// Kind of an edit recorded in [`EditRecord`].
+///
+/// This type is marked as `#[non_exhaustive]` since more variations may be supported in the future.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum EditRecordKind {
+    /// The text was inserted at the start position.
+    Insert,
+    /// The text was deleted from the start position.
+    Delete,
+}
+
+/// An edit applied to the text of textarea, returned from [`TextArea::drain_edits`]. Edits by undo and redo are also
+/// recorded. Positions are 0-base character-wise (row, col) positions. For [`EditRecordKind::Insert`], the range
+/// from `start` to `end` is the inserted text in the text after the edit. For [`EditRecordKind::Delete`], the range
+/// is the deleted text in the text before the edit.
+///
+/// This type is marked as `#[non_exhaustive]` since more fields may be added in the future.
+///
+/// [`TextArea::drain_edits`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.drain_edits
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EditRecord {
+    /// Kind of the edit.
+    pub kind: EditRecordKind,
+    /// Start position of the edited range.
+    pub start: (usize, usize),
+    /// End position of the edited range.
+    pub end: (usize, usize),
+    /// Inserted or deleted text. Lines are joined with `\n`.
+    pub text: String,
+    /// Revision of the text after this edit was applied. It is incremented on each edit.
+    pub revision: u64,
+    /// `true` when this edit was applied by [`TextArea::apply_remote_edit`].
+    ///
+    /// [`TextArea::apply_remote_edit`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.apply_remote_edit
+    pub remote: bool,
+}
+
+impl EditRecord {
+    // `before` and `after` are the positions passed to `EditKind::apply`
+    fn new(kind: &EditKind, before: &Pos, after: &Pos, revision: u64, remote: bool) -> Self {
+        let (record_kind, text) = kind.text();
+        let start = kind.start(before, after);
+        let end = kind.end_pos(start);
+        Self {
+            kind: record_kind,
+            start: (start.row, start.col),
+            end: (end.row, end.col),
+            text,
+            revision,
+            remote,
+        }
+    }
+}
+
+// Position at the end of the text inserted at the `start` position
+fn end_pos(start: &Pos, text: &str) -> Pos {
+    match text.rsplit_once('\n') {
+        Some((head, last)) => Pos::new(
+            start.row + head.matches('\n').count() + 1,
+            last.chars().count(),
+            last.len(),
+        ),
+        None => Pos::new(
+            start.row,
+            start.col + text.chars().count(),
+            start.offset + text.len(),
+        ),
+    }
+}
+
+fn pos_key(p: &Pos) -> (usize, usize) {
+    (p.row, p.offset)
+}
+
+// Range of the text replaced by an edit. `old_end` is the end of the range in the text before the edit and `new_end`
+// is the end of the range in the text after the edit.
+pub struct Change {
+    start: Pos,
+    old_end: Pos,
+    new_end: Pos,
+}
+
+impl Change {
+    // `before` and `after` are the positions passed to `EditKind::apply`
+    pub fn new(kind: &EditKind, before: &Pos, after: &Pos) -> Self {
+        let start = kind.start(before, after).clone();
+        let end = kind.end_pos(&start);
+        match kind.record_kind() {
+            EditRecordKind::Insert => Change {
+                old_end: start.clone(),
+                new_end: end,
+                start,
+            },
+            EditRecordKind::Delete => Change {
+                old_end: end,
+                new_end: start.clone(),
+                start,
+            },
+        }
+    }
+
+    // Move the position at or after the end of the range as if the range were replaced
+    fn shift(p: &Pos, from: &Pos, to: &Pos) -> Pos {
+        if p.row == from.row {
+            Pos::new(
+                to.row,
+                to.col + p.col - from.col,
+                to.offset + p.offset - from.offset,
+            )
+        } else {
+            Pos::new(p.row - from.row + to.row, p.col, p.offset)
+        }
+    }
+
+    // Rows of the start, the end before this change, and the end after this change
+    pub fn rows(&self) -> (usize, usize, usize) {
+        (self.start.row, self.old_end.row, self.new_end.row)
+    }
+
+    // Columns of the start and the end before this change
+    pub fn cols(&self) -> (usize, usize) {
+        (self.start.col, self.old_end.col)
+    }
+
+    // Map the position in the text before this change into the text after this change. The position at the start of
+    // inserted text moves to the end of the text.
+    pub fn map(&self, p: &Pos) -> Pos {
+        if pos_key(p) >= pos_key(&self.old_end) {
+            Self::shift(p, &self.old_end, &self.new_end)
+        } else if pos_key(p) <= pos_key(&self.start) {
+            p.clone()
+        } else {
+            self.new_end.clone()
+        }
+    }
+
+    // Map the position like `map`, but the position at the start of inserted text stays before the text
+    pub fn map_before_insertion(&self, p: &Pos) -> Pos {
+        if pos_key(p) <= pos_key(&self.start) {
+            p.clone()
+        } else {
+            self.map(p)
+        }
+    }
+}
+
+const MAX_JUMPS: usize = 100;
+
+// Cursor positions before jumps, like the jumplist of Vim. The positions follow edits of the text.
+#[derive(Clone, Debug, Default)]
+pub struct JumpList {
+    positions: VecDeque<Pos>,
+    index: usize, // Equals to the length of `positions` unless jumping back
+}
+
+impl JumpList {
+    // Add the position to the end. Another position on the same row is removed so that each line appears only once.
+    pub fn push(&mut self, pos: Pos) {
+        self.positions.retain(|p| p.row != pos.row);
+        if self.positions.len() == MAX_JUMPS {
+            self.positions.pop_front();
+        }
+        self.positions.push_back(pos);
+        self.index = self.positions.len();
+    }
+
+    // Returns the previous position. When jumping back from the latest position, the `cursor` is added to the end so
+    // that the cursor can come back by `forward`.
+    pub fn back(&mut self, cursor: Pos) -> Option<&Pos> {
+        if self.positions.is_empty() {
+            return None;
+        }
+        if self.index == self.positions.len() {
+            self.push(cursor);
+            self.index = self.positions.len() - 1;
+        }
+        self.index = self.index.checked_sub(1)?;
+        self.positions.get(self.index)
+    }
+
+    pub fn forward(&mut self) -> Option<&Pos> {
+        if self.index + 1 >= self.positions.len() {
+            return None;
+        }
+        self.index += 1;
+        self.positions.get(self.index)
+    }
+
+    #[cfg(feature = "arbitrary")]
+    pub fn positions(&self) -> impl Iterator<Item = &Pos> {
+        self.positions.iter()
+    }
+
+    fn follow(&mut self, change: &Change) {
+        for pos in self.positions.iter_mut() {
+            *pos = change.map(pos);
+        }
+    }
+}
+
+// Edits applied to the text which have not been retrieved yet
+#[derive(Clone, Debug, Default)]
+struct EditLog {
+    revision: u64,
+    records: Option<Vec<EditRecord>>, // `None` when recording is disabled
+    jumps: JumpList, // Updated on each edit since the positions need to follow the edit
+    anchors: Anchors, // Same as `jumps`
+    conceals: Conceals, // Same as `jumps`
+    damage: Damage,  // Same as `jumps`
+    line_meta: LineMeta, // Same as `jumps`
+    last_selection: Option<(Pos, Pos)>, // Selection start and cursor of the last selection. Same as `jumps`
+    selections: Vec<(Pos, Pos)>, // Start and exclusive end of the secondary selections. Same as `jumps`
+}
+
+impl EditLog {
+    fn record(&mut self, kind: &EditKind, before: &Pos, after: &Pos, remote: bool) {
+        self.revision += 1;
+        let change = Change::new(kind, before, after);
+        self.jumps.follow(&change);
+        self.anchors.follow(&change);
+        self.conceals.follow(&change);
+        self.damage.follow(&change);
+        self.line_meta.follow(&change);
+        if let Some((start, cursor)) = &mut self.last_selection {
+            *start = change.map(start);
+            *cursor = change.map(cursor);
+        }
+        for (start, end) in &mut self.selections {
+            *start = change.map(start);
+            *end = change.map(end);
+        }
+        if let Some(records) = &mut self.records {
+            records.push(EditRecord::new(kind, before, after, self.revision, remote));
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Edit {
     kind: EditKind,
     before: Pos,
     after: Pos,
+    time: Option<Instant>, // When the edit was made. `None` when the clock is not available
+    revision: u64,         // Revision of the text after the edit was applied last time
 }
 
 impl Edit {
@@ -102,15 +410,67 @@ impl Edit {
             kind,
             before,
             after,
+            time: util::now(),
+            revision: 0,
         }
     }
 
-    pub fn redo(&self, lines: &mut Vec<String>) {
+    pub fn redo(&self, lines: &mut Vec<String>, metrics: &mut Metrics) {
         self.kind.apply(lines, &self.before, &self.after);
+        metrics.update(lines, &self.kind, &self.before, &self.after);
+    }
+
+    pub fn undo(&self, lines: &mut Vec<String>, metrics: &mut Metrics) {
+        let kind = self.kind.invert(); // Undo is redo of inverted edit
+        kind.apply(lines, &self.after, &self.before);
+        metrics.update(lines, &kind, &self.after, &self.before);
+    }
+
+    fn record_redo(&mut self, log: &mut EditLog) {
+        log.record(&self.kind, &self.before, &self.after, false);
+        self.revision = log.revision;
+    }
+
+    fn record_undo(&self, log: &mut EditLog) {
+        log.record(&self.kind.invert(), &self.after, &self.before, false);
+    }
+
+    fn change(&self) -> Change {
+        Change::new(&self.kind, &self.before, &self.after)
+    }
+
+    // Swap the order of this edit and the `remote` change applied after this edit. The `remote` change is updated so
+    // that it can be applied before this edit, and this edit is updated so that it can be applied after the `remote`
+    // change. Returns `false` when they conflict.
+    fn rebase(&mut self, remote: &mut Change) -> bool {
+        let change = self.change();
+        if pos_key(&remote.old_end) <= pos_key(&change.start) {
+            // The remote change is before this edit
+            self.before = remote.map(&self.before);
+            self.after = remote.map(&self.after);
+            true
+        } else if pos_key(&remote.start) >= pos_key(&change.new_end) {
+            // The remote change is after this edit
+            remote.start = Change::shift(&remote.start, &change.new_end, &change.old_end);
+            remote.old_end = Change::shift(&remote.old_end, &change.new_end, &change.old_end);
+            remote.new_end = Change::shift(&remote.new_end, &change.new_end, &change.old_end);
+            true
+        } else {
+            false
+        }
     }
 
-    pub fn undo(&self, lines: &mut Vec<String>) {
-        self.kind.invert().apply(lines, &self.after, &self.before); // Undo is redo of inverted edit
+    // Approximate size of the memory used by the edit
+    fn size(&self) -> usize {
+        use EditKind::*;
+        let text = match &self.kind {
+            InsertChar(_) | DeleteChar(_) | InsertNewline | DeleteNewline => 0,
+            InsertStr(s) | DeleteStr(s) => s.len(),
+            InsertChunk(c) | DeleteChunk(c) => {
+                c.iter().map(|l| mem::size_of::<String>() + l.len()).sum()
+            }
+        };
+        mem::size_of::<Self>() + text
     }
 
     pub fn cursor_before(&self) -> (usize, usize) {
@@ -126,7 +486,16 @@ impl Edit {
 pub struct History {
     index: usize,
     max_items: usize,
-    edits: VecDeque<Edit>,
+    max_bytes: usize,
+    bytes: usize,               // Total size of the edits in `edits`
+    edits: VecDeque<Vec<Edit>>, // Each item is a group of edits which are undone/redone at once
+    group_depth: usize,
+    group_started: bool,
+    saved: Option<usize>, // Index at the save point. `None` when the save point can no longer be reached
+    recording: bool,      // `false` while edits are excluded from the history
+    pushes: u64,          // Number of edits pushed to the history including excluded ones
+    merge_interval: Option<Duration>, // Edits made within the interval after the previous edit are merged
+    log: EditLog,
 }
 
 impl History {
@@ -134,54 +503,620 @@ impl History {
         Self {
             index: 0,
             max_items,
+            max_bytes: usize::MAX,
+            bytes: 0,
             edits: VecDeque::new(),
+            group_depth: 0,
+            group_started: false,
+            saved: Some(0),
+            recording: true,
+            pushes: 0,
+            merge_interval: None,
+            log: EditLog::default(),
         }
     }
 
-    pub fn push(&mut self, edit: Edit) {
+    pub fn push(&mut self, mut edit: Edit) {
+        edit.record_redo(&mut self.log);
+        self.pushes += 1;
+
+        if !self.recording {
+            self.rebase(edit.change());
+            return;
+        }
+
         if self.max_items == 0 {
+            self.saved = None;
             return;
         }
 
+        if self.group_started {
+            if let Some(group) = self.edits.back_mut() {
+                self.bytes += edit.size();
+                group.push(edit);
+                if self.saved == Some(self.index) {
+                    self.saved = None; // The group containing the save point was modified
+                }
+                self.evict();
+                return;
+            }
+        }
+
+        if self.follows_last_edit(&edit) {
+            if let Some(group) = self.edits.back_mut() {
+                self.bytes += edit.size();
+                group.push(edit);
+                self.group_started = self.group_depth > 0;
+                self.evict();
+                return;
+            }
+        }
+
         if self.edits.len() == self.max_items {
-            self.edits.pop_front();
-            self.index = self.index.saturating_sub(1);
+            self.pop_front();
         }
 
         if self.index < self.edits.len() {
-            self.edits.truncate(self.index);
+            self.truncate(self.index);
+            if self.saved.map_or(false, |i| i > self.index) {
+                self.saved = None;
+            }
         }
 
         self.index += 1;
-        self.edits.push_back(edit);
+        self.bytes += edit.size();
+        self.edits.push_back(vec![edit]);
+        self.group_started = self.group_depth > 0;
+        self.evict();
+    }
+
+    fn pop_front(&mut self) {
+        if let Some(group) = self.edits.pop_front() {
+            self.bytes -= group.iter().map(Edit::size).sum::<usize>();
+            self.index = self.index.saturating_sub(1);
+            self.saved = self.saved.and_then(|i| i.checked_sub(1));
+        }
+    }
+
+    fn truncate(&mut self, len: usize) {
+        for group in self.edits.drain(len..) {
+            self.bytes -= group.iter().map(Edit::size).sum::<usize>();
+        }
+    }
+
+    // Remove the oldest history items until the size of the history fits in the max size. The group which is being
+    // built is kept until it ends.
+    fn evict(&mut self) {
+        while self.bytes > self.max_bytes && !self.edits.is_empty() {
+            if self.index == 0 {
+                self.truncate(0); // Only redo items remain
+                break;
+            }
+            if self.group_started && self.edits.len() == 1 {
+                break;
+            }
+            self.pop_front();
+        }
+    }
+
+    // Return if the edit was made within the merge interval after the last edit in the history. The edit is not merged
+    // when the last history item was undone or is at the save point so that undo can go back to the saved text.
+    fn follows_last_edit(&self, edit: &Edit) -> bool {
+        let (interval, time) = match (self.merge_interval, edit.time) {
+            (Some(interval), Some(time)) => (interval, time),
+            _ => return false,
+        };
+        if self.index == 0 || self.index != self.edits.len() || self.saved == Some(self.index) {
+            return false;
+        }
+        match self
+            .edits
+            .back()
+            .and_then(|g| g.last())
+            .and_then(|e| e.time)
+        {
+            Some(last) => time.saturating_duration_since(last) <= interval,
+            None => false,
+        }
     }
 
-    pub fn redo(&mut self, lines: &mut Vec<String>) -> Option<(usize, usize)> {
+    // Edits pushed until the corresponding `end_group` call are merged into one history item. Groups can be nested.
+    pub fn begin_group(&mut self) {
+        self.group_depth += 1;
+    }
+
+    pub fn end_group(&mut self) {
+        self.group_depth = self.group_depth.saturating_sub(1);
+        if self.group_depth == 0 && self.group_started {
+            self.group_started = false;
+            self.evict();
+        }
+    }
+
+    pub fn redo(
+        &mut self,
+        lines: &mut Vec<String>,
+        metrics: &mut Metrics,
+    ) -> Option<(usize, usize)> {
         if self.index == self.edits.len() {
             return None;
         }
-        let edit = &self.edits[self.index];
-        edit.redo(lines);
+        self.group_started = false;
+        let group = &mut self.edits[self.index];
+        for edit in group.iter_mut() {
+            edit.redo(lines, metrics);
+            edit.record_redo(&mut self.log);
+        }
         self.index += 1;
-        Some(edit.cursor_after())
+        group.last().map(Edit::cursor_after)
     }
 
-    pub fn undo(&mut self, lines: &mut Vec<String>) -> Option<(usize, usize)> {
+    pub fn undo(
+        &mut self,
+        lines: &mut Vec<String>,
+        metrics: &mut Metrics,
+    ) -> Option<(usize, usize)> {
         self.index = self.index.checked_sub(1)?;
-        let edit = &self.edits[self.index];
-        edit.undo(lines);
-        Some(edit.cursor_before())
+        self.group_started = false;
+        let group = &self.edits[self.index];
+        for edit in group.iter().rev() {
+            edit.undo(lines, metrics);
+            edit.record_undo(&mut self.log);
+        }
+        group.first().map(Edit::cursor_before)
+    }
+
+    // Undo the history items containing edits made after the time. Returns the cursor position after the last undo
+    pub fn undo_until(
+        &mut self,
+        time: Instant,
+        lines: &mut Vec<String>,
+        metrics: &mut Metrics,
+    ) -> Option<(usize, usize)> {
+        let mut cursor = None;
+        while self.index > 0
+            && self.edits[self.index - 1]
+                .iter()
+                .any(|e| e.time.map_or(false, |t| t > time))
+        {
+            cursor = self.undo(lines, metrics).or(cursor);
+        }
+        cursor
+    }
+
+    // Redo the history items whose edits were all made at or before the time. Edits made when the clock was not available
+    // are not redone. Returns the cursor position after the last redo
+    pub fn redo_until(
+        &mut self,
+        time: Instant,
+        lines: &mut Vec<String>,
+        metrics: &mut Metrics,
+    ) -> Option<(usize, usize)> {
+        let mut cursor = None;
+        while self.index < self.edits.len()
+            && self.edits[self.index]
+                .iter()
+                .all(|e| e.time.map_or(false, |t| t <= time))
+        {
+            cursor = self.redo(lines, metrics).or(cursor);
+        }
+        cursor
+    }
+
+    // The last edit which can be undone
+    pub fn last_edit(&self) -> Option<EditRecord> {
+        let edit = self.edits.get(self.index.checked_sub(1)?)?.last()?;
+        Some(EditRecord::new(
+            &edit.kind,
+            &edit.before,
+            &edit.after,
+            edit.revision,
+            false,
+        ))
     }
 
     pub fn max_items(&self) -> usize {
         self.max_items
     }
+
+    // Clear the undo/redo history and change the max number of items. The modified state and the edit log are kept
+    pub fn set_max_items(&mut self, max_items: usize) {
+        self.max_items = max_items;
+        self.clear();
+    }
+
+    // Clear the undo/redo history. The modified state and the edit log are kept
+    pub fn clear(&mut self) {
+        let modified = self.is_modified();
+        self.index = 0;
+        self.bytes = 0;
+        self.edits.clear();
+        self.group_started = false;
+        self.saved = if modified { None } else { Some(0) };
+    }
+
+    pub fn max_bytes(&self) -> usize {
+        self.max_bytes
+    }
+
+    pub fn set_max_bytes(&mut self, max_bytes: usize) {
+        self.max_bytes = max_bytes;
+        self.evict();
+    }
+
+    pub fn merge_interval(&self) -> Option<Duration> {
+        self.merge_interval
+    }
+
+    pub fn set_merge_interval(&mut self, interval: Option<Duration>) {
+        self.merge_interval = interval;
+    }
+
+    pub fn pushes(&self) -> u64 {
+        self.pushes
+    }
+
+    // Returns the previous state
+    pub fn set_recording(&mut self, recording: bool) -> bool {
+        mem::replace(&mut self.recording, recording)
+    }
+
+    pub fn mark_saved(&mut self) {
+        self.saved = Some(self.index);
+    }
+
+    pub fn is_modified(&self) -> bool {
+        self.saved != Some(self.index)
+    }
+
+    // Make the text modified until the next save point even if undo/redo goes back to the current save point
+    pub fn mark_modified(&mut self) {
+        self.saved = None;
+    }
+
+    // Apply the edit which originated outside of the textarea without pushing it to the history. Edits in the undo
+    // history are rebased on the edit so that they can still be undone. When an edit conflicts with the remote edit,
+    // it and all older edits are discarded. The redo history and the save point are always discarded.
+    pub fn apply_remote(
+        &mut self,
+        lines: &mut Vec<String>,
+        metrics: &mut Metrics,
+        start: Pos,
+        end: Pos,
+        text: &str,
+    ) {
+        // Apply the remote edit as deletion followed by insertion
+        if pos_key(&start) != pos_key(&end) {
+            let kind = if start.row == end.row {
+                EditKind::DeleteStr(Arc::new(
+                    lines[start.row][start.offset..end.offset].to_string(),
+                ))
+            } else {
+                let mut deleted = vec![lines[start.row][start.offset..].to_string()];
+                deleted.extend(lines[start.row + 1..end.row].iter().cloned());
+                deleted.push(lines[end.row][..end.offset].to_string());
+                EditKind::DeleteChunk(Arc::new(deleted))
+            };
+            kind.apply(lines, &end, &start);
+            metrics.update(lines, &kind, &end, &start);
+            self.log.record(&kind, &end, &start, true);
+        }
+        if !text.is_empty() {
+            let new_end = end_pos(&start, text);
+            let kind = if text.contains('\n') {
+                EditKind::InsertChunk(Arc::new(text.split('\n').map(String::from).collect()))
+            } else {
+                EditKind::InsertStr(Arc::new(text.to_string()))
+            };
+            kind.apply(lines, &start, &new_end);
+            metrics.update(lines, &kind, &start, &new_end);
+            self.log.record(&kind, &start, &new_end, true);
+        }
+
+        let remote = Change {
+            new_end: end_pos(&start, text),
+            start,
+            old_end: end,
+        };
+        self.rebase(remote);
+    }
+
+    // Rebase the edits in the undo history on the change which was applied to the text without being pushed to the
+    // history.
+    fn rebase(&mut self, mut remote: Change) {
+        self.truncate(self.index);
+        self.saved = None;
+        self.group_started = false;
+
+        for i in (0..self.index).rev() {
+            let rebased = self.edits[i]
+                .iter_mut()
+                .rev()
+                .all(|edit| edit.rebase(&mut remote));
+            if !rebased {
+                for _ in 0..=i {
+                    self.pop_front();
+                }
+                break;
+            }
+        }
+    }
+
+    pub fn set_edit_log(&mut self, enabled: bool) {
+        if !enabled {
+            self.log.records = None;
+        } else if self.log.records.is_none() {
+            self.log.records = Some(vec![]);
+        }
+    }
+
+    pub fn edit_log(&self) -> bool {
+        self.log.records.is_some()
+    }
+
+    pub fn drain_edit_log(&mut self) -> Vec<EditRecord> {
+        self.log.records.as_mut().map(mem::take).unwrap_or_default()
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.log.revision
+    }
+
+    pub fn jumps_mut(&mut self) -> &mut JumpList {
+        &mut self.log.jumps
+    }
+
+    #[cfg(feature = "arbitrary")]
+    pub fn jumps_positions(&self) -> impl Iterator<Item = &Pos> {
+        self.log.jumps.positions()
+    }
+
+    pub fn anchors(&self) -> &Anchors {
+        &self.log.anchors
+    }
+
+    pub fn anchors_mut(&mut self) -> &mut Anchors {
+        &mut self.log.anchors
+    }
+
+    pub fn conceals(&self) -> &Conceals {
+        &self.log.conceals
+    }
+
+    pub fn conceals_mut(&mut self) -> &mut Conceals {
+        &mut self.log.conceals
+    }
+
+    pub fn damage(&self) -> &Damage {
+        &self.log.damage
+    }
+
+    pub fn damage_mut(&mut self) -> &mut Damage {
+        &mut self.log.damage
+    }
+
+    pub fn line_meta(&self) -> &LineMeta {
+        &self.log.line_meta
+    }
+
+    pub fn line_meta_mut(&mut self) -> &mut LineMeta {
+        &mut self.log.line_meta
+    }
+
+    pub fn last_selection(&self) -> Option<&(Pos, Pos)> {
+        self.log.last_selection.as_ref()
+    }
+
+    pub fn set_last_selection(&mut self, selection: Option<(Pos, Pos)>) {
+        self.log.last_selection = selection;
+    }
+
+    pub fn selections(&self) -> &[(Pos, Pos)] {
+        &self.log.selections
+    }
+
+    pub fn set_selections(&mut self, selections: Vec<(Pos, Pos)>) {
+        self.log.selections = selections;
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn undo_redo_group() {
+        let mut lines = vec!["".to_string()];
+        let mut m = Metrics::new(&lines);
+        let mut h = History::new(10);
+
+        let insert =
+            |h: &mut History, lines: &mut Vec<String>, m: &mut Metrics, col: usize, c: char| {
+                let edit = Edit::new(
+                    EditKind::InsertChar(c),
+                    Pos::new(0, col, col),
+                    Pos::new(0, col + 1, col + 1),
+                );
+                edit.redo(lines, m);
+                h.push(edit);
+            };
+
+        insert(&mut h, &mut lines, &mut m, 0, 'a');
+        h.begin_group();
+        insert(&mut h, &mut lines, &mut m, 1, 'b');
+        h.begin_group(); // Nested group is merged into the outer group
+        insert(&mut h, &mut lines, &mut m, 2, 'c');
+        h.end_group();
+        insert(&mut h, &mut lines, &mut m, 3, 'd');
+        h.end_group();
+        insert(&mut h, &mut lines, &mut m, 4, 'e');
+        assert_eq!(lines, ["abcde"]);
+
+        assert_eq!(h.undo(&mut lines, &mut m), Some((0, 4)));
+        assert_eq!(lines, ["abcd"]);
+        assert_eq!(h.undo(&mut lines, &mut m), Some((0, 1)));
+        assert_eq!(lines, ["a"]);
+        assert_eq!(h.undo(&mut lines, &mut m), Some((0, 0)));
+        assert_eq!(lines, [""]);
+        assert_eq!(h.undo(&mut lines, &mut m), None);
+
+        assert_eq!(h.redo(&mut lines, &mut m), Some((0, 1)));
+        assert_eq!(h.redo(&mut lines, &mut m), Some((0, 4)));
+        assert_eq!(lines, ["abcd"]);
+        assert_eq!(h.redo(&mut lines, &mut m), Some((0, 5)));
+        assert_eq!(lines, ["abcde"]);
+        assert_eq!(h.redo(&mut lines, &mut m), None);
+        assert_eq!(m, Metrics::new(&lines));
+    }
+
+    #[test]
+    fn max_bytes() {
+        let mut lines = vec!["".to_string()];
+        let mut m = Metrics::new(&lines);
+        let mut h = History::new(10);
+
+        let insert = |h: &mut History, lines: &mut Vec<String>, m: &mut Metrics, s: &str| {
+            let col = lines[0].len();
+            let edit = Edit::new(
+                EditKind::InsertStr(Arc::new(s.to_string())),
+                Pos::new(0, col, col),
+                Pos::new(0, col + s.len(), col + s.len()),
+            );
+            edit.redo(lines, m);
+            h.push(edit);
+        };
+        let size = mem::size_of::<Edit>();
+
+        h.set_max_bytes(3 * size + 10);
+        insert(&mut h, &mut lines, &mut m, "aaaa");
+        insert(&mut h, &mut lines, &mut m, "bbbb");
+        assert_eq!(h.bytes, 2 * size + 8);
+        insert(&mut h, &mut lines, &mut m, "cccc"); // The oldest item is evicted
+        assert_eq!(h.bytes, 2 * size + 8);
+        assert_eq!(h.edits.len(), 2);
+
+        // The group being built is not evicted until it ends
+        h.begin_group();
+        insert(&mut h, &mut lines, &mut m, "dddd");
+        insert(&mut h, &mut lines, &mut m, "eeee");
+        insert(&mut h, &mut lines, &mut m, "ffff");
+        insert(&mut h, &mut lines, &mut m, "gggg");
+        assert_eq!(h.edits.len(), 1);
+        h.end_group();
+        assert_eq!(h.edits.len(), 0);
+        assert_eq!(h.bytes, 0);
+        assert_eq!(h.undo(&mut lines, &mut m), None);
+
+        // Shrinking the max size evicts the redo items as well
+        h.set_max_bytes(usize::MAX);
+        insert(&mut h, &mut lines, &mut m, "hhhh");
+        insert(&mut h, &mut lines, &mut m, "iiii");
+        h.undo(&mut lines, &mut m);
+        h.undo(&mut lines, &mut m);
+        h.set_max_bytes(size);
+        assert_eq!(h.edits.len(), 0);
+        assert_eq!(h.bytes, 0);
+        assert_eq!(lines, ["aaaabbbbccccddddeeeeffffgggg"]);
+        assert_eq!(m, Metrics::new(&lines));
+    }
+
+    #[test]
+    fn jump_list() {
+        let mut j = JumpList::default();
+        let pos = |row, col| Pos::new(row, col, col);
+        let row = |p: Option<&Pos>| p.map(|p| p.row);
+
+        assert_eq!(row(j.back(pos(0, 0))), None);
+        j.push(pos(1, 0));
+        j.push(pos(2, 0));
+        j.push(pos(1, 1)); // Moves the entry on the same row to the end
+        assert_eq!(row(j.back(pos(3, 0))), Some(1));
+        assert_eq!(row(j.back(pos(1, 1))), Some(2));
+        assert_eq!(row(j.back(pos(2, 0))), None);
+        assert_eq!(row(j.forward()), Some(1));
+        assert_eq!(row(j.forward()), Some(3));
+        assert_eq!(row(j.forward()), None);
+
+        // Inserting lines before the positions shifts them
+        let kind = EditKind::InsertChunk(Arc::new(vec![
+            "a".to_string(),
+            "".to_string(),
+            "b".to_string(),
+        ]));
+        j.follow(&Change::new(&kind, &pos(0, 0), &pos(2, 1)));
+        assert_eq!(row(j.back(pos(0, 0))), Some(3));
+        assert_eq!(row(j.back(pos(0, 0))), Some(4));
+
+        for i in 0..MAX_JUMPS + 10 {
+            j.push(pos(i, 0));
+        }
+        assert_eq!(j.positions.len(), MAX_JUMPS);
+        assert_eq!(j.positions[0].row, 10);
+    }
+
+    #[test]
+    fn save_point() {
+        let mut lines = vec!["".to_string()];
+        let mut m = Metrics::new(&lines);
+        let mut h = History::new(2);
+
+        let insert = |h: &mut History, lines: &mut Vec<String>, m: &mut Metrics, col: usize| {
+            let edit = Edit::new(
+                EditKind::InsertChar('a'),
+                Pos::new(0, col, col),
+                Pos::new(0, col + 1, col + 1),
+            );
+            edit.redo(lines, m);
+            h.push(edit);
+        };
+
+        assert!(!h.is_modified());
+        insert(&mut h, &mut lines, &mut m, 0);
+        assert!(h.is_modified());
+        h.mark_saved();
+        assert!(!h.is_modified());
+
+        // Undo/redo across the save point
+        h.undo(&mut lines, &mut m);
+        assert!(h.is_modified());
+        h.redo(&mut lines, &mut m);
+        assert!(!h.is_modified());
+
+        // The save point is discarded when the redo history containing it is truncated
+        h.undo(&mut lines, &mut m);
+        insert(&mut h, &mut lines, &mut m, 0);
+        h.undo(&mut lines, &mut m);
+        assert!(h.is_modified());
+        h.redo(&mut lines, &mut m);
+        assert!(h.is_modified());
+
+        // The save point follows the history when the oldest item is dropped
+        h.mark_saved();
+        insert(&mut h, &mut lines, &mut m, 1);
+        insert(&mut h, &mut lines, &mut m, 2);
+        h.undo(&mut lines, &mut m);
+        assert!(h.is_modified());
+        h.undo(&mut lines, &mut m);
+        assert!(!h.is_modified());
+
+        // The save point is discarded when it is dropped from the history
+        insert(&mut h, &mut lines, &mut m, 1);
+        insert(&mut h, &mut lines, &mut m, 2);
+        insert(&mut h, &mut lines, &mut m, 3);
+        h.undo(&mut lines, &mut m);
+        h.undo(&mut lines, &mut m);
+        assert!(h.is_modified());
+
+        // Appending an edit to the group at the save point modifies the text
+        let mut h = History::new(2);
+        h.begin_group();
+        insert(&mut h, &mut lines, &mut m, 0);
+        h.mark_saved();
+        insert(&mut h, &mut lines, &mut m, 1);
+        h.end_group();
+        assert!(h.is_modified());
+    }
+
     #[test]
     fn insert_delete_chunk() {
         #[rustfmt::skip]
@@ -630,7 +1565,7 @@ mod tests {
                 Pos::new(row, col, offset)
             };
             let mut lines: Vec<_> = before.iter().map(|s| s.to_string()).collect();
-            let chunk: Vec<_> = input.iter().map(|s| s.to_string()).collect();
+            let chunk: Arc<Vec<_>> = Arc::new(input.iter().map(|s| s.to_string()).collect());
             let after_pos = {
                 let row = row + input.len() - 1;
                 let last = input.last().unwrap();
@@ -638,13 +1573,68 @@ mod tests {
                 Pos::new(row, col, last.len())
             };
 
+            let mut metrics = Metrics::new(&lines);
+
             let edit = EditKind::InsertChunk(chunk.clone());
             edit.apply(&mut lines, &before_pos, &after_pos);
             assert_eq!(&lines, expected, "{test:?}");
+            metrics.update(&lines, &edit, &before_pos, &after_pos);
+            assert_eq!(metrics, Metrics::new(&lines), "{test:?}");
 
             let edit = EditKind::DeleteChunk(chunk);
             edit.apply(&mut lines, &after_pos, &before_pos);
             assert_eq!(&lines, &before, "{test:?}");
+            metrics.update(&lines, &edit, &after_pos, &before_pos);
+            assert_eq!(metrics, Metrics::new(&lines), "{test:?}");
         }
     }
+
+    #[test]
+    fn merge_interval() {
+        let mut lines = vec!["".to_string()];
+        let mut m = Metrics::new(&lines);
+        let mut h = History::new(10);
+        h.set_merge_interval(Some(Duration::from_secs(1)));
+        let start = Instant::now();
+
+        let insert =
+            |h: &mut History, lines: &mut Vec<String>, m: &mut Metrics, c: char, ms: u64| {
+                let col = lines[0].len();
+                let mut edit = Edit::new(
+                    EditKind::InsertChar(c),
+                    Pos::new(0, col, col),
+                    Pos::new(0, col + 1, col + 1),
+                );
+                edit.time = Some(start + Duration::from_millis(ms));
+                edit.redo(lines, m);
+                h.push(edit);
+            };
+
+        insert(&mut h, &mut lines, &mut m, 'a', 0);
+        insert(&mut h, &mut lines, &mut m, 'b', 500);
+        insert(&mut h, &mut lines, &mut m, 'c', 1500);
+        assert_eq!(h.edits.len(), 1);
+        insert(&mut h, &mut lines, &mut m, 'd', 3000); // Made after pausing longer than the interval
+        assert_eq!(h.edits.len(), 2);
+        h.undo(&mut lines, &mut m);
+        assert_eq!(lines, ["abc"]);
+
+        // Edits are not merged into the undone item
+        insert(&mut h, &mut lines, &mut m, 'e', 3100);
+        assert_eq!(h.edits.len(), 2);
+
+        // Edits are not merged into the item at the save point
+        h.mark_saved();
+        insert(&mut h, &mut lines, &mut m, 'f', 3200);
+        assert_eq!(h.edits.len(), 3);
+        h.undo(&mut lines, &mut m);
+        assert!(!h.is_modified());
+        assert_eq!(lines, ["abce"]);
+
+        // Edits are not merged when the interval is not set
+        h.set_merge_interval(None);
+        insert(&mut h, &mut lines, &mut m, 'g', 3300);
+        insert(&mut h, &mut lines, &mut m, 'h', 3400);
+        assert_eq!(h.edits.len(), 4);
+    }
 }
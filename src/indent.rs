@@ -0,0 +1,104 @@
+use crate::util::spaces;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+/// Policy of indentation used by [`TextArea::insert_tab`], [`TextArea::indent`], and rendering tab characters.
+///
+/// This type is marked as `#[non_exhaustive]` since more fields may be added in the future. Use
+/// [`IndentPolicy::spaces`] or [`IndentPolicy::hard_tab`] to create an instance. See also:
+/// [`TextArea::set_indent_policy`]
+///
+/// ```
+/// use tui_textarea::{IndentPolicy, TextArea};
+///
+/// let mut textarea = TextArea::from(["a"]);
+/// textarea.move_cursor(tui_textarea::CursorMove::End);
+///
+/// // Spaces are inserted until the next tab stop
+/// textarea.set_indent_policy(IndentPolicy::spaces(300));
+/// textarea.insert_tab();
+/// assert_eq!(textarea.lines()[0].len(), 300);
+///
+/// // The same number of spaces are always inserted
+/// let mut policy = IndentPolicy::spaces(2);
+/// policy.keep_alignment = false;
+/// textarea.set_indent_policy(policy);
+/// textarea.insert_tab();
+/// assert_eq!(textarea.lines()[0].len(), 302);
+/// ```
+///
+/// [`TextArea::insert_tab`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.insert_tab
+/// [`TextArea::indent`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.indent
+/// [`TextArea::set_indent_policy`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.set_indent_policy
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct IndentPolicy {
+    /// Width of a tab stop in display columns. Setting 0 disables tab inputs. The default value is 4.
+    pub width: usize,
+    /// Whether a hard tab is inserted instead of spaces. The default value is `false`.
+    pub hard_tab: bool,
+    /// Whether spaces inserted by a tab input align the cursor to the next tab stop. When `false`, the spaces of the
+    /// width are always inserted. This has no effect when a hard tab is inserted. The default value is `true`.
+    pub keep_alignment: bool,
+}
+
+impl Default for IndentPolicy {
+    fn default() -> Self {
+        Self::spaces(4)
+    }
+}
+
+impl IndentPolicy {
+    /// Create a policy to indent with spaces. Tab stops are placed every `width` columns.
+    /// ```
+    /// use tui_textarea::IndentPolicy;
+    ///
+    /// let policy = IndentPolicy::spaces(2);
+    /// assert_eq!(policy.width, 2);
+    /// assert!(!policy.hard_tab);
+    /// ```
+    pub fn spaces(width: usize) -> Self {
+        Self {
+            width,
+            hard_tab: false,
+            keep_alignment: true,
+        }
+    }
+
+    /// Create a policy to indent with a hard tab. A tab character is rendered up to the next tab stop placed every
+    /// `width` columns.
+    /// ```
+    /// use tui_textarea::IndentPolicy;
+    ///
+    /// let policy = IndentPolicy::hard_tab(8);
+    /// assert_eq!(policy.width, 8);
+    /// assert!(policy.hard_tab);
+    /// ```
+    pub fn hard_tab(width: usize) -> Self {
+        Self {
+            width,
+            hard_tab: true,
+            keep_alignment: true,
+        }
+    }
+
+    // String for one level of indentation
+    pub(crate) fn indent(&self) -> Cow<'static, str> {
+        if self.hard_tab {
+            Cow::Borrowed("\t")
+        } else {
+            spaces(self.width)
+        }
+    }
+
+    // Number of spaces inserted by a tab input at the display column
+    pub(crate) fn soft_tab_len(&self, col: usize) -> usize {
+        if self.keep_alignment {
+            self.width - col % self.width
+        } else {
+            self.width
+        }
+    }
+}
@@ -1,6 +1,7 @@
-use super::{Input, Key};
+use super::{Input, Key, MediaKey, ModifierKey};
 use crate::crossterm::event::{
-    Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseEvent, MouseEventKind,
+    Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MediaKeyCode, ModifierKeyCode,
+    MouseEvent, MouseEventKind,
 };
 
 impl From<Event> for Input {
@@ -25,7 +26,7 @@ impl From<KeyCode> for Key {
             KeyCode::Right => Key::Right,
             KeyCode::Up => Key::Up,
             KeyCode::Down => Key::Down,
-            KeyCode::Tab => Key::Tab,
+            KeyCode::Tab | KeyCode::BackTab => Key::Tab,
             KeyCode::Delete => Key::Delete,
             KeyCode::Home => Key::Home,
             KeyCode::End => Key::End,
@@ -33,7 +34,60 @@ impl From<KeyCode> for Key {
             KeyCode::PageDown => Key::PageDown,
             KeyCode::Esc => Key::Esc,
             KeyCode::F(x) => Key::F(x),
-            _ => Key::Null,
+            KeyCode::Insert => Key::Insert,
+            KeyCode::CapsLock => Key::CapsLock,
+            KeyCode::ScrollLock => Key::ScrollLock,
+            KeyCode::NumLock => Key::NumLock,
+            KeyCode::PrintScreen => Key::PrintScreen,
+            KeyCode::Pause => Key::Pause,
+            KeyCode::Menu => Key::Menu,
+            KeyCode::KeypadBegin => Key::KeypadBegin,
+            KeyCode::Media(code) => Key::Media(code.into()),
+            KeyCode::Modifier(code) => Key::Modifier(code.into()),
+            KeyCode::Null => Key::Null,
+        }
+    }
+}
+
+impl From<MediaKeyCode> for MediaKey {
+    /// Convert [`crossterm::event::MediaKeyCode`] into [`MediaKey`].
+    fn from(code: MediaKeyCode) -> Self {
+        match code {
+            MediaKeyCode::Play => MediaKey::Play,
+            MediaKeyCode::Pause => MediaKey::Pause,
+            MediaKeyCode::PlayPause => MediaKey::PlayPause,
+            MediaKeyCode::Reverse => MediaKey::Reverse,
+            MediaKeyCode::Stop => MediaKey::Stop,
+            MediaKeyCode::FastForward => MediaKey::FastForward,
+            MediaKeyCode::Rewind => MediaKey::Rewind,
+            MediaKeyCode::TrackNext => MediaKey::TrackNext,
+            MediaKeyCode::TrackPrevious => MediaKey::TrackPrevious,
+            MediaKeyCode::Record => MediaKey::Record,
+            MediaKeyCode::LowerVolume => MediaKey::LowerVolume,
+            MediaKeyCode::RaiseVolume => MediaKey::RaiseVolume,
+            MediaKeyCode::MuteVolume => MediaKey::MuteVolume,
+        }
+    }
+}
+
+impl From<ModifierKeyCode> for ModifierKey {
+    /// Convert [`crossterm::event::ModifierKeyCode`] into [`ModifierKey`].
+    fn from(code: ModifierKeyCode) -> Self {
+        match code {
+            ModifierKeyCode::LeftShift => ModifierKey::LeftShift,
+            ModifierKeyCode::LeftControl => ModifierKey::LeftControl,
+            ModifierKeyCode::LeftAlt => ModifierKey::LeftAlt,
+            ModifierKeyCode::LeftSuper => ModifierKey::LeftSuper,
+            ModifierKeyCode::LeftHyper => ModifierKey::LeftHyper,
+            ModifierKeyCode::LeftMeta => ModifierKey::LeftMeta,
+            ModifierKeyCode::RightShift => ModifierKey::RightShift,
+            ModifierKeyCode::RightControl => ModifierKey::RightControl,
+            ModifierKeyCode::RightAlt => ModifierKey::RightAlt,
+            ModifierKeyCode::RightSuper => ModifierKey::RightSuper,
+            ModifierKeyCode::RightHyper => ModifierKey::RightHyper,
+            ModifierKeyCode::RightMeta => ModifierKey::RightMeta,
+            ModifierKeyCode::IsoLevel3Shift => ModifierKey::IsoLevel3Shift,
+            ModifierKeyCode::IsoLevel5Shift => ModifierKey::IsoLevel5Shift,
         }
     }
 }
@@ -49,7 +103,8 @@ impl From<KeyEvent> for Input {
 
         let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
         let alt = key.modifiers.contains(KeyModifiers::ALT);
-        let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+        // Some terminals report `BackTab` without the Shift modifier
+        let shift = key.modifiers.contains(KeyModifiers::SHIFT) || key.code == KeyCode::BackTab;
         let key = Key::from(key.code);
 
         Self {
@@ -142,9 +197,35 @@ mod tests {
                 ),
                 input(Key::F(1), true, true, true),
             ),
+            (
+                key_event(KeyCode::BackTab, KeyModifiers::empty()),
+                input(Key::Tab, false, false, true),
+            ),
             (
                 key_event(KeyCode::NumLock, KeyModifiers::CONTROL),
-                input(Key::Null, true, false, false),
+                input(Key::NumLock, true, false, false),
+            ),
+            (
+                key_event(KeyCode::Insert, KeyModifiers::empty()),
+                input(Key::Insert, false, false, false),
+            ),
+            (
+                key_event(
+                    KeyCode::Media(MediaKeyCode::PlayPause),
+                    KeyModifiers::empty(),
+                ),
+                input(Key::Media(MediaKey::PlayPause), false, false, false),
+            ),
+            (
+                key_event(
+                    KeyCode::Modifier(ModifierKeyCode::LeftShift),
+                    KeyModifiers::SHIFT,
+                ),
+                input(Key::Modifier(ModifierKey::LeftShift), false, false, true),
+            ),
+            (
+                key_event(KeyCode::Null, KeyModifiers::empty()),
+                input(Key::Null, false, false, false),
             ),
         ] {
             assert_eq!(Input::from(from), to, "{:?} -> {:?}", from, to);
@@ -48,6 +48,26 @@ pub enum Key {
     PageDown,
     /// Escape key
     Esc,
+    /// Insert key
+    Insert,
+    /// Caps lock key. This key is supported by crossterm and termwiz only
+    CapsLock,
+    /// Scroll lock key. This key is supported by crossterm and termwiz only
+    ScrollLock,
+    /// Num lock key. This key is supported by crossterm and termwiz only
+    NumLock,
+    /// Print screen key. This key is supported by crossterm and termwiz only
+    PrintScreen,
+    /// Pause key. This key is supported by crossterm and termwiz only
+    Pause,
+    /// Menu key. This key is supported by crossterm and termwiz only
+    Menu,
+    /// Begin key, the center key of the keypad (`5` while num lock is off). This key is supported by crossterm only
+    KeypadBegin,
+    /// Media key such as play and volume keys. See [`MediaKey`]
+    Media(MediaKey),
+    /// Modifier key pressed alone such as left shift key. See [`ModifierKey`]
+    Modifier(ModifierKey),
     /// Copy key. This key is supported by termwiz only
     Copy,
     /// Cut key. This key is supported by termwiz only
@@ -68,6 +88,84 @@ impl Default for Key {
     }
 }
 
+/// Media key input kind such as play and volume keys. This is supported by crossterm and termwiz only. Note that
+/// crossterm reports media keys only when the keyboard enhancement flags are enabled with
+/// `crossterm::event::PushKeyboardEnhancementFlags`.
+///
+/// This type is marked as `#[non_exhaustive]` since more keys may be supported in the future.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Hash, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MediaKey {
+    /// Play media key
+    Play,
+    /// Pause media key
+    Pause,
+    /// Play/Pause media key
+    PlayPause,
+    /// Reverse media key
+    Reverse,
+    /// Stop media key
+    Stop,
+    /// Fast-forward media key
+    FastForward,
+    /// Rewind media key
+    Rewind,
+    /// Next-track media key
+    TrackNext,
+    /// Previous-track media key
+    TrackPrevious,
+    /// Record media key
+    Record,
+    /// Lower-volume media key
+    LowerVolume,
+    /// Raise-volume media key
+    RaiseVolume,
+    /// Mute media key
+    MuteVolume,
+}
+
+/// Modifier key pressed alone. This is supported by crossterm and termwiz only. Note that crossterm reports modifier
+/// keys only when the keyboard enhancement flags are enabled with `crossterm::event::PushKeyboardEnhancementFlags`.
+/// Modifier keys pressed with other keys are reported as the `ctrl`, `alt`, and `shift` fields of [`Input`] instead.
+///
+/// This type is marked as `#[non_exhaustive]` since more keys may be supported in the future.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Hash, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ModifierKey {
+    /// Left shift key
+    LeftShift,
+    /// Left control key
+    LeftControl,
+    /// Left alt key
+    LeftAlt,
+    /// Left super key (Windows key or Command key)
+    LeftSuper,
+    /// Left hyper key
+    LeftHyper,
+    /// Left meta key
+    LeftMeta,
+    /// Right shift key
+    RightShift,
+    /// Right control key
+    RightControl,
+    /// Right alt key
+    RightAlt,
+    /// Right super key (Windows key or Command key)
+    RightSuper,
+    /// Right hyper key
+    RightHyper,
+    /// Right meta key
+    RightMeta,
+    /// ISO level 3 shift key (AltGr key)
+    IsoLevel3Shift,
+    /// ISO level 5 shift key
+    IsoLevel5Shift,
+}
+
 /// Backend-agnostic key input type.
 ///
 /// When `crossterm`, `termion`, `termwiz` features are enabled, converting respective key input types into this
@@ -19,7 +19,8 @@ impl From<KeyEvent> for Input {
     /// when 'Shift + A' is pressed with US keyboard, termion passes `termion::event::Key::Char('A')`. We cannot know
     /// how the 'A' character was input.
     ///
-    /// So the `shift` field of the returned `Input` instance is always `false` except for combinations with arrow keys.
+    /// So the `shift` field of the returned `Input` instance is always `false` except for combinations with arrow keys
+    /// and `termion::event::Key::BackTab` which is converted to `Shift+Tab`.
     /// For example, `termion::event::Key::Char('A')` is converted to `Input { key: Key::Char('A'), shift: false, .. }`.
     fn from(key: KeyEvent) -> Self {
         #[cfg(feature = "termion")]
@@ -39,7 +40,8 @@ impl From<KeyEvent> for Input {
             KeyEvent::ShiftUp
             | KeyEvent::ShiftRight
             | KeyEvent::ShiftDown
-            | KeyEvent::ShiftLeft => (false, false, true),
+            | KeyEvent::ShiftLeft
+            | KeyEvent::BackTab => (false, false, true),
             _ => (false, false, false),
         };
 
@@ -47,6 +49,7 @@ impl From<KeyEvent> for Input {
         let (ctrl, alt, shift) = match key {
             KeyEvent::Ctrl(_) => (true, false, false),
             KeyEvent::Alt(_) => (false, true, false),
+            KeyEvent::BackTab => (false, false, true),
             _ => (false, false, false),
         };
 
@@ -71,6 +74,7 @@ impl From<KeyEvent> for Input {
             KeyEvent::PageDown => Key::PageDown,
             KeyEvent::BackTab => Key::Tab,
             KeyEvent::Delete => Key::Delete,
+            KeyEvent::Insert => Key::Insert,
             KeyEvent::Esc => Key::Esc,
             KeyEvent::F(x) => Key::F(x),
             _ => Key::Null,
@@ -91,6 +95,7 @@ impl From<KeyEvent> for Input {
             KeyEvent::PageDown => Key::PageDown,
             KeyEvent::BackTab => Key::Tab,
             KeyEvent::Delete => Key::Delete,
+            KeyEvent::Insert => Key::Insert,
             KeyEvent::Esc => Key::Esc,
             KeyEvent::F(x) => Key::F(x),
             _ => Key::Null,
@@ -156,7 +161,8 @@ mod tests {
             (KeyEvent::Char('\n'), input(Key::Enter, false, false, false)),
             (KeyEvent::Char('\r'), input(Key::Enter, false, false, false)),
             (KeyEvent::F(1), input(Key::F(1), false, false, false)),
-            (KeyEvent::BackTab, input(Key::Tab, false, false, false)),
+            (KeyEvent::Insert, input(Key::Insert, false, false, false)),
+            (KeyEvent::BackTab, input(Key::Tab, false, false, true)),
             (KeyEvent::Null, input(Key::Null, false, false, false)),
             #[cfg(feature = "termion")]
             (KeyEvent::ShiftDown, input(Key::Down, false, false, true)),
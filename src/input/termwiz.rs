@@ -1,4 +1,4 @@
-use super::{Input, Key};
+use super::{Input, Key, MediaKey, ModifierKey};
 use termwiz::input::{
     InputEvent, KeyCode, KeyEvent, Modifiers, MouseButtons, MouseEvent, PixelMouseEvent,
 };
@@ -37,6 +37,28 @@ impl From<KeyCode> for Key {
             KeyCode::Copy => Key::Copy,
             KeyCode::Cut => Key::Cut,
             KeyCode::Paste => Key::Paste,
+            KeyCode::Insert => Key::Insert,
+            KeyCode::CapsLock => Key::CapsLock,
+            KeyCode::ScrollLock => Key::ScrollLock,
+            KeyCode::NumLock => Key::NumLock,
+            KeyCode::PrintScreen => Key::PrintScreen,
+            KeyCode::Pause => Key::Pause,
+            KeyCode::Menu => Key::Menu,
+            KeyCode::MediaPlayPause => Key::Media(MediaKey::PlayPause),
+            KeyCode::MediaStop => Key::Media(MediaKey::Stop),
+            KeyCode::MediaNextTrack => Key::Media(MediaKey::TrackNext),
+            KeyCode::MediaPrevTrack => Key::Media(MediaKey::TrackPrevious),
+            KeyCode::VolumeDown => Key::Media(MediaKey::LowerVolume),
+            KeyCode::VolumeUp => Key::Media(MediaKey::RaiseVolume),
+            KeyCode::VolumeMute => Key::Media(MediaKey::MuteVolume),
+            KeyCode::LeftShift => Key::Modifier(ModifierKey::LeftShift),
+            KeyCode::LeftControl => Key::Modifier(ModifierKey::LeftControl),
+            KeyCode::LeftAlt => Key::Modifier(ModifierKey::LeftAlt),
+            KeyCode::LeftWindows => Key::Modifier(ModifierKey::LeftSuper),
+            KeyCode::RightShift => Key::Modifier(ModifierKey::RightShift),
+            KeyCode::RightControl => Key::Modifier(ModifierKey::RightControl),
+            KeyCode::RightAlt => Key::Modifier(ModifierKey::RightAlt),
+            KeyCode::RightWindows => Key::Modifier(ModifierKey::RightSuper),
             _ => Key::Null,
         }
     }
@@ -179,7 +201,23 @@ mod tests {
             ),
             (
                 key_event(KeyCode::NumLock, Modifiers::CTRL),
-                input(Key::Null, true, false, false),
+                input(Key::NumLock, true, false, false),
+            ),
+            (
+                key_event(KeyCode::Insert, Modifiers::empty()),
+                input(Key::Insert, false, false, false),
+            ),
+            (
+                key_event(KeyCode::VolumeUp, Modifiers::empty()),
+                input(Key::Media(MediaKey::RaiseVolume), false, false, false),
+            ),
+            (
+                key_event(KeyCode::RightControl, Modifiers::CTRL),
+                input(Key::Modifier(ModifierKey::RightControl), true, false, false),
+            ),
+            (
+                key_event(KeyCode::Numpad0, Modifiers::empty()),
+                input(Key::Null, false, false, false),
             ),
         ] {
             assert_eq!(Input::from(from.clone()), to, "{:?} -> {:?}", from, to);
@@ -0,0 +1,155 @@
+use crate::input::Input;
+use crate::textarea::TextArea;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+// Action bound to a key sequence.
+type KeyActionFn = dyn Fn(&mut TextArea<'_>) -> bool + Send + Sync;
+
+#[derive(Clone)]
+pub(crate) struct KeyAction(Arc<KeyActionFn>);
+
+impl KeyAction {
+    pub(crate) fn call(&self, textarea: &mut TextArea<'_>) -> bool {
+        (self.0)(textarea)
+    }
+}
+
+impl fmt::Debug for KeyAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("KeyAction")
+    }
+}
+
+/// Key bindings of key sequences (chords) such as `g g` or `Space f` to actions on a textarea.
+///
+/// A keymap is set to a textarea by [`TextArea::set_keymap`](https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.set_keymap).
+/// Then [`TextArea::input`](https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.input) holds
+/// keys while they are a prefix of some key sequence in the keymap and calls the action when the whole sequence is
+/// typed. When the typed keys turn out not to be bound, they are handled as usual inputs.
+///
+/// When a key sequence is bound and it is also a prefix of a longer sequence (like `g` and `g g`), the textarea waits
+/// for the next key until the timeout and then calls the action of the shorter sequence. The default timeout is one
+/// second.
+/// ```
+/// use tui_textarea::{TextArea, Keymap, Input, Key, CursorMove};
+///
+/// let key = |c| Input { key: Key::Char(c), ..Default::default() };
+///
+/// let mut keymap = Keymap::new();
+/// keymap.bind([key('g'), key('g')], |t| {
+///     t.move_cursor(CursorMove::Top);
+///     false
+/// });
+///
+/// let mut textarea = TextArea::from(["hello", "world"]);
+/// textarea.move_cursor(CursorMove::Bottom);
+/// textarea.set_keymap(keymap);
+///
+/// textarea.input(key('g'));
+/// assert_eq!(textarea.pending_keys(), [key('g')]);
+/// textarea.input(key('g'));
+/// assert!(textarea.pending_keys().is_empty());
+/// assert_eq!(textarea.cursor(), (0, 0));
+///
+/// // Keys which are not bound are inserted as usual
+/// textarea.input(key('g'));
+/// textarea.input(key('!'));
+/// assert_eq!(textarea.lines(), ["g!hello", "world"]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Keymap {
+    bindings: HashMap<Vec<Input>, KeyAction>,
+    timeout: Option<Duration>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Keymap {
+    /// Create a new empty keymap with one second timeout.
+    pub fn new() -> Self {
+        Self {
+            bindings: HashMap::new(),
+            timeout: Some(Duration::from_secs(1)),
+        }
+    }
+
+    /// Bind the key sequence to the action. The action is called with the textarea and returns if the text was
+    /// modified or not. Binding the same key sequence again replaces the previous action. An empty key sequence is
+    /// ignored.
+    ///
+    /// While the action is running, the keymap is not applied to the inputs. So the action can call
+    /// [`TextArea::input`](https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.input) to
+    /// handle the keys as usual inputs.
+    pub fn bind<K, F>(&mut self, keys: impl IntoIterator<Item = K>, action: F)
+    where
+        K: Into<Input>,
+        F: Fn(&mut TextArea<'_>) -> bool + Send + Sync + 'static,
+    {
+        let keys: Vec<_> = keys.into_iter().map(Into::into).collect();
+        if !keys.is_empty() {
+            self.bindings.insert(keys, KeyAction(Arc::new(action)));
+        }
+    }
+
+    /// Remove the binding of the key sequence. This method returns `false` when the key sequence was not bound.
+    /// ```
+    /// use tui_textarea::{Keymap, Input, Key};
+    ///
+    /// let mut keymap = Keymap::new();
+    /// let space = Input { key: Key::Char(' '), ..Default::default() };
+    /// let f = Input { key: Key::Char('f'), ..Default::default() };
+    ///
+    /// keymap.bind([space.clone(), f.clone()], |_| false);
+    /// assert!(keymap.is_bound([space.clone(), f.clone()]));
+    /// assert!(!keymap.is_bound([space.clone()]));
+    ///
+    /// assert!(keymap.unbind([space.clone(), f.clone()]));
+    /// assert!(!keymap.unbind([space, f]));
+    /// ```
+    pub fn unbind<K: Into<Input>>(&mut self, keys: impl IntoIterator<Item = K>) -> bool {
+        let keys: Vec<_> = keys.into_iter().map(Into::into).collect();
+        self.bindings.remove(&keys).is_some()
+    }
+
+    /// Check if the key sequence is bound to some action.
+    pub fn is_bound<K: Into<Input>>(&self, keys: impl IntoIterator<Item = K>) -> bool {
+        let keys: Vec<_> = keys.into_iter().map(Into::into).collect();
+        self.bindings.contains_key(&keys)
+    }
+
+    /// Remove all bindings.
+    pub fn clear(&mut self) {
+        self.bindings.clear();
+    }
+
+    /// Set how long the textarea waits for the next key of a key sequence. When the timeout passed, the pending keys
+    /// are resolved by the next input or by
+    /// [`TextArea::process_pending_keys`](https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.process_pending_keys).
+    /// `None` means waiting for the next key forever.
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// Get how long the textarea waits for the next key of a key sequence.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    pub(crate) fn get(&self, keys: &[Input]) -> Option<&KeyAction> {
+        self.bindings.get(keys)
+    }
+
+    // Check if some key sequence longer than the keys starts with them
+    pub(crate) fn has_longer(&self, keys: &[Input]) -> bool {
+        self.bindings
+            .keys()
+            .any(|k| k.len() > keys.len() && k.starts_with(keys))
+    }
+}
@@ -7,16 +7,44 @@
 #[cfg(all(feature = "ratatui", feature = "tuirs"))]
 compile_error!("ratatui support and tui-rs support are exclusive. only one of them can be enabled at the same time. see https://github.com/rhysd/tui-textarea#installation");
 
+mod anchor;
+mod completion;
+mod conceal;
+mod control;
 mod cursor;
+mod damage;
+mod diff;
+mod expand;
+#[cfg(feature = "file")]
+mod file;
+mod form;
+#[cfg(feature = "arbitrary")]
+mod fuzz;
 mod highlight;
 mod history;
+mod indent;
 mod input;
+mod keymap;
+mod line_ending;
+mod line_meta;
+mod link;
+mod mask;
+mod metrics;
+mod placeholder;
+mod prompt;
 mod scroll;
 #[cfg(feature = "search")]
 mod search;
+mod state;
+mod text_input;
 mod textarea;
+mod textarea_set;
 mod util;
+#[cfg(feature = "vim")]
+mod vim;
+mod whitespace;
 mod widget;
+mod width;
 mod word;
 
 #[cfg(feature = "ratatui")]
@@ -37,7 +65,30 @@ use termion;
 #[cfg(feature = "tuirs-termion")]
 use termion_15 as termion;
 
-pub use cursor::CursorMove;
-pub use input::{Input, Key};
+pub use anchor::{AnchorGrowth, AnchorId};
+pub use control::ControlCharStyle;
+pub use cursor::{CursorChange, CursorMove};
+pub use diff::DiffKind;
+#[cfg(feature = "file")]
+pub use file::Encoding;
+pub use form::Form;
+pub use history::{EditRecord, EditRecordKind};
+pub use indent::IndentPolicy;
+pub use input::{Input, Key, MediaKey, ModifierKey};
+pub use keymap::Keymap;
+pub use line_ending::LineEnding;
+pub use mask::MaskPolicy;
+pub use placeholder::PlaceholderCursor;
 pub use scroll::Scrolling;
+#[cfg(feature = "search")]
+pub use search::{MatchAction, MatchRef, SearchDirection, SearchMode};
+pub use state::{PersistentState, Snapshot};
+pub use text_input::TextInput;
 pub use textarea::TextArea;
+pub use textarea_set::TextAreaSet;
+#[cfg(feature = "vim")]
+pub use vim::{VimMode, VimState};
+pub use whitespace::WhitespaceStyle;
+pub use widget::TextAreaState;
+pub use width::{CharWidth, UnicodeWidth};
+pub use word::{WordBoundary, WordScanner};
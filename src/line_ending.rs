@@ -0,0 +1,93 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Line ending used when the lines in the textarea are joined into one text such as [`TextArea::text`] and
+/// [`TextArea::yank_text`].
+///
+/// Note that text lines in the textarea never contain line endings. Both `\n` and `\r\n` in text inserted into the
+/// textarea are recognized as newlines regardless of this setting.
+///
+/// This type is marked as `#[non_exhaustive]` since more variations may be supported in the future. See also:
+/// [`TextArea::set_line_ending`]
+///
+/// [`TextArea::text`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.text
+/// [`TextArea::yank_text`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.yank_text
+/// [`TextArea::set_line_ending`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.set_line_ending
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum LineEnding {
+    /// `\n` (Unix style). This is the default.
+    Lf,
+    /// `\r\n` (Windows style).
+    CrLf,
+}
+
+impl LineEnding {
+    /// Get the string of the line ending.
+    /// ```
+    /// use tui_textarea::LineEnding;
+    ///
+    /// assert_eq!(LineEnding::Lf.as_str(), "\n");
+    /// assert_eq!(LineEnding::CrLf.as_str(), "\r\n");
+    /// ```
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Lf => "\n",
+            Self::CrLf => "\r\n",
+        }
+    }
+}
+
+// Detect the line ending of the first line in the text and whether the text contains both kinds of line endings
+pub fn detect(text: &str) -> (Option<LineEnding>, bool) {
+    let mut detected = None;
+    for (i, _) in text.match_indices('\n') {
+        let found = if text[..i].ends_with('\r') {
+            LineEnding::CrLf
+        } else {
+            LineEnding::Lf
+        };
+        match detected {
+            None => detected = Some(found),
+            Some(le) if le != found => return (detected, true),
+            Some(_) => {}
+        }
+    }
+    (detected, false)
+}
+
+impl Default for LineEnding {
+    fn default() -> Self {
+        Self::Lf
+    }
+}
+
+impl fmt::Display for LineEnding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_line_endings() {
+        let tests = [
+            ("", (None, false)),
+            ("abc", (None, false)),
+            ("a\nb\n", (Some(LineEnding::Lf), false)),
+            ("a\r\nb\r\n", (Some(LineEnding::CrLf), false)),
+            ("a\r\nb\nc", (Some(LineEnding::CrLf), true)),
+            ("a\nb\r\nc", (Some(LineEnding::Lf), true)),
+            ("\r\n", (Some(LineEnding::CrLf), false)),
+            ("a\rb\n", (Some(LineEnding::Lf), false)),
+        ];
+        for (text, expected) in tests {
+            assert_eq!(detect(text), expected, "{:?}", text);
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use crate::history::Change;
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+// Arbitrary data associated with lines. Like anchors, the rows follow edits so that the data stays with the lines
+// in the current text.
+#[derive(Clone, Default)]
+pub struct LineMeta {
+    rows: BTreeMap<usize, Arc<dyn Any + Send + Sync>>,
+}
+
+impl LineMeta {
+    pub fn set(&mut self, row: usize, meta: Arc<dyn Any + Send + Sync>) {
+        self.rows.insert(row, meta);
+    }
+
+    pub fn get(&self, row: usize) -> Option<&(dyn Any + Send + Sync)> {
+        self.rows.get(&row).map(|m| m.as_ref())
+    }
+
+    pub fn remove(&mut self, row: usize) -> bool {
+        self.rows.remove(&row).is_some()
+    }
+
+    pub fn clear(&mut self) {
+        self.rows.clear();
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = usize> + '_ {
+        self.rows.keys().copied()
+    }
+
+    pub fn follow(&mut self, change: &Change) {
+        if self.rows.is_empty() {
+            return;
+        }
+        let (start, old_end, new_end) = change.rows();
+        let (start_col, old_end_col) = change.cols();
+
+        let mut replaced = self.rows.split_off(&start);
+        let after = replaced.split_off(&(old_end + 1));
+
+        // When the edit starts and ends at the head of lines, such as lines inserted above the line or whole lines
+        // deleted, the line at the end is the line kept after the edit. Otherwise the line at the start is kept, such
+        // as the first line of split or joined lines. Data of the other replaced lines is dropped.
+        let kept = if start_col == 0 && old_end_col == 0 {
+            replaced.remove(&old_end).map(|m| (new_end, m))
+        } else {
+            replaced.remove(&start).map(|m| (start, m))
+        };
+        if let Some((row, meta)) = kept {
+            self.rows.insert(row, meta);
+        }
+        for (row, meta) in after {
+            self.rows.insert(row - old_end + new_end, meta);
+        }
+    }
+}
+
+impl fmt::Debug for LineMeta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LineMeta")
+            .field("rows", &self.rows.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::EditKind;
+    use crate::util::Pos;
+
+    fn change(kind: EditKind, before: (usize, usize), after: (usize, usize)) -> Change {
+        // Offsets equal to columns since the test text is ASCII
+        let before = Pos::new(before.0, before.1, before.1);
+        let after = Pos::new(after.0, after.1, after.1);
+        Change::new(&kind, &before, &after)
+    }
+
+    fn follow(rows: &[usize], change: &Change) -> Vec<(usize, usize)> {
+        let mut meta = LineMeta::default();
+        for &row in rows {
+            meta.set(row, Arc::new(row));
+        }
+        meta.follow(change);
+        meta.rows
+            .iter()
+            .map(|(r, m)| (*m.downcast_ref::<usize>().unwrap(), *r))
+            .collect()
+    }
+
+    #[test]
+    fn follow_edits() {
+        let lines = |s: &str| Arc::new(s.split('\n').map(String::from).collect::<Vec<_>>());
+        let tests = [
+            // Split the line at the middle
+            (
+                change(EditKind::InsertNewline, (1, 2), (2, 0)),
+                vec![(0, 0), (1, 1), (2, 3)],
+            ),
+            // Split the line at the head
+            (
+                change(EditKind::InsertNewline, (1, 0), (2, 0)),
+                vec![(0, 0), (1, 2), (2, 3)],
+            ),
+            // Join the line with the next line
+            (
+                change(EditKind::DeleteNewline, (2, 0), (1, 3)),
+                vec![(0, 0), (1, 1)],
+            ),
+            // Insert lines above the line
+            (
+                change(EditKind::InsertChunk(lines("a\nb\n")), (1, 0), (3, 0)),
+                vec![(0, 0), (1, 3), (2, 4)],
+            ),
+            // Delete whole lines
+            (
+                change(EditKind::DeleteChunk(lines("abc\n")), (2, 0), (1, 0)),
+                vec![(0, 0), (2, 1)],
+            ),
+            // Delete lines across the middle of the lines
+            (
+                change(EditKind::DeleteChunk(lines("c\ndef\ng")), (3, 1), (1, 2)),
+                vec![(0, 0), (1, 1)],
+            ),
+        ];
+        for (change, want) in tests {
+            assert_eq!(follow(&[0, 1, 2], &change), want, "{:?}", change.rows());
+        }
+    }
+}
@@ -0,0 +1,135 @@
+// Schemes of URLs detected as links. `mailto:` has no slashes after the scheme.
+const SCHEMES: &[&str] = &["https://", "http://", "ftp://", "file://", "mailto:"];
+
+// Characters which cannot be part of a URL written in a text
+fn is_url_char(c: char) -> bool {
+    !c.is_whitespace() && !c.is_control() && !matches!(c, '<' | '>' | '"' | '`')
+}
+
+// Length of the scheme at the head of the string if it is one of the known schemes
+fn scheme_len(s: &str) -> Option<usize> {
+    SCHEMES.iter().find_map(|scheme| {
+        let head = s.get(..scheme.len())?;
+        head.eq_ignore_ascii_case(scheme).then(|| scheme.len())
+    })
+}
+
+// Trim punctuations at the end of the URL which are likely part of the sentence around it such as `.` in
+// "see https://example.com." or `)` in "(https://example.com)". A closing bracket is kept when the URL contains the
+// opening one like "https://en.wikipedia.org/wiki/Rust_(programming_language)".
+fn trim_url_end(url: &str) -> usize {
+    let mut end = url.len();
+    while let Some(c) = url[..end].chars().next_back() {
+        let trimmed = match c {
+            '.' | ',' | ':' | ';' | '!' | '?' | '\'' => true,
+            ')' | ']' | '}' => {
+                let open = match c {
+                    ')' => '(',
+                    ']' => '[',
+                    _ => '{',
+                };
+                let s = &url[..end];
+                s.matches(open).count() < s.matches(c).count()
+            }
+            _ => false,
+        };
+        if !trimmed {
+            break;
+        }
+        end -= c.len_utf8();
+    }
+    end
+}
+
+// Find the next link at or after the byte offset `start` in the line. The byte offsets of the start and the end of the
+// link are returned.
+fn next_link(line: &str, mut start: usize) -> Option<(usize, usize)> {
+    while let Some(i) = line[start..].find(|c: char| c.is_ascii_alphabetic()) {
+        let head = start + i;
+        let after_word_char = line[..head]
+            .chars()
+            .next_back()
+            .map_or(false, |c| c.is_alphanumeric() || c == '_');
+        if !after_word_char {
+            if let Some(len) = scheme_len(&line[head..]) {
+                let rest = &line[head..];
+                let len_url = rest.find(|c| !is_url_char(c)).unwrap_or(rest.len());
+                let end = head + trim_url_end(&rest[..len_url]);
+                if end > head + len {
+                    return Some((head, end));
+                }
+            }
+        }
+        // Skip the rest of the word
+        let rest = &line[head..];
+        start = head
+            + rest
+                .find(|c: char| !c.is_alphanumeric())
+                .unwrap_or(rest.len());
+    }
+    None
+}
+
+// Iterator of the byte offset ranges of URLs in a line
+pub struct Links<'a> {
+    line: &'a str,
+    offset: usize,
+}
+
+impl<'a> Links<'a> {
+    pub fn new(line: &'a str) -> Self {
+        Self { line, offset: 0 }
+    }
+}
+
+impl Iterator for Links<'_> {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (start, end) = next_link(self.line, self.offset)?;
+        self.offset = end;
+        Some((start, end))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn links(line: &str) -> Vec<&str> {
+        Links::new(line).map(|(s, e)| &line[s..e]).collect()
+    }
+
+    #[test]
+    fn find_links() {
+        let tests: &[(&str, &[&str])] = &[
+            ("", &[]),
+            ("no link here", &[]),
+            ("https://example.com", &["https://example.com"]),
+            (
+                "see https://example.com/a?b=c#d and http://x.org",
+                &["https://example.com/a?b=c#d", "http://x.org"],
+            ),
+            ("see https://example.com.", &["https://example.com"]),
+            ("(https://example.com)", &["https://example.com"]),
+            (
+                "https://en.wikipedia.org/wiki/Rust_(programming_language)",
+                &["https://en.wikipedia.org/wiki/Rust_(programming_language)"],
+            ),
+            ("<https://example.com>", &["https://example.com"]),
+            ("\"https://example.com\"", &["https://example.com"]),
+            ("HTTPS://EXAMPLE.COM", &["HTTPS://EXAMPLE.COM"]),
+            ("mailto:foo@example.com", &["mailto:foo@example.com"]),
+            ("file:///tmp/a.txt", &["file:///tmp/a.txt"]),
+            ("https://", &[]),
+            ("https://.", &[]),
+            ("xhttps://example.com", &[]),
+            ("_https://example.com", &[]),
+            ("あhttps://example.com", &[]),
+            ("git://example.com", &[]),
+        ];
+        for (line, expected) in tests {
+            assert_eq!(&links(line), expected, "{:?}", line);
+        }
+    }
+}
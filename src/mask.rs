@@ -0,0 +1,31 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Specify which part of the text is masked when a mask character is set by [`TextArea::set_mask_char`].
+///
+/// This type is marked as `#[non_exhaustive]` since more variations may be supported in the future. See also:
+/// [`TextArea::set_mask_policy`]
+///
+/// [`TextArea::set_mask_char`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.set_mask_char
+/// [`TextArea::set_mask_policy`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.set_mask_policy
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MaskPolicy {
+    /// Mask all characters in the textarea. This is the default policy.
+    All,
+    /// Mask all characters except for the last N characters of each line. This is useful for credential-style fields
+    /// which show only the last few digits such as a card number.
+    AllExceptLast(usize),
+    /// Mask all characters but reveal the character which was typed last while the given duration has not elapsed
+    /// since it was typed. The character is revealed only while the cursor stays just after it. Note that the
+    /// textarea needs to be rendered again after the duration to mask the character.
+    RevealWhile(Duration),
+}
+
+impl Default for MaskPolicy {
+    fn default() -> Self {
+        Self::All
+    }
+}
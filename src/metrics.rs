@@ -0,0 +1,188 @@
+use crate::history::EditKind;
+use crate::util::Pos;
+
+// Statistics of the text which are updated incrementally on each edit so that they can be retrieved in O(1).
+#[derive(Clone, Debug, Default)]
+pub struct Metrics {
+    chars: usize,
+    bytes: usize,
+    words: usize,
+    line_words: Vec<usize>, // Number of words in each line
+    edits: u64, // Number of edits applied to the text, which is not a statistic of the text itself
+}
+
+impl PartialEq for Metrics {
+    fn eq(&self, other: &Self) -> bool {
+        self.chars == other.chars
+            && self.bytes == other.bytes
+            && self.words == other.words
+            && self.line_words == other.line_words
+    }
+}
+
+fn count_words(line: &str) -> usize {
+    line.split_whitespace().count()
+}
+
+// Numbers of words around the range before and after an edit in the line. The edit inserted `line[start..end]` and
+// removed `removed` at `start`. Words are counted from the whitespace before the range to the whitespace after it
+// since words outside them are not affected by the edit.
+fn count_words_around(line: &str, start: usize, end: usize, removed: &str) -> (usize, usize) {
+    let head = line[..start].rfind(char::is_whitespace).unwrap_or(0);
+    let tail = line[end..]
+        .find(char::is_whitespace)
+        .map_or(line.len(), |i| end + i);
+    let old = format!("{}{}{}", &line[head..start], removed, &line[end..tail]);
+    (count_words(&old), count_words(&line[head..tail]))
+}
+
+impl Metrics {
+    pub fn new(lines: &[String]) -> Self {
+        let line_words: Vec<_> = lines.iter().map(|l| count_words(l)).collect();
+        let newlines = lines.len().saturating_sub(1);
+        Self {
+            chars: lines.iter().map(|l| l.chars().count()).sum::<usize>() + newlines,
+            bytes: lines.iter().map(String::len).sum::<usize>() + newlines,
+            words: line_words.iter().sum(),
+            line_words,
+            edits: 0,
+        }
+    }
+
+    // Update the metrics after the edit was applied to the lines. `before` and `after` are the positions passed to
+    // `EditKind::apply`.
+    pub fn update(&mut self, lines: &[String], kind: &EditKind, before: &Pos, after: &Pos) {
+        use EditKind::*;
+
+        self.edits += 1;
+        let (chars, bytes, rows) = match kind {
+            InsertChar(c) | DeleteChar(c) => (1, c.len_utf8(), 1),
+            InsertNewline | DeleteNewline => (1, 1, 2),
+            InsertStr(s) | DeleteStr(s) => (s.chars().count(), s.len(), 1),
+            InsertChunk(c) | DeleteChunk(c) => {
+                let newlines = c.len() - 1;
+                let chars = c.iter().map(|l| l.chars().count()).sum::<usize>() + newlines;
+                let bytes = c.iter().map(String::len).sum::<usize>() + newlines;
+                (chars, bytes, c.len())
+            }
+        };
+
+        let (row, old_rows) = match kind {
+            InsertChar(_) | InsertNewline | InsertStr(_) | InsertChunk(_) => {
+                self.chars += chars;
+                self.bytes += bytes;
+                (before.row, 1)
+            }
+            DeleteChar(_) | DeleteNewline | DeleteStr(_) | DeleteChunk(_) => {
+                self.chars -= chars;
+                self.bytes -= bytes;
+                (after.row, rows)
+            }
+        };
+
+        // Edits in a line recount words only around the edited range so that editing a very long line is fast
+        let mut buf = [0; 4];
+        let in_line = match kind {
+            InsertChar(_) | InsertStr(_) => Some((before.offset, before.offset + bytes, "")),
+            DeleteStr(s) => Some((after.offset, after.offset, s.as_str())),
+            DeleteChar(c) => Some((after.offset, after.offset, &*c.encode_utf8(&mut buf))),
+            _ => None,
+        };
+        if let Some((start, end, removed)) = in_line {
+            let line = &lines[row];
+            if end <= line.len() && line.is_char_boundary(start) && line.is_char_boundary(end) {
+                let (old, new) = count_words_around(line, start, end, removed);
+                self.line_words[row] = self.line_words[row] - old + new;
+                self.words = self.words - old + new;
+                return;
+            }
+        }
+
+        // Recount words only in the lines modified by the edit
+        let old_rows = old_rows.min(self.line_words.len() - row);
+        let new_rows = old_rows + lines.len() - self.line_words.len();
+        let removed: usize = self
+            .line_words
+            .splice(
+                row..row + old_rows,
+                lines[row..row + new_rows].iter().map(|l| count_words(l)),
+            )
+            .sum();
+        self.words -= removed;
+        self.words += self.line_words[row..row + new_rows].iter().sum::<usize>();
+    }
+
+    pub fn chars(&self) -> usize {
+        self.chars
+    }
+
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    pub fn words(&self) -> usize {
+        self.words
+    }
+
+    pub fn edits(&self) -> u64 {
+        self.edits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn new_metrics() {
+        let tests: &[(&[&str], usize, usize, usize)] = &[
+            (&[""], 0, 0, 0),
+            (&["hello world"], 11, 11, 2),
+            (&["  hello ", "", "  world\t!"], 19, 19, 3),
+            (&["あいう えお"], 6, 16, 2),
+        ];
+        for (lines, chars, bytes, words) in tests {
+            let lines: Vec<_> = lines.iter().map(|s| s.to_string()).collect();
+            let m = Metrics::new(&lines);
+            assert_eq!(m.chars(), *chars, "{:?}", lines);
+            assert_eq!(m.bytes(), *bytes, "{:?}", lines);
+            assert_eq!(m.words(), *words, "{:?}", lines);
+        }
+    }
+
+    #[test]
+    fn update_words_in_line() {
+        let tests = [
+            ("foo bar", "foo  bar", EditKind::InsertChar(' '), 3, 4),
+            ("foo bar", "foobar", EditKind::DeleteChar(' '), 4, 3),
+            ("foo bar", "foo b ar", EditKind::InsertChar(' '), 5, 6),
+            (
+                "foo bar baz",
+                "foo baz",
+                EditKind::DeleteStr(Arc::new("bar ".into())),
+                8,
+                4,
+            ),
+            (
+                "foo",
+                "foo bar piyo",
+                EditKind::InsertStr(Arc::new(" bar piyo".into())),
+                3,
+                12,
+            ),
+            ("  ", "  a", EditKind::InsertChar('a'), 2, 3),
+            ("a", "", EditKind::DeleteChar('a'), 1, 0),
+        ];
+        for (before, after, kind, before_offset, after_offset) in tests {
+            let mut m = Metrics::new(&[before.to_string()]);
+            let lines = [after.to_string()];
+            let (b, a) = (
+                Pos::new(0, before_offset, before_offset),
+                Pos::new(0, after_offset, after_offset),
+            );
+            m.update(&lines, &kind, &b, &a);
+            assert_eq!(m, Metrics::new(&lines), "{:?} -> {:?}", before, after);
+        }
+    }
+}
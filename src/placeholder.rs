@@ -0,0 +1,27 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Specify how the cursor is rendered while the placeholder text is shown in the empty textarea.
+///
+/// This type is marked as `#[non_exhaustive]` since more variations may be supported in the future. See also:
+/// [`TextArea::set_placeholder_cursor`]
+///
+/// [`TextArea::set_placeholder_cursor`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.set_placeholder_cursor
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PlaceholderCursor {
+    /// Render the cursor as a blank cell before the placeholder text. This is the default.
+    Before,
+    /// Render the cursor over the first character of the placeholder text. The placeholder text is not shifted by the
+    /// cursor cell.
+    Over,
+    /// Don't render the cursor while the placeholder text is shown.
+    Hidden,
+}
+
+impl Default for PlaceholderCursor {
+    fn default() -> Self {
+        Self::Before
+    }
+}
@@ -0,0 +1,98 @@
+// Input history of prompts recalled like readline. While recalling the history, the text being edited is stashed and
+// it is restored after the latest entry.
+#[derive(Clone, Debug, Default)]
+pub struct PromptHistory {
+    entries: Vec<String>,
+    index: Option<usize>, // `None` while editing a new entry
+    stash: String,
+}
+
+impl PromptHistory {
+    // Empty entries and the same entry as the latest one are ignored. The recall is reset since a new entry is
+    // usually pushed on submitting the text.
+    pub fn push(&mut self, entry: String) {
+        self.reset();
+        if !entry.is_empty() && self.entries.last() != Some(&entry) {
+            self.entries.push(entry);
+        }
+    }
+
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.reset();
+    }
+
+    pub fn reset(&mut self) {
+        self.index = None;
+        self.stash.clear();
+    }
+
+    // Returns the previous entry to replace the current text with
+    pub fn prev(&mut self, current: String) -> Option<&str> {
+        let index = match self.index {
+            Some(0) => return None,
+            Some(i) => i - 1,
+            None if self.entries.is_empty() => return None,
+            None => {
+                self.stash = current;
+                self.entries.len() - 1
+            }
+        };
+        self.index = Some(index);
+        Some(&self.entries[index])
+    }
+
+    // Returns the next entry or the stashed text to replace the current text with
+    pub fn next(&mut self) -> Option<String> {
+        match self.index {
+            None => None,
+            Some(i) if i + 1 < self.entries.len() => {
+                self.index = Some(i + 1);
+                Some(self.entries[i + 1].clone())
+            }
+            Some(_) => {
+                self.index = None;
+                Some(std::mem::take(&mut self.stash))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recall_entries() {
+        let mut h = PromptHistory::default();
+        assert_eq!(h.prev("draft".into()), None);
+        assert_eq!(h.next(), None);
+
+        for e in ["a", "b", "", "b", "c"] {
+            h.push(e.into());
+        }
+        assert_eq!(h.entries(), ["a", "b", "c"]);
+
+        assert_eq!(h.prev("draft".into()), Some("c"));
+        assert_eq!(h.prev("c".into()), Some("b"));
+        assert_eq!(h.prev("b".into()), Some("a"));
+        assert_eq!(h.prev("a".into()), None);
+        assert_eq!(h.next().as_deref(), Some("b"));
+        assert_eq!(h.next().as_deref(), Some("c"));
+        assert_eq!(h.next().as_deref(), Some("draft"));
+        assert_eq!(h.next(), None);
+
+        h.prev("x".into());
+        h.push("d".into());
+        assert_eq!(h.next(), None);
+        assert_eq!(h.prev("".into()), Some("d"));
+
+        h.clear();
+        assert!(h.entries().is_empty());
+        assert_eq!(h.next(), None);
+    }
+}
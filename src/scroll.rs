@@ -1,15 +1,17 @@
 use crate::widget::Viewport;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+use std::cmp;
 
 /// Specify how to scroll the textarea.
 ///
 /// This type is marked as `#[non_exhaustive]` since more variations may be supported in the future. Note that the cursor will
-/// not move until it goes out the viewport. See also: [`TextArea::scroll`]
+/// not move until it goes out the viewport. The scroll position is clamped so that the top row of the viewport does not go
+/// beyond the last line and the left column does not go beyond the end of the longest line. See also: [`TextArea::scroll`]
 ///
 /// [`TextArea::scroll`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.scroll
 #[non_exhaustive]
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Scrolling {
     /// Scroll the textarea by rows (vertically) and columns (horizontally). Passing positive scroll amounts to `rows` and `cols`
@@ -40,7 +42,9 @@ pub enum Scrolling {
     /// assert_eq!(textarea.cursor(), (3, 0));
     /// ```
     Delta { rows: i16, cols: i16 },
-    /// Scroll down the textarea by one page.
+    /// Scroll down the textarea by one page. The rows set by [`TextArea::set_page_overlap`] are kept visible.
+    ///
+    /// [`TextArea::set_page_overlap`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.set_page_overlap
     ///
     /// ```
     /// # use ratatui::buffer::Buffer;
@@ -66,7 +70,9 @@ pub enum Scrolling {
     /// assert_eq!(textarea.cursor(), (19, 0)); // Reached bottom of the textarea
     /// ```
     PageDown,
-    /// Scroll up the textarea by one page.
+    /// Scroll up the textarea by one page. The rows set by [`TextArea::set_page_overlap`] are kept visible.
+    ///
+    /// [`TextArea::set_page_overlap`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.set_page_overlap
     ///
     /// ```
     /// # use ratatui::buffer::Buffer;
@@ -152,30 +158,154 @@ pub enum Scrolling {
     /// assert_eq!(textarea.cursor(), (11, 0));
     /// ```
     HalfPageUp,
+    /// Scroll the textarea by rows. Positive numbers scroll it down and negative numbers scroll it up. This is the same
+    /// as `Scrolling::Delta { rows, cols: 0 }`.
+    ///
+    /// ```
+    /// # use ratatui::buffer::Buffer;
+    /// # use ratatui::layout::Rect;
+    /// # use ratatui::widgets::Widget as _;
+    /// use tui_textarea::{TextArea, Scrolling};
+    ///
+    /// // Let's say terminal height is 8.
+    ///
+    /// // Create textarea with 20 lines "0", "1", "2", "3", ...
+    /// let mut textarea: TextArea = (0..20).into_iter().map(|i| i.to_string()).collect();
+    /// # // Call `render` at least once to populate terminal size
+    /// # let r = Rect { x: 0, y: 0, width: 24, height: 8 };
+    /// # let mut b = Buffer::empty(r.clone());
+    /// # textarea.render(r, &mut b);
+    ///
+    /// textarea.scroll(Scrolling::Lines(3));
+    /// assert_eq!(textarea.scroll_offset(), (3, 0));
+    ///
+    /// // The top row does not go beyond the last line
+    /// textarea.scroll(Scrolling::Lines(100));
+    /// assert_eq!(textarea.scroll_offset(), (19, 0));
+    /// ```
+    Lines(i16),
+    /// Scroll the textarea by columns. Positive numbers scroll it right and negative numbers scroll it left. This is
+    /// the same as `Scrolling::Delta { rows: 0, cols }`.
+    ///
+    /// ```
+    /// # use ratatui::buffer::Buffer;
+    /// # use ratatui::layout::Rect;
+    /// # use ratatui::widgets::Widget as _;
+    /// use tui_textarea::{TextArea, Scrolling};
+    ///
+    /// let mut textarea = TextArea::from(["abcdefghijklmnopqrstuvwxyz"]);
+    /// # let r = Rect { x: 0, y: 0, width: 8, height: 4 };
+    /// # let mut b = Buffer::empty(r.clone());
+    /// # textarea.render(r, &mut b);
+    ///
+    /// textarea.scroll(Scrolling::Columns(10));
+    /// assert_eq!(textarea.scroll_offset(), (0, 10));
+    /// // The cursor was moved into the viewport
+    /// assert_eq!(textarea.cursor(), (0, 10));
+    ///
+    /// // The left column does not go beyond the end of the longest line
+    /// textarea.scroll(Scrolling::Columns(100));
+    /// assert_eq!(textarea.scroll_offset(), (0, 26));
+    /// ```
+    Columns(i16),
+    /// Scroll the textarea vertically by the number of pages. One page is the height of the viewport. Positive numbers
+    /// scroll it down and negative numbers scroll it up. For example, `Scrolling::Pages(1.0)` is the same as
+    /// [`Scrolling::PageDown`] without page overlap and `Scrolling::Pages(-0.5)` is the same as
+    /// [`Scrolling::HalfPageUp`]. The number of rows is rounded toward zero. Unlike [`Scrolling::PageDown`], the
+    /// overlap set by [`TextArea::set_page_overlap`] is not applied.
+    ///
+    /// [`TextArea::set_page_overlap`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.set_page_overlap
+    ///
+    /// ```
+    /// # use ratatui::buffer::Buffer;
+    /// # use ratatui::layout::Rect;
+    /// # use ratatui::widgets::Widget as _;
+    /// use tui_textarea::{TextArea, Scrolling};
+    ///
+    /// // Let's say terminal height is 8.
+    ///
+    /// // Create textarea with 20 lines "0", "1", "2", "3", ...
+    /// let mut textarea: TextArea = (0..20).into_iter().map(|i| i.to_string()).collect();
+    /// # // Call `render` at least once to populate terminal size
+    /// # let r = Rect { x: 0, y: 0, width: 24, height: 8 };
+    /// # let mut b = Buffer::empty(r.clone());
+    /// # textarea.render(r, &mut b);
+    ///
+    /// // Scroll down by a quarter page (2 lines)
+    /// textarea.scroll(Scrolling::Pages(0.25));
+    /// assert_eq!(textarea.scroll_offset(), (2, 0));
+    /// assert_eq!(textarea.cursor(), (2, 0));
+    /// ```
+    Pages(f32),
+    /// Scroll the textarea horizontally by the number of pages. One page is the width of the viewport. Positive
+    /// numbers scroll it right and negative numbers scroll it left. For example, `Scrolling::HorizontalPages(0.5)`
+    /// scrolls it right by half of the viewport width. The number of columns is rounded toward zero.
+    ///
+    /// ```
+    /// # use ratatui::buffer::Buffer;
+    /// # use ratatui::layout::Rect;
+    /// # use ratatui::widgets::Widget as _;
+    /// use tui_textarea::{TextArea, Scrolling};
+    ///
+    /// let mut textarea = TextArea::from(["abcdefghijklmnopqrstuvwxyz"]);
+    /// # let r = Rect { x: 0, y: 0, width: 8, height: 4 };
+    /// # let mut b = Buffer::empty(r.clone());
+    /// # textarea.render(r, &mut b);
+    ///
+    /// // Let's say terminal width is 8. Scroll right by half of the width
+    /// textarea.scroll(Scrolling::HorizontalPages(0.5));
+    /// assert_eq!(textarea.scroll_offset(), (0, 4));
+    /// ```
+    HorizontalPages(f32),
+    /// Scroll the textarea to show the row at the top of the viewport. The row is clamped to the last line.
+    ///
+    /// ```
+    /// # use ratatui::buffer::Buffer;
+    /// # use ratatui::layout::Rect;
+    /// # use ratatui::widgets::Widget as _;
+    /// use tui_textarea::{TextArea, Scrolling};
+    ///
+    /// // Create textarea with 20 lines "0", "1", "2", "3", ...
+    /// let mut textarea: TextArea = (0..20).into_iter().map(|i| i.to_string()).collect();
+    /// # let r = Rect { x: 0, y: 0, width: 24, height: 8 };
+    /// # let mut b = Buffer::empty(r.clone());
+    /// # textarea.render(r, &mut b);
+    ///
+    /// textarea.scroll(Scrolling::ToRow(10));
+    /// assert_eq!(textarea.scroll_offset(), (10, 0));
+    /// assert_eq!(textarea.cursor(), (10, 0));
+    /// ```
+    ToRow(usize),
+}
+
+// Clamp the scroll position into `0..=max`
+fn clamp(pos: i64, max: usize) -> u16 {
+    let max = cmp::min(max, u16::MAX as usize) as i64;
+    pos.clamp(0, max) as u16
 }
 
 impl Scrolling {
-    pub(crate) fn scroll(self, viewport: &mut Viewport) {
-        let (rows, cols) = match self {
-            Self::Delta { rows, cols } => (rows, cols),
-            Self::PageDown => {
-                let (_, _, _, height) = viewport.rect();
-                (height as i16, 0)
-            }
-            Self::PageUp => {
-                let (_, _, _, height) = viewport.rect();
-                (-(height as i16), 0)
-            }
-            Self::HalfPageDown => {
-                let (_, _, _, height) = viewport.rect();
-                ((height as i16) / 2, 0)
-            }
-            Self::HalfPageUp => {
-                let (_, _, _, height) = viewport.rect();
-                (-(height as i16) / 2, 0)
-            }
+    pub(crate) fn scroll(self, viewport: &mut Viewport, lines: &[String], page_overlap: u16) {
+        let (row, col, width, height) = viewport.rect();
+        // One page keeps the overlapping rows visible but moves the viewport at least one row
+        let page = cmp::max(height.saturating_sub(page_overlap), cmp::min(height, 1)) as i64;
+        let (row, col, width, height) = (row as i64, col as i64, width as i64, height as i64);
+        // Float to integer conversion rounds toward zero and saturates
+        let pages = |n: f32, len: i64| (len as f32 * n) as i64;
+        let (row, col) = match self {
+            Self::Delta { rows, cols } => (row + rows as i64, col + cols as i64),
+            Self::PageDown => (row + page, col),
+            Self::PageUp => (row - page, col),
+            Self::HalfPageDown => (row + height / 2, col),
+            Self::HalfPageUp => (row - height / 2, col),
+            Self::Lines(rows) => (row + rows as i64, col),
+            Self::Columns(cols) => (row, col + cols as i64),
+            Self::Pages(n) => (row + pages(n, height), col),
+            Self::HorizontalPages(n) => (row, col + pages(n, width)),
+            Self::ToRow(r) => (cmp::min(r, i64::MAX as usize) as i64, col),
         };
-        viewport.scroll(rows, cols);
+        let max_col = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+        viewport.set_scroll_top(clamp(row, lines.len() - 1), clamp(col, max_col));
     }
 }
 
@@ -213,4 +343,92 @@ mod tests {
         textarea.scroll((1, 0));
         assert_eq!(textarea.cursor(), (3, 0));
     }
+
+    #[test]
+    fn clamped() {
+        use crate::ratatui::buffer::Buffer;
+        use crate::ratatui::layout::Rect;
+        use crate::ratatui::widgets::Widget as _;
+        use crate::TextArea;
+
+        let mut textarea: TextArea = (0..20).map(|i| "x".repeat(i)).collect();
+        let r = Rect {
+            x: 0,
+            y: 0,
+            width: 8,
+            height: 8,
+        };
+        let mut b = Buffer::empty(r);
+        textarea.render(r, &mut b);
+
+        let tests = [
+            (Scrolling::Lines(-1), (0, 0)),
+            (Scrolling::Columns(-1), (0, 0)),
+            (Scrolling::Delta { rows: 3, cols: 4 }, (3, 4)),
+            (Scrolling::PageDown, (11, 4)),
+            (Scrolling::PageDown, (19, 4)),
+            (Scrolling::Lines(i16::MAX), (19, 4)),
+            (Scrolling::HalfPageUp, (15, 4)),
+            (Scrolling::Pages(-1.5), (3, 4)),
+            (Scrolling::Pages(-100.0), (0, 4)),
+            (Scrolling::Pages(f32::NAN), (0, 4)),
+            (Scrolling::HorizontalPages(1.0), (0, 12)),
+            (Scrolling::Columns(i16::MAX), (0, 19)),
+            (Scrolling::HorizontalPages(-0.75), (0, 13)),
+            (Scrolling::ToRow(5), (5, 13)),
+            (Scrolling::ToRow(usize::MAX), (19, 13)),
+            (
+                Scrolling::Delta {
+                    rows: -100,
+                    cols: -100,
+                },
+                (0, 0),
+            ),
+        ];
+        for (i, (scrolling, offset)) in tests.into_iter().enumerate() {
+            textarea.scroll(scrolling);
+            assert_eq!(textarea.scroll_offset(), offset, "{}: {:?}", i, scrolling);
+            let (row, col) = textarea.cursor();
+            assert!(
+                row >= offset.0 && row < offset.0 + 8,
+                "{}: {:?}",
+                i,
+                scrolling
+            );
+            assert!(col <= textarea.lines()[row].len(), "{}: {:?}", i, scrolling);
+        }
+    }
+
+    #[test]
+    fn page_overlap() {
+        use crate::ratatui::buffer::Buffer;
+        use crate::ratatui::layout::Rect;
+        use crate::ratatui::widgets::Widget as _;
+        use crate::TextArea;
+
+        let mut textarea: TextArea = (0..20).map(|i| i.to_string()).collect();
+        let r = Rect {
+            x: 0,
+            y: 0,
+            width: 24,
+            height: 8,
+        };
+        let mut b = Buffer::empty(r);
+        textarea.render(r, &mut b);
+
+        let tests = [
+            (3, Scrolling::PageDown, (5, 0)),
+            (3, Scrolling::PageUp, (0, 0)),
+            (100, Scrolling::PageDown, (1, 0)),
+            (100, Scrolling::PageUp, (0, 0)),
+            (2, Scrolling::HalfPageDown, (4, 0)),
+            (2, Scrolling::Pages(1.0), (12, 0)),
+            (0, Scrolling::PageDown, (19, 0)),
+        ];
+        for (i, (overlap, scrolling, offset)) in tests.into_iter().enumerate() {
+            textarea.set_page_overlap(overlap);
+            textarea.scroll(scrolling);
+            assert_eq!(textarea.scroll_offset(), offset, "{}: {:?}", i, scrolling);
+        }
+    }
 }
@@ -1,17 +1,228 @@
 use crate::ratatui::style::{Color, Style};
 use regex::Regex;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::mem;
+
+/// Strategy to match the query of text search set by [`TextArea::set_search_pattern`].
+///
+/// This type is marked as `#[non_exhaustive]` since more variations may be supported in the future. See also:
+/// [`TextArea::set_search_mode`]
+///
+/// [`TextArea::set_search_pattern`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.set_search_pattern
+/// [`TextArea::set_search_mode`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.set_search_mode
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SearchMode {
+    /// The query is a regular expression handled by [`regex`] crate. This is the default.
+    Regex,
+    /// The query is a plain string and matches to the same substring. Regular expression meta characters such as `.`
+    /// or `(` are not interpreted. This is faster than [`SearchMode::Regex`] on large text.
+    Literal,
+    /// Characters of the query match when they appear in the same order in a line, possibly with other characters
+    /// between them. For example `fb` matches to `foo_bar`. Each match is the shortest range containing all the
+    /// characters. Case is ignored unless the query contains some uppercase character (smart case). This is useful
+    /// for quickly jumping to some position.
+    Fuzzy,
+}
+
+impl Default for SearchMode {
+    fn default() -> Self {
+        Self::Regex
+    }
+}
+
+/// Direction of text search used by [`TextArea::search_next`] and [`TextArea::search_prev`]. This is useful to
+/// implement `/` and `?` searches of Vim, whose `n` and `N` repeat the search in the same and the opposite directions.
+/// See also: [`TextArea::set_search_direction`]
+///
+/// [`TextArea::search_next`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.search_next
+/// [`TextArea::search_prev`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.search_prev
+/// [`TextArea::set_search_direction`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.set_search_direction
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SearchDirection {
+    /// Search toward the end of the text like [`TextArea::search_forward`]. This is the default.
+    ///
+    /// [`TextArea::search_forward`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.search_forward
+    Forward,
+    /// Search toward the start of the text like [`TextArea::search_back`].
+    ///
+    /// [`TextArea::search_back`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.search_back
+    Backward,
+}
+
+impl Default for SearchDirection {
+    fn default() -> Self {
+        Self::Forward
+    }
+}
+
+/// A match of the text search passed to the callback of [`TextArea::for_each_match`]. Positions are 0-base and
+/// character-wise.
+///
+/// This type is marked as `#[non_exhaustive]` since more fields may be added in the future.
+///
+/// [`TextArea::for_each_match`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.for_each_match
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MatchRef<'a> {
+    /// Row of the line containing the match.
+    pub row: usize,
+    /// Column where the match starts.
+    pub start: usize,
+    /// Column where the match ends (exclusive).
+    pub end: usize,
+    /// Matched text.
+    pub text: &'a str,
+    /// Whole line containing the match.
+    pub line: &'a str,
+}
+
+/// What to do with a match in the callback of [`TextArea::for_each_match`].
+///
+/// This type is marked as `#[non_exhaustive]` since more actions may be supported in the future.
+///
+/// [`TextArea::for_each_match`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.for_each_match
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MatchAction {
+    /// Replace the matched text with the string.
+    Replace(String),
+    /// Keep the matched text and continue to the next match.
+    Skip,
+    /// Keep the matched text and stop visiting the rest of matches.
+    Stop,
+}
+
+#[derive(Clone, Debug)]
+enum Matcher {
+    Regex(Regex),
+    Literal(String),
+    Fuzzy { query: Vec<char>, ignore_case: bool },
+}
+
+impl Matcher {
+    fn new(query: &str, mode: SearchMode) -> Result<Self, regex::Error> {
+        Ok(match mode {
+            SearchMode::Regex => Self::Regex(Regex::new(query)?),
+            SearchMode::Literal => Self::Literal(query.to_string()),
+            SearchMode::Fuzzy => {
+                let ignore_case = !query.chars().any(char::is_uppercase);
+                Self::Fuzzy {
+                    query: query.chars().collect(),
+                    ignore_case,
+                }
+            }
+        })
+    }
+
+    // Find the first match starting at or after the byte offset `start` as (start, end) byte offsets
+    fn find_at(&self, line: &str, start: usize) -> Option<(usize, usize)> {
+        match self {
+            Self::Regex(r) => r.find_at(line, start).map(|m| (m.start(), m.end())),
+            Self::Literal(s) => line[start..]
+                .find(s.as_str())
+                .map(|i| (start + i, start + i + s.len())),
+            Self::Fuzzy { query, ignore_case } => find_fuzzy(line, start, query, *ignore_case),
+        }
+    }
+
+    fn find(&self, line: &str) -> Option<(usize, usize)> {
+        self.find_at(line, 0)
+    }
+
+    fn find_iter<'a>(&'a self, line: &'a str) -> Matches<'a> {
+        match self {
+            Self::Regex(r) => Matches::Regex(r.find_iter(line)),
+            _ => Matches::Other {
+                matcher: self,
+                line,
+                start: 0,
+            },
+        }
+    }
+}
+
+fn find_fuzzy(
+    line: &str,
+    start: usize,
+    query: &[char],
+    ignore_case: bool,
+) -> Option<(usize, usize)> {
+    let eq = |c: char, q: char| c == q || ignore_case && c.to_lowercase().eq(q.to_lowercase());
+
+    // Find the end of the first subsequence matching to the query
+    let mut chars = line[start..].char_indices();
+    let mut end = start;
+    for &q in query {
+        let (i, c) = chars.find(|&(_, c)| eq(c, q))?;
+        end = start + i + c.len_utf8();
+    }
+
+    // Shorten the match by matching the query backward from the end
+    let mut chars = line[start..end].char_indices().rev();
+    let mut begin = end;
+    for &q in query.iter().rev() {
+        let (i, _) = chars.find(|&(_, c)| eq(c, q))?;
+        begin = start + i;
+    }
+
+    Some((begin, end))
+}
+
+// Non-overlapping matches in a line as (start, end) byte offsets
+enum Matches<'a> {
+    Regex(regex::Matches<'a, 'a>),
+    // Matches of literal and fuzzy queries are never empty since empty query is not allowed
+    Other {
+        matcher: &'a Matcher,
+        line: &'a str,
+        start: usize,
+    },
+}
+
+impl Iterator for Matches<'_> {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Regex(m) => m.next().map(|m| (m.start(), m.end())),
+            Self::Other {
+                matcher,
+                line,
+                start,
+            } => {
+                let (s, e) = matcher.find_at(line, *start)?;
+                *start = e;
+                Some((s, e))
+            }
+        }
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct Search {
-    pub pat: Option<Regex>,
+    pub query: String,
+    pub mode: SearchMode,
+    pub direction: SearchDirection,
+    matcher: Option<Matcher>,
+    current: Option<(usize, usize, usize)>, // (row, start, end) of the match the cursor last jumped to
     pub style: Style,
+    pub current_style: Option<Style>,
 }
 
 impl Default for Search {
     fn default() -> Self {
         Self {
-            pat: None,
+            query: String::new(),
+            mode: SearchMode::default(),
+            direction: SearchDirection::default(),
+            matcher: None,
+            current: None,
             style: Style::default().bg(Color::Blue),
+            current_style: None,
         }
     }
 }
@@ -21,31 +232,88 @@ impl Search {
         &'a self,
         line: &'a str,
     ) -> Option<impl Iterator<Item = (usize, usize)> + 'a> {
-        let pat = self.pat.as_ref()?;
-        let matches = pat.find_iter(line).map(|m| (m.start(), m.end()));
-        Some(matches)
+        let matcher = self.matcher.as_ref()?;
+        Some(matcher.find_iter(line))
+    }
+
+    // The match which the cursor last jumped to on the row with its style. Text may be modified after the jump so the
+    // caller should check the range is still one of the matches
+    pub fn current_match(&self, row: usize) -> Option<(usize, usize, Style)> {
+        let style = self.current_style?;
+        match self.current {
+            Some((r, start, end)) if r == row => Some((start, end, style)),
+            _ => None,
+        }
+    }
+
+    pub fn regex(&self) -> Option<&Regex> {
+        match &self.matcher {
+            Some(Matcher::Regex(r)) => Some(r),
+            _ => None,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.matcher.is_some()
     }
 
     pub fn set_pattern(&mut self, query: &str) -> Result<(), regex::Error> {
-        match &self.pat {
-            Some(r) if r.as_str() == query => {}
-            _ if query.is_empty() => self.pat = None,
-            _ => self.pat = Some(Regex::new(query)?),
+        if self.matcher.is_some() && self.query == query {
+            return Ok(());
+        }
+        self.current = None;
+        if query.is_empty() {
+            self.matcher = None;
+        } else {
+            self.matcher = Some(Matcher::new(query, self.mode)?);
         }
+        self.query = query.to_string();
         Ok(())
     }
 
+    // The current query is compiled again with the new mode. When it fails, the text search stops
+    pub fn set_mode(&mut self, mode: SearchMode) -> Result<(), regex::Error> {
+        if self.mode == mode {
+            return Ok(());
+        }
+        self.mode = mode;
+        self.current = None;
+        let query = mem::take(&mut self.query);
+        if self.matcher.take().is_none() {
+            return Ok(());
+        }
+        self.set_pattern(&query)
+    }
+
     pub fn forward(
         &mut self,
         lines: &[String],
         cursor: (usize, usize),
         match_cursor: bool,
     ) -> Option<(usize, usize)> {
-        let pat = if let Some(pat) = &self.pat {
-            pat
-        } else {
-            return None;
-        };
+        let (row, col, (start, end)) = self.find_forward(lines, cursor, match_cursor)?;
+        self.current = Some((row, start, end));
+        Some((row, col))
+    }
+
+    pub fn back(
+        &mut self,
+        lines: &[String],
+        cursor: (usize, usize),
+        match_cursor: bool,
+    ) -> Option<(usize, usize)> {
+        let (row, col, (start, end)) = self.find_back(lines, cursor, match_cursor)?;
+        self.current = Some((row, start, end));
+        Some((row, col))
+    }
+
+    fn find_forward(
+        &self,
+        lines: &[String],
+        cursor: (usize, usize),
+        match_cursor: bool,
+    ) -> Option<(usize, usize, (usize, usize))> {
+        let pat = self.matcher.as_ref()?;
         let (row, col) = cursor;
         let current_line = &lines[row];
 
@@ -53,24 +321,24 @@ impl Search {
         let start_col = if match_cursor { col } else { col + 1 };
         if let Some((i, _)) = current_line.char_indices().nth(start_col) {
             if let Some(m) = pat.find_at(current_line, i) {
-                let col = start_col + current_line[i..m.start()].chars().count();
-                return Some((row, col));
+                let col = start_col + current_line[i..m.0].chars().count();
+                return Some((row, col, m));
             }
         }
 
         // Search lines after cursor
         for (i, line) in lines[row + 1..].iter().enumerate() {
             if let Some(m) = pat.find(line) {
-                let col = line[..m.start()].chars().count();
-                return Some((row + 1 + i, col));
+                let col = line[..m.0].chars().count();
+                return Some((row + 1 + i, col, m));
             }
         }
 
         // Search lines before cursor (wrap)
         for (i, line) in lines[..row].iter().enumerate() {
             if let Some(m) = pat.find(line) {
-                let col = line[..m.start()].chars().count();
-                return Some((i, col));
+                let col = line[..m.0].chars().count();
+                return Some((i, col, m));
             }
         }
 
@@ -81,27 +349,23 @@ impl Search {
             .map(|(i, _)| i)
             .unwrap_or(current_line.len());
         if let Some(m) = pat.find(current_line) {
-            let i = m.start();
+            let i = m.0;
             if i <= col_idx {
                 let col = current_line[..i].chars().count();
-                return Some((row, col));
+                return Some((row, col, m));
             }
         }
 
         None
     }
 
-    pub fn back(
-        &mut self,
+    fn find_back(
+        &self,
         lines: &[String],
         cursor: (usize, usize),
         match_cursor: bool,
-    ) -> Option<(usize, usize)> {
-        let pat = if let Some(pat) = &self.pat {
-            pat
-        } else {
-            return None;
-        };
+    ) -> Option<(usize, usize, (usize, usize))> {
+        let pat = self.matcher.as_ref()?;
         let (row, col) = cursor;
         let current_line = &lines[row];
 
@@ -109,13 +373,9 @@ impl Search {
         if col > 0 || match_cursor {
             let start_col = if match_cursor { col } else { col - 1 };
             if let Some((i, _)) = current_line.char_indices().nth(start_col) {
-                if let Some(m) = pat
-                    .find_iter(current_line)
-                    .take_while(|m| m.start() <= i)
-                    .last()
-                {
-                    let col = current_line[..m.start()].chars().count();
-                    return Some((row, col));
+                if let Some(m) = pat.find_iter(current_line).take_while(|m| m.0 <= i).last() {
+                    let col = current_line[..m.0].chars().count();
+                    return Some((row, col, m));
                 }
             }
         }
@@ -123,28 +383,24 @@ impl Search {
         // Search lines before cursor
         for (i, line) in lines[..row].iter().enumerate().rev() {
             if let Some(m) = pat.find_iter(line).last() {
-                let col = line[..m.start()].chars().count();
-                return Some((i, col));
+                let col = line[..m.0].chars().count();
+                return Some((i, col, m));
             }
         }
 
         // Search lines after cursor (wrap)
         for (i, line) in lines[row + 1..].iter().enumerate().rev() {
             if let Some(m) = pat.find_iter(line).last() {
-                let col = line[..m.start()].chars().count();
-                return Some((row + 1 + i, col));
+                let col = line[..m.0].chars().count();
+                return Some((row + 1 + i, col, m));
             }
         }
 
         // Search current line after cursor
         if let Some((i, _)) = current_line.char_indices().nth(col) {
-            if let Some(m) = pat
-                .find_iter(current_line)
-                .skip_while(|m| m.start() < i)
-                .last()
-            {
-                let col = col + current_line[i..m.start()].chars().count();
-                return Some((row, col));
+            if let Some(m) = pat.find_iter(current_line).skip_while(|m| m.0 < i).last() {
+                let col = col + current_line[i..m.0].chars().count();
+                return Some((row, col, m));
             }
         }
 
@@ -167,4 +423,81 @@ mod tests {
         s.set_pattern("").unwrap();
         assert!(s.matches("fo foo bar fooo").is_none());
     }
+
+    #[test]
+    fn literal() {
+        let mut s = Search::default();
+        s.set_mode(SearchMode::Literal).unwrap();
+        s.set_pattern("a.(").unwrap();
+
+        let m: Vec<_> = s.matches("a.( abc a.(a.(").unwrap().collect();
+        assert_eq!(m, [(0, 3), (8, 11), (11, 14)]);
+
+        s.set_pattern("aa").unwrap();
+        let m: Vec<_> = s.matches("aaaaa").unwrap().collect();
+        assert_eq!(m, [(0, 2), (2, 4)]);
+    }
+
+    #[test]
+    fn fuzzy() {
+        let mut s = Search::default();
+        s.set_mode(SearchMode::Fuzzy).unwrap();
+        s.set_pattern("fb").unwrap();
+
+        let m: Vec<_> = s.matches("foo fbar f_Bar fb").unwrap().collect();
+        assert_eq!(m, [(4, 6), (9, 12), (15, 17)]);
+
+        // Shortest range ending at the first match
+        let m: Vec<_> = s.matches("ff-f-b").unwrap().collect();
+        assert_eq!(m, [(3, 6)]);
+
+        // Smart case
+        s.set_pattern("fB").unwrap();
+        let m: Vec<_> = s.matches("fb f_B").unwrap().collect();
+        assert_eq!(m, [(3, 6)]);
+
+        s.set_pattern("あい").unwrap();
+        let m: Vec<_> = s.matches("あうい").unwrap().collect();
+        assert_eq!(m, [(0, 9)]);
+    }
+
+    #[test]
+    fn set_mode() {
+        let mut s = Search::default();
+        s.set_pattern("a.").unwrap();
+        let m: Vec<_> = s.matches("a.ab").unwrap().collect();
+        assert_eq!(m, [(0, 2), (2, 4)]);
+
+        s.set_mode(SearchMode::Literal).unwrap();
+        assert!(s.regex().is_none());
+        let m: Vec<_> = s.matches("a.ab").unwrap().collect();
+        assert_eq!(m, [(0, 2)]);
+
+        // Invalid pattern as regular expression stops the search
+        s.set_pattern("(").unwrap();
+        assert!(s.set_mode(SearchMode::Regex).is_err());
+        assert!(s.matches("(").is_none());
+        assert_eq!(s.mode, SearchMode::Regex);
+    }
+
+    #[test]
+    fn current_match() {
+        let lines = ["foo bar foo".to_string(), "bar foo".to_string()];
+        let mut s = Search::default();
+        s.set_pattern("fo+").unwrap();
+        assert_eq!(s.forward(&lines, (0, 0), false), Some((0, 8)));
+        assert_eq!(s.current_match(0), None); // No style is set
+
+        let style = Style::default().bg(Color::Red);
+        s.current_style = Some(style);
+        assert_eq!(s.current_match(0), Some((8, 11, style)));
+        assert_eq!(s.current_match(1), None);
+
+        assert_eq!(s.back(&lines, (0, 0), false), Some((1, 4)));
+        assert_eq!(s.current_match(0), None);
+        assert_eq!(s.current_match(1), Some((4, 7, style)));
+
+        s.set_pattern("bar").unwrap();
+        assert_eq!(s.current_match(1), None);
+    }
 }
@@ -0,0 +1,84 @@
+use crate::indent::IndentPolicy;
+use crate::mask::MaskPolicy;
+use crate::ratatui::style::Style;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// A snapshot of the state of [`TextArea`] which can be persisted and restored later. With `serde` feature, this type
+/// implements [`serde::Serialize`] and [`serde::Deserialize`] so that applications can save the draft of forms between
+/// sessions. The state consists of text lines, the cursor position, the text selection, the scroll position, and
+/// styles. Note that undo/redo history, the block, and the alignment are not included.
+///
+/// This type is marked as `#[non_exhaustive]` since more fields may be added in the future. Use
+/// [`TextArea::to_state`] to create an instance and [`TextArea::from_state`] to restore a textarea from it.
+///
+/// [`TextArea`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html
+/// [`TextArea::to_state`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.to_state
+/// [`TextArea::from_state`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.from_state
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PersistentState {
+    /// Text lines.
+    pub lines: Vec<String>,
+    /// 0-base character-wise (row, col) cursor position.
+    pub cursor: (usize, usize),
+    /// 0-base character-wise (row, col) position where the text selection started.
+    pub selection_start: Option<(usize, usize)>,
+    /// (row, col) position of the top-left corner of the viewport.
+    pub scroll_top: (u16, u16),
+    /// Style of the textarea.
+    pub style: Style,
+    /// Style of the cursor.
+    pub cursor_style: Style,
+    /// Style of the line at the cursor.
+    pub cursor_line_style: Style,
+    /// Style of line numbers. `None` means line numbers are not shown.
+    pub line_number_style: Option<Style>,
+    /// Style of the text selection.
+    pub selection_style: Style,
+    /// Placeholder text.
+    pub placeholder: String,
+    /// Style of the placeholder text.
+    pub placeholder_style: Style,
+    /// Character to mask text.
+    pub mask: Option<char>,
+    /// Policy of masking text.
+    pub mask_policy: MaskPolicy,
+    /// Policy of indentation.
+    pub indent: IndentPolicy,
+}
+
+/// A snapshot of the text, the cursor, and the text selection of [`TextArea`] created by [`TextArea::snapshot`]. Unlike
+/// cloning the whole textarea, styles, the history, and other settings are not copied. Note that the text itself is
+/// copied on creating a snapshot, which takes time proportional to the size of the text. Once created, cloning a
+/// snapshot is cheap since the copied text is shared between the clones. This is useful to implement "cancel edit" in
+/// modal editors, by restoring the snapshot with [`TextArea::restore`].
+///
+/// [`TextArea`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html
+/// [`TextArea::snapshot`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.snapshot
+/// [`TextArea::restore`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.restore
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Snapshot {
+    pub(crate) lines: Arc<Vec<String>>,
+    pub(crate) cursor: (usize, usize),
+    pub(crate) selection_start: Option<(usize, usize)>,
+}
+
+impl Snapshot {
+    /// Text lines in the snapshot.
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    /// 0-base character-wise (row, col) cursor position in the snapshot.
+    pub fn cursor(&self) -> (usize, usize) {
+        self.cursor
+    }
+
+    /// 0-base character-wise (row, col) position where the text selection started in the snapshot.
+    pub fn selection_start(&self) -> Option<(usize, usize)> {
+        self.selection_start
+    }
+}
@@ -0,0 +1,321 @@
+use crate::cursor::CursorMove;
+use crate::input::{Input, Key};
+use crate::ratatui::buffer::Buffer;
+use crate::ratatui::layout::Rect;
+use crate::ratatui::style::Style;
+use crate::ratatui::widgets::Widget;
+use crate::textarea::TextArea;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+// Callback to list completion candidates.
+type CompleterFn = dyn FnMut(&str) -> Vec<String> + Send;
+
+#[derive(Clone)]
+struct Completer(Arc<Mutex<CompleterFn>>);
+
+impl fmt::Debug for Completer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Completer")
+    }
+}
+
+fn single_line(text: &str) -> String {
+    text.replace("\r\n", " ")
+        .replace(|c| c == '\n' || c == '\r', " ")
+}
+
+/// A single-line text input widget for prompts built on [`TextArea`]. The text never contains newlines, it is
+/// scrolled horizontally when it is longer than the width of the widget, and the following keys are handled by
+/// [`TextInput::input`] in addition to the key mappings of [`TextArea::input`]:
+///
+/// | Mappings                     | Description                                      |
+/// |------------------------------|--------------------------------------------------|
+/// | `Enter`, `Ctrl+M`            | Do nothing. Handle them to submit the text       |
+/// | `Ctrl+P`, `↑`                | Recall the previous entry in the input history   |
+/// | `Ctrl+N`, `↓`                | Recall the next entry in the input history       |
+/// | `Tab`                        | Complete the text with [`TextInput::complete`]   |
+///
+/// The input history is the prompt history of the underlying textarea in prompt mode (see
+/// [`TextArea::set_prompt_mode`]). Settings which are not provided by this type such as styles and the block can be changed via
+/// [`TextInput::textarea_mut`].
+/// ```
+/// use tui_textarea::{TextInput, Input, Key};
+///
+/// let mut prompt = TextInput::default();
+/// prompt.push_history("hello");
+///
+/// prompt.input(Input { key: Key::Char('a'), ..Default::default() });
+/// assert_eq!(prompt.text(), "a");
+///
+/// // Enter does not insert a newline
+/// assert!(!prompt.input(Input { key: Key::Enter, ..Default::default() }));
+/// assert_eq!(prompt.text(), "a");
+///
+/// // Recall the history entry
+/// prompt.input(Input { key: Key::Up, ..Default::default() });
+/// assert_eq!(prompt.text(), "hello");
+///
+/// // Submit the text and clear the input
+/// assert_eq!(prompt.submit(), "hello");
+/// assert_eq!(prompt.text(), "");
+/// ```
+#[derive(Clone, Debug)]
+pub struct TextInput<'a> {
+    textarea: TextArea<'a>,
+    completer: Option<Completer>,
+    candidates: Vec<String>,
+}
+
+impl<'a> Default for TextInput<'a> {
+    fn default() -> Self {
+        Self::new("")
+    }
+}
+
+impl<'a> TextInput<'a> {
+    /// Create a new [`TextInput`] instance with the initial text. Newlines in the text are replaced with spaces. The
+    /// cursor is placed at the end of the text.
+    /// ```
+    /// use tui_textarea::TextInput;
+    ///
+    /// let input = TextInput::new("hello\nworld");
+    /// assert_eq!(input.text(), "hello world");
+    /// assert_eq!(input.cursor(), 11);
+    /// ```
+    pub fn new(text: impl AsRef<str>) -> Self {
+        let mut textarea = TextArea::new(vec![single_line(text.as_ref())]);
+        textarea.set_cursor_line_style(Style::default());
+        textarea.set_prompt_mode(true);
+        textarea.move_cursor(CursorMove::End);
+        Self {
+            textarea,
+            completer: None,
+            candidates: vec![],
+        }
+    }
+
+    /// Handle a key input. The key mappings are described in the documentation of [`TextInput`]. This method returns
+    /// if the input modified the text or not.
+    pub fn input(&mut self, input: impl Into<Input>) -> bool {
+        let input = input.into();
+        match input {
+            Input {
+                key: Key::Char('m'),
+                ctrl: true,
+                alt: false,
+                ..
+            }
+            | Input {
+                key: Key::Char('\n' | '\r'),
+                ctrl: false,
+                alt: false,
+                ..
+            }
+            | Input {
+                key: Key::Enter, ..
+            } => false,
+            Input {
+                key: Key::Tab,
+                ctrl: false,
+                alt: false,
+                ..
+            } => self.complete(),
+            Input {
+                key: Key::Char('y'),
+                ctrl: true,
+                alt: false,
+                ..
+            }
+            | Input {
+                key: Key::Paste, ..
+            } => {
+                let text = single_line(&self.textarea.yank_text());
+                !text.is_empty() && self.textarea.insert_str(text)
+            }
+            input => self.textarea.input(input),
+        }
+    }
+
+    /// Get the current text.
+    pub fn text(&self) -> &str {
+        &self.textarea.lines()[0]
+    }
+
+    /// Replace the text. Newlines in the text are replaced with spaces. The cursor is moved to the end of the text.
+    /// The replacement can be undone. This method returns if the text was modified or not.
+    /// ```
+    /// use tui_textarea::TextInput;
+    ///
+    /// let mut input = TextInput::new("hello");
+    /// input.set_text("goodbye");
+    /// assert_eq!(input.text(), "goodbye");
+    /// assert_eq!(input.cursor(), 7);
+    /// ```
+    pub fn set_text(&mut self, text: impl AsRef<str>) -> bool {
+        let modified = self.textarea.set_lines(vec![single_line(text.as_ref())]);
+        self.textarea.move_cursor(CursorMove::End);
+        modified
+    }
+
+    /// Get the character-wise 0-base cursor position in the text.
+    pub fn cursor(&self) -> usize {
+        self.textarea.cursor().1
+    }
+
+    /// Take the current text as a submitted entry. The text is added to the input history unless it is empty or the
+    /// same as the latest entry, then the input is cleared.
+    pub fn submit(&mut self) -> String {
+        let text = self.text().to_string();
+        self.push_history(text.clone());
+        self.candidates.clear();
+        self.textarea.set_lines(vec![]);
+        text
+    }
+
+    /// Add an entry to the input history. Empty text and the text which is the same as the latest entry are ignored.
+    /// Newlines in the entry are replaced with spaces.
+    pub fn push_history(&mut self, entry: impl AsRef<str>) {
+        self.textarea
+            .push_prompt_history(single_line(entry.as_ref()));
+    }
+
+    /// Get the entries of the input history from the oldest to the latest.
+    pub fn history(&self) -> &[String] {
+        self.textarea.prompt_history()
+    }
+
+    /// Replace the text with the previous entry in the input history. When starting to recall the history, the text
+    /// being edited is stashed and it is restored by [`TextInput::next_history`]. This method returns if some
+    /// entry was recalled or not.
+    /// ```
+    /// use tui_textarea::TextInput;
+    ///
+    /// let mut input = TextInput::new("draft");
+    /// input.push_history("first");
+    /// input.push_history("second");
+    ///
+    /// input.prev_history();
+    /// assert_eq!(input.text(), "second");
+    /// input.prev_history();
+    /// assert_eq!(input.text(), "first");
+    /// assert!(!input.prev_history());
+    ///
+    /// input.next_history();
+    /// input.next_history();
+    /// assert_eq!(input.text(), "draft");
+    /// ```
+    pub fn prev_history(&mut self) -> bool {
+        self.textarea.prev_prompt_history()
+    }
+
+    /// Replace the text with the next entry in the input history. After the latest entry, the stashed text is
+    /// restored. This method returns if some entry was recalled or not.
+    pub fn next_history(&mut self) -> bool {
+        self.textarea.next_prompt_history()
+    }
+
+    /// Set the callback which lists the completion candidates. It receives the text before the cursor and returns
+    /// candidates which replace the text. See [`TextInput::complete`].
+    pub fn set_completer(&mut self, completer: impl FnMut(&str) -> Vec<String> + Send + 'static) {
+        self.completer = Some(Completer(Arc::new(Mutex::new(completer))));
+    }
+
+    /// Complete the text before the cursor with the candidates listed by the callback set by
+    /// [`TextInput::set_completer`]. Like readline, the text is replaced with the candidate when there is only one
+    /// candidate, or with the longest common prefix of the candidates. The candidates can be retrieved by
+    /// [`TextInput::completion_candidates`] to show them. This method returns if the text was modified or not.
+    /// ```
+    /// use tui_textarea::TextInput;
+    ///
+    /// let mut input = TextInput::new("git ch");
+    /// input.set_completer(|text| {
+    ///     ["git checkout", "git cherry-pick", "git commit"]
+    ///         .iter()
+    ///         .filter(|c| c.starts_with(text))
+    ///         .map(|c| c.to_string())
+    ///         .collect()
+    /// });
+    ///
+    /// assert!(input.complete());
+    /// assert_eq!(input.text(), "git che");
+    /// assert_eq!(input.completion_candidates(), ["git checkout", "git cherry-pick"]);
+    /// ```
+    pub fn complete(&mut self) -> bool {
+        let completer = match &self.completer {
+            Some(completer) => completer.clone(),
+            None => return false,
+        };
+        let col = self.cursor();
+        let before = self.textarea.text_in_range((0, 0), (0, col));
+        self.candidates = match completer.0.lock() {
+            Ok(mut f) => f(&before),
+            Err(_) => return false,
+        };
+
+        let mut prefix = match self.candidates.first() {
+            Some(c) => single_line(c),
+            None => return false,
+        };
+        for c in &self.candidates[1..] {
+            let len = prefix
+                .char_indices()
+                .zip(c.chars())
+                .find(|((_, a), b)| a != b)
+                .map(|((i, _), _)| i)
+                .unwrap_or_else(|| prefix.len().min(c.len()));
+            prefix.truncate(len);
+        }
+        if prefix == before || prefix.is_empty() {
+            return false;
+        }
+        self.textarea.replace_range((0, 0), (0, col), prefix)
+    }
+
+    /// Get the candidates listed by the last call of [`TextInput::complete`].
+    pub fn completion_candidates(&self) -> &[String] {
+        &self.candidates
+    }
+
+    /// Set the character to mask the text. This is useful for password inputs. See [`TextArea::set_mask_char`].
+    pub fn set_mask_char(&mut self, mask: char) {
+        self.textarea.set_mask_char(mask);
+    }
+
+    /// Clear the mask character set by [`TextInput::set_mask_char`].
+    pub fn clear_mask_char(&mut self) {
+        self.textarea.clear_mask_char();
+    }
+
+    /// Get the character to mask the text.
+    pub fn mask_char(&self) -> Option<char> {
+        self.textarea.mask_char()
+    }
+
+    /// Set the placeholder text shown while the text is empty.
+    pub fn set_placeholder_text(&mut self, placeholder: impl Into<String>) {
+        self.textarea.set_placeholder_text(placeholder);
+    }
+
+    /// Get the underlying [`TextArea`].
+    pub fn textarea(&self) -> &TextArea<'a> {
+        &self.textarea
+    }
+
+    /// Get the underlying [`TextArea`] mutably to change its settings. Don't insert newlines to the text via the
+    /// returned reference.
+    pub fn textarea_mut(&mut self) -> &mut TextArea<'a> {
+        &mut self.textarea
+    }
+
+    /// Convert this instance into the underlying [`TextArea`].
+    pub fn into_textarea(self) -> TextArea<'a> {
+        self.textarea
+    }
+}
+
+impl Widget for &TextInput<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        self.textarea.render(area, buf);
+    }
+}
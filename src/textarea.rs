@@ -1,60 +1,155 @@
-use crate::cursor::CursorMove;
-use crate::highlight::LineHighlighter;
-use crate::history::{Edit, EditKind, History};
+use crate::anchor::{AnchorGrowth, AnchorId};
+use crate::completion::Completion;
+use crate::control::ControlCharStyle;
+use crate::cursor::{CursorChange, CursorMove};
+use crate::diff::{DiffKind, DiffMarks};
+use crate::expand::{expand_range, Expansion};
+#[cfg(feature = "file")]
+use crate::file::{self, Encoding};
+use crate::highlight::{
+    display_width, trailing_whitespace_start, Highlight, LineHighlighter, Scratch,
+};
+use crate::history::{Edit, EditKind, EditRecord, History};
+use crate::indent::IndentPolicy;
 use crate::input::{Input, Key};
-use crate::ratatui::layout::Alignment;
+use crate::keymap::Keymap;
+use crate::line_ending::{self, LineEnding};
+use crate::link::Links;
+use crate::mask::MaskPolicy;
+use crate::metrics::Metrics;
+use crate::placeholder::PlaceholderCursor;
+use crate::prompt::PromptHistory;
+use crate::ratatui::buffer::Buffer;
+use crate::ratatui::layout::{Alignment, Rect};
 use crate::ratatui::style::{Color, Modifier, Style};
+use crate::ratatui::text::Span;
 use crate::ratatui::widgets::{Block, Widget};
 use crate::scroll::Scrolling;
 #[cfg(feature = "search")]
-use crate::search::Search;
-use crate::util::{spaces, Pos};
-use crate::widget::Viewport;
-use crate::word::{find_word_exclusive_end_forward, find_word_start_backward};
+use crate::search::{MatchAction, MatchRef, Search, SearchDirection, SearchMode};
+use crate::state::{PersistentState, Snapshot};
+use crate::util::{self, num_digits, spaces, OffsetCache, Pos};
+use crate::whitespace::WhitespaceStyle;
+use crate::widget::{RenderCache, Viewport};
+use crate::width::{CharWidth, SharedCharWidth};
+use crate::word::{
+    find_word_exclusive_end_forward, find_word_start_backward, WordBoundary, WordScanner, Words,
+};
 #[cfg(feature = "ratatui")]
 use ratatui::text::Line;
-use std::cmp::Ordering;
+use std::any::Any;
+use std::borrow::Cow;
+use std::cmp::{self, Ordering};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
 use std::fmt;
+#[cfg(feature = "file")]
+use std::fs;
+use std::hash::{Hash, Hasher};
+#[cfg(feature = "file")]
+use std::io::{self, Read, Write};
+use std::mem;
+use std::ops::{Bound, Range, RangeBounds, RangeInclusive};
+#[cfg(feature = "file")]
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 #[cfg(feature = "tuirs")]
 use tui::text::Spans as Line;
-use unicode_width::UnicodeWidthChar as _;
 
+// Maximum number of inputs queued by `TextArea::queue_input`. Inputs beyond it are dropped so that runaway expansion
+// of macros cannot exhaust the memory.
+const MAX_QUEUED_INPUTS: usize = 1 << 20;
+
+// Yanked text shares its storage with the history so that cutting and pasting a large text don't copy it
 #[derive(Debug, Clone)]
 enum YankText {
-    Piece(String),
-    Chunk(Vec<String>),
+    Piece(Arc<String>),
+    Chunk(Arc<Vec<String>>),
 }
 
 impl Default for YankText {
     fn default() -> Self {
-        Self::Piece(String::new())
+        Self::Piece(Arc::default())
     }
 }
 
 impl From<String> for YankText {
     fn from(s: String) -> Self {
-        Self::Piece(s)
+        Self::Piece(Arc::new(s))
     }
 }
 impl From<Vec<String>> for YankText {
     fn from(mut c: Vec<String>) -> Self {
         match c.len() {
             0 => Self::default(),
-            1 => Self::Piece(c.remove(0)),
-            _ => Self::Chunk(c),
+            1 => Self::Piece(Arc::new(c.remove(0))),
+            _ => Self::Chunk(Arc::new(c)),
         }
     }
 }
 
-impl fmt::Display for YankText {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl YankText {
+    fn join(&self, line_ending: LineEnding) -> String {
         match self {
-            Self::Piece(s) => write!(f, "{}", s),
-            Self::Chunk(ss) => write!(f, "{}", ss.join("\n")),
+            Self::Piece(s) => s.to_string(),
+            Self::Chunk(ss) => ss.join(line_ending.as_str()),
         }
     }
 }
 
+// Callback called on cursor changes. Like other callbacks and functions set to the textarea, it is wrapped with `Arc`
+// so that `TextArea` can implement `Clone`. The cloned textarea shares the same callback.
+type CursorChangeFn = dyn FnMut(&CursorChange) + Send;
+
+#[derive(Clone)]
+struct CursorChangeCallback(Arc<Mutex<CursorChangeFn>>);
+
+impl fmt::Debug for CursorChangeCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CursorChangeCallback")
+    }
+}
+
+// Function to post-process lines on rendering.
+type LinePostprocessorFn = dyn for<'l> Fn(usize, Line<'l>) -> Line<'l> + Send + Sync;
+
+#[derive(Clone)]
+struct LinePostprocessor(Arc<LinePostprocessorFn>);
+
+impl fmt::Debug for LinePostprocessor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The address identifies the function in the key of the render cache
+        write!(
+            f,
+            "LinePostprocessor({:p})",
+            Arc::as_ptr(&self.0) as *const ()
+        )
+    }
+}
+
+// Function to transform text inserted from external sources.
+type PasteFilterFn = dyn Fn(String) -> String + Send + Sync;
+
+#[derive(Clone)]
+struct PasteFilter(Arc<PasteFilterFn>);
+
+impl fmt::Debug for PasteFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PasteFilter")
+    }
+}
+
+// State shared by all rows rendered in one frame. It is calculated once per frame instead of for each row
+pub(crate) struct RenderPass<'r> {
+    lnum_len: u8,
+    width: usize,      // Width of the lines including the horizontally scrolled part
+    view_width: usize, // Width of the viewport to align rows
+    selection: Option<(Pos, Pos)>, // Primary selection
+    selections: &'r [(Pos, Pos)], // Secondary selections overlapping the rendered rows
+    scratch: Scratch,
+}
+
 /// A type to manage state of textarea. These are some important methods:
 ///
 /// - [`TextArea::default`] creates an empty textarea.
@@ -79,7 +174,9 @@ impl fmt::Display for YankText {
 /// ```
 ///
 /// It implements [`ratatui::widgets::Widget`] trait so it can be rendered to a terminal screen via
-/// [`ratatui::Frame::render_widget`] method.
+/// [`ratatui::Frame::render_widget`] method. It also implements [`ratatui::widgets::StatefulWidget`] trait to render
+/// multiple views of one textarea with their own scroll positions. See [`TextAreaState`](crate::TextAreaState) for more
+/// details.
 /// ```no_run
 /// use ratatui::backend::CrosstermBackend;
 /// use ratatui::layout::{Constraint, Direction, Layout};
@@ -109,22 +206,83 @@ pub struct TextArea<'a> {
     block: Option<Block<'a>>,
     style: Style,
     cursor: (usize, usize), // 0-base
-    tab_len: u8,
-    hard_tab_indent: bool,
+    indent: IndentPolicy,
     history: History,
     cursor_line_style: Style,
     line_number_style: Option<Style>,
     pub(crate) viewport: Viewport,
+    page_overlap: u16,
+    pub(crate) last_frame: RenderCache,
+    pub(crate) render_cache: bool,
+    render_generation: u64, // Bumped by `touch_render` when states affecting the rendering are modified
     pub(crate) cursor_style: Style,
     yank: YankText,
     #[cfg(feature = "search")]
     search: Search,
     alignment: Alignment,
+    row_alignments: HashMap<usize, Alignment>,
     pub(crate) placeholder: String,
     pub(crate) placeholder_style: Style,
+    placeholder_cursor: PlaceholderCursor,
+    placeholder_alignment: Option<Alignment>,
     mask: Option<char>,
+    mask_policy: MaskPolicy,
+    last_typed: Option<(Instant, (usize, usize))>,
+    // Inputs of the last change with if they were handled with shortcuts, and the cursor position after the change.
+    // The position is `None` when the next input which modifies the text starts a new change.
+    last_change: Vec<(Input, bool)>,
+    last_change_cursor: Option<(usize, usize)>,
+    input_queue: VecDeque<Input>,
+    keymap: Option<Keymap>,
+    pending_keys: Vec<Input>,
+    pending_since: Option<Instant>, // When the last pending key was typed
     selection_start: Option<(usize, usize)>,
+    expansions: Vec<Expansion>, // Selections before `expand_selection` to restore them with `shrink_selection`
     select_style: Style,
+    preedit: Option<(String, usize)>,
+    preedit_style: Style,
+    metrics: Metrics,
+    offsets: OffsetCache,
+    on_cursor_change: Option<CursorChangeCallback>,
+    line_postprocessor: Option<LinePostprocessor>,
+    paste_filter: Option<PasteFilter>,
+    completion: Completion,
+    inline_hint: Option<(String, Style)>,
+    whitespace: WhitespaceStyle,
+    trailing_whitespace_style: Option<Style>,
+    control_chars: ControlCharStyle,
+    control_char_style: Style,
+    link_style: Option<Style>,
+    line_number_width: Option<u8>,
+    line_ending: LineEnding,
+    detected_line_ending: Option<LineEnding>,
+    mixed_line_endings: bool,
+    words: Words,
+    highlights: Vec<Highlight>,
+    merge_styles: bool,
+    selection_inclusive: bool,
+    virtual_edit: bool,
+    virtual_cursor: Option<(usize, usize)>, // Cursor placed beyond the end of line in virtual edit mode
+    cursor_line_fullwidth: bool,
+    cursor_hidden: bool,
+    focused: bool,
+    unfocused_cursor_style: Option<Style>,
+    unfocused_cursor_line_style: Style,
+    unfocused_selection_style: Style,
+    cursor_blink: Option<Duration>,
+    blink_since: Option<Instant>, // When the cursor started blinking. `None` until the next tick
+    blink_hidden: bool,
+    eol_annotations: HashMap<usize, Span<'a>>,
+    diff_marks: DiffMarks,
+    char_width: SharedCharWidth,
+    prompt_history: PromptHistory,
+    prompt_mode: bool,
+    #[cfg(feature = "file")]
+    encoding: Encoding,
+    #[cfg(feature = "file")]
+    final_newline: bool,
+    #[cfg(feature = "file")]
+    trim_on_save: bool,
 }
 
 /// Convert any iterator whose elements can be converted into [`String`] into [`TextArea`]. Each [`String`] element is
@@ -209,35 +367,166 @@ impl<'a> TextArea<'a> {
             lines.push(String::new());
         }
 
+        let metrics = Metrics::new(&lines);
         Self {
             lines,
             block: None,
             style: Style::default(),
             cursor: (0, 0),
-            tab_len: 4,
-            hard_tab_indent: false,
+            indent: IndentPolicy::default(),
             history: History::new(50),
             cursor_line_style: Style::default().add_modifier(Modifier::UNDERLINED),
             line_number_style: None,
             viewport: Viewport::default(),
+            page_overlap: 0,
+            last_frame: RenderCache::default(),
+            render_cache: false,
+            render_generation: 0,
             cursor_style: Style::default().add_modifier(Modifier::REVERSED),
             yank: YankText::default(),
             #[cfg(feature = "search")]
             search: Search::default(),
             alignment: Alignment::Left,
+            row_alignments: HashMap::new(),
             placeholder: String::new(),
             placeholder_style: Style::default().fg(Color::DarkGray),
+            placeholder_cursor: PlaceholderCursor::default(),
+            placeholder_alignment: None,
             mask: None,
+            mask_policy: MaskPolicy::default(),
+            last_typed: None,
+            last_change: vec![],
+            last_change_cursor: None,
+            input_queue: VecDeque::new(),
+            keymap: None,
+            pending_keys: vec![],
+            pending_since: None,
             selection_start: None,
+            expansions: vec![],
             select_style: Style::default().bg(Color::LightBlue),
+            preedit: None,
+            preedit_style: Style::default().add_modifier(Modifier::UNDERLINED),
+            metrics,
+            offsets: OffsetCache::default(),
+            on_cursor_change: None,
+            line_postprocessor: None,
+            paste_filter: None,
+            completion: Completion::default(),
+            inline_hint: None,
+            whitespace: WhitespaceStyle::default(),
+            trailing_whitespace_style: None,
+            control_chars: ControlCharStyle::default(),
+            control_char_style: Style::default().fg(Color::Blue),
+            link_style: None,
+            line_number_width: None,
+            line_ending: LineEnding::default(),
+            detected_line_ending: None,
+            mixed_line_endings: false,
+            words: Words::default(),
+            highlights: vec![],
+            merge_styles: true,
+            selection_inclusive: false,
+            virtual_edit: false,
+            virtual_cursor: None,
+            cursor_line_fullwidth: false,
+            cursor_hidden: false,
+            focused: true,
+            unfocused_cursor_style: None,
+            unfocused_cursor_line_style: Style::default(),
+            unfocused_selection_style: Style::default().bg(Color::DarkGray),
+            cursor_blink: None,
+            blink_since: None,
+            blink_hidden: false,
+            eol_annotations: HashMap::new(),
+            diff_marks: DiffMarks::default(),
+            char_width: SharedCharWidth::default(),
+            prompt_history: PromptHistory::default(),
+            prompt_mode: false,
+            #[cfg(feature = "file")]
+            encoding: Encoding::default(),
+            #[cfg(feature = "file")]
+            final_newline: true,
+            #[cfg(feature = "file")]
+            trim_on_save: false,
+        }
+    }
+
+    /// Create a snapshot of the current state of the textarea. The snapshot can be restored by
+    /// [`TextArea::from_state`]. With `serde` feature, the snapshot can be serialized and deserialized. See
+    /// [`PersistentState`] for the details of the state.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["hello", "world"]);
+    /// textarea.move_cursor(CursorMove::Bottom);
+    /// textarea.set_placeholder_text("Enter your message");
+    ///
+    /// let state = textarea.to_state();
+    /// assert_eq!(state.lines, ["hello", "world"]);
+    /// assert_eq!(state.cursor, (1, 0));
+    ///
+    /// let restored = TextArea::from_state(state);
+    /// assert_eq!(restored.lines(), ["hello", "world"]);
+    /// assert_eq!(restored.cursor(), (1, 0));
+    /// assert_eq!(restored.placeholder_text(), "Enter your message");
+    /// ```
+    pub fn to_state(&self) -> PersistentState {
+        PersistentState {
+            lines: self.lines.clone(),
+            cursor: self.cursor,
+            selection_start: self.selection_start,
+            scroll_top: self.viewport.scroll_top(),
+            style: self.style,
+            cursor_style: self.cursor_style,
+            cursor_line_style: self.cursor_line_style,
+            line_number_style: self.line_number_style,
+            selection_style: self.select_style,
+            placeholder: self.placeholder.clone(),
+            placeholder_style: self.placeholder_style,
+            mask: self.mask,
+            mask_policy: self.mask_policy,
+            indent: self.indent,
         }
     }
 
+    /// Restore a textarea from the state created by [`TextArea::to_state`]. Positions in the state are adjusted to
+    /// fit within the text since the state may come from outside (e.g. deserialized from a file).
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut state = TextArea::from(["abc"]).to_state();
+    /// state.cursor = (10, 10); // Out of the text
+    ///
+    /// let textarea = TextArea::from_state(state);
+    /// assert_eq!(textarea.cursor(), (0, 3));
+    /// ```
+    pub fn from_state(state: PersistentState) -> Self {
+        let mut textarea = Self::new(state.lines);
+        textarea.cursor = textarea.clamp_position(state.cursor);
+        textarea.selection_start = state.selection_start.map(|p| textarea.clamp_position(p));
+        let (row, col) = state.scroll_top;
+        textarea.viewport.set_scroll_top(row, col);
+        textarea.style = state.style;
+        textarea.cursor_style = state.cursor_style;
+        textarea.cursor_line_style = state.cursor_line_style;
+        textarea.line_number_style = state.line_number_style;
+        textarea.select_style = state.selection_style;
+        textarea.placeholder = state.placeholder;
+        textarea.placeholder_style = state.placeholder_style;
+        textarea.mask = state.mask;
+        textarea.mask_policy = state.mask_policy;
+        textarea.indent = state.indent;
+        textarea
+    }
+
     /// Handle a key input with default key mappings. For default key mappings, see the table in
     /// [the module document](./index.html).
     /// `crossterm`, `termion`, and `termwiz` features enable conversion from their own key event types into
     /// [`Input`] so this method can take the event values directly.
     /// This method returns if the input modified text contents or not in the textarea.
+    ///
+    /// When a keymap is set by [`TextArea::set_keymap`], key sequences in the keymap are resolved before handling the
+    /// input. See [`Keymap`] for more details.
     /// ```ignore
     /// use tui_textarea::{TextArea, Key, Input};
     ///
@@ -271,6 +560,157 @@ impl<'a> TextArea<'a> {
     /// ```
     pub fn input(&mut self, input: impl Into<Input>) -> bool {
         let input = input.into();
+        if self.keymap.is_some() {
+            return self.track_cursor(|t| {
+                let modified = t.process_pending_keys();
+                t.pending_keys.push(input);
+                t.resolve_pending_keys(false) || modified
+            });
+        }
+        self.track_cursor(|t| t.record_change(input, true))
+    }
+
+    // Resolve the pending keys with the keymap. Keys are kept pending while they may be followed by more keys of some
+    // key sequence unless `flush` is `true`. Keys which are not bound are handled as usual inputs.
+    fn resolve_pending_keys(&mut self, flush: bool) -> bool {
+        let mut modified = false;
+        while !self.pending_keys.is_empty() {
+            let keymap = match self.keymap.take() {
+                Some(keymap) => keymap,
+                None => break,
+            };
+            if !flush && keymap.has_longer(&self.pending_keys) {
+                self.keymap = Some(keymap);
+                self.pending_since = util::now();
+                return modified;
+            }
+            // Resolve the longest bound sequence at the head of the pending keys
+            let mut keys = mem::take(&mut self.pending_keys);
+            let bound = (1..=keys.len())
+                .rev()
+                .find_map(|n| keymap.get(&keys[..n]).map(|a| (n, a.clone())));
+            let rest = match bound {
+                Some((n, action)) => {
+                    // The keymap is not applied while running the action so that the action can handle inputs as usual
+                    modified |= action.call(self);
+                    keys.split_off(n)
+                }
+                None => {
+                    let rest = keys.split_off(1);
+                    modified |= self.record_change(keys.remove(0), true);
+                    rest
+                }
+            };
+            if self.keymap.is_none() {
+                self.keymap = Some(keymap); // Keep the keymap set by the action
+            }
+            self.pending_keys = rest;
+        }
+        self.pending_since = None;
+        modified
+    }
+
+    /// Set the keymap to bind key sequences such as `g g` to actions. [`TextArea::input`] holds the keys which may be
+    /// a part of some key sequence in the keymap. Setting a keymap replaces the previous one and discards the pending
+    /// keys. See [`Keymap`] for the example.
+    pub fn set_keymap(&mut self, keymap: Keymap) {
+        self.keymap = Some(keymap);
+        self.clear_pending_keys();
+    }
+
+    /// Get the keymap set by [`TextArea::set_keymap`].
+    pub fn keymap(&self) -> Option<&Keymap> {
+        self.keymap.as_ref()
+    }
+
+    /// Remove the keymap set by [`TextArea::set_keymap`] and return it. The pending keys are handled as usual inputs.
+    pub fn remove_keymap(&mut self) -> Option<Keymap> {
+        let keymap = self.keymap.take();
+        let keys = mem::take(&mut self.pending_keys);
+        self.pending_since = None;
+        self.track_cursor(|t| {
+            for key in keys {
+                t.record_change(key, true);
+            }
+        });
+        keymap
+    }
+
+    /// Get the keys typed so far which may be a part of some key sequence in the keymap. Applications can show them
+    /// in the status line like `showcmd` of Vim.
+    pub fn pending_keys(&self) -> &[Input] {
+        &self.pending_keys
+    }
+
+    /// Resolve the pending keys when the timeout of the keymap passed since the last key was typed. When the pending
+    /// keys are bound to an action, the action is called. Otherwise they are handled as usual inputs. Call this method
+    /// periodically (e.g. on the tick of the event loop) so that key sequences like `g` which is a prefix of `g g` are
+    /// resolved without waiting for the next key. This method returns if the text was modified or not.
+    /// ```
+    /// use std::time::Duration;
+    /// use tui_textarea::{TextArea, Keymap, Input, Key};
+    ///
+    /// let key = |c| Input { key: Key::Char(c), ..Default::default() };
+    ///
+    /// let mut keymap = Keymap::new();
+    /// keymap.set_timeout(Some(Duration::ZERO));
+    /// keymap.bind([key('j'), key('k')], |t| t.delete_line_by_head());
+    ///
+    /// let mut textarea = TextArea::default();
+    /// textarea.set_keymap(keymap);
+    ///
+    /// textarea.input(key('j'));
+    /// assert_eq!(textarea.pending_keys(), [key('j')]);
+    ///
+    /// assert!(textarea.process_pending_keys());
+    /// assert!(textarea.pending_keys().is_empty());
+    /// assert_eq!(textarea.lines(), ["j"]);
+    /// ```
+    pub fn process_pending_keys(&mut self) -> bool {
+        let timed_out = match (&self.keymap, self.pending_since) {
+            (Some(keymap), Some(since)) => keymap.timeout().map_or(false, |t| since.elapsed() >= t),
+            _ => false,
+        };
+        if !timed_out {
+            return false;
+        }
+        self.track_cursor(|t| t.resolve_pending_keys(true))
+    }
+
+    /// Discard the pending keys without handling them.
+    pub fn clear_pending_keys(&mut self) {
+        self.pending_keys.clear();
+        self.pending_since = None;
+    }
+
+    // Handle the input and record it as a part of the last change when it pushed some edit to the history. Consecutive
+    // inputs which modify the text are recorded as one change, like typing a word.
+    fn record_change(&mut self, input: Input, shortcuts: bool) -> bool {
+        let (cursor, pushes) = (self.cursor, self.history.pushes());
+        let modified = if shortcuts {
+            self.handle_input(input.clone())
+        } else {
+            self.handle_input_without_shortcuts(input.clone())
+        };
+        if self.history.pushes() == pushes {
+            self.last_change_cursor = None;
+            return modified;
+        }
+        if self.last_change_cursor != Some(cursor) {
+            self.last_change.clear();
+        }
+        self.last_change.push((input, shortcuts));
+        self.last_change_cursor = Some(self.cursor);
+        modified
+    }
+
+    fn handle_input(&mut self, input: Input) -> bool {
+        if self.selected_completion().is_some() {
+            if let Some(modified) = self.input_completion(&input) {
+                return modified;
+            }
+        }
+
         let modified = match input {
             Input {
                 key: Key::Char('m'),
@@ -383,6 +823,9 @@ impl<'a> TextArea<'a> {
                 alt: false,
                 shift,
             } => {
+                if self.prompt_mode && !shift && self.cursor.0 + 1 == self.lines.len() {
+                    return self.next_prompt_history();
+                }
                 self.move_cursor_with_shift(CursorMove::Down, shift);
                 false
             }
@@ -398,6 +841,9 @@ impl<'a> TextArea<'a> {
                 alt: false,
                 shift,
             } => {
+                if self.prompt_mode && !shift && self.cursor.0 == 0 {
+                    return self.prev_prompt_history();
+                }
                 self.move_cursor_with_shift(CursorMove::Up, shift);
                 false
             }
@@ -680,6 +1126,53 @@ impl<'a> TextArea<'a> {
         modified
     }
 
+    // Handle a key input while the completion popup is shown. Returns `None` when the input is not for completion.
+    fn input_completion(&mut self, input: &Input) -> Option<bool> {
+        match input {
+            Input {
+                key: Key::Tab,
+                ctrl: false,
+                alt: false,
+                ..
+            } => Some(self.accept_completion()),
+            Input {
+                key: Key::Char('n'),
+                ctrl: true,
+                alt: false,
+                ..
+            }
+            | Input {
+                key: Key::Down,
+                ctrl: false,
+                alt: false,
+                ..
+            } => {
+                self.select_next_completion();
+                Some(false)
+            }
+            Input {
+                key: Key::Char('p'),
+                ctrl: true,
+                alt: false,
+                ..
+            }
+            | Input {
+                key: Key::Up,
+                ctrl: false,
+                alt: false,
+                ..
+            } => {
+                self.select_prev_completion();
+                Some(false)
+            }
+            Input { key: Key::Esc, .. } => {
+                self.clear_completion();
+                Some(false)
+            }
+            _ => None,
+        }
+    }
+
     /// Handle a key input without default key mappings. This method handles only
     ///
     /// - Single character input without modifier keys
@@ -693,7 +1186,12 @@ impl<'a> TextArea<'a> {
     /// This method is useful when you want to define your own key mappings and don't want default key mappings.
     /// See 'Define your own key mappings' section in [the module document](./index.html).
     pub fn input_without_shortcuts(&mut self, input: impl Into<Input>) -> bool {
-        match input.into() {
+        let input = input.into();
+        self.track_cursor(|t| t.record_change(input, false))
+    }
+
+    fn handle_input_without_shortcuts(&mut self, input: Input) -> bool {
+        match input {
             Input {
                 key: Key::Char(c),
                 ctrl: false,
@@ -740,11 +1238,248 @@ impl<'a> TextArea<'a> {
         }
     }
 
+    /// Handle a key input with default key mappings repeatedly `count` times. All modifications caused by the inputs
+    /// are recorded as one undo/redo entry so that a single [`TextArea::undo`] call reverts them. This is useful to
+    /// implement count prefix of Vim such as `3x` or `5j`. This method returns if the inputs modified text contents or
+    /// not in the textarea.
+    /// ```
+    /// use tui_textarea::{TextArea, Input, Key};
+    ///
+    /// let mut textarea = TextArea::from(["abcdef"]);
+    ///
+    /// // Delete 3 characters like `3x` in Vim
+    /// let input = Input { key: Key::Delete, ctrl: false, alt: false, shift: false };
+    /// assert!(textarea.input_with_count(input, 3));
+    /// assert_eq!(textarea.lines(), ["def"]);
+    ///
+    /// // The deletions are undone at once
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["abcdef"]);
+    /// ```
+    pub fn input_with_count(&mut self, input: impl Into<Input>, count: usize) -> bool {
+        self.track_cursor(|t| t.input_with_count_inner(input, count))
+    }
+
+    fn input_with_count_inner(&mut self, input: impl Into<Input>, count: usize) -> bool {
+        let input = input.into();
+        self.with_history_group(|t| {
+            let mut modified = false;
+            for _ in 0..count {
+                modified |= t.input(input.clone());
+            }
+            modified
+        })
+    }
+
+    /// Repeat the last change made by key inputs at the current cursor position like `.` command of Vim. A change is
+    /// a sequence of consecutive inputs passed to [`TextArea::input`] or [`TextArea::input_without_shortcuts`] which
+    /// modified the text, such as typing a word, deleting a word, or pasting the yanked text. Moving the cursor or an
+    /// input which doesn't modify the text ends the change. Undo and redo are not changes. The repeated change is
+    /// recorded as one history entry. This method returns if the text was modified or not.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove, Input, Key};
+    ///
+    /// let mut textarea = TextArea::from(["foo", "bar"]);
+    ///
+    /// for c in "// ".chars() {
+    ///     textarea.input(Input { key: Key::Char(c), ..Default::default() });
+    /// }
+    /// assert_eq!(textarea.lines(), ["// foo", "bar"]);
+    ///
+    /// // Insert "// " at the head of the next line
+    /// textarea.move_cursor(CursorMove::Down);
+    /// textarea.move_cursor(CursorMove::Head);
+    /// textarea.repeat_last_change();
+    /// assert_eq!(textarea.lines(), ["// foo", "// bar"]);
+    ///
+    /// // The repeated change is undone at once
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["// foo", "bar"]);
+    /// ```
+    pub fn repeat_last_change(&mut self) -> bool {
+        let inputs = mem::take(&mut self.last_change);
+        if inputs.is_empty() {
+            return false;
+        }
+        let modified = self.track_cursor(|t| {
+            t.with_history_group(|t| {
+                let mut modified = false;
+                for (input, shortcuts) in inputs.iter().cloned() {
+                    modified |= t.record_change(input, shortcuts);
+                }
+                modified
+            })
+        });
+        self.last_change = inputs;
+        self.last_change_cursor = None;
+        modified
+    }
+
+    /// Queue a key input to handle it later with [`TextArea::process_queued`]. This is useful to replay a large number
+    /// of inputs such as keyboard macros or pasted scripts across multiple frames without blocking the UI. At most
+    /// 1,048,576 inputs can be queued to guard against runaway expansion of macros. This method returns `false` when
+    /// the queue is full and the input was dropped.
+    /// ```
+    /// use tui_textarea::{TextArea, Input, Key};
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// for c in "hello".chars() {
+    ///     assert!(textarea.queue_input(Input { key: Key::Char(c), ..Default::default() }));
+    /// }
+    /// assert_eq!(textarea.queued_inputs(), 5);
+    /// assert_eq!(textarea.lines(), [""]);
+    /// ```
+    pub fn queue_input(&mut self, input: impl Into<Input>) -> bool {
+        if self.input_queue.len() >= MAX_QUEUED_INPUTS {
+            return false;
+        }
+        self.input_queue.push_back(input.into());
+        true
+    }
+
+    /// Handle at most `max` inputs queued by [`TextArea::queue_input`] in order with default key mappings like
+    /// [`TextArea::input`]. Call this method on each tick of the application's event loop until
+    /// [`TextArea::queued_inputs`] returns 0. This method returns if the handled inputs modified text contents or not.
+    /// ```
+    /// use tui_textarea::{TextArea, Input, Key};
+    ///
+    /// let mut textarea = TextArea::default();
+    /// for c in "hello".chars() {
+    ///     textarea.queue_input(Input { key: Key::Char(c), ..Default::default() });
+    /// }
+    ///
+    /// // Handle 3 inputs per tick
+    /// assert!(textarea.process_queued(3));
+    /// assert_eq!(textarea.lines(), ["hel"]);
+    /// assert!(textarea.process_queued(3));
+    /// assert_eq!(textarea.lines(), ["hello"]);
+    /// assert_eq!(textarea.queued_inputs(), 0);
+    /// assert!(!textarea.process_queued(3));
+    /// ```
+    pub fn process_queued(&mut self, max: usize) -> bool {
+        let mut modified = false;
+        for _ in 0..max {
+            match self.input_queue.pop_front() {
+                Some(input) => modified |= self.input(input),
+                None => break,
+            }
+        }
+        modified
+    }
+
+    /// Get the number of inputs queued by [`TextArea::queue_input`] which have not been handled yet.
+    pub fn queued_inputs(&self) -> usize {
+        self.input_queue.len()
+    }
+
+    /// Discard all inputs queued by [`TextArea::queue_input`] which have not been handled yet.
+    /// ```
+    /// use tui_textarea::{TextArea, Input, Key};
+    ///
+    /// let mut textarea = TextArea::default();
+    /// textarea.queue_input(Input { key: Key::Char('a'), ..Default::default() });
+    ///
+    /// textarea.clear_queued_inputs();
+    /// assert!(!textarea.process_queued(1));
+    /// assert_eq!(textarea.lines(), [""]);
+    /// ```
+    pub fn clear_queued_inputs(&mut self) {
+        self.input_queue.clear();
+    }
+
+    // Run the function and call the cursor change callback if the cursor or the selection was changed by it. The
+    // callback is taken while running the function so that nested calls don't call it more than once. Public methods
+    // with long bodies pass their `*_inner` counterparts to this function.
+    fn track_cursor<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        let cursor = self.cursor;
+        let ret = self.notify_cursor_change(f);
+        // Keep the cursor visible while it is moving, like a blinking cursor of terminals
+        if self.cursor != cursor {
+            self.reset_blink();
+        }
+        ret
+    }
+
+    fn notify_cursor_change<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        let callback = match self.on_cursor_change.take() {
+            Some(callback) => callback,
+            None => return self.with_virtual_cursor(f),
+        };
+        let (old_cursor, old_selection) = (self.cursor(), self.selection_range());
+        let ret = self.with_virtual_cursor(f);
+        let (new_cursor, new_selection) = (self.cursor(), self.selection_range());
+        if old_cursor != new_cursor || old_selection != new_selection {
+            let change = CursorChange {
+                old_cursor,
+                new_cursor,
+                old_selection,
+                new_selection,
+            };
+            if let Ok(mut f) = callback.0.lock() {
+                f(&change);
+            }
+        }
+        self.on_cursor_change = Some(callback);
+        ret
+    }
+
+    // Run the function and reset the cursor beyond the end of line when the function moved the cursor without
+    // touching the virtual cursor.
+    fn with_virtual_cursor<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        let (cursor, virtual_cursor) = (self.cursor, self.virtual_cursor);
+        let ret = f(self);
+        if self.cursor != cursor && self.virtual_cursor == virtual_cursor {
+            self.virtual_cursor = None;
+        }
+        if self.selection_start.is_none() && !self.history.selections().is_empty() {
+            // Secondary selections exist only while the primary selection is ongoing
+            self.history.set_selections(vec![]);
+        }
+        ret
+    }
+
+    // Number of columns between the end of line and the cursor placed beyond it in virtual edit mode
+    fn virtual_padding(&self) -> usize {
+        let (row, col) = self.cursor;
+        match self.virtual_cursor {
+            Some((r, c)) if r == row && col == self.lines[row].chars().count() => {
+                c.saturating_sub(col)
+            }
+            _ => 0,
+        }
+    }
+
+    // Run the edit after inserting spaces to fill the gap between the end of line and the cursor in virtual edit mode.
+    // The spaces and the edit are recorded as one history entry.
+    fn with_virtual_padding<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        let padding = self.virtual_padding();
+        self.virtual_cursor = None;
+        if padding == 0 {
+            return f(self);
+        }
+        self.with_history_group(|t| {
+            t.insert_piece(Arc::new(" ".repeat(padding)));
+            f(t)
+        })
+    }
+
+    // Run the function with recording all edits in it as one history entry.
+    fn with_history_group<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        self.history.begin_group();
+        let ret = f(self);
+        self.history.end_group();
+        ret
+    }
+
     fn push_history(&mut self, kind: EditKind, before: Pos, after_offset: usize) {
         let (row, col) = self.cursor;
         let after = Pos::new(row, col, after_offset);
+        self.metrics.update(&self.lines, &kind, &before, &after);
+        self.offsets.set(self.metrics.edits(), after.clone());
         let edit = Edit::new(kind, before, after);
         self.history.push(edit);
+        self.last_typed = None;
     }
 
     /// Insert a single character at current cursor position.
@@ -757,26 +1492,33 @@ impl<'a> TextArea<'a> {
     /// assert_eq!(textarea.lines(), ["a"]);
     /// ```
     pub fn insert_char(&mut self, c: char) {
+        self.track_cursor(|t| t.insert_char_inner(c))
+    }
+
+    fn insert_char_inner(&mut self, c: char) {
         if c == '\n' || c == '\r' {
             self.insert_newline();
             return;
         }
 
+        if self.has_secondary_selections() {
+            self.edit_selections(c.encode_utf8(&mut [0; 4]), |_, s, e| (s, e));
+            return;
+        }
+
         self.delete_selection(false);
-        let (row, col) = self.cursor;
-        let line = &mut self.lines[row];
-        let i = line
-            .char_indices()
-            .nth(col)
-            .map(|(i, _)| i)
-            .unwrap_or(line.len());
-        line.insert(i, c);
-        self.cursor.1 += 1;
-        self.push_history(
-            EditKind::InsertChar(c),
-            Pos::new(row, col, i),
-            i + c.len_utf8(),
-        );
+        self.with_virtual_padding(|t| {
+            let (row, col) = t.cursor;
+            let i = t.cached_line_offset(row, col);
+            t.lines[row].insert(i, c);
+            t.cursor.1 += 1;
+            t.push_history(
+                EditKind::InsertChar(c),
+                Pos::new(row, col, i),
+                i + c.len_utf8(),
+            );
+            t.last_typed = util::now().map(|at| (at, (row, col)));
+        })
     }
 
     /// Insert a string at current cursor position. This method returns if some text was inserted or not in the textarea.
@@ -793,29 +1535,39 @@ impl<'a> TextArea<'a> {
     /// assert_eq!(textarea.lines(), ["hello, world", "goodbye, world"]);
     /// ```
     pub fn insert_str<S: AsRef<str>>(&mut self, s: S) -> bool {
-        let modified = self.delete_selection(false);
-        let mut lines: Vec<_> = s
-            .as_ref()
-            .split('\n')
-            .map(|s| s.strip_suffix('\r').unwrap_or(s).to_string())
-            .collect();
-        match lines.len() {
-            0 => modified,
-            1 => self.insert_piece(lines.remove(0)),
-            _ => self.insert_chunk(lines),
-        }
+        let s = match &self.paste_filter {
+            Some(f) => {
+                let filtered = (f.0)(s.as_ref().to_string());
+                if filtered.is_empty() {
+                    return false; // The text was rejected by the filter
+                }
+                Cow::Owned(filtered)
+            }
+            None => Cow::Borrowed(s.as_ref()),
+        };
+        self.track_cursor(|t| {
+            let mut lines: Vec<_> = s
+                .split('\n')
+                .map(|s| s.strip_suffix('\r').unwrap_or(s).to_string())
+                .collect();
+            if t.has_secondary_selections() {
+                return t.edit_selections(&lines.join("\n"), |_, s, e| (s, e));
+            }
+            let modified = t.delete_selection(false);
+            match lines.len() {
+                0 => modified,
+                1 if lines[0].is_empty() => modified,
+                1 => t.with_virtual_padding(|t| t.insert_piece(Arc::new(lines.remove(0)))),
+                _ => t.with_virtual_padding(|t| t.insert_chunk(Arc::new(lines))),
+            }
+        })
     }
 
-    fn insert_chunk(&mut self, chunk: Vec<String>) -> bool {
+    fn insert_chunk(&mut self, chunk: Arc<Vec<String>>) -> bool {
         debug_assert!(chunk.len() > 1, "Chunk size must be > 1: {:?}", chunk);
 
         let (row, col) = self.cursor;
-        let line = &mut self.lines[row];
-        let i = line
-            .char_indices()
-            .nth(col)
-            .map(|(i, _)| i)
-            .unwrap_or(line.len());
+        let i = self.cached_line_offset(row, col);
         let before = Pos::new(row, col, i);
 
         let (row, col) = (
@@ -833,25 +1585,20 @@ impl<'a> TextArea<'a> {
         true
     }
 
-    fn insert_piece(&mut self, s: String) -> bool {
+    fn insert_piece(&mut self, s: Arc<String>) -> bool {
         if s.is_empty() {
             return false;
         }
 
         let (row, col) = self.cursor;
-        let line = &mut self.lines[row];
         debug_assert!(
             !s.contains('\n'),
             "string given to TextArea::insert_piece must not contain newline: {:?}",
-            line,
+            self.lines[row],
         );
 
-        let i = line
-            .char_indices()
-            .nth(col)
-            .map(|(i, _)| i)
-            .unwrap_or(line.len());
-        line.insert_str(i, &s);
+        let i = self.cached_line_offset(row, col);
+        self.lines[row].insert_str(i, &s);
         let end_offset = i + s.len();
 
         self.cursor.1 += s.chars().count();
@@ -863,12 +1610,14 @@ impl<'a> TextArea<'a> {
         self.cursor = (start.row, start.col);
 
         if start.row == end.row {
-            let removed = self.lines[start.row]
-                .drain(start.offset..end.offset)
-                .as_str()
-                .to_string();
+            let removed = Arc::new(
+                self.lines[start.row]
+                    .drain(start.offset..end.offset)
+                    .as_str()
+                    .to_string(),
+            );
             if should_yank {
-                self.yank = removed.clone().into();
+                self.yank = YankText::Piece(removed.clone());
             }
             self.push_history(EditKind::DeleteStr(removed), end, start.offset);
             return;
@@ -886,13 +1635,17 @@ impl<'a> TextArea<'a> {
             deleted.push(last_line);
         }
 
-        if should_yank {
-            self.yank = YankText::Chunk(deleted.clone());
-        }
-
         let edit = if deleted.len() == 1 {
-            EditKind::DeleteStr(deleted.remove(0))
+            let removed = Arc::new(deleted.remove(0));
+            if should_yank {
+                self.yank = YankText::Piece(removed.clone());
+            }
+            EditKind::DeleteStr(removed)
         } else {
+            let deleted = Arc::new(deleted);
+            if should_yank {
+                self.yank = YankText::Chunk(deleted.clone());
+            }
             EditKind::DeleteChunk(deleted)
         };
         self.push_history(edit, end, start.offset);
@@ -917,6 +1670,10 @@ impl<'a> TextArea<'a> {
     /// assert_eq!(textarea.lines(), ["🐱", "🐮"]);
     /// ```
     pub fn delete_str(&mut self, chars: usize) -> bool {
+        self.track_cursor(|t| t.delete_str_inner(chars))
+    }
+
+    fn delete_str_inner(&mut self, chars: usize) -> bool {
         if self.delete_selection(false) {
             return true;
         }
@@ -944,23 +1701,20 @@ impl<'a> TextArea<'a> {
             }
         };
 
+        let start_offset = self.cached_line_offset(start_row, start_col);
         let line = &self.lines[start_row];
-        let start_offset = {
-            line.char_indices()
-                .nth(start_col)
-                .map(|(i, _)| i)
-                .unwrap_or(line.len())
-        };
 
         // First line
         if let Some((offset_delta, col_delta)) = find_end(&line[start_offset..]) {
             let end_offset = start_offset + offset_delta;
             let end_col = start_col + col_delta;
-            let removed = self.lines[start_row]
-                .drain(start_offset..end_offset)
-                .as_str()
-                .to_string();
-            self.yank = removed.clone().into();
+            let removed = Arc::new(
+                self.lines[start_row]
+                    .drain(start_offset..end_offset)
+                    .as_str()
+                    .to_string(),
+            );
+            self.yank = YankText::Piece(removed.clone());
             self.push_history(
                 EditKind::DeleteStr(removed),
                 Pos::new(start_row, end_col, end_offset),
@@ -983,6 +1737,13 @@ impl<'a> TextArea<'a> {
             r += 1;
         }
 
+        if r == self.lines.len() {
+            // Reached the end of text. Delete until the end of the last line
+            r -= 1;
+            offset = self.lines[r].len();
+            col = self.lines[r].chars().count();
+        }
+
         let start = Pos::new(start_row, start_col, start_offset);
         let end = Pos::new(r, col, offset);
         self.delete_range(start, end, true);
@@ -1008,10 +1769,11 @@ impl<'a> TextArea<'a> {
         }
 
         let (row, _) = self.cursor;
+        let i = self.cached_line_offset(row, col);
         let line = &mut self.lines[row];
-        if let Some((i, _)) = line.char_indices().nth(col) {
+        if i < line.len() {
             let (bytes, chars) = bytes_and_chars(chars, &line[i..]);
-            let removed = line.drain(i..i + bytes).as_str().to_string();
+            let removed = Arc::new(line.drain(i..i + bytes).as_str().to_string());
 
             self.cursor = (row, col);
             self.push_history(
@@ -1019,7 +1781,7 @@ impl<'a> TextArea<'a> {
                 Pos::new(row, col + chars, i + bytes),
                 i,
             );
-            self.yank = removed.into();
+            self.yank = YankText::Piece(removed);
             true
         } else {
             false
@@ -1041,24 +1803,27 @@ impl<'a> TextArea<'a> {
     /// assert_eq!(textarea.lines(), ["hi      "]);
     /// ```
     pub fn insert_tab(&mut self) -> bool {
+        self.track_cursor(|t| t.insert_tab_inner())
+    }
+
+    fn insert_tab_inner(&mut self) -> bool {
         let modified = self.delete_selection(false);
-        if self.tab_len == 0 {
+        if self.indent.width == 0 {
             return modified;
         }
 
-        if self.hard_tab_indent {
+        if self.indent.hard_tab {
             self.insert_char('\t');
             return true;
         }
 
-        let (row, col) = self.cursor;
-        let width: usize = self.lines[row]
-            .chars()
-            .take(col)
-            .map(|c| c.width().unwrap_or(0))
-            .sum();
-        let len = self.tab_len - (width % self.tab_len as usize) as u8;
-        self.insert_piece(spaces(len).to_string())
+        self.with_virtual_padding(|t| {
+            let (row, col) = t.cursor;
+            let line = &t.lines[row][..t.line_offset(row, col)];
+            let width = display_width(line, 0, None, t.control_chars, &t.char_width);
+            let len = t.indent.soft_tab_len(width);
+            t.insert_piece(Arc::new(spaces(len).into_owned()))
+        })
     }
 
     /// Insert a newline at current cursor position.
@@ -1072,15 +1837,20 @@ impl<'a> TextArea<'a> {
     /// assert_eq!(textarea.lines(), ["h", "i"]);
     /// ```
     pub fn insert_newline(&mut self) {
+        self.track_cursor(|t| t.insert_newline_inner())
+    }
+
+    fn insert_newline_inner(&mut self) {
+        if self.has_secondary_selections() {
+            self.edit_selections("\n", |_, s, e| (s, e));
+            return;
+        }
+
         self.delete_selection(false);
 
         let (row, col) = self.cursor;
+        let offset = self.cached_line_offset(row, col);
         let line = &mut self.lines[row];
-        let offset = line
-            .char_indices()
-            .nth(col)
-            .map(|(i, _)| i)
-            .unwrap_or(line.len());
         let next_line = line[offset..].to_string();
         line.truncate(offset);
 
@@ -1101,6 +1871,10 @@ impl<'a> TextArea<'a> {
     /// assert_eq!(textarea.lines(), ["helloworld"]);
     /// ```
     pub fn delete_newline(&mut self) -> bool {
+        self.track_cursor(|t| t.delete_newline_inner())
+    }
+
+    fn delete_newline_inner(&mut self) -> bool {
         if self.delete_selection(false) {
             return true;
         }
@@ -1133,17 +1907,37 @@ impl<'a> TextArea<'a> {
     /// assert_eq!(textarea.lines(), ["bc"]);
     /// ```
     pub fn delete_char(&mut self) -> bool {
+        self.track_cursor(|t| t.delete_char_inner())
+    }
+
+    fn delete_char_inner(&mut self) -> bool {
+        if self.has_secondary_selections() {
+            // Empty selections delete the characters before them
+            return self.edit_selections("", |text, s, e| match text[..s].chars().next_back() {
+                Some(c) if s == e => (s - c.len_utf8(), e),
+                _ => (s, e),
+            });
+        }
+
         if self.delete_selection(false) {
             return true;
         }
 
+        // In virtual edit mode, the cursor beyond the end of line just moves back
+        let padding = self.virtual_padding();
+        if padding > 0 {
+            self.virtual_cursor = Some((self.cursor.0, self.cursor.1 + padding - 1));
+            return false;
+        }
+
         let (row, col) = self.cursor;
         if col == 0 {
             return self.delete_newline();
         }
 
+        let offset = self.cached_line_offset(row, col - 1);
         let line = &mut self.lines[row];
-        if let Some((offset, c)) = line.char_indices().nth(col - 1) {
+        if let Some(c) = line[offset..].chars().next() {
             line.remove(offset);
             self.cursor.1 -= 1;
             self.push_history(
@@ -1169,10 +1963,26 @@ impl<'a> TextArea<'a> {
     /// assert_eq!(textarea.lines(), ["ac"]);
     /// ```
     pub fn delete_next_char(&mut self) -> bool {
+        self.track_cursor(|t| t.delete_next_char_inner())
+    }
+
+    fn delete_next_char_inner(&mut self) -> bool {
+        if self.has_secondary_selections() {
+            // Empty selections delete the characters after them
+            return self.edit_selections("", |text, s, e| match text[e..].chars().next() {
+                Some(c) if s == e => (s, e + c.len_utf8()),
+                _ => (s, e),
+            });
+        }
+
         if self.delete_selection(false) {
             return true;
         }
 
+        if self.virtual_padding() > 0 {
+            return false; // No character after the cursor beyond the end of line
+        }
+
         let before = self.cursor;
         self.move_cursor_with_shift(CursorMove::Forward, false);
         if before == self.cursor {
@@ -1182,6 +1992,56 @@ impl<'a> TextArea<'a> {
         self.delete_char()
     }
 
+    /// Replace the character under the cursor with the character without moving the cursor like `r` command of Vim.
+    /// The replacement is recorded as one edit so that a single undo restores the previous character. Unlike deleting
+    /// the character and inserting a new one, the cursor stays at the same column even if the widths of the
+    /// characters differ. The text selection is cancelled. This method returns if the character was replaced or not.
+    /// Nothing is replaced when the cursor is at the end of line, or the character is a newline or the same as the
+    /// character under the cursor.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["aあc"]);
+    ///
+    /// textarea.move_cursor(CursorMove::Forward);
+    /// assert!(textarea.replace_char('b'));
+    /// assert_eq!(textarea.lines(), ["abc"]);
+    /// assert_eq!(textarea.cursor(), (0, 1));
+    ///
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["aあc"]);
+    ///
+    /// // Nothing to replace at the end of line
+    /// textarea.move_cursor(CursorMove::End);
+    /// assert!(!textarea.replace_char('d'));
+    /// ```
+    pub fn replace_char(&mut self, c: char) -> bool {
+        self.track_cursor(|t| {
+            t.cancel_selection();
+            if c == '\n' || c == '\r' || t.virtual_padding() > 0 {
+                return false;
+            }
+            let (row, col) = t.cursor;
+            let i = t.cached_line_offset(row, col);
+            let old = match t.lines[row][i..].chars().next() {
+                Some(old) if old != c => old,
+                _ => return false,
+            };
+            // Insert the new character before deleting the old one so that undo restores the cursor at the column
+            t.with_history_group(|t| {
+                t.insert_piece(Arc::new(c.to_string()));
+                let start = i + c.len_utf8();
+                t.delete_range(
+                    Pos::new(row, col + 1, start),
+                    Pos::new(row, col + 2, start + old.len_utf8()),
+                    false,
+                );
+            });
+            t.cursor = (row, col);
+            true
+        })
+    }
+
     /// Delete string from cursor to end of the line. When the cursor is at end of line, the newline next to the cursor
     /// is removed. This method returns if some text was deleted or not in the textarea.
     /// ```
@@ -1197,6 +2057,10 @@ impl<'a> TextArea<'a> {
     /// assert_eq!(textarea.lines(), ["ab"]);
     /// ```
     pub fn delete_line_by_end(&mut self) -> bool {
+        self.track_cursor(|t| t.delete_line_by_end_inner())
+    }
+
+    fn delete_line_by_end_inner(&mut self) -> bool {
         if self.delete_selection(false) {
             return true;
         }
@@ -1221,6 +2085,10 @@ impl<'a> TextArea<'a> {
     /// assert_eq!(textarea.lines(), ["cde"]);
     /// ```
     pub fn delete_line_by_head(&mut self) -> bool {
+        self.track_cursor(|t| t.delete_line_by_head_inner())
+    }
+
+    fn delete_line_by_head_inner(&mut self) -> bool {
         if self.delete_selection(false) {
             return true;
         }
@@ -1233,6 +2101,7 @@ impl<'a> TextArea<'a> {
     /// Delete a word before cursor. Word boundary appears at spaces, punctuations, and others. For example `fn foo(a)`
     /// consists of words `fn`, `foo`, `(`, `a`, `)`. When the cursor is at head of line, the newline before the cursor
     /// will be removed.
+    /// Word boundaries can be changed with [`TextArea::set_word_boundary_mode`] and [`TextArea::set_word_chars`].
     ///
     /// This method returns if some text was deleted or not in the textarea.
     ///
@@ -1249,11 +2118,15 @@ impl<'a> TextArea<'a> {
     /// assert_eq!(textarea.lines(), ["aaa "]);
     /// ```
     pub fn delete_word(&mut self) -> bool {
+        self.track_cursor(|t| t.delete_word_inner())
+    }
+
+    fn delete_word_inner(&mut self) -> bool {
         if self.delete_selection(false) {
             return true;
         }
         let (r, c) = self.cursor;
-        if let Some(col) = find_word_start_backward(&self.lines[r], c) {
+        if let Some(col) = find_word_start_backward(&self.lines[r], c, &self.words) {
             self.delete_piece(col, c - col)
         } else if c > 0 {
             self.delete_piece(0, c)
@@ -1265,6 +2138,7 @@ impl<'a> TextArea<'a> {
     /// Delete a word next to cursor. Word boundary appears at spaces, punctuations, and others. For example `fn foo(a)`
     /// consists of words `fn`, `foo`, `(`, `a`, `)`. When the cursor is at end of line, the newline next to the cursor
     /// will be removed.
+    /// Word boundaries can be changed with [`TextArea::set_word_boundary_mode`] and [`TextArea::set_word_chars`].
     ///
     /// This method returns if some text was deleted or not in the textarea.
     ///
@@ -1279,12 +2153,16 @@ impl<'a> TextArea<'a> {
     /// assert_eq!(textarea.lines(), [" ccc"]);
     /// ```
     pub fn delete_next_word(&mut self) -> bool {
+        self.track_cursor(|t| t.delete_next_word_inner())
+    }
+
+    fn delete_next_word_inner(&mut self) -> bool {
         if self.delete_selection(false) {
             return true;
         }
         let (r, c) = self.cursor;
         let line = &self.lines[r];
-        if let Some(col) = find_word_exclusive_end_forward(line, c) {
+        if let Some(col) = find_word_exclusive_end_forward(line, c, &self.words) {
             self.delete_piece(c, col - c)
         } else {
             let end_col = line.chars().count();
@@ -1299,731 +2177,4911 @@ impl<'a> TextArea<'a> {
         }
     }
 
-    /// Paste a string previously deleted by [`TextArea::delete_line_by_head`], [`TextArea::delete_line_by_end`],
-    /// [`TextArea::delete_word`], [`TextArea::delete_next_word`]. This method returns if some text was inserted or not
-    /// in the textarea.
+    /// Set the definition of word boundaries used by word-wise cursor moves ([`CursorMove::WordForward`],
+    /// [`CursorMove::WordEnd`], [`CursorMove::WordBack`]) and word-wise deletions ([`TextArea::delete_word`],
+    /// [`TextArea::delete_next_word`]). The default value is [`WordBoundary::Default`].
     /// ```
-    /// use tui_textarea::{TextArea, CursorMove};
+    /// use tui_textarea::{TextArea, CursorMove, WordBoundary};
     ///
-    /// let mut textarea = TextArea::from(["aaa bbb ccc"]);
+    /// let mut textarea = TextArea::from(["parseHttpRequest"]);
     ///
+    /// textarea.set_word_boundary_mode(WordBoundary::SubWord);
+    /// assert_eq!(textarea.word_boundary_mode(), WordBoundary::SubWord);
+    ///
+    /// textarea.move_cursor(CursorMove::WordForward);
+    /// assert_eq!(textarea.cursor(), (0, 5));
     /// textarea.delete_next_word();
-    /// textarea.move_cursor(CursorMove::End);
-    /// textarea.paste();
-    /// assert_eq!(textarea.lines(), [" bbb cccaaa"]);
+    /// assert_eq!(textarea.lines(), ["parseRequest"]);
     /// ```
-    pub fn paste(&mut self) -> bool {
-        self.delete_selection(false);
-        match self.yank.clone() {
-            YankText::Piece(s) => self.insert_piece(s),
-            YankText::Chunk(c) => self.insert_chunk(c),
-        }
+    pub fn set_word_boundary_mode(&mut self, boundary: WordBoundary) {
+        self.words.boundary = boundary;
     }
 
-    /// Start text selection at the cursor position. If text selection is already ongoing, the start position is reset.
+    /// Get the definition of word boundaries set by [`TextArea::set_word_boundary_mode`].
+    pub fn word_boundary_mode(&self) -> WordBoundary {
+        self.words.boundary
+    }
+
+    /// Set the predicate to decide which characters are word characters. Word boundaries appear between word
+    /// characters and other non-space characters. By default, all characters except for spaces and ASCII punctuations
+    /// are word characters. This is useful for example to treat `-` and `.` as part of words when editing file paths.
+    /// The predicate is used by word-wise cursor moves and word-wise deletions. It is ignored when the word boundary
+    /// mode is [`WordBoundary::WhitespaceOnly`].
     /// ```
     /// use tui_textarea::{TextArea, CursorMove};
     ///
-    /// let mut textarea = TextArea::from(["aaa bbb ccc"]);
+    /// let mut textarea = TextArea::from(["~/.config/foo-bar.txt"]);
     ///
-    /// textarea.start_selection();
-    /// textarea.move_cursor(CursorMove::WordForward);
-    /// textarea.copy();
-    /// assert_eq!(textarea.yank_text(), "aaa ");
+    /// textarea.set_word_chars(|c| c.is_alphanumeric() || c == '-' || c == '.');
+    /// textarea.move_cursor(CursorMove::End);
+    /// textarea.delete_word();
+    /// assert_eq!(textarea.lines(), ["~/.config/"]);
+    ///
+    /// // Restore the default word characters
+    /// textarea.clear_word_chars();
+    /// textarea.delete_word();
+    /// assert_eq!(textarea.lines(), ["~/.config"]);
     /// ```
-    pub fn start_selection(&mut self) {
-        self.selection_start = Some(self.cursor);
+    pub fn set_word_chars(&mut self, is_word_char: impl Fn(char) -> bool + Send + Sync + 'static) {
+        self.words.chars = Some(Arc::new(is_word_char));
     }
 
-    /// Stop the current text selection. This method does nothing if text selection is not ongoing.
+    /// Clear the predicate set by [`TextArea::set_word_chars`] and restore the default word characters.
+    pub fn clear_word_chars(&mut self) {
+        self.words.chars = None;
+    }
+
+    /// Create a [`WordScanner`] of the line at the row with the word definition of this textarea set by
+    /// [`TextArea::set_word_boundary_mode`] and [`TextArea::set_word_chars`]. `None` is returned when the row is out
+    /// of the text.
     /// ```
-    /// use tui_textarea::{TextArea, CursorMove};
-    ///
-    /// let mut textarea = TextArea::from(["aaa bbb ccc"]);
+    /// use tui_textarea::{TextArea, WordBoundary};
     ///
-    /// textarea.start_selection();
-    /// textarea.move_cursor(CursorMove::WordForward);
+    /// let mut textarea = TextArea::from(["fooBar baz"]);
+    /// textarea.set_word_boundary_mode(WordBoundary::SubWord);
     ///
-    /// // Cancel the ongoing text selection
-    /// textarea.cancel_selection();
+    /// let scanner = textarea.word_scanner(0).unwrap();
+    /// assert_eq!(scanner.next_word_start(0), Some(3));
+    /// assert_eq!(scanner.word_at(8), Some((7, 10)));
     ///
-    /// // As the result, this `copy` call does nothing
-    /// textarea.copy();
-    /// assert_eq!(textarea.yank_text(), "");
+    /// assert!(textarea.word_scanner(1).is_none());
     /// ```
-    pub fn cancel_selection(&mut self) {
-        self.selection_start = None;
+    pub fn word_scanner(&self, row: usize) -> Option<WordScanner<'_>> {
+        let line = self.lines.get(row)?;
+        Some(WordScanner::with_words(line, self.words.clone()))
     }
 
-    /// Select the entire text. Cursor moves to the end of the text buffer. When text selection is already ongoing,
-    /// it is canceled.
+    #[cfg(feature = "vim")]
+    pub(crate) fn words(&self) -> &Words {
+        &self.words
+    }
+
+    /// Paste a string previously deleted by [`TextArea::delete_line_by_head`], [`TextArea::delete_line_by_end`],
+    /// [`TextArea::delete_word`], [`TextArea::delete_next_word`]. This method returns if some text was inserted or not
+    /// in the textarea.
     /// ```
     /// use tui_textarea::{TextArea, CursorMove};
     ///
-    /// let mut textarea = TextArea::from(["aaa", "bbb", "ccc"]);
-    ///
-    /// textarea.select_all();
-    ///
-    /// // Cut the entire text;
-    /// textarea.cut();
+    /// let mut textarea = TextArea::from(["aaa bbb ccc"]);
     ///
-    /// assert_eq!(textarea.lines(), [""]); // Buffer is now empty
-    /// assert_eq!(textarea.yank_text(), "aaa\nbbb\nccc");
+    /// textarea.delete_next_word();
+    /// textarea.move_cursor(CursorMove::End);
+    /// textarea.paste();
+    /// assert_eq!(textarea.lines(), [" bbb cccaaa"]);
     /// ```
-    pub fn select_all(&mut self) {
-        self.move_cursor(CursorMove::Jump(u16::MAX, u16::MAX));
-        self.selection_start = Some((0, 0));
+    pub fn paste(&mut self) -> bool {
+        self.track_cursor(|t| t.paste_inner())
     }
 
-    /// Return if text selection is ongoing or not.
+    fn paste_inner(&mut self) -> bool {
+        self.delete_selection(false);
+        match self.yank.clone() {
+            YankText::Piece(s) if s.is_empty() => false,
+            YankText::Piece(s) => self.with_virtual_padding(|t| t.insert_piece(s)),
+            YankText::Chunk(c) => self.with_virtual_padding(|t| t.insert_chunk(c)),
+        }
+    }
+
+    /// Replace the whole text with the given lines. Unlike creating a new [`TextArea`], only the range which actually
+    /// differs from the current text is edited. So the cursor, the text selection, the scroll position, and the
+    /// undo/redo history are preserved. This is useful when the text is reloaded from outside (e.g. the file was
+    /// modified on disk). The replacement is recorded as one history entry so it can be undone at once. This method
+    /// returns if the text was modified or not. A line containing `\n` is split into multiple lines.
+    ///
+    /// A position after the changed range is shifted along with the edit. A position inside the changed range moves
+    /// to the end of the new text in the range.
     /// ```
-    /// use tui_textarea::{TextArea};
+    /// use tui_textarea::{TextArea, CursorMove};
     ///
-    /// let mut textarea = TextArea::default();
+    /// let mut textarea = TextArea::from(["hello", "world"]);
+    /// textarea.move_cursor(CursorMove::Bottom);
+    /// textarea.move_cursor(CursorMove::End);
     ///
-    /// assert!(!textarea.is_selecting());
-    /// textarea.start_selection();
-    /// assert!(textarea.is_selecting());
-    /// textarea.cancel_selection();
-    /// assert!(!textarea.is_selecting());
+    /// textarea.set_lines(vec!["hello".to_string(), "new".to_string(), "world".to_string()]);
+    /// assert_eq!(textarea.lines(), ["hello", "new", "world"]);
+    /// assert_eq!(textarea.cursor(), (2, 5)); // The cursor follows the text
+    ///
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["hello", "world"]);
     /// ```
-    pub fn is_selecting(&self) -> bool {
-        self.selection_start.is_some()
+    pub fn set_lines(&mut self, lines: Vec<String>) -> bool {
+        self.track_cursor(|t| t.set_lines_inner(lines))
     }
 
-    fn line_offset(&self, row: usize, col: usize) -> usize {
-        let line = self
-            .lines
-            .get(row)
-            .unwrap_or(&self.lines[self.lines.len() - 1]);
-        line.char_indices()
-            .nth(col)
-            .map(|(i, _)| i)
-            .unwrap_or(line.len())
+    fn set_lines_inner(&mut self, mut lines: Vec<String>) -> bool {
+        if lines.is_empty() {
+            lines.push(String::new());
+        }
+
+        let old = self.lines.join("\n");
+        let new = lines.join("\n");
+
+        // Find the common prefix and suffix of the old and new texts
+        let mut prefix = old
+            .bytes()
+            .zip(new.bytes())
+            .take_while(|(a, b)| a == b)
+            .count();
+        while !old.is_char_boundary(prefix) {
+            prefix -= 1;
+        }
+        if prefix == old.len() && prefix == new.len() {
+            return false;
+        }
+        let mut suffix = old
+            .bytes()
+            .rev()
+            .zip(new.bytes().rev())
+            .take(cmp::min(old.len(), new.len()) - prefix)
+            .take_while(|(a, b)| a == b)
+            .count();
+        while !old.is_char_boundary(old.len() - suffix) {
+            suffix -= 1;
+        }
+        self.replace_text_range(prefix, old.len() - suffix, &new[prefix..new.len() - suffix]);
+        debug_assert_eq!(self.lines.join("\n"), new);
+        true
     }
 
-    /// Set the style used for text selection. The default style is light blue.
+    /// Replace the whole text with the given string. Both `\n` and `\r\n` are recognized as newlines. The line endings
+    /// in the string are remembered and returned by [`TextArea::detected_line_ending`] and
+    /// [`TextArea::has_mixed_line_endings`]. See [`TextArea::set_lines`] for the details of how the text is replaced.
     /// ```
     /// use tui_textarea::TextArea;
-    /// use ratatui::style::{Style, Color};
     ///
-    /// let mut textarea = TextArea::default();
+    /// let mut textarea = TextArea::from(["hello, world"]);
     ///
-    /// // Change the selection color from the default to Red
-    /// textarea.set_selection_style(Style::default().bg(Color::Red));
-    /// assert_eq!(textarea.selection_style(), Style::default().bg(Color::Red));
+    /// textarea.set_text("hello, world\ngoodbye, world");
+    /// assert_eq!(textarea.lines(), ["hello, world", "goodbye, world"]);
     /// ```
-    pub fn set_selection_style(&mut self, style: Style) {
-        self.select_style = style;
+    pub fn set_text(&mut self, text: impl AsRef<str>) -> bool {
+        let text = text.as_ref();
+        let (detected, mixed) = line_ending::detect(text);
+        self.detected_line_ending = detected;
+        self.mixed_line_endings = mixed;
+        let lines = text
+            .split('\n')
+            .map(|s| s.strip_suffix('\r').unwrap_or(s).to_string())
+            .collect();
+        self.set_lines(lines)
     }
 
-    /// Get the style used for text selection.
+    /// Append the lines after the last line of the text. This is useful for applications which stream content such as
+    /// logs and chat messages. Unlike inserting the text with [`TextArea::insert_str`], the cursor, the text selection,
+    /// and the scroll position are not moved, and the work is proportional to the size of the appended lines rather
+    /// than the size of the whole text. When the textarea is empty, its empty line is replaced with the appended lines.
+    /// Both `\n` and `\r\n` in the lines are recognized as newlines. The appended lines are recorded as one history
+    /// entry. To exclude them from the history, call this method in [`TextArea::with_history_disabled`]. This method
+    /// returns if the text was modified or not.
     /// ```
     /// use tui_textarea::TextArea;
-    /// use ratatui::style::{Style, Color};
     ///
     /// let mut textarea = TextArea::default();
     ///
-    /// assert_eq!(textarea.selection_style(), Style::default().bg(Color::LightBlue));
+    /// textarea.append_lines(["hello", "world"]);
+    /// assert_eq!(textarea.lines(), ["hello", "world"]);
+    /// assert_eq!(textarea.cursor(), (0, 0));
+    ///
+    /// textarea.append_lines(vec!["goodbye".to_string()]);
+    /// assert_eq!(textarea.lines(), ["hello", "world", "goodbye"]);
+    /// assert_eq!(textarea.cursor(), (0, 0));
+    ///
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["hello", "world"]);
     /// ```
-    pub fn selection_style(&mut self) -> Style {
-        self.select_style
-    }
-
-    fn selection_positions(&self) -> Option<(Pos, Pos)> {
-        let (sr, sc) = self.selection_start?;
-        let (er, ec) = self.cursor;
-        let (so, eo) = (self.line_offset(sr, sc), self.line_offset(er, ec));
-        let s = Pos::new(sr, sc, so);
-        let e = Pos::new(er, ec, eo);
-        match (sr, so).cmp(&(er, eo)) {
-            Ordering::Less => Some((s, e)),
-            Ordering::Equal => None,
-            Ordering::Greater => Some((e, s)),
+    pub fn append_lines<I>(&mut self, lines: I) -> bool
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let mut chunk = vec![];
+        if !self.is_empty() {
+            chunk.push(String::new()); // Newline at the end of the last line
+        }
+        for line in lines {
+            let line = line.as_ref();
+            chunk.extend(
+                line.split('\n')
+                    .map(|s| s.strip_suffix('\r').unwrap_or(s).to_string()),
+            );
+        }
+        if chunk.is_empty() || chunk == [""] {
+            return false;
         }
-    }
 
-    fn take_selection_positions(&mut self) -> Option<(Pos, Pos)> {
-        let range = self.selection_positions();
-        self.cancel_selection();
-        range
+        self.track_cursor(|t| {
+            let row = t.lines.len() - 1;
+            let line = &t.lines[row];
+            let before = Pos::new(row, line.chars().count(), line.len());
+            let last = chunk.last().unwrap();
+            let after = if chunk.len() == 1 {
+                Pos::new(
+                    row,
+                    before.col + last.chars().count(),
+                    before.offset + last.len(),
+                )
+            } else {
+                Pos::new(row + chunk.len() - 1, last.chars().count(), last.len())
+            };
+            let kind = if chunk.len() == 1 {
+                EditKind::InsertStr(Arc::new(chunk.remove(0)))
+            } else {
+                EditKind::InsertChunk(Arc::new(chunk))
+            };
+            kind.apply(&mut t.lines, &before, &after);
+            t.metrics.update(&t.lines, &kind, &before, &after);
+            t.history.push(Edit::new(kind, before, after));
+            t.last_typed = None;
+            true
+        })
     }
 
-    /// Copy the selection text to the yank buffer. When nothing is selected, this method does nothing.
-    /// To get the yanked text, use [`TextArea::yank_text`].
+    /// Replace the text between the two 0-base character-wise (row, col) positions with the given string. The end
+    /// position is exclusive. Positions are clamped to fit within the text and the order of the two positions does not
+    /// matter. Both `\n` and `\r\n` in the string are recognized as newlines. This is useful to apply edits which
+    /// are represented as ranges, such as text edits from language servers.
+    ///
+    /// The replacement is recorded as one history entry. The cursor and the text selection are adjusted to follow the
+    /// edit. A position inside the replaced range moves to the end of the inserted text. When lines are inserted or
+    /// deleted above the viewport, the scroll position is also adjusted so that the content on the screen stays
+    /// stable. This method returns if the text was modified or not.
     /// ```
-    /// use tui_textarea::{TextArea, Key, Input, CursorMove};
+    /// use tui_textarea::TextArea;
     ///
-    /// let mut textarea = TextArea::from(["Hello World"]);
+    /// let mut textarea = TextArea::from(["fn foo() {}", "foo();"]);
     ///
-    /// // Start text selection at 'W'
-    /// textarea.move_cursor(CursorMove::WordForward);
-    /// textarea.start_selection();
+    /// textarea.replace_range((0, 3), (0, 6), "bar");
+    /// assert_eq!(textarea.lines(), ["fn bar() {}", "foo();"]);
     ///
-    /// // Select the word "World" and copy the selected text
-    /// textarea.move_cursor(CursorMove::End);
-    /// textarea.copy();
+    /// // Replace a range across lines
+    /// textarea.replace_range((0, 10), (1, 3), "\n    bar");
+    /// assert_eq!(textarea.lines(), ["fn bar() {", "    bar();"]);
     ///
-    /// assert_eq!(textarea.yank_text(), "World");
-    /// assert_eq!(textarea.lines(), ["Hello World"]); // Text does not change
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["fn bar() {}", "foo();"]);
     /// ```
-    pub fn copy(&mut self) {
-        if let Some((start, end)) = self.take_selection_positions() {
-            if start.row == end.row {
-                self.yank = self.lines[start.row][start.offset..end.offset]
-                    .to_string()
-                    .into();
-                return;
-            }
-            let mut chunk = vec![self.lines[start.row][start.offset..].to_string()];
-            chunk.extend(self.lines[start.row + 1..end.row].iter().cloned());
-            chunk.push(self.lines[end.row][..end.offset].to_string());
-            self.yank = YankText::Chunk(chunk);
-        }
+    pub fn replace_range(
+        &mut self,
+        start: (usize, usize),
+        end: (usize, usize),
+        text: impl AsRef<str>,
+    ) -> bool {
+        self.track_cursor(|t| {
+            let start = t.text_offset(t.clamp_position(start));
+            let end = t.text_offset(t.clamp_position(end));
+            let text = text.as_ref().replace("\r\n", "\n");
+            t.replace_text_range(cmp::min(start, end), cmp::max(start, end), &text)
+        })
     }
 
-    /// Cut the selected text and place it in the yank buffer. This method returns whether the text was modified.
-    /// The cursor will move to the start position of the text selection.
-    /// To get the yanked text, use [`TextArea::yank_text`].
+    /// Get the text between the two 0-base character-wise (row, col) positions. The end position is exclusive. Lines
+    /// are joined with `\n`. Positions are clamped to fit within the text and the order of the two positions does not
+    /// matter.
     /// ```
-    /// use tui_textarea::{TextArea, Key, Input, CursorMove};
-    ///
-    /// let mut textarea = TextArea::from(["Hello World"]);
-    ///
-    /// // Start text selection at 'W'
-    /// textarea.move_cursor(CursorMove::WordForward);
-    /// textarea.start_selection();
+    /// use tui_textarea::TextArea;
     ///
-    /// // Select the word "World" and copy the selected text
-    /// textarea.move_cursor(CursorMove::End);
-    /// textarea.cut();
+    /// let textarea = TextArea::from(["fn foo() {", "    bar();", "}"]);
     ///
-    /// assert_eq!(textarea.yank_text(), "World");
-    /// assert_eq!(textarea.lines(), ["Hello "]);
+    /// assert_eq!(textarea.text_in_range((0, 3), (0, 6)), "foo");
+    /// assert_eq!(textarea.text_in_range((0, 9), (1, 7)), "{\n    bar");
+    /// assert_eq!(textarea.text_in_range((2, 0), (10, 10)), "}");
     /// ```
-    pub fn cut(&mut self) -> bool {
-        self.delete_selection(true)
+    pub fn text_in_range(&self, start: (usize, usize), end: (usize, usize)) -> String {
+        let (start, end) = (self.clamp_position(start), self.clamp_position(end));
+        let (start, end) = (cmp::min(start, end), cmp::max(start, end));
+        let (so, eo) = (
+            self.line_offset(start.0, start.1),
+            self.line_offset(end.0, end.1),
+        );
+        if start.0 == end.0 {
+            return self.lines[start.0][so..eo].to_string();
+        }
+        let mut text = self.lines[start.0][so..].to_string();
+        for line in &self.lines[start.0 + 1..end.0] {
+            text.push('\n');
+            text.push_str(line);
+        }
+        text.push('\n');
+        text.push_str(&self.lines[end.0][..eo]);
+        text
     }
 
-    fn delete_selection(&mut self, should_yank: bool) -> bool {
-        if let Some((s, e)) = self.take_selection_positions() {
-            self.delete_range(s, e, should_yank);
-            return true;
+    /// Insert the lines before the line at the `row`. When the row is equal to or larger than the number of lines, the
+    /// lines are appended at the end of the text. Both `\n` and `\r\n` in the lines are recognized as newlines. This is
+    /// useful to edit structured content such as todo lists and tables row by row without moving the cursor. To
+    /// append lines to the text without the history entry cost of the whole text, see [`TextArea::append_lines`].
+    ///
+    /// The insertion is recorded as one history entry. The cursor and the text selection stay on the same lines, so
+    /// they are shifted down when the lines are inserted above them. This method returns if some lines were inserted
+    /// or not.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["- [ ] foo", "- [ ] bar"]);
+    /// textarea.move_cursor(CursorMove::Down);
+    ///
+    /// textarea.insert_lines_at(1, ["- [x] done"]);
+    /// assert_eq!(textarea.lines(), ["- [ ] foo", "- [x] done", "- [ ] bar"]);
+    /// assert_eq!(textarea.cursor(), (2, 0)); // The cursor stays on "- [ ] bar"
+    ///
+    /// // Append lines at the end
+    /// textarea.insert_lines_at(usize::MAX, ["- [ ] baz"]);
+    /// assert_eq!(textarea.lines(), ["- [ ] foo", "- [x] done", "- [ ] bar", "- [ ] baz"]);
+    ///
+    /// textarea.undo();
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["- [ ] foo", "- [ ] bar"]);
+    /// ```
+    pub fn insert_lines_at<I>(&mut self, row: usize, lines: I) -> bool
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let mut inserted = vec![];
+        for line in lines {
+            inserted.extend(
+                line.as_ref()
+                    .split('\n')
+                    .map(|s| s.strip_suffix('\r').unwrap_or(s).to_string()),
+            );
         }
-        false
+        if inserted.is_empty() {
+            return false;
+        }
+        let text = inserted.join("\n");
+        self.track_cursor(|t| {
+            let len = t.lines.len();
+            if row == 0 {
+                // Positions at the head of the text would stay before the inserted lines
+                let (cursor, selection_start) = (t.cursor, t.selection_start);
+                t.replace_text_range(0, 0, &format!("{}\n", text));
+                let n = inserted.len();
+                t.cursor = (cursor.0 + n, cursor.1);
+                t.selection_start = selection_start.map(|(r, c)| (r + n, c));
+            } else {
+                // Insert after the end of the previous line so that positions on the row follow the line
+                let row = cmp::min(row, len);
+                let offset = t.text_offset((row - 1, t.lines[row - 1].chars().count()));
+                t.replace_text_range(offset, offset, &format!("\n{}", text));
+            }
+            true
+        })
     }
 
-    /// Move the cursor to the position specified by the [`CursorMove`] parameter. For each kind of cursor moves, see
-    /// the document of [`CursorMove`].
+    /// Delete the lines in the range of rows and return the deleted lines. The range is clamped to fit within the
+    /// text. When all lines are deleted, one empty line remains. This is useful to edit structured content row by row
+    /// without moving the cursor. Unlike [`TextArea::cut`], the deleted lines are not yanked.
+    ///
+    /// The deletion is recorded as one history entry. The cursor and the text selection stay on the same lines. A
+    /// position on the deleted lines moves to the head of the line after them, or the end of the line before them
+    /// when the last line was deleted. An empty vector is returned when no line was deleted.
     /// ```
     /// use tui_textarea::{TextArea, CursorMove};
     ///
-    /// let mut textarea = TextArea::from(["abc", "def"]);
+    /// let mut textarea = TextArea::from(["a", "b", "c", "d"]);
+    /// textarea.move_cursor(CursorMove::Bottom);
     ///
-    /// textarea.move_cursor(CursorMove::Forward);
-    /// assert_eq!(textarea.cursor(), (0, 1));
-    /// textarea.move_cursor(CursorMove::Down);
-    /// assert_eq!(textarea.cursor(), (1, 1));
+    /// assert_eq!(textarea.delete_lines(1..3), ["b", "c"]);
+    /// assert_eq!(textarea.lines(), ["a", "d"]);
+    /// assert_eq!(textarea.cursor(), (1, 0));
+    ///
+    /// assert_eq!(textarea.delete_lines(1..), ["d"]);
+    /// assert_eq!(textarea.lines(), ["a"]);
+    ///
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["a", "d"]);
     /// ```
-    pub fn move_cursor(&mut self, m: CursorMove) {
-        self.move_cursor_with_shift(m, self.selection_start.is_some());
+    pub fn delete_lines(&mut self, rows: impl RangeBounds<usize>) -> Vec<String> {
+        let len = self.lines.len();
+        let start = match rows.start_bound() {
+            Bound::Included(&s) => s,
+            Bound::Excluded(&s) => s.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let end = match rows.end_bound() {
+            Bound::Included(&e) => e.saturating_add(1),
+            Bound::Excluded(&e) => e,
+            Bound::Unbounded => len,
+        };
+        let end = cmp::min(end, len);
+        if start >= end {
+            return vec![];
+        }
+        let deleted = self.lines[start..end].to_vec();
+        self.track_cursor(|t| {
+            let (s, e) = if end < len {
+                // Delete the lines with the newlines after them
+                (t.text_offset((start, 0)), t.text_offset((end, 0)))
+            } else if start > 0 {
+                // Delete the lines with the newline before them
+                let s = t.text_offset((start - 1, t.lines[start - 1].chars().count()));
+                (
+                    s,
+                    t.text_offset((len - 1, t.lines[len - 1].chars().count())),
+                )
+            } else {
+                (
+                    0,
+                    t.text_offset((len - 1, t.lines[len - 1].chars().count())),
+                )
+            };
+            t.replace_text_range(s, e, "");
+        });
+        deleted
     }
 
-    fn move_cursor_with_shift(&mut self, m: CursorMove, shift: bool) {
-        if let Some(cursor) = m.next_cursor(self.cursor, &self.lines, &self.viewport) {
-            if shift {
-                if self.selection_start.is_none() {
-                    self.start_selection();
+    /// Remove spaces and tabs at the end of every line. The removals are recorded as one history entry so they can be
+    /// undone at once. The cursor and the selection stay at the same positions, or move to the end of line when they
+    /// were in the removed whitespaces. This method returns the number of the lines which were changed. With `file`
+    /// feature, `TextArea::set_trim_trailing_whitespace_on_save` runs this method automatically on saving a file.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["abc  ", "def", "\t", "ghi \t "]);
+    /// textarea.move_cursor(CursorMove::Jump(0, 4));
+    ///
+    /// assert_eq!(textarea.trim_trailing_whitespace(), 3);
+    /// assert_eq!(textarea.lines(), ["abc", "def", "", "ghi"]);
+    /// assert_eq!(textarea.cursor(), (0, 3));
+    ///
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["abc  ", "def", "\t", "ghi \t "]);
+    /// ```
+    pub fn trim_trailing_whitespace(&mut self) -> usize {
+        self.track_cursor(|t| {
+            let (cursor, selection_start) = (t.cursor, t.selection_start);
+            let trimmed = t.with_history_group(|t| {
+                let mut trimmed = 0;
+                for row in 0..t.lines.len() {
+                    let line = &t.lines[row];
+                    let start = trailing_whitespace_start(line);
+                    if start == line.len() {
+                        continue;
+                    }
+                    // Trailing whitespaces are ASCII so the number of bytes is the number of characters
+                    let end_col = line.chars().count();
+                    let start_col = end_col - (line.len() - start);
+                    let end = Pos::new(row, end_col, line.len());
+                    t.delete_range(Pos::new(row, start_col, start), end, false);
+                    trimmed += 1;
+                }
+                trimmed
+            });
+            t.cursor = t.clamp_position(cursor);
+            t.selection_start = selection_start.map(|pos| t.clamp_position(pos));
+            trimmed
+        })
+    }
+
+    /// Make sure that the text ends with a newline by appending an empty line when the last line is not empty. Like
+    /// [`TextArea::append_lines`], the cursor, the text selection, and the scroll position are not moved and the
+    /// appended line is recorded as one history entry. This method returns if the text was modified or not. An empty
+    /// text is not modified.
+    ///
+    /// Note that [`TextArea::text`] does not add a line ending at the end of the text. With `file` feature, use
+    /// `TextArea::set_final_newline` instead to put a newline at the end of the file on saving it.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["hello", "world"]);
+    ///
+    /// assert!(textarea.ensure_trailing_newline());
+    /// assert_eq!(textarea.text(), "hello\nworld\n");
+    ///
+    /// // The text already ends with a newline
+    /// assert!(!textarea.ensure_trailing_newline());
+    /// ```
+    pub fn ensure_trailing_newline(&mut self) -> bool {
+        if self.lines[self.lines.len() - 1].is_empty() {
+            return false;
+        }
+        self.append_lines([""])
+    }
+
+    /// Collapse consecutive blank lines so that at most `max` blank lines continue. Lines which contain only spaces
+    /// and tabs are also blank lines. The removals are recorded as one history entry. The cursor and the text
+    /// selection follow the edits like [`TextArea::replace_range`]. This method returns the number of the removed
+    /// lines.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["a", "", "  ", "", "b", "", "c", "", ""]);
+    ///
+    /// assert_eq!(textarea.collapse_multiple_blank_lines(1), 3);
+    /// assert_eq!(textarea.lines(), ["a", "", "b", "", "c", ""]);
+    ///
+    /// assert_eq!(textarea.collapse_multiple_blank_lines(0), 3);
+    /// assert_eq!(textarea.lines(), ["a", "b", "c"]);
+    ///
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["a", "", "b", "", "c", ""]);
+    /// ```
+    pub fn collapse_multiple_blank_lines(&mut self, max: usize) -> usize {
+        let is_blank = |line: &str| trailing_whitespace_start(line) == 0;
+        // Ranges of the rows to be removed
+        let mut removed = vec![];
+        let mut row = 0;
+        while row < self.lines.len() {
+            let start = row;
+            while row < self.lines.len() && is_blank(&self.lines[row]) {
+                row += 1;
+            }
+            if row - start > max {
+                removed.push((start + max, row));
+            }
+            row += 1;
+        }
+        if removed.is_empty() {
+            return 0;
+        }
+
+        let len = self.lines.len();
+        self.track_cursor(|t| {
+            t.with_history_group(|t| {
+                // Remove the lines from the bottom so that the rows above are not changed
+                for &(start, end) in removed.iter().rev() {
+                    let last = t.lines.len() - 1;
+                    let (from, to) = if end <= last {
+                        ((start, 0), (end, 0))
+                    } else if start > 0 {
+                        // Remove the newline before the blank lines at the end of the text
+                        let prev = t.lines[start - 1].chars().count();
+                        ((start - 1, prev), (last, t.lines[last].chars().count()))
+                    } else {
+                        ((0, 0), (last, t.lines[last].chars().count()))
+                    };
+                    let (from, to) = (t.text_offset(from), t.text_offset(to));
+                    t.replace_text_range(from, to, "");
                 }
+            });
+        });
+        len - self.lines.len()
+    }
+
+    fn clamp_position(&self, (row, col): (usize, usize)) -> (usize, usize) {
+        let row = cmp::min(row, self.lines.len() - 1);
+        let col = cmp::min(col, self.lines[row].chars().count());
+        (row, col)
+    }
+
+    // Replace the byte range in the text where lines are joined with newlines. The cursor and the selection follow
+    // the edit.
+    fn replace_text_range(&mut self, start: usize, end: usize, text: &str) -> bool {
+        if start == end && text.is_empty() {
+            return false;
+        }
+
+        self.follow_edit(start, end, text.len(), |t| {
+            t.with_history_group(|t| {
+                let start = pos_at_text_offset(&t.lines, start);
+                let end = pos_at_text_offset(&t.lines, end);
+                t.cursor = (start.row, start.col);
+                if start.row != end.row || start.offset != end.offset {
+                    t.delete_range(start, end, false);
+                }
+                let mut inserted: Vec<_> = text.split('\n').map(String::from).collect();
+                if inserted.len() == 1 {
+                    t.insert_piece(Arc::new(inserted.remove(0)));
+                } else {
+                    t.insert_chunk(Arc::new(inserted));
+                }
+            });
+        });
+        true
+    }
+
+    // Run the `edit` which replaces the byte range from `start` to `end` in the text with a text whose length is
+    // `len`, then move the cursor and the selection to follow the edit. A position inside the range moves to the end
+    // of the new text. The scroll position also follows the edit so that the lines on the screen don't move.
+    fn follow_edit(&mut self, start: usize, end: usize, len: usize, edit: impl FnOnce(&mut Self)) {
+        let new_end = start + len;
+        let map_offset = |offset: usize| {
+            if offset <= start {
+                offset
+            } else if offset >= end {
+                offset - end + new_end
             } else {
-                self.cancel_selection();
+                new_end
             }
-            self.cursor = cursor;
+        };
+        let cursor = map_offset(self.text_offset(self.cursor));
+        let selection_start = self
+            .selection_start
+            .take()
+            .map(|pos| map_offset(self.text_offset(pos)));
+        let start_row = pos_at_text_offset(&self.lines, start).row;
+        let end_row = pos_at_text_offset(&self.lines, end).row;
+
+        edit(self);
+
+        // Keep the lines at the top of the viewport
+        let (top_row, top_col) = self.viewport.scroll_top();
+        let top_row = top_row as usize;
+        let top_row = if top_row > end_row {
+            top_row - end_row + pos_at_text_offset(&self.lines, new_end).row
+        } else {
+            cmp::min(top_row, start_row)
+        };
+        // The viewport cannot scroll beyond the `u16::MAX`th row. Saturate the position rather than wrapping it
+        self.viewport
+            .set_scroll_top(cmp::min(top_row, u16::MAX as usize) as u16, top_col);
+
+        let pos = pos_at_text_offset(&self.lines, cursor);
+        self.cursor = (pos.row, pos.col);
+        self.selection_start = selection_start.map(|offset| {
+            let pos = pos_at_text_offset(&self.lines, offset);
+            (pos.row, pos.col)
+        });
+    }
+
+    /// Create an anchored range between the two 0-base character-wise (row, col) positions. The range follows all
+    /// subsequent edits including undo, redo, and remote edits, and the current range can be retrieved by
+    /// [`TextArea::resolve_anchor`] with the returned [`AnchorId`]. This is useful to keep positions such as
+    /// diagnostics, lint underlines, and fold regions while the text is edited. `growth` decides whether text
+    /// inserted at the boundaries of the range extends the range. When the text in the range is deleted, the range
+    /// becomes empty at the deleted position. Positions are clamped to fit within the text and the order of the two
+    /// positions does not matter.
+    /// ```
+    /// use tui_textarea::{TextArea, AnchorGrowth};
+    ///
+    /// let mut textarea = TextArea::from(["let x = foo();"]);
+    /// let id = textarea.create_anchor((0, 8), (0, 11), AnchorGrowth::Neither);
+    ///
+    /// textarea.insert_str("// TODO\n");
+    /// assert_eq!(textarea.resolve_anchor(id), Some(((1, 8), (1, 11))));
+    ///
+    /// // Text inserted at the end of the range does not extend the range
+    /// textarea.replace_range((1, 11), (1, 11), "_bar");
+    /// assert_eq!(textarea.lines(), ["// TODO", "let x = foo_bar();"]);
+    /// assert_eq!(textarea.resolve_anchor(id), Some(((1, 8), (1, 11))));
+    ///
+    /// // Undo also moves the range
+    /// textarea.undo();
+    /// textarea.undo();
+    /// assert_eq!(textarea.resolve_anchor(id), Some(((0, 8), (0, 11))));
+    /// ```
+    pub fn create_anchor(
+        &mut self,
+        start: (usize, usize),
+        end: (usize, usize),
+        growth: AnchorGrowth,
+    ) -> AnchorId {
+        let (start, end) = (self.clamp_position(start), self.clamp_position(end));
+        let (start, end) = (cmp::min(start, end), cmp::max(start, end));
+        let start = Pos::new(start.0, start.1, self.line_offset(start.0, start.1));
+        let end = Pos::new(end.0, end.1, self.line_offset(end.0, end.1));
+        self.history.anchors_mut().create(start, end, growth)
+    }
+
+    /// Get the current range of the anchor created by [`TextArea::create_anchor`] as the pair of 0-base
+    /// character-wise (row, col) start and end positions. `None` is returned when the anchor was removed.
+    pub fn resolve_anchor(&self, id: AnchorId) -> Option<((usize, usize), (usize, usize))> {
+        self.history
+            .anchors()
+            .get(id)
+            .map(|(start, end)| ((start.row, start.col), (end.row, end.col)))
+    }
+
+    /// Remove the anchor created by [`TextArea::create_anchor`]. This method returns `false` when the anchor was
+    /// already removed.
+    pub fn remove_anchor(&mut self, id: AnchorId) -> bool {
+        self.history.anchors_mut().remove(id)
+    }
+
+    /// Remove all anchors created by [`TextArea::create_anchor`].
+    pub fn clear_anchors(&mut self) {
+        self.history.anchors_mut().clear();
+    }
+
+    /// Associate the arbitrary data with the line at the `row`, such as a done flag of a todo item, a fold state, or
+    /// diagnostics of the line. The association follows subsequent edits including undo, redo, and remote edits so
+    /// that the data stays with the line. When a line is split, the data stays with the first line unless the line is
+    /// split at its head. When lines are joined, the data of the first line is kept. The data of deleted lines is
+    /// dropped and is not restored by undo. Data already associated with the line is replaced. This method returns
+    /// `false` when the row is out of the text.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["buy milk", "write code"]);
+    /// textarea.set_line_meta(1, true);
+    ///
+    /// // Insert a line above the line
+    /// textarea.insert_str("call mom\n");
+    /// assert_eq!(textarea.line_meta::<bool>(2), Some(&true));
+    /// assert_eq!(textarea.line_meta::<bool>(1), None);
+    ///
+    /// // The data of the other type is not retrieved
+    /// assert_eq!(textarea.line_meta::<u32>(2), None);
+    ///
+    /// textarea.undo();
+    /// assert_eq!(textarea.line_meta::<bool>(1), Some(&true));
+    /// ```
+    pub fn set_line_meta<T: Any + Send + Sync>(&mut self, row: usize, meta: T) -> bool {
+        if row >= self.lines.len() {
+            return false;
+        }
+        self.history.line_meta_mut().set(row, Arc::new(meta));
+        true
+    }
+
+    /// Get the data associated with the line at the `row` by [`TextArea::set_line_meta`]. `None` is returned when no
+    /// data is associated with the line or the data is not the type `T`.
+    pub fn line_meta<T: Any>(&self, row: usize) -> Option<&T> {
+        self.history.line_meta().get(row)?.downcast_ref()
+    }
+
+    /// Remove the data associated with the line at the `row` by [`TextArea::set_line_meta`]. This method returns
+    /// `false` when no data is associated with the line.
+    pub fn remove_line_meta(&mut self, row: usize) -> bool {
+        self.history.line_meta_mut().remove(row)
+    }
+
+    /// Remove all data associated with lines by [`TextArea::set_line_meta`].
+    pub fn clear_line_meta(&mut self) {
+        self.history.line_meta_mut().clear();
+    }
+
+    /// Return the iterator of the rows of the lines which have data associated by [`TextArea::set_line_meta`] in
+    /// ascending order.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["a", "b", "c"]);
+    /// textarea.set_line_meta(2, "note");
+    /// textarea.set_line_meta(0, 42);
+    ///
+    /// assert_eq!(textarea.line_meta_rows().collect::<Vec<_>>(), [0, 2]);
+    /// ```
+    pub fn line_meta_rows(&self) -> impl Iterator<Item = usize> + '_ {
+        self.history.line_meta().rows()
+    }
+
+    /// Conceal the text between the two 0-base character-wise (row, col) positions on rendering and show the
+    /// replacement instead, such as an emoji for `:smile:` or nothing for the target of a markdown link. Only how the
+    /// text is rendered changes. The text itself is not modified and the cursor moves over the concealed characters
+    /// as usual. While the cursor is in the range, the text is rendered as it is so that the cursor is visible. When
+    /// the mask character is set by [`TextArea::set_mask_char`], the replacement is masked as well as the text.
+    ///
+    /// Like [`TextArea::create_anchor`], the range follows subsequent edits and the returned [`AnchorId`] is used to
+    /// remove the concealment by [`TextArea::remove_conceal`]. Text inserted at the boundaries is not concealed. When
+    /// the text in the range is deleted, the replacement is no longer rendered. Positions are clamped to fit within
+    /// the text and the order of the two positions does not matter.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["see [docs](https://example.com) :smile:"]);
+    /// textarea.conceal_range((0, 10), (0, 31), "");
+    /// textarea.conceal_range((0, 32), (0, 39), "😄");
+    ///
+    /// assert_eq!(textarea.render_to_string(20, 1), "see [docs] 😄       ");
+    ///
+    /// // Concealed ranges follow edits
+    /// textarea.insert_str("> ");
+    /// assert_eq!(textarea.lines(), ["> see [docs](https://example.com) :smile:"]);
+    /// assert_eq!(textarea.render_to_string(20, 1), "> see [docs] 😄     ");
+    /// ```
+    pub fn conceal_range(
+        &mut self,
+        start: (usize, usize),
+        end: (usize, usize),
+        replacement: impl Into<String>,
+    ) -> AnchorId {
+        let (start, end) = (self.clamp_position(start), self.clamp_position(end));
+        let (start, end) = (cmp::min(start, end), cmp::max(start, end));
+        let start = Pos::new(start.0, start.1, self.line_offset(start.0, start.1));
+        let end = Pos::new(end.0, end.1, self.line_offset(end.0, end.1));
+        let replacement = replacement.into();
+        self.touch_render(|t| t.history.conceals_mut().create(start, end, replacement))
+    }
+
+    /// Remove the concealment created by [`TextArea::conceal_range`]. This method returns `false` when it was already
+    /// removed.
+    pub fn remove_conceal(&mut self, id: AnchorId) -> bool {
+        self.touch_render(|t| t.history.conceals_mut().remove(id))
+    }
+
+    /// Remove all concealments created by [`TextArea::conceal_range`].
+    pub fn clear_conceals(&mut self) {
+        self.touch_render(|t| t.history.conceals_mut().clear());
+    }
+
+    /// Apply the edit which originated outside of this textarea, such as an edit by another user in collaborative
+    /// editing. The text between the two 0-base character-wise (row, col) positions is replaced with the given
+    /// string in the same manner as [`TextArea::replace_range`]. The cursor, the text selection, and the scroll
+    /// position are moved to follow the edit so that the local view is not disturbed. Like other scrolling, the scroll
+    /// position saturates at the [`u16::MAX`]th row. When the edit pushes the top of the viewport beyond it, the
+    /// viewport stays at the row and the lines on the screen move.
+    ///
+    /// Unlike [`TextArea::replace_range`], the edit is not pushed to the undo history. Local edits in the undo history
+    /// are adjusted so that undoing them does not revert the remote edit. When a local edit overlaps with the remote
+    /// edit, the local edit and older ones are removed from the history. The redo history is cleared. The remote edit
+    /// is recorded by [`TextArea::drain_edits`] with `remote` flag. This method returns if the text was modified or
+    /// not.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["hello", "world"]);
+    /// textarea.move_cursor(CursorMove::Bottom);
+    /// textarea.move_cursor(CursorMove::End);
+    /// textarea.insert_char('!');
+    ///
+    /// // Another user inserts a line at the top
+    /// textarea.apply_remote_edit((0, 0), (0, 0), "title\n");
+    /// assert_eq!(textarea.lines(), ["title", "hello", "world!"]);
+    /// assert_eq!(textarea.cursor(), (2, 6)); // The cursor follows the text
+    ///
+    /// // Undo reverts only the local edit
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["title", "hello", "world"]);
+    /// ```
+    pub fn apply_remote_edit(
+        &mut self,
+        start: (usize, usize),
+        end: (usize, usize),
+        text: impl AsRef<str>,
+    ) -> bool {
+        self.track_cursor(|t| t.remote_edit(start, end, text.as_ref()))
+    }
+
+    fn remote_edit(&mut self, start: (usize, usize), end: (usize, usize), text: &str) -> bool {
+        let start = self.text_offset(self.clamp_position(start));
+        let end = self.text_offset(self.clamp_position(end));
+        let (start, end) = (cmp::min(start, end), cmp::max(start, end));
+        let text = text.replace("\r\n", "\n");
+        if start == end && text.is_empty() {
+            return false;
+        }
+
+        self.follow_edit(start, end, text.len(), |t| {
+            let start = pos_at_text_offset(&t.lines, start);
+            let end = pos_at_text_offset(&t.lines, end);
+            t.history
+                .apply_remote(&mut t.lines, &mut t.metrics, start, end, &text);
+        });
+        self.last_typed = None;
+        true
+    }
+
+    // Byte offset of the (row, col) position in the text where lines are joined with newlines
+    fn text_offset(&self, (row, col): (usize, usize)) -> usize {
+        let lines: usize = self.lines[..row].iter().map(|l| l.len() + 1).sum();
+        lines + self.line_offset(row, col)
+    }
+
+    /// Start text selection at the cursor position. If text selection is already ongoing, the start position is reset.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["aaa bbb ccc"]);
+    ///
+    /// textarea.start_selection();
+    /// textarea.move_cursor(CursorMove::WordForward);
+    /// textarea.copy();
+    /// assert_eq!(textarea.yank_text(), "aaa ");
+    /// ```
+    pub fn start_selection(&mut self) {
+        self.track_cursor(|t| t.start_selection_inner())
+    }
+
+    fn start_selection_inner(&mut self) {
+        self.remember_selection();
+        self.history.set_selections(vec![]);
+        self.selection_start = Some(self.cursor);
+    }
+
+    /// Stop the current text selection. This method does nothing if text selection is not ongoing.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["aaa bbb ccc"]);
+    ///
+    /// textarea.start_selection();
+    /// textarea.move_cursor(CursorMove::WordForward);
+    ///
+    /// // Cancel the ongoing text selection
+    /// textarea.cancel_selection();
+    ///
+    /// // As the result, this `copy` call does nothing
+    /// textarea.copy();
+    /// assert_eq!(textarea.yank_text(), "");
+    /// ```
+    pub fn cancel_selection(&mut self) {
+        self.track_cursor(|t| t.cancel_selection_inner())
+    }
+
+    fn cancel_selection_inner(&mut self) {
+        self.remember_selection();
+        self.selection_start = None;
+    }
+
+    // Remember the ongoing selection so that it can be restored by `reselect_last`. The remembered positions follow
+    // the edits after this call.
+    fn remember_selection(&mut self) {
+        if let Some(start) = self.selection_start {
+            // This may be called while the text is being edited so the positions may be out of the text
+            let (row, col) = self.clamp_position(start);
+            let start = Pos::new(row, col, self.line_offset(row, col));
+            let (row, col) = self.clamp_position(self.cursor);
+            let cursor = Pos::new(row, col, self.line_offset(row, col));
+            self.history.set_last_selection(Some((start, cursor)));
+        }
+    }
+
+    /// Get the range of the last text selection which was cancelled. The range follows the edits after the selection
+    /// was cancelled, including undo and redo. Like [`TextArea::selection_range`], the first element of the pair is
+    /// always smaller than the second one. `None` is returned when no text selection has been cancelled yet.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["hello world"]);
+    /// assert_eq!(textarea.last_selection_range(), None);
+    ///
+    /// // Select "world" and cancel the selection
+    /// textarea.move_cursor(CursorMove::WordForward);
+    /// textarea.start_selection();
+    /// textarea.move_cursor(CursorMove::End);
+    /// textarea.cancel_selection();
+    /// assert_eq!(textarea.last_selection_range(), Some(((0, 6), (0, 11))));
+    ///
+    /// // The range follows the edit
+    /// textarea.move_cursor(CursorMove::Head);
+    /// textarea.insert_str(">> ");
+    /// assert_eq!(textarea.last_selection_range(), Some(((0, 9), (0, 14))));
+    /// ```
+    pub fn last_selection_range(&self) -> Option<((usize, usize), (usize, usize))> {
+        let (start, cursor) = self.history.last_selection()?;
+        let (start, cursor) = ((start.row, start.col), (cursor.row, cursor.col));
+        Some(if start > cursor {
+            (cursor, start)
+        } else {
+            (start, cursor)
+        })
+    }
+
+    /// Restore the last text selection returned by [`TextArea::last_selection_range`]. The cursor is placed at the
+    /// same end of the selection as before. When text selection is ongoing, it is remembered as the last selection
+    /// instead. This is equivalent to `gv` of Vim. This method returns `false` when no selection was cancelled yet.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["hello world"]);
+    ///
+    /// textarea.start_selection();
+    /// textarea.move_cursor(CursorMove::WordEnd);
+    /// textarea.cancel_selection();
+    /// textarea.move_cursor(CursorMove::End);
+    ///
+    /// assert!(textarea.reselect_last());
+    /// assert_eq!(textarea.selection_range(), Some(((0, 0), (0, 4))));
+    /// assert_eq!(textarea.cursor(), (0, 4));
+    /// ```
+    pub fn reselect_last(&mut self) -> bool {
+        let (start, cursor) = match self.history.last_selection() {
+            Some((start, cursor)) => ((start.row, start.col), (cursor.row, cursor.col)),
+            None => return false,
+        };
+        self.track_cursor(|t| {
+            t.remember_selection();
+            t.history.set_selections(vec![]);
+            t.selection_start = Some(start);
+            t.cursor = cursor;
+        });
+        true
+    }
+
+    /// Expand the selection to the smallest syntactic unit which contains it. The units are a word, a sequence of
+    /// non-whitespace characters, lines without indentation, whole lines, a paragraph separated by blank lines, the
+    /// inside of the brackets `()`, `[]`, `{}`, `<>`, the brackets themselves, and the entire text. When nothing is
+    /// selected, the unit at the cursor is selected. Calling this method repeatedly selects the larger units. This
+    /// method returns `false` when the selection cannot be expanded anymore. See also: [`TextArea::shrink_selection`]
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["    call(foo.bar, baz)"]);
+    /// textarea.move_cursor(CursorMove::Jump(0, 10));
+    ///
+    /// // Select "foo"
+    /// textarea.expand_selection();
+    /// assert_eq!(textarea.selection_range(), Some(((0, 9), (0, 12))));
+    ///
+    /// // Select "foo.bar,"
+    /// textarea.expand_selection();
+    /// assert_eq!(textarea.selection_range(), Some(((0, 9), (0, 17))));
+    ///
+    /// // Select "foo.bar, baz"
+    /// textarea.expand_selection();
+    /// assert_eq!(textarea.selection_range(), Some(((0, 9), (0, 21))));
+    ///
+    /// // Select "(foo.bar, baz)"
+    /// textarea.expand_selection();
+    /// assert_eq!(textarea.selection_range(), Some(((0, 8), (0, 22))));
+    /// ```
+    pub fn expand_selection(&mut self) -> bool {
+        let (start, end) = match self.selection_offsets() {
+            Some((s, e, _)) => (s, e),
+            None => {
+                let offset = self.text_offset(self.clamp_position(self.cursor));
+                (offset, offset)
+            }
+        };
+        let (s, e) = match expand_range(&self.lines, start, end, &self.words) {
+            Some(range) => range,
+            None => return false,
+        };
+        if self.selection_start.is_none()
+            || self.expansions.last().map(|x| x.range) != Some((start, end))
+        {
+            // The selection was changed after the last expansion
+            self.expansions.clear();
         }
+        self.expansions.push(Expansion {
+            selection_start: self.selection_start,
+            cursor: self.cursor,
+            range: (s, e),
+        });
+        self.track_cursor(|t| {
+            t.remember_selection();
+            t.select_text_offsets(s, e, true);
+        });
+        true
+    }
+
+    /// Restore the selection before the last [`TextArea::expand_selection`] call. When the selection was not expanded
+    /// or it was changed after the expansion, this method does nothing and returns `false`.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["hello world"]);
+    ///
+    /// textarea.expand_selection();
+    /// textarea.expand_selection();
+    /// assert_eq!(textarea.selection_range(), Some(((0, 0), (0, 11))));
+    ///
+    /// assert!(textarea.shrink_selection());
+    /// assert_eq!(textarea.selection_range(), Some(((0, 0), (0, 5))));
+    ///
+    /// // The selection before the first expansion is restored
+    /// assert!(textarea.shrink_selection());
+    /// assert!(!textarea.is_selecting());
+    ///
+    /// assert!(!textarea.shrink_selection());
+    /// ```
+    pub fn shrink_selection(&mut self) -> bool {
+        let current = self.selection_offsets().map(|(s, e, _)| (s, e));
+        let expansion = match self.expansions.pop() {
+            Some(x) if current == Some(x.range) => x,
+            _ => {
+                self.expansions.clear();
+                return false;
+            }
+        };
+        self.track_cursor(|t| {
+            t.selection_start = expansion.selection_start.map(|p| t.clamp_position(p));
+            t.cursor = t.clamp_position(expansion.cursor);
+        });
+        true
+    }
+
+    // Byte offsets of all selections in the text where lines are joined with newlines sorted by their positions, and
+    // the index of the primary selection. An empty primary selection is at the cursor.
+    fn selections_offsets(&self) -> (Vec<(usize, usize)>, usize) {
+        let primary = match (self.selection_offsets(), self.selection_start) {
+            (Some((s, e, _)), _) => (s, e),
+            (None, Some(_)) => {
+                let offset = self.text_offset(self.cursor);
+                (offset, offset)
+            }
+            (None, None) => return (vec![], 0),
+        };
+        let mut heads = Vec::with_capacity(self.lines.len());
+        let mut head = 0;
+        for line in &self.lines {
+            heads.push(head);
+            head += line.len() + 1;
+        }
+        let mut ranges = vec![(primary.0, primary.1, true)];
+        ranges.extend(
+            self.history
+                .selections()
+                .iter()
+                .map(|(s, e)| (heads[s.row] + s.offset, heads[e.row] + e.offset, false)),
+        );
+        ranges.sort_unstable();
+        let primary = ranges.iter().position(|r| r.2).unwrap();
+        (
+            ranges.into_iter().map(|(s, e, _)| (s, e)).collect(),
+            primary,
+        )
+    }
+
+    // Select the byte ranges in the text where lines are joined with newlines. The cursor is placed at the end of the
+    // primary selection.
+    fn select_text_offsets_all(&mut self, ranges: &[(usize, usize)], primary: usize) {
+        let (start, end) = ranges[primary];
+        self.select_text_offsets(start, end, true);
+        let lines = &self.lines;
+        let secondaries = ranges
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| i != primary)
+            .map(|(_, &(s, e))| (pos_at_text_offset(lines, s), pos_at_text_offset(lines, e)))
+            .collect();
+        self.history.set_selections(secondaries);
+    }
+
+    // Edits are applied to all selections while secondary selections exist
+    fn has_secondary_selections(&self) -> bool {
+        !self.history.selections().is_empty()
+    }
+
+    // Texts of all selections joined with newlines
+    fn selections_text(&self) -> YankText {
+        let (ranges, _) = self.selections_offsets();
+        let text = self.lines.join("\n");
+        let joined = ranges
+            .iter()
+            .map(|&(s, e)| &text[s..e])
+            .collect::<Vec<_>>()
+            .join("\n");
+        joined
+            .split('\n')
+            .map(String::from)
+            .collect::<Vec<_>>()
+            .into()
+    }
+
+    // Replace the text of each selection with the string in one undo step like `replace_selections`. `range` maps the
+    // byte range of a selection in the text to the range to replace so that an empty selection can delete the
+    // character around it. The selections are collapsed at the ends of the inserted strings so that the following
+    // edits are also applied to all of them. This method returns if the text was modified or not.
+    fn edit_selections(
+        &mut self,
+        text: &str,
+        range: impl Fn(&str, usize, usize) -> (usize, usize),
+    ) -> bool {
+        let (ranges, primary) = self.selections_offsets();
+        let joined = self.lines.join("\n");
+        let mut merged: Vec<(usize, usize)> = Vec::with_capacity(ranges.len());
+        let mut primary_merged = 0;
+        for (i, (s, e)) in ranges.into_iter().enumerate() {
+            let (s, e) = range(&joined, s, e);
+            match merged.last_mut() {
+                Some(last) if s < last.1 || s == last.0 => last.1 = cmp::max(last.1, e),
+                _ => merged.push((s, e)),
+            }
+            if i == primary {
+                primary_merged = merged.len() - 1;
+            }
+        }
+        if text.is_empty() && merged.iter().all(|(s, e)| s == e) {
+            return false;
+        }
+        self.with_history_group(|t| {
+            for &(s, e) in merged.iter().rev() {
+                t.replace_text_range(s, e, text);
+            }
+        });
+        let (mut removed, mut added) = (0, 0);
+        let mut cursors: Vec<_> = merged
+            .iter()
+            .map(|&(s, e)| {
+                let end = s - removed + added + text.len();
+                removed += e - s;
+                added += text.len();
+                (end, end)
+            })
+            .collect();
+        // Selections collapsed at the same position are merged
+        let primary = cursors[primary_merged];
+        cursors.dedup();
+        let primary = cursors.iter().position(|&c| c == primary).unwrap();
+        self.select_text_offsets_all(&cursors, primary);
+        true
+    }
+
+    /// Get the ranges of all selections sorted by their positions. In addition to the primary selection returned by
+    /// [`TextArea::selection_range`], multiple secondary selections can be created by
+    /// [`TextArea::split_selection_on_newlines`] or [`TextArea::select_all_matches`]. Each range is a pair of the start
+    /// position and the exclusive end position of the selected text. The secondary selections follow edits to the
+    /// text and they are cleared when the primary selection is cancelled. While they exist, [`TextArea::insert_char`],
+    /// [`TextArea::insert_str`], [`TextArea::delete_char`], [`TextArea::delete_next_char`], and [`TextArea::cut`] edit
+    /// all selections in one undo step and collapse them at the ends of the edits, and [`TextArea::copy`] yanks the
+    /// texts of the selections joined with newlines. An empty vector is returned when text selection is not ongoing.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["aaa", "bbb", "ccc"]);
+    /// assert!(textarea.selections().is_empty());
+    ///
+    /// textarea.select_all();
+    /// textarea.split_selection_on_newlines();
+    /// assert_eq!(
+    ///     textarea.selections(),
+    ///     [((0, 0), (0, 3)), ((1, 0), (1, 3)), ((2, 0), (2, 3))],
+    /// );
+    ///
+    /// textarea.cancel_selection();
+    /// assert!(textarea.selections().is_empty());
+    /// ```
+    pub fn selections(&self) -> Vec<((usize, usize), (usize, usize))> {
+        let (ranges, _) = self.selections_offsets();
+        let lines = &self.lines;
+        ranges
+            .into_iter()
+            .map(|(s, e)| {
+                let (s, e) = (pos_at_text_offset(lines, s), pos_at_text_offset(lines, e));
+                ((s.row, s.col), (e.row, e.col))
+            })
+            .collect()
+    }
+
+    /// Get the index of the primary selection in the ranges returned by [`TextArea::selections`]. The primary
+    /// selection is the one which has the cursor. `None` is returned when text selection is not ongoing.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["aaa", "bbb", "ccc"]);
+    /// assert_eq!(textarea.primary_selection_index(), None);
+    ///
+    /// textarea.move_cursor(CursorMove::Down);
+    /// textarea.start_selection();
+    /// textarea.move_cursor(CursorMove::Bottom);
+    /// textarea.move_cursor(CursorMove::End);
+    /// textarea.split_selection_on_newlines();
+    /// assert_eq!(textarea.primary_selection_index(), Some(0));
+    /// assert_eq!(textarea.selection_range(), Some(((1, 0), (1, 3))));
+    /// ```
+    pub fn primary_selection_index(&self) -> Option<usize> {
+        self.selection_start?;
+        Some(self.selections_offsets().1)
+    }
+
+    /// Remove all secondary selections and keep only the primary selection. This is equivalent to `,` of Helix.
+    /// This method returns `false` when there was no secondary selection.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["aaa", "bbb"]);
+    ///
+    /// textarea.select_all();
+    /// textarea.split_selection_on_newlines();
+    /// assert_eq!(textarea.selections().len(), 2);
+    ///
+    /// assert!(textarea.keep_primary_selection());
+    /// assert_eq!(textarea.selections(), [((0, 0), (0, 3))]);
+    /// ```
+    pub fn keep_primary_selection(&mut self) -> bool {
+        if self.history.selections().is_empty() {
+            return false;
+        }
+        self.history.set_selections(vec![]);
+        true
+    }
+
+    /// Split each selection into the selections of its lines. Newlines are not included in the new selections and
+    /// empty lines are not selected. The first line of the primary selection becomes the new primary selection. This
+    /// is equivalent to `Alt-s` of Helix. This method returns the number of selections after the split. When text
+    /// selection is not ongoing, it does nothing and returns 0.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["abc", "", "def", "ghi"]);
+    ///
+    /// textarea.move_cursor(CursorMove::Forward);
+    /// textarea.start_selection();
+    /// textarea.move_cursor(CursorMove::Jump(2, 2));
+    ///
+    /// assert_eq!(textarea.split_selection_on_newlines(), 2);
+    /// assert_eq!(textarea.selections(), [((0, 1), (0, 3)), ((2, 0), (2, 2))]);
+    /// ```
+    pub fn split_selection_on_newlines(&mut self) -> usize {
+        let (ranges, primary) = self.selections_offsets();
+        if ranges.is_empty() {
+            return 0;
+        }
+        let text = self.lines.join("\n");
+        let mut split = vec![];
+        let mut new_primary = None;
+        for (i, &(start, end)) in ranges.iter().enumerate() {
+            if i == primary {
+                new_primary = Some(split.len());
+            }
+            let mut s = start;
+            for line in text[start..end].split('\n') {
+                if !line.is_empty() {
+                    split.push((s, s + line.len()));
+                }
+                s += line.len() + 1;
+            }
+        }
+        if split.is_empty() {
+            return ranges.len();
+        }
+        let primary = cmp::min(new_primary.unwrap_or(0), split.len() - 1);
+        self.track_cursor(|t| t.select_text_offsets_all(&split, primary));
+        split.len()
+    }
+
+    /// Select all matches of the regular expression in the selections. When text selection is not ongoing, matches in
+    /// the entire text are selected. The first match in the primary selection becomes the new primary selection.
+    /// This is equivalent to `s` of Helix. This method returns the number of the selected matches. When no match is
+    /// found, the selections are not changed and 0 is returned. An error is returned when the pattern is an invalid
+    /// regular expression. This method is available when `search` feature is enabled.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["let a = 1;", "let bb = 22;"]);
+    ///
+    /// assert_eq!(textarea.select_all_matches(r"\d+").unwrap(), 2);
+    /// assert_eq!(textarea.selections(), [((0, 8), (0, 9)), ((1, 9), (1, 11))]);
+    ///
+    /// // Narrow down the selections
+    /// assert_eq!(textarea.select_all_matches(r"2").unwrap(), 2);
+    /// assert_eq!(textarea.selections(), [((1, 9), (1, 10)), ((1, 10), (1, 11))]);
+    /// ```
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn select_all_matches(&mut self, pattern: impl AsRef<str>) -> Result<usize, regex::Error> {
+        let re = regex::Regex::new(pattern.as_ref())?;
+        let text = self.lines.join("\n");
+        let (mut ranges, mut primary) = self.selections_offsets();
+        if ranges.is_empty() {
+            ranges.push((0, text.len()));
+            primary = 0;
+        }
+        let mut matches = vec![];
+        let mut new_primary = None;
+        for (i, &(start, end)) in ranges.iter().enumerate() {
+            if i == primary {
+                new_primary = Some(matches.len());
+            }
+            // Search the entire text so that anchors such as `^` and `\b` see the text around the selection
+            let mut at = start;
+            while let Some(m) = re.find_at(&text, at) {
+                if m.end() > end {
+                    break;
+                }
+                if m.start() < m.end() {
+                    matches.push((m.start(), m.end()));
+                    at = m.end();
+                } else {
+                    at = m.end() + text[m.end()..].chars().next().map_or(1, char::len_utf8);
+                }
+                if at > end {
+                    break;
+                }
+            }
+        }
+        if matches.is_empty() {
+            return Ok(0);
+        }
+        let primary = cmp::min(new_primary.unwrap_or(0), matches.len() - 1);
+        self.track_cursor(|t| t.select_text_offsets_all(&matches, primary));
+        Ok(matches.len())
+    }
+
+    /// Replace the text of each selection with the string in one undo step. The inserted strings are selected after
+    /// the replacement so that the following operations are applied to all of them. This method returns `false` when
+    /// text selection is not ongoing.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["foo", "bar"]);
+    ///
+    /// textarea.select_all();
+    /// textarea.split_selection_on_newlines();
+    /// assert!(textarea.replace_selections("qux"));
+    /// assert_eq!(textarea.lines(), ["qux", "qux"]);
+    /// assert_eq!(textarea.selections(), [((0, 0), (0, 3)), ((1, 0), (1, 3))]);
+    ///
+    /// // Replacing the selections is undone at once
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["foo", "bar"]);
+    /// ```
+    pub fn replace_selections(&mut self, text: impl AsRef<str>) -> bool {
+        let (ranges, primary) = self.selections_offsets();
+        if ranges.is_empty() {
+            return false;
+        }
+        let text = text.as_ref();
+        // Overlapping selections are replaced once
+        let mut merged: Vec<(usize, usize)> = Vec::with_capacity(ranges.len());
+        let mut primary_merged = 0;
+        for (i, (s, e)) in ranges.into_iter().enumerate() {
+            match merged.last_mut() {
+                Some(last) if s < last.1 => last.1 = cmp::max(last.1, e),
+                _ => merged.push((s, e)),
+            }
+            if i == primary {
+                primary_merged = merged.len() - 1;
+            }
+        }
+        self.track_cursor(|t| {
+            t.with_history_group(|t| {
+                for &(s, e) in merged.iter().rev() {
+                    t.replace_text_range(s, e, text);
+                }
+            });
+            // Offsets of the inserted strings are shifted by the preceding replacements
+            let (mut removed, mut added) = (0, 0);
+            let inserted: Vec<_> = merged
+                .iter()
+                .map(|&(s, e)| {
+                    let start = s - removed + added;
+                    removed += e - s;
+                    added += text.len();
+                    (start, start + text.len())
+                })
+                .collect();
+            t.select_text_offsets_all(&inserted, primary_merged);
+        });
+        true
+    }
+
+    /// Rotate the texts of the selections. The text of each selection moves to the next selection and the text of the
+    /// last selection moves to the first one. The rotation is recorded as one undo step and the rotated texts are
+    /// selected after it. This is equivalent to `Alt-)` of Helix. This method returns `false` when there are less
+    /// than 2 selections or some selections overlap.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["foo", "bar", "baz"]);
+    ///
+    /// textarea.select_all();
+    /// textarea.split_selection_on_newlines();
+    /// assert!(textarea.rotate_selection_contents());
+    /// assert_eq!(textarea.lines(), ["baz", "foo", "bar"]);
+    ///
+    /// // The rotation is undone at once
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["foo", "bar", "baz"]);
+    /// ```
+    pub fn rotate_selection_contents(&mut self) -> bool {
+        let (ranges, primary) = self.selections_offsets();
+        if ranges.len() < 2 || ranges.windows(2).any(|w| w[0].1 > w[1].0) {
+            return false;
+        }
+        let text = self.lines.join("\n");
+        let mut contents: Vec<_> = ranges.iter().map(|&(s, e)| &text[s..e]).collect();
+        contents.rotate_right(1);
+        self.track_cursor(|t| {
+            t.with_history_group(|t| {
+                for (&(s, e), content) in ranges.iter().zip(contents.iter()).rev() {
+                    t.replace_text_range(s, e, content);
+                }
+            });
+            let (mut removed, mut added) = (0, 0);
+            let rotated: Vec<_> = ranges
+                .iter()
+                .zip(contents.iter())
+                .map(|(&(s, e), content)| {
+                    let start = s - removed + added;
+                    removed += e - s;
+                    added += content.len();
+                    (start, start + content.len())
+                })
+                .collect();
+            t.select_text_offsets_all(&rotated, primary);
+        });
+        true
+    }
+
+    /// Align the starts of the selections by inserting spaces before them so that all of them are at the same display
+    /// column. The insertion is recorded as one undo step. This is equivalent to `&` of Kakoune. This method returns
+    /// `false` when nothing was inserted or some selections start in the same line.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["    foo", "bar", "baz"]);
+    ///
+    /// // Select from "foo" to "baz" and split the selection into lines
+    /// textarea.move_cursor(CursorMove::Jump(0, 4));
+    /// textarea.start_selection();
+    /// textarea.move_cursor(CursorMove::Bottom);
+    /// textarea.move_cursor(CursorMove::End);
+    /// textarea.split_selection_on_newlines();
+    ///
+    /// assert!(textarea.align_selections());
+    /// assert_eq!(textarea.lines(), ["    foo", "    bar", "    baz"]);
+    /// assert_eq!(textarea.selections(), [((0, 4), (0, 7)), ((1, 4), (1, 7)), ((2, 4), (2, 7))]);
+    ///
+    /// // The selections are already aligned
+    /// assert!(!textarea.align_selections());
+    /// ```
+    pub fn align_selections(&mut self) -> bool {
+        let (ranges, primary) = self.selections_offsets();
+        let starts: Vec<_> = ranges
+            .iter()
+            .map(|&(s, _)| pos_at_text_offset(&self.lines, s))
+            .collect();
+        if starts.windows(2).any(|w| w[0].row == w[1].row) {
+            return false;
+        }
+        let columns: Vec<_> = starts
+            .iter()
+            .map(|p| {
+                let line = &self.lines[p.row][..p.offset];
+                display_width(
+                    line,
+                    self.indent.width,
+                    self.mask,
+                    self.control_chars,
+                    &self.char_width,
+                )
+            })
+            .collect();
+        let target = match columns.iter().max() {
+            Some(&c) => c,
+            None => return false,
+        };
+        if columns.iter().all(|&c| c == target) {
+            return false;
+        }
+        self.track_cursor(|t| {
+            t.with_history_group(|t| {
+                for (&(s, _), &col) in ranges.iter().zip(columns.iter()).rev() {
+                    t.replace_text_range(s, s, &spaces(target - col));
+                }
+            });
+            let mut added = 0;
+            let aligned: Vec<_> = ranges
+                .iter()
+                .zip(columns.iter())
+                .map(|(&(s, e), &col)| {
+                    added += target - col;
+                    (s + added, e + added)
+                })
+                .collect();
+            t.select_text_offsets_all(&aligned, primary);
+        });
+        true
+    }
+
+    /// Select the entire text. Cursor moves to the end of the text buffer. When text selection is already ongoing,
+    /// it is canceled.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["aaa", "bbb", "ccc"]);
+    ///
+    /// textarea.select_all();
+    ///
+    /// // Cut the entire text;
+    /// textarea.cut();
+    ///
+    /// assert_eq!(textarea.lines(), [""]); // Buffer is now empty
+    /// assert_eq!(textarea.yank_text(), "aaa\nbbb\nccc");
+    /// ```
+    pub fn select_all(&mut self) {
+        self.track_cursor(|t| t.select_all_inner())
+    }
+
+    fn select_all_inner(&mut self) {
+        self.move_cursor(CursorMove::Jump(u16::MAX, u16::MAX));
+        self.history.set_selections(vec![]);
+        self.selection_start = Some((0, 0));
+    }
+
+    /// Return if text selection is ongoing or not.
+    /// ```
+    /// use tui_textarea::{TextArea};
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// assert!(!textarea.is_selecting());
+    /// textarea.start_selection();
+    /// assert!(textarea.is_selecting());
+    /// textarea.cancel_selection();
+    /// assert!(!textarea.is_selecting());
+    /// ```
+    pub fn is_selecting(&self) -> bool {
+        self.selection_start.is_some()
+    }
+
+    // Same as `line_offset` but scans the line from the position converted last time. This is much faster when the
+    // column is around the last edit in a very long line.
+    fn cached_line_offset(&mut self, row: usize, col: usize) -> usize {
+        self.offsets
+            .offset(&self.lines[row], self.metrics.edits(), row, col)
+    }
+
+    fn line_offset(&self, row: usize, col: usize) -> usize {
+        let line = self
+            .lines
+            .get(row)
+            .unwrap_or(&self.lines[self.lines.len() - 1]);
+        line.char_indices()
+            .nth(col)
+            .map(|(i, _)| i)
+            .unwrap_or(line.len())
+    }
+
+    /// Set the callback which is called when the cursor position or the text selection is changed by methods of
+    /// [`TextArea`] such as [`TextArea::input`] and [`TextArea::move_cursor`]. The callback receives a
+    /// [`CursorChange`] which reports the positions before and after the change. It is called at most once per method
+    /// call even if the cursor moves several times internally. This is useful to show an autocomplete popup or to
+    /// sync a preview pane with the cursor.
+    ///
+    /// The callback is shared with clones of the textarea. Setting a new callback replaces the previous one.
+    /// ```
+    /// use std::sync::mpsc;
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["hello"]);
+    /// let (tx, rx) = mpsc::channel();
+    /// textarea.set_on_cursor_change(move |change| tx.send(*change).unwrap());
+    ///
+    /// textarea.move_cursor(CursorMove::End);
+    /// let change = rx.try_recv().unwrap();
+    /// assert_eq!(change.old_cursor, (0, 0));
+    /// assert_eq!(change.new_cursor, (0, 5));
+    ///
+    /// // Nothing is reported when the cursor does not move
+    /// textarea.move_cursor(CursorMove::End);
+    /// assert!(rx.try_recv().is_err());
+    /// ```
+    pub fn set_on_cursor_change(&mut self, callback: impl FnMut(&CursorChange) + Send + 'static) {
+        self.on_cursor_change = Some(CursorChangeCallback(Arc::new(Mutex::new(callback))));
+    }
+
+    /// Remove the callback set by [`TextArea::set_on_cursor_change`].
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["hello"]);
+    /// textarea.set_on_cursor_change(|_| panic!("not called"));
+    /// textarea.remove_on_cursor_change();
+    /// textarea.move_cursor(CursorMove::End);
+    /// ```
+    pub fn remove_on_cursor_change(&mut self) {
+        self.on_cursor_change = None;
+    }
+
+    /// Set the function to post-process each line on rendering. The function receives the 0-base row and the line
+    /// built by the textarea with all the styles such as the cursor, the selection, and the highlights, and returns
+    /// the line to render. This is useful to add decorations which are not built into this crate, such as rainbow
+    /// parentheses or underlined URLs. The returned line should keep the width of the text not to break the
+    /// horizontal scroll and the completion popup. When the function depends on states outside of it, call
+    /// [`TextArea::invalidate_render_cache`] after the states are changed.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["see https://example.com", "hello"]);
+    ///
+    /// // Mark rows which contain URLs
+    /// textarea.set_line_postprocessor(|row, mut line| {
+    ///     if line.spans.iter().any(|s| s.content.contains("https://")) {
+    ///         line.spans.push(format!(" <{}", row + 1).into());
+    ///     }
+    ///     line
+    /// });
+    /// assert_eq!(
+    ///     textarea.render_to_string(30, 2),
+    ///     "see https://example.com <1    \nhello                         ",
+    /// );
+    /// ```
+    pub fn set_line_postprocessor(
+        &mut self,
+        f: impl for<'l> Fn(usize, Line<'l>) -> Line<'l> + Send + Sync + 'static,
+    ) {
+        self.touch_render(|t| t.line_postprocessor = Some(LinePostprocessor(Arc::new(f))));
+    }
+
+    /// Remove the function set by [`TextArea::set_line_postprocessor`].
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["hello"]);
+    /// textarea.set_line_postprocessor(|_, _| "replaced".into());
+    /// assert_eq!(textarea.render_to_string(8, 1), "replaced");
+    ///
+    /// textarea.remove_line_postprocessor();
+    /// assert_eq!(textarea.render_to_string(8, 1), "hello   ");
+    /// ```
+    pub fn remove_line_postprocessor(&mut self) {
+        self.touch_render(|t| t.line_postprocessor = None);
+    }
+
+    /// Set the function to transform text inserted from external sources before it enters the text buffer and the
+    /// history. The function is called with the text given to [`TextArea::insert_str`] and [`TextArea::set_yank_text`]
+    /// (so the text pasted by [`TextArea::paste`] is filtered when it is set), and returns the text to insert. This
+    /// is useful to strip ANSI escape sequences, normalize smart quotes, or convert tabs in pasted text. When the
+    /// function returns an empty string, [`TextArea::insert_str`] rejects the text and does nothing including
+    /// deleting the selection. Text typed by keys and text cut or copied in the textarea are not filtered.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    /// textarea.set_paste_filter(|s| s.replace('\t', "    ").replace(['“', '”'], "\""));
+    ///
+    /// textarea.insert_str("\t“quoted”");
+    /// assert_eq!(textarea.lines(), ["    \"quoted\""]);
+    ///
+    /// // Reject text containing control characters
+    /// textarea.set_paste_filter(|s| {
+    ///     if s.chars().any(|c| c.is_control() && c != '\n') {
+    ///         String::new()
+    ///     } else {
+    ///         s
+    ///     }
+    /// });
+    /// assert!(!textarea.insert_str("\x1b[31mred"));
+    /// assert_eq!(textarea.lines(), ["    \"quoted\""]);
+    /// ```
+    pub fn set_paste_filter(&mut self, f: impl Fn(String) -> String + Send + Sync + 'static) {
+        self.paste_filter = Some(PasteFilter(Arc::new(f)));
+    }
+
+    /// Remove the function set by [`TextArea::set_paste_filter`].
+    pub fn remove_paste_filter(&mut self) {
+        self.paste_filter = None;
+    }
+
+    /// Set if the textarea behaves as a prompt. In prompt mode, `Ctrl+P`/`↑` on the first line recalls the previous
+    /// entry in the prompt history and `Ctrl+N`/`↓` on the last line recalls the next entry, like readline. See
+    /// [`TextArea::prev_prompt_history`] and [`TextArea::next_prompt_history`]. It is disabled by default.
+    /// ```
+    /// use tui_textarea::{TextArea, Input, Key};
+    ///
+    /// let mut textarea = TextArea::default();
+    /// textarea.set_prompt_mode(true);
+    /// textarea.push_prompt_history("ls -l");
+    ///
+    /// textarea.insert_str("git");
+    /// textarea.input(Input { key: Key::Up, ..Default::default() });
+    /// assert_eq!(textarea.lines(), ["ls -l"]);
+    /// textarea.input(Input { key: Key::Down, ..Default::default() });
+    /// assert_eq!(textarea.lines(), ["git"]);
+    /// ```
+    pub fn set_prompt_mode(&mut self, enabled: bool) {
+        self.prompt_mode = enabled;
+    }
+
+    /// Get if the textarea behaves as a prompt. See [`TextArea::set_prompt_mode`].
+    pub fn prompt_mode(&self) -> bool {
+        self.prompt_mode
+    }
+
+    /// Add an entry to the prompt history. Empty text and the text which is the same as the latest entry are ignored.
+    /// Recalling the history is reset, so call this method when the text is submitted.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    /// textarea.push_prompt_history("foo");
+    /// textarea.push_prompt_history("");
+    /// textarea.push_prompt_history("bar");
+    /// textarea.push_prompt_history("bar");
+    /// assert_eq!(textarea.prompt_history(), ["foo", "bar"]);
+    /// ```
+    pub fn push_prompt_history(&mut self, entry: impl Into<String>) {
+        self.prompt_history.push(entry.into());
+    }
+
+    /// Get the entries of the prompt history from the oldest to the latest.
+    pub fn prompt_history(&self) -> &[String] {
+        self.prompt_history.entries()
+    }
+
+    /// Remove all entries of the prompt history.
+    pub fn clear_prompt_history(&mut self) {
+        self.prompt_history.clear();
+    }
+
+    /// Replace the text with the previous entry in the prompt history and move the cursor to the end. When starting to
+    /// recall the history, the text being edited is stashed and it is restored by
+    /// [`TextArea::next_prompt_history`]. The replacement is recorded in the undo history. This method returns if
+    /// some entry was recalled or not.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["draft"]);
+    /// textarea.push_prompt_history("first");
+    /// textarea.push_prompt_history("second");
+    ///
+    /// assert!(textarea.prev_prompt_history());
+    /// assert_eq!(textarea.lines(), ["second"]);
+    /// assert!(textarea.prev_prompt_history());
+    /// assert_eq!(textarea.lines(), ["first"]);
+    /// assert!(!textarea.prev_prompt_history());
+    ///
+    /// textarea.next_prompt_history();
+    /// assert!(textarea.next_prompt_history());
+    /// assert_eq!(textarea.lines(), ["draft"]);
+    /// assert!(!textarea.next_prompt_history());
+    /// ```
+    pub fn prev_prompt_history(&mut self) -> bool {
+        let current = self.lines.join("\n");
+        let entry = match self.prompt_history.prev(current) {
+            Some(entry) => entry.to_string(),
+            None => return false,
+        };
+        self.recall_prompt(&entry);
+        true
+    }
+
+    /// Replace the text with the next entry in the prompt history and move the cursor to the end. After the latest
+    /// entry, the text stashed by [`TextArea::prev_prompt_history`] is restored. This method returns if some entry
+    /// was recalled or not.
+    pub fn next_prompt_history(&mut self) -> bool {
+        let entry = match self.prompt_history.next() {
+            Some(entry) => entry,
+            None => return false,
+        };
+        self.recall_prompt(&entry);
+        true
+    }
+
+    fn recall_prompt(&mut self, entry: &str) {
+        self.track_cursor(|t| {
+            t.set_text(entry);
+            t.move_cursor(CursorMove::Bottom);
+            t.move_cursor(CursorMove::End);
+        })
+    }
+
+    /// Set the candidates of completion. While some candidates match the word before the cursor, they are shown in
+    /// a popup at the cursor and [`TextArea::input`] handles the following keys for completion:
+    ///
+    /// - `Tab` accepts the selected candidate (see [`TextArea::accept_completion`])
+    /// - `Ctrl+N`/`↓` and `Ctrl+P`/`↑` select the next/previous candidate
+    /// - `Esc` closes the popup by clearing the candidates
+    ///
+    /// A word consists of alphanumeric characters and `_`. Candidates are filtered with the word before the cursor as
+    /// prefix, so you can keep the candidates while the user is typing. The first matching candidate is selected
+    /// initially.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["pri"]);
+    /// textarea.move_cursor(CursorMove::End);
+    ///
+    /// textarea.set_completion_items(vec!["print".to_string(), "println".to_string(), "format".to_string()]);
+    /// assert_eq!(textarea.selected_completion(), Some("print"));
+    ///
+    /// textarea.select_next_completion();
+    /// assert_eq!(textarea.selected_completion(), Some("println"));
+    /// ```
+    pub fn set_completion_items(&mut self, items: Vec<String>) {
+        self.touch_render(|t| t.completion.set_items(items));
+    }
+
+    /// Get the candidates of completion set by [`TextArea::set_completion_items`].
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    /// assert!(textarea.completion_items().is_empty());
+    ///
+    /// textarea.set_completion_items(vec!["foo".to_string()]);
+    /// assert_eq!(textarea.completion_items(), ["foo"]);
+    /// ```
+    pub fn completion_items(&self) -> &[String] {
+        self.completion.items()
+    }
+
+    /// Clear the candidates of completion. This closes the completion popup.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    /// textarea.set_completion_items(vec!["foo".to_string()]);
+    /// textarea.clear_completion();
+    /// assert_eq!(textarea.selected_completion(), None);
+    /// ```
+    pub fn clear_completion(&mut self) {
+        self.touch_render(|t| t.completion.set_items(vec![]));
+    }
+
+    // Word before the cursor which is completed
+    fn completion_prefix(&self) -> &str {
+        let (row, col) = self.cursor;
+        let line = &self.lines[row];
+        let end = self.line_offset(row, col);
+        let start = line[..end]
+            .char_indices()
+            .rev()
+            .take_while(|(_, c)| c.is_alphanumeric() || *c == '_')
+            .last()
+            .map(|(i, _)| i)
+            .unwrap_or(end);
+        &line[start..end]
+    }
+
+    /// Get the selected candidate of completion. This returns `None` when no candidate matches the word before the
+    /// cursor, which means the completion popup is not shown.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["foo"]);
+    /// textarea.move_cursor(CursorMove::End);
+    ///
+    /// textarea.set_completion_items(vec!["bar".to_string(), "foobar".to_string()]);
+    /// assert_eq!(textarea.selected_completion(), Some("foobar"));
+    ///
+    /// textarea.insert_char('x');
+    /// assert_eq!(textarea.selected_completion(), None);
+    /// ```
+    pub fn selected_completion(&self) -> Option<&str> {
+        if self.completion.items().is_empty() {
+            return None; // Avoid finding the word before the cursor on every input
+        }
+        self.completion.selected(self.completion_prefix())
+    }
+
+    /// Select the next candidate of completion. When the last candidate is selected, the first one is selected. This
+    /// method returns if some candidate matches the word before the cursor.
+    pub fn select_next_completion(&mut self) -> bool {
+        let prefix = self.completion_prefix().to_string();
+        self.touch_render(|t| t.completion.select_next(&prefix))
+    }
+
+    /// Select the previous candidate of completion. When the first candidate is selected, the last one is selected.
+    /// This method returns if some candidate matches the word before the cursor.
+    pub fn select_prev_completion(&mut self) -> bool {
+        let prefix = self.completion_prefix().to_string();
+        self.touch_render(|t| t.completion.select_prev(&prefix))
+    }
+
+    /// Accept the selected candidate of completion. The rest of the candidate after the word before the cursor is
+    /// inserted as one undo/redo entry, and the candidates are cleared. This method returns if some text was
+    /// inserted or not.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["let x = pri"]);
+    /// textarea.move_cursor(CursorMove::End);
+    ///
+    /// textarea.set_completion_items(vec!["println!".to_string()]);
+    /// assert!(textarea.accept_completion());
+    /// assert_eq!(textarea.lines(), ["let x = println!"]);
+    /// assert!(textarea.completion_items().is_empty());
+    ///
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["let x = pri"]);
+    /// ```
+    pub fn accept_completion(&mut self) -> bool {
+        let suffix = match self.selected_completion() {
+            Some(item) => item[self.completion_prefix().len()..].to_string(),
+            None => return false,
+        };
+        self.clear_completion();
+        self.with_history_group(|t| t.insert_str(suffix))
+    }
+
+    /// Set the style of the completion popup. The default style is dark gray background.
+    /// ```
+    /// use tui_textarea::TextArea;
+    /// use ratatui::style::{Style, Color};
+    ///
+    /// let mut textarea = TextArea::default();
+    /// textarea.set_completion_style(Style::default().bg(Color::Blue));
+    /// assert_eq!(textarea.completion_style(), Style::default().bg(Color::Blue));
+    /// ```
+    pub fn set_completion_style(&mut self, style: Style) {
+        self.touch_render(|t| t.completion.style = style);
+    }
+
+    /// Get the style of the completion popup.
+    pub fn completion_style(&self) -> Style {
+        self.completion.style
+    }
+
+    /// Set the style of the selected candidate in the completion popup. The default style is reversed dark gray
+    /// background.
+    /// ```
+    /// use tui_textarea::TextArea;
+    /// use ratatui::style::{Style, Color};
+    ///
+    /// let mut textarea = TextArea::default();
+    /// textarea.set_completion_selected_style(Style::default().bg(Color::Yellow));
+    /// assert_eq!(textarea.completion_selected_style(), Style::default().bg(Color::Yellow));
+    /// ```
+    pub fn set_completion_selected_style(&mut self, style: Style) {
+        self.touch_render(|t| t.completion.selected_style = style);
+    }
+
+    /// Get the style of the selected candidate in the completion popup.
+    pub fn completion_selected_style(&self) -> Style {
+        self.completion.selected_style
+    }
+
+    // Width of the line numbers and the diff signs rendered before the text
+    // Number of digits of line numbers in the gutter
+    pub(crate) fn line_number_len(&self) -> u8 {
+        self.line_number_width
+            .unwrap_or_else(|| num_digits(self.lines.len()))
+    }
+
+    pub(crate) fn gutter_width(&self) -> u16 {
+        let mut width = 0;
+        if self.line_number_style.is_some() {
+            width += self.line_number_len() as u16 + 2; // `+ 2` for margins
+        }
+        if !self.diff_marks.is_empty() {
+            width += 2;
+        }
+        width
+    }
+
+    // Spaces put between the gutter and the text to align the line at the `row` in the `width` by its row alignment.
+    // Lines which don't fit in the width are not aligned. One cell is reserved for the cursor at the end of line. The
+    // width is of the viewport without the horizontally scrolled part so that scrolling does not change the padding.
+    pub(crate) fn row_padding(&self, row: usize, width: usize) -> usize {
+        if self.alignment != Alignment::Left {
+            return 0;
+        }
+        let alignment = match self.row_alignments.get(&row) {
+            Some(&a) if a != Alignment::Left => a,
+            _ => return 0,
+        };
+        let line = match self.lines.get(row) {
+            Some(line) => line,
+            None => return 0,
+        };
+        let text_width = display_width(
+            line,
+            self.indent.width,
+            self.mask,
+            self.control_chars,
+            &self.char_width,
+        ) + 1;
+        let space = width.saturating_sub(self.gutter_width() as usize + text_width);
+        match alignment {
+            Alignment::Center => space / 2,
+            _ => space,
+        }
+    }
+
+    // Render the completion popup below the word before the cursor. `area` is the inner area of the textarea.
+    pub(crate) fn render_completion(
+        &self,
+        area: Rect,
+        top_row: u16,
+        top_col: u16,
+        buf: &mut Buffer,
+    ) {
+        let prefix = self.completion_prefix();
+        if self.completion.selected(prefix).is_none() {
+            return;
+        }
+        // The position is relative to the viewport. It is clamped to the visible area since the viewport cannot
+        // follow the cursor beyond the `u16::MAX`th row or column
+        let (row, col) = self.cursor;
+        let last = |len: u16| len.saturating_sub(1) as usize;
+        let y = cmp::min(row.saturating_sub(top_row as usize), last(area.height)) as u16;
+        let line = &self.lines[row];
+        let start = self.line_offset(row, col) - prefix.len();
+        let width = display_width(
+            &line[..start],
+            self.indent.width,
+            self.mask,
+            self.control_chars,
+            &self.char_width,
+        );
+        let padding = self.row_padding(row, area.width as usize);
+        let x = (width + padding + self.gutter_width() as usize).saturating_sub(top_col as usize);
+        let x = cmp::min(x, last(area.width)) as u16;
+        self.completion
+            .render(prefix, area.x + x, area.y + y, area, buf);
+    }
+
+    /// Set if the text selection includes the character at its end position. When enabled, the character under the
+    /// cursor (or under the selection start, whichever comes later) is selected like visual mode of Vim or
+    /// selections of Helix. When the end position is at the end of line, the newline is selected. This setting is
+    /// respected by copying, cutting, deleting, and rendering the selection. It is disabled by default, which means
+    /// the selection ends just before the cursor.
+    ///
+    /// Note that [`TextArea::selection_range`] still returns the selection start and the cursor positions as-is.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["abcd"]);
+    /// textarea.set_selection_inclusive(true);
+    /// assert!(textarea.selection_inclusive());
+    ///
+    /// textarea.start_selection();
+    /// textarea.move_cursor(CursorMove::Forward);
+    /// textarea.copy();
+    /// assert_eq!(textarea.yank_text(), "ab"); // 'b' under the cursor is included
+    /// ```
+    pub fn set_selection_inclusive(&mut self, enabled: bool) {
+        self.touch_render(|t| t.selection_inclusive = enabled);
+    }
+
+    /// Get if the text selection includes the character at its end position.
+    pub fn selection_inclusive(&self) -> bool {
+        self.selection_inclusive
+    }
+
+    /// Set the style used for text selection. The default style is light blue.
+    /// ```
+    /// use tui_textarea::TextArea;
+    /// use ratatui::style::{Style, Color};
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// // Change the selection color from the default to Red
+    /// textarea.set_selection_style(Style::default().bg(Color::Red));
+    /// assert_eq!(textarea.selection_style(), Style::default().bg(Color::Red));
+    /// ```
+    pub fn set_selection_style(&mut self, style: Style) {
+        self.touch_render(|t| t.select_style = style);
+    }
+
+    /// Get the style used for text selection.
+    /// ```
+    /// use tui_textarea::TextArea;
+    /// use ratatui::style::{Style, Color};
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// assert_eq!(textarea.selection_style(), Style::default().bg(Color::LightBlue));
+    /// ```
+    pub fn selection_style(&mut self) -> Style {
+        self.select_style
+    }
+
+    /// Set if styles of overlapping decorations are merged or not. Decorations are the cursor line, text selection,
+    /// search matches, the cursor, and highlights added by [`TextArea::add_highlight`]. When enabled, the styles are
+    /// combined with [`Style::patch`] so that, for example, the selection background is kept while the cursor adds
+    /// its modifiers. When disabled, the style of the topmost decoration replaces the styles under it as older
+    /// versions did. This is enabled by default.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    /// assert!(textarea.merge_styles());
+    ///
+    /// // Use the legacy behavior
+    /// textarea.set_merge_styles(false);
+    /// assert!(!textarea.merge_styles());
+    /// ```
+    pub fn set_merge_styles(&mut self, enabled: bool) {
+        self.touch_render(|t| t.merge_styles = enabled);
+    }
+
+    /// Get if styles of overlapping decorations are merged or not.
+    pub fn merge_styles(&self) -> bool {
+        self.merge_styles
+    }
+
+    /// Highlight the text in the given range with the style. This is useful to show diagnostics such as spell-check
+    /// errors or references found by a language server. The range is a pair of 0-base character-wise (row, col)
+    /// positions and the end position is exclusive. A range can span multiple lines.
+    ///
+    /// The `id` is an arbitrary number chosen by the caller to identify highlights. Multiple ranges can share the same
+    /// `id` and they can be removed at once by [`TextArea::remove_highlight`].
+    ///
+    /// When highlights overlap, their styles are merged with [`Style::patch`]. The style of the highlight with the
+    /// higher `priority` is patched on top. When the priorities are the same, the highlight added later is patched on
+    /// top. Custom highlights are always patched under the cursor, search matches, and text selection. See
+    /// [`TextArea::set_merge_styles`] to replace the styles instead of merging them.
+    ///
+    /// Highlights are not moved by text edits. Update them when the text is modified.
+    /// ```
+    /// use tui_textarea::TextArea;
+    /// use ratatui::style::{Style, Modifier};
+    ///
+    /// const SPELL_CHECK: usize = 1;
+    ///
+    /// let mut textarea = TextArea::from(["helo wrld"]);
+    /// let style = Style::default().add_modifier(Modifier::UNDERLINED);
+    /// textarea.add_highlight(SPELL_CHECK, ((0, 0), (0, 4)), style, 0);
+    /// textarea.add_highlight(SPELL_CHECK, ((0, 5), (0, 9)), style, 0);
+    ///
+    /// // Remove all spell-check highlights
+    /// assert!(textarea.remove_highlight(SPELL_CHECK));
+    /// ```
+    pub fn add_highlight(
+        &mut self,
+        id: usize,
+        range: ((usize, usize), (usize, usize)),
+        style: Style,
+        priority: u16,
+    ) {
+        let (start, end) = if range.0 <= range.1 {
+            range
+        } else {
+            (range.1, range.0)
+        };
+        self.touch_render(|t| {
+            t.highlights.push(Highlight {
+                id,
+                start,
+                end,
+                style,
+                priority,
+            })
+        });
+    }
+
+    /// Remove all highlights with the given `id` added by [`TextArea::add_highlight`]. This method returns if any
+    /// highlight was removed.
+    /// ```
+    /// use tui_textarea::TextArea;
+    /// use ratatui::style::{Style, Color};
+    ///
+    /// let mut textarea = TextArea::from(["hello"]);
+    /// textarea.add_highlight(1, ((0, 0), (0, 2)), Style::default().bg(Color::Red), 0);
+    ///
+    /// assert!(textarea.remove_highlight(1));
+    /// assert!(!textarea.remove_highlight(1));
+    /// ```
+    pub fn remove_highlight(&mut self, id: usize) -> bool {
+        self.touch_render(|t| {
+            let len = t.highlights.len();
+            t.highlights.retain(|h| h.id != id);
+            t.highlights.len() != len
+        })
+    }
+
+    /// Remove all highlights added by [`TextArea::add_highlight`].
+    /// ```
+    /// use tui_textarea::TextArea;
+    /// use ratatui::style::{Style, Color};
+    ///
+    /// let mut textarea = TextArea::from(["hello"]);
+    /// textarea.add_highlight(1, ((0, 0), (0, 2)), Style::default().bg(Color::Red), 0);
+    /// textarea.add_highlight(2, ((0, 1), (0, 4)), Style::default().bg(Color::Blue), 1);
+    ///
+    /// textarea.clear_highlights();
+    /// assert!(!textarea.remove_highlight(1));
+    /// assert!(!textarea.remove_highlight(2));
+    /// ```
+    pub fn clear_highlights(&mut self) {
+        self.touch_render(|t| t.highlights.clear());
+    }
+
+    pub(crate) fn selection_positions(&self) -> Option<(Pos, Pos)> {
+        let (sr, sc) = self.selection_start?;
+        let (er, ec) = self.cursor;
+        let (so, eo) = (self.line_offset(sr, sc), self.line_offset(er, ec));
+        let s = Pos::new(sr, sc, so);
+        let e = Pos::new(er, ec, eo);
+        let (s, e) = match (sr, so).cmp(&(er, eo)) {
+            Ordering::Less => (s, e),
+            Ordering::Equal if !self.selection_inclusive => return None,
+            Ordering::Equal => (s, e),
+            Ordering::Greater => (e, s),
+        };
+        if !self.selection_inclusive {
+            return Some((s, e));
+        }
+
+        // Include the character at the end position. At the end of line, the newline is included
+        let line = &self.lines[e.row];
+        let e = if let Some(c) = line[e.offset..].chars().next() {
+            Pos::new(e.row, e.col + 1, e.offset + c.len_utf8())
+        } else if e.row + 1 < self.lines.len() {
+            Pos::new(e.row + 1, 0, 0)
+        } else {
+            e
+        };
+        if (s.row, s.offset) == (e.row, e.offset) {
+            return None;
+        }
+        Some((s, e))
+    }
+
+    fn take_selection_positions(&mut self) -> Option<(Pos, Pos)> {
+        let range = self.selection_positions();
+        self.cancel_selection();
+        range
+    }
+
+    /// Copy the selection text to the yank buffer. When nothing is selected, this method does nothing.
+    /// To get the yanked text, use [`TextArea::yank_text`].
+    /// ```
+    /// use tui_textarea::{TextArea, Key, Input, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["Hello World"]);
+    ///
+    /// // Start text selection at 'W'
+    /// textarea.move_cursor(CursorMove::WordForward);
+    /// textarea.start_selection();
+    ///
+    /// // Select the word "World" and copy the selected text
+    /// textarea.move_cursor(CursorMove::End);
+    /// textarea.copy();
+    ///
+    /// assert_eq!(textarea.yank_text(), "World");
+    /// assert_eq!(textarea.lines(), ["Hello World"]); // Text does not change
+    /// ```
+    pub fn copy(&mut self) {
+        self.track_cursor(|t| t.copy_inner())
+    }
+
+    fn copy_inner(&mut self) {
+        if self.has_secondary_selections() {
+            self.yank = self.selections_text();
+            self.cancel_selection();
+            return;
+        }
+
+        if let Some((start, end)) = self.take_selection_positions() {
+            if start.row == end.row {
+                self.yank = self.lines[start.row][start.offset..end.offset]
+                    .to_string()
+                    .into();
+                return;
+            }
+            let mut chunk = Vec::with_capacity(end.row - start.row + 1);
+            chunk.push(self.lines[start.row][start.offset..].to_string());
+            chunk.extend(self.lines[start.row + 1..end.row].iter().cloned());
+            chunk.push(self.lines[end.row][..end.offset].to_string());
+            self.yank = YankText::Chunk(Arc::new(chunk));
+        }
+    }
+
+    /// Cut the selected text and place it in the yank buffer. This method returns whether the text was modified.
+    /// The cursor will move to the start position of the text selection.
+    /// To get the yanked text, use [`TextArea::yank_text`].
+    /// ```
+    /// use tui_textarea::{TextArea, Key, Input, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["Hello World"]);
+    ///
+    /// // Start text selection at 'W'
+    /// textarea.move_cursor(CursorMove::WordForward);
+    /// textarea.start_selection();
+    ///
+    /// // Select the word "World" and copy the selected text
+    /// textarea.move_cursor(CursorMove::End);
+    /// textarea.cut();
+    ///
+    /// assert_eq!(textarea.yank_text(), "World");
+    /// assert_eq!(textarea.lines(), ["Hello "]);
+    /// ```
+    pub fn cut(&mut self) -> bool {
+        self.track_cursor(|t| {
+            if t.has_secondary_selections() {
+                t.yank = t.selections_text();
+                return t.edit_selections("", |_, s, e| (s, e));
+            }
+            t.delete_selection(true)
+        })
+    }
+
+    fn delete_selection(&mut self, should_yank: bool) -> bool {
+        if let Some((s, e)) = self.take_selection_positions() {
+            self.delete_range(s, e, should_yank);
+            return true;
+        }
+        false
+    }
+
+    /// Surround the selected text with the `open` and `close` delimiters. The insertions are recorded as one history
+    /// entry and the selection is kept over the original text. This is useful to implement operations such as
+    /// wrapping text with brackets or toggling bold text in Markdown. When nothing is selected, this method does
+    /// nothing. This method returns if the text was modified or not.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["hello world"]);
+    ///
+    /// // Select "world"
+    /// textarea.move_cursor(CursorMove::WordForward);
+    /// textarea.start_selection();
+    /// textarea.move_cursor(CursorMove::End);
+    ///
+    /// textarea.surround_selection("**", "**");
+    /// assert_eq!(textarea.lines(), ["hello **world**"]);
+    /// assert_eq!(textarea.selection_range(), Some(((0, 8), (0, 13))));
+    ///
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["hello world"]);
+    /// ```
+    pub fn surround_selection(&mut self, open: impl AsRef<str>, close: impl AsRef<str>) -> bool {
+        self.track_cursor(|t| {
+            let (start, end, forward) = match t.selection_offsets() {
+                Some(range) => range,
+                None => return false,
+            };
+            let open = open.as_ref().replace("\r\n", "\n");
+            let close = close.as_ref().replace("\r\n", "\n");
+            if open.is_empty() && close.is_empty() {
+                return false;
+            }
+            t.with_history_group(|t| {
+                t.replace_text_range(end, end, &close);
+                t.replace_text_range(start, start, &open);
+            });
+            t.select_text_offsets(start + open.len(), end + open.len(), forward);
+            true
+        })
+    }
+
+    /// Remove the delimiters surrounding the selected text, which is the reverse operation of
+    /// [`TextArea::surround_selection`]. The delimiters are searched just outside the selection first, then at both
+    /// ends of the selected text. A pair of brackets (`()`, `[]`, `{}`, `<>`) or the same sequence of an ASCII
+    /// punctuation such as `**`, `"`, `~~` is recognized as delimiters. The deletions are recorded as one history entry
+    /// and the selection is kept over the text without the delimiters. This method returns if some delimiters were
+    /// removed or not.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["hello **world**"]);
+    ///
+    /// // Select "world"
+    /// textarea.move_cursor(CursorMove::Jump(0, 8));
+    /// textarea.start_selection();
+    /// textarea.move_cursor(CursorMove::Jump(0, 13));
+    ///
+    /// textarea.unsurround_selection();
+    /// assert_eq!(textarea.lines(), ["hello world"]);
+    /// assert_eq!(textarea.selection_range(), Some(((0, 6), (0, 11))));
+    ///
+    /// // Select "(world)" including the brackets
+    /// let mut textarea = TextArea::from(["hello (world)"]);
+    /// textarea.move_cursor(CursorMove::Jump(0, 6));
+    /// textarea.start_selection();
+    /// textarea.move_cursor(CursorMove::End);
+    ///
+    /// textarea.unsurround_selection();
+    /// assert_eq!(textarea.lines(), ["hello world"]);
+    /// ```
+    pub fn unsurround_selection(&mut self) -> bool {
+        self.track_cursor(|t| {
+            let (start, end, forward) = match t.selection_offsets() {
+                Some(range) => range,
+                None => return false,
+            };
+            let text = t.lines.join("\n");
+            let (start, end, open, close) = if let Some((o, c)) =
+                find_delimiters(text[..start].chars().rev(), text[end..].chars())
+            {
+                (start - o, end + c, o, c)
+            } else {
+                let selected = &text[start..end];
+                match find_delimiters(selected.chars(), selected.chars().rev()) {
+                    Some((o, c)) if o + c <= selected.len() => (start, end, o, c),
+                    _ => return false,
+                }
+            };
+            t.with_history_group(|t| {
+                t.replace_text_range(end - close, end, "");
+                t.replace_text_range(start, start + open, "");
+            });
+            t.select_text_offsets(start, end - open - close, forward);
+            true
+        })
+    }
+
+    // Byte offsets of the selected range in the text where lines are joined with newlines, and whether the cursor is
+    // at the end of the selection or not. `None` when nothing is selected.
+    fn selection_offsets(&self) -> Option<(usize, usize, bool)> {
+        let (s, e) = self.selection_positions()?;
+        let forward = self.selection_start? <= self.cursor;
+        Some((
+            self.text_offset((s.row, s.col)),
+            self.text_offset((e.row, e.col)),
+            forward,
+        ))
+    }
+
+    // Select the byte range in the text where lines are joined with newlines. The cursor is placed at the end of the
+    // range when `forward` is true.
+    fn select_text_offsets(&mut self, start: usize, end: usize, forward: bool) {
+        let s = pos_at_text_offset(&self.lines, start);
+        let e = pos_at_text_offset(&self.lines, end);
+        let (s, mut e) = ((s.row, s.col), (e.row, e.col));
+        if self.selection_inclusive && s < e {
+            // The end position is the last selected character in inclusive selection
+            e = if e.1 > 0 {
+                (e.0, e.1 - 1)
+            } else {
+                (e.0 - 1, self.lines[e.0 - 1].chars().count())
+            };
+        }
+        if forward {
+            self.selection_start = Some(s);
+            self.cursor = e;
+        } else {
+            self.selection_start = Some(e);
+            self.cursor = s;
+        }
+    }
+
+    /// Set the text being composed by an input method (IME). The pair consists of the composing text and the cursor
+    /// position in the text as the number of characters. The composing text is rendered at the cursor position but it
+    /// is not inserted into the text buffer until [`TextArea::commit_preedit`] is called. Setting `None` clears the
+    /// composing text. When the mask character is set by [`TextArea::set_mask_char`], the composing text is also
+    /// masked.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// textarea.set_preedit(Some(("にほん".to_string(), 3)));
+    /// assert_eq!(textarea.preedit(), Some(("にほん", 3)));
+    /// assert_eq!(textarea.lines(), [""]); // The text buffer is not modified
+    ///
+    /// textarea.set_preedit(None);
+    /// assert_eq!(textarea.preedit(), None);
+    /// ```
+    pub fn set_preedit(&mut self, preedit: Option<(String, usize)>) {
+        self.touch_render(|t| t.preedit = preedit);
+    }
+
+    /// Get the text being composed by an input method and the cursor position in it. When no text is being composed,
+    /// this method returns `None`.
+    pub fn preedit(&self) -> Option<(&str, usize)> {
+        self.preedit.as_ref().map(|(s, c)| (s.as_str(), *c))
+    }
+
+    /// Insert the text being composed by an input method at the cursor position and finish the composition. This
+    /// method returns if some text was inserted or not in the textarea.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// textarea.set_preedit(Some(("日本".to_string(), 2)));
+    /// assert!(textarea.commit_preedit());
+    /// assert_eq!(textarea.lines(), ["日本"]);
+    /// assert_eq!(textarea.preedit(), None);
+    /// ```
+    pub fn commit_preedit(&mut self) -> bool {
+        self.track_cursor(|t| match t.touch_render(|t| t.preedit.take()) {
+            Some((text, _)) => t.insert_str(text),
+            None => false,
+        })
+    }
+
+    /// Discard the text being composed by an input method without modifying the text buffer.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// textarea.set_preedit(Some(("にほん".to_string(), 3)));
+    /// textarea.cancel_preedit();
+    /// assert_eq!(textarea.preedit(), None);
+    /// assert_eq!(textarea.lines(), [""]);
+    /// ```
+    pub fn cancel_preedit(&mut self) {
+        self.touch_render(|t| t.preedit = None);
+    }
+
+    /// Set the style of the text being composed by an input method. The default style is underlined.
+    /// ```
+    /// use ratatui::style::{Style, Modifier};
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// let style = Style::default().add_modifier(Modifier::ITALIC);
+    /// textarea.set_preedit_style(style);
+    /// assert_eq!(textarea.preedit_style(), style);
+    /// ```
+    pub fn set_preedit_style(&mut self, style: Style) {
+        self.touch_render(|t| t.preedit_style = style);
+    }
+
+    /// Get the style of the text being composed by an input method.
+    pub fn preedit_style(&self) -> Style {
+        self.preedit_style
+    }
+
+    /// Set the inline hint (ghost text) rendered at the cursor with the given style, such as a suggestion from an AI
+    /// assistant or fish-style autosuggestion from the input history. The hint is not a part of the text buffer. So it
+    /// does not affect the cursor position, the text selection, and the text. The cursor is rendered on the head of
+    /// the hint. Setting `None` removes the hint. While the text composed by an input method is set by
+    /// [`TextArea::set_preedit`], the hint is not rendered.
+    ///
+    /// The hint is not cleared automatically on edits. Update or remove it when the text is modified.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    /// use ratatui::style::{Style, Color};
+    ///
+    /// let mut textarea = TextArea::from(["git ch"]);
+    /// textarea.move_cursor(CursorMove::End);
+    ///
+    /// let style = Style::default().fg(Color::DarkGray);
+    /// textarea.set_inline_hint(Some(("eckout main".to_string(), style)));
+    /// assert_eq!(textarea.inline_hint(), Some(("eckout main", style)));
+    /// assert_eq!(textarea.lines(), ["git ch"]); // The text is not modified
+    /// assert_eq!(textarea.cursor(), (0, 6));
+    /// ```
+    pub fn set_inline_hint(&mut self, hint: Option<(String, Style)>) {
+        self.touch_render(|t| t.inline_hint = hint);
+    }
+
+    /// Get the inline hint set by [`TextArea::set_inline_hint`] and its style.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let textarea = TextArea::default();
+    /// assert_eq!(textarea.inline_hint(), None);
+    /// ```
+    pub fn inline_hint(&self) -> Option<(&str, Style)> {
+        self.inline_hint.as_ref().map(|(h, s)| (h.as_str(), *s))
+    }
+
+    /// Set the annotation rendered after the end of the line at the `row`, such as inline diagnostics, blame
+    /// information, or measurement hints. Like [`TextArea::set_inline_hint`], the annotation is virtual text which is
+    /// not a part of the text buffer. It does not affect the cursor position, the text selection, and the text. Only
+    /// one annotation can be set for each line. Setting a new annotation to the same line replaces the previous one.
+    ///
+    /// The annotation is rendered right after the end of line. Put some spaces at the head of the annotation to
+    /// separate it from the line. Annotations are not moved by text edits. Update them when the text is modified.
+    /// ```
+    /// use tui_textarea::TextArea;
+    /// use ratatui::style::{Style, Color};
+    /// use ratatui::text::Span;
+    ///
+    /// let mut textarea = TextArea::from(["let x = 1"]);
+    ///
+    /// let style = Style::default().fg(Color::Red);
+    /// textarea.set_eol_annotation(0, Span::styled("  ← expected `;`", style));
+    /// assert_eq!(textarea.eol_annotation(0), Some(&Span::styled("  ← expected `;`", style)));
+    /// assert_eq!(textarea.lines(), ["let x = 1"]); // The text is not modified
+    ///
+    /// textarea.remove_eol_annotation(0);
+    /// assert_eq!(textarea.eol_annotation(0), None);
+    /// ```
+    pub fn set_eol_annotation(&mut self, row: usize, annotation: Span<'a>) {
+        self.touch_render(|t| t.eol_annotations.insert(row, annotation));
+    }
+
+    /// Get the annotation rendered after the end of the line at the `row`.
+    pub fn eol_annotation(&self, row: usize) -> Option<&Span<'a>> {
+        self.eol_annotations.get(&row)
+    }
+
+    /// Remove the annotation of the line at the `row` and return it. When the line has no annotation, this method
+    /// returns `None`.
+    pub fn remove_eol_annotation(&mut self, row: usize) -> Option<Span<'a>> {
+        self.touch_render(|t| t.eol_annotations.remove(&row))
+    }
+
+    /// Remove all annotations set by [`TextArea::set_eol_annotation`].
+    pub fn clear_eol_annotations(&mut self) {
+        self.touch_render(|t| t.eol_annotations.clear());
+    }
+
+    /// Mark lines as added, removed, or changed to view a diff in the textarea. Each item is a pair of a row and the
+    /// kind of its change. Marked lines are rendered with the background color of their kinds and signs such as `+`
+    /// and `-` are rendered in the gutter before the text (after line numbers if shown). Setting marks replaces the
+    /// previous ones. Like [`TextArea::set_eol_annotation`], marks are not moved by text edits.
+    ///
+    /// To make a read-only diff viewer like code review tools, stop passing key inputs which modify the text to the
+    /// textarea. The background colors can be customized by [`TextArea::set_diff_style`].
+    /// ```
+    /// use tui_textarea::{DiffKind, TextArea};
+    ///
+    /// let mut textarea = TextArea::from(["fn main() {", "    foo();", "    bar();", "}"]);
+    /// textarea.set_diff_marks(vec![(1, DiffKind::Removed), (2, DiffKind::Added)]);
+    /// assert_eq!(textarea.diff_mark(1), Some(DiffKind::Removed));
+    /// assert_eq!(textarea.diff_mark(0), None);
+    ///
+    /// assert_eq!(
+    ///     textarea.render_to_string(15, 4),
+    ///     [
+    ///         "  fn main() {  ",
+    ///         "-     foo();   ",
+    ///         "+     bar();   ",
+    ///         "  }            ",
+    ///     ]
+    ///     .join("\n"),
+    /// );
+    /// ```
+    pub fn set_diff_marks(&mut self, marks: impl IntoIterator<Item = (usize, DiffKind)>) {
+        self.touch_render(|t| t.diff_marks.set(marks));
+    }
+
+    /// Get the kind of the change marked on the line at the `row` by [`TextArea::set_diff_marks`].
+    pub fn diff_mark(&self, row: usize) -> Option<DiffKind> {
+        self.diff_marks.get(row)
+    }
+
+    /// Remove all marks set by [`TextArea::set_diff_marks`]. The gutter for the signs is no longer rendered.
+    pub fn clear_diff_marks(&mut self) {
+        self.touch_render(|t| t.diff_marks.clear());
+    }
+
+    /// Set the style of lines marked with the kind by [`TextArea::set_diff_marks`]. The style is applied to the whole
+    /// line including its sign in the gutter.
+    /// ```
+    /// use ratatui::style::{Color, Style};
+    /// use tui_textarea::{DiffKind, TextArea};
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// let style = Style::default().fg(Color::Green);
+    /// textarea.set_diff_style(DiffKind::Added, style);
+    /// assert_eq!(textarea.diff_style(DiffKind::Added), style);
+    /// ```
+    pub fn set_diff_style(&mut self, kind: DiffKind, style: Style) {
+        self.touch_render(|t| t.diff_marks.set_style(kind, style));
+    }
+
+    /// Get the style of lines marked with the kind by [`TextArea::set_diff_marks`].
+    pub fn diff_style(&self, kind: DiffKind) -> Style {
+        self.diff_marks.style(kind)
+    }
+
+    /// Move the cursor to the position specified by the [`CursorMove`] parameter. For each kind of cursor moves, see
+    /// the document of [`CursorMove`].
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["abc", "def"]);
+    ///
+    /// textarea.move_cursor(CursorMove::Forward);
+    /// assert_eq!(textarea.cursor(), (0, 1));
+    /// textarea.move_cursor(CursorMove::Down);
+    /// assert_eq!(textarea.cursor(), (1, 1));
+    /// ```
+    pub fn move_cursor(&mut self, m: CursorMove) {
+        self.track_cursor(|t| t.move_cursor_inner(m))
+    }
+
+    fn move_cursor_inner(&mut self, m: CursorMove) {
+        self.move_cursor_with_shift(m, self.selection_start.is_some());
+    }
+
+    /// Compute the range which the [`CursorMove`] repeated `count` times from the position `from` would cover without
+    /// moving the cursor. This is useful to implement operators such as `d{motion}` in modal key mappings. The returned
+    /// range is a pair of the start and end positions ordered in the text. The end position is exclusive so that the
+    /// range can be passed to [`TextArea::text_in_range`] and [`TextArea::replace_range`] as-is. `from` is clamped to
+    /// the text. When `count` is 0 or the motion cannot move, the range is empty.
+    ///
+    /// The range is adjusted for the kind of the motion like Vim:
+    ///
+    /// - Motions to the end of word ([`CursorMove::WordEnd`], [`CursorMove::WordEndBack`], and
+    ///   [`CursorMove::WordSpacingEndBack`]) include the character at the end of the range.
+    /// - Motions across lines ([`CursorMove::Up`], [`CursorMove::Down`], [`CursorMove::Top`], [`CursorMove::Bottom`],
+    ///   [`CursorMove::DownToFirstNonSpace`], [`CursorMove::UpToFirstNonSpace`], and
+    ///   [`CursorMove::LineStartNonSpaceOf`]) cover the whole lines including their newline. When the lines include the
+    ///   last line, the range starts from the end of the previous line instead so that replacing the range with an
+    ///   empty string removes the lines.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["abc def ghi", "jkl", "mno"]);
+    ///
+    /// let (start, end) = textarea.range_for_motion((0, 0), CursorMove::WordForward, 2);
+    /// assert_eq!((start, end), ((0, 0), (0, 8)));
+    /// assert_eq!(textarea.text_in_range(start, end), "abc def ");
+    ///
+    /// // The character at the end of word is included
+    /// let (start, end) = textarea.range_for_motion((0, 0), CursorMove::WordEnd, 1);
+    /// assert_eq!(textarea.text_in_range(start, end), "abc");
+    ///
+    /// // Backward motions return the ordered range and the whole lines are covered
+    /// assert_eq!(textarea.range_for_motion((1, 1), CursorMove::Up, 1), ((0, 0), (2, 0)));
+    ///
+    /// // The cursor is not moved
+    /// assert_eq!(textarea.cursor(), (0, 0));
+    /// ```
+    pub fn range_for_motion(
+        &self,
+        from: (usize, usize),
+        motion: CursorMove,
+        count: usize,
+    ) -> ((usize, usize), (usize, usize)) {
+        let row = cmp::min(from.0, self.lines.len() - 1);
+        let col = cmp::min(from.1, self.lines[row].chars().count());
+        let from = (row, col);
+        let mut to = from;
+        for _ in 0..count {
+            match motion.next_cursor(to, &self.lines, &self.viewport, &self.words) {
+                Some(next) if next != to => to = next,
+                _ => break,
+            }
+        }
+        if to == from {
+            return (from, from);
+        }
+
+        let (start, mut end) = (cmp::min(from, to), cmp::max(from, to));
+        let line_len = |row: usize| self.lines[row].chars().count();
+        if motion.linewise() {
+            return if end.0 + 1 < self.lines.len() {
+                ((start.0, 0), (end.0 + 1, 0))
+            } else if start.0 > 0 {
+                (
+                    (start.0 - 1, line_len(start.0 - 1)),
+                    (end.0, line_len(end.0)),
+                )
+            } else {
+                ((0, 0), (end.0, line_len(end.0)))
+            };
+        }
+        if motion.inclusive() {
+            end.1 = cmp::min(end.1 + 1, line_len(end.0));
+        }
+        (start, end)
+    }
+
+    /// Move the cursor back to the position before the last jump, like `Ctrl+O` in Vim. Moving the cursor to another
+    /// line with [`CursorMove::Top`], [`CursorMove::Bottom`], [`CursorMove::Jump`], [`CursorMove::JumpVirtual`],
+    /// [`CursorMove::LineStartNonSpaceOf`], and text search records the position before the move in the jumplist. Positions in the jumplist follow edits of
+    /// the text. This method returns `false` when there is no position to go back.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["a", "b", "c", "d"]);
+    /// textarea.move_cursor(CursorMove::Down);
+    /// textarea.move_cursor(CursorMove::Bottom);
+    /// textarea.move_cursor(CursorMove::Jump(2, 0));
+    ///
+    /// assert!(textarea.jump_back());
+    /// assert_eq!(textarea.cursor(), (3, 0));
+    /// assert!(textarea.jump_back());
+    /// assert_eq!(textarea.cursor(), (1, 0));
+    /// assert!(!textarea.jump_back());
+    ///
+    /// // Positions follow edits
+    /// textarea.move_cursor(CursorMove::Top);
+    /// textarea.insert_str("x\n");
+    /// assert!(textarea.jump_back());
+    /// assert_eq!(textarea.cursor(), (2, 0));
+    ///
+    /// assert!(textarea.jump_forward());
+    /// assert_eq!(textarea.cursor(), (1, 0));
+    /// ```
+    pub fn jump_back(&mut self) -> bool {
+        self.track_cursor(|t| {
+            let (row, col) = t.cursor;
+            let cursor = Pos::new(row, col, t.line_offset(row, col));
+            match t.history.jumps_mut().back(cursor) {
+                Some(pos) => {
+                    let pos = (pos.row, pos.col);
+                    t.cursor = t.clamp_position(pos);
+                    true
+                }
+                None => false,
+            }
+        })
+    }
+
+    /// Move the cursor forward to the position where [`TextArea::jump_back`] moved the cursor from, like `Ctrl+I` in
+    /// Vim. This method returns `false` when there is no position to go forward.
+    pub fn jump_forward(&mut self) -> bool {
+        self.track_cursor(|t| match t.history.jumps_mut().forward() {
+            Some(pos) => {
+                let pos = (pos.row, pos.col);
+                t.cursor = t.clamp_position(pos);
+                true
+            }
+            None => false,
+        })
+    }
+
+    /// Record the current cursor position in the jumplist. This is useful to make custom cursor moves such as 'go to
+    /// definition' return by [`TextArea::jump_back`]. Only one position is recorded for each line.
+    pub fn push_jump(&mut self) {
+        self.push_jump_at(self.cursor);
+    }
+
+    fn push_jump_at(&mut self, (row, col): (usize, usize)) {
+        let offset = self.line_offset(row, col);
+        self.history.jumps_mut().push(Pos::new(row, col, offset));
+    }
+
+    fn move_cursor_with_shift(&mut self, m: CursorMove, shift: bool) {
+        let prev = self.cursor;
+        self.move_cursor_without_jump(m, shift);
+        let jump = matches!(
+            m,
+            CursorMove::Top
+                | CursorMove::Bottom
+                | CursorMove::Jump(..)
+                | CursorMove::JumpVirtual(..)
+                | CursorMove::LineStartNonSpaceOf(..)
+        );
+        if jump && self.cursor.0 != prev.0 {
+            self.push_jump_at(prev);
+        }
+    }
+
+    // Move the cursor to the position like `CursorMove::Jump` without recording the position in the jumplist. The
+    // position is not limited to `u16` unlike `CursorMove::Jump`
+    #[cfg(feature = "vim")]
+    pub(crate) fn jump_keeping_jumps(&mut self, row: usize, col: usize) {
+        self.track_cursor(|t| {
+            let row = cmp::min(row, t.lines.len() - 1);
+            let col = cmp::min(col, t.lines[row].chars().count());
+            if t.selection_start.is_none() {
+                t.cancel_selection();
+            }
+            t.cursor = (row, col);
+        })
+    }
+
+    fn move_cursor_without_jump(&mut self, m: CursorMove, shift: bool) {
+        if self.virtual_edit && self.move_virtual_cursor(m, shift) {
+            return;
+        }
+        if let Some(cursor) = m.next_cursor(self.cursor, &self.lines, &self.viewport, &self.words) {
+            if shift {
+                if self.selection_start.is_none() {
+                    self.start_selection();
+                }
+            } else {
+                self.cancel_selection();
+            }
+            self.cursor = cursor;
+        }
+    }
+
+    // Move the cursor in virtual edit mode. Returns `true` when the move was handled.
+    fn move_virtual_cursor(&mut self, m: CursorMove, shift: bool) -> bool {
+        let (row, col) = self.cursor();
+        let len = self.lines[row].chars().count();
+        let virtual_col = match m {
+            CursorMove::JumpVirtual(_, c) => c as usize,
+            CursorMove::Up | CursorMove::Down => col,
+            CursorMove::Forward if col >= len => col + 1,
+            CursorMove::Back if col > len => col - 1,
+            _ => return false,
+        };
+        if shift {
+            if self.selection_start.is_none() {
+                self.start_selection();
+            }
+        } else {
+            self.cancel_selection();
+        }
+        if let CursorMove::Forward | CursorMove::Back = m {
+            self.cursor = (row, len);
+        } else if let Some(cursor) =
+            m.next_cursor((row, col), &self.lines, &self.viewport, &self.words)
+        {
+            self.cursor = cursor;
+        }
+        let (row, len) = (self.cursor.0, self.lines[self.cursor.0].chars().count());
+        self.virtual_cursor = (virtual_col > len).then(|| (row, virtual_col));
+        true
+    }
+
+    /// Enable or disable virtual edit mode. In virtual edit mode, the cursor can be placed beyond the end of the line
+    /// like `virtualedit=all` in Vim. This is useful for block selections and editing tables. The cursor is placed
+    /// there by [`CursorMove::JumpVirtual`], [`CursorMove::Up`], [`CursorMove::Down`] keeping the column, and
+    /// [`CursorMove::Forward`] at the end of line. When some text is inserted at the position, spaces are inserted to
+    /// fill the gap between the end of line and the cursor. Virtual edit mode is disabled by default.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["hello", "", "world"]);
+    /// textarea.set_virtual_edit(true);
+    /// assert!(textarea.virtual_edit());
+    ///
+    /// // The column is kept on the empty line
+    /// textarea.move_cursor(CursorMove::Jump(0, 3));
+    /// textarea.move_cursor(CursorMove::Down);
+    /// assert_eq!(textarea.cursor(), (1, 3));
+    ///
+    /// textarea.insert_str("|");
+    /// assert_eq!(textarea.lines(), ["hello", "   |", "world"]);
+    ///
+    /// // The padding and the insertion are undone at once
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["hello", "", "world"]);
+    /// ```
+    pub fn set_virtual_edit(&mut self, enabled: bool) {
+        self.virtual_edit = enabled;
+        if !enabled {
+            self.virtual_cursor = None;
+        }
+    }
+
+    /// Get if virtual edit mode is enabled or not. See [`TextArea::set_virtual_edit`].
+    pub fn virtual_edit(&self) -> bool {
+        self.virtual_edit
+    }
+
+    /// Undo the last modification. This method returns if the undo modified text contents or not in the textarea.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["abc def"]);
+    ///
+    /// textarea.delete_next_word();
+    /// assert_eq!(textarea.lines(), [" def"]);
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["abc def"]);
+    /// ```
+    pub fn undo(&mut self) -> bool {
+        self.track_cursor(|t| t.undo_inner())
+    }
+
+    fn undo_inner(&mut self) -> bool {
+        if let Some(cursor) = self.history.undo(&mut self.lines, &mut self.metrics) {
+            self.cancel_selection();
+            self.cursor = cursor;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Redo the last undo change. This method returns if the redo modified text contents or not in the textarea.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["abc def"]);
+    ///
+    /// textarea.delete_next_word();
+    /// assert_eq!(textarea.lines(), [" def"]);
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["abc def"]);
+    /// textarea.redo();
+    /// assert_eq!(textarea.lines(), [" def"]);
+    /// ```
+    pub fn redo(&mut self) -> bool {
+        self.track_cursor(|t| t.redo_inner())
+    }
+
+    fn redo_inner(&mut self) -> bool {
+        if let Some(cursor) = self.history.redo(&mut self.lines, &mut self.metrics) {
+            self.cancel_selection();
+            self.cursor = cursor;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Undo all modifications made after the `time` to go back to the text at the time, like `:earlier` command of
+    /// Vim. Edits which were recorded as one history entry are undone together. This method returns if the text was
+    /// modified or not. On targets without clock such as `wasm32-unknown-unknown`, modifications are not timestamped
+    /// and this method does nothing.
+    /// ```
+    /// use std::time::Instant;
+    /// # use std::thread::sleep;
+    /// # use std::time::Duration;
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// textarea.insert_str("hello");
+    /// # sleep(Duration::from_millis(1));
+    /// let time = Instant::now();
+    /// # sleep(Duration::from_millis(1));
+    /// textarea.insert_str(", world");
+    /// textarea.insert_char('!');
+    ///
+    /// textarea.undo_until(time);
+    /// assert_eq!(textarea.lines(), ["hello"]);
+    ///
+    /// textarea.redo_until(Instant::now());
+    /// assert_eq!(textarea.lines(), ["hello, world!"]);
+    /// ```
+    pub fn undo_until(&mut self, time: Instant) -> bool {
+        self.track_cursor(|t| {
+            if let Some(cursor) = t.history.undo_until(time, &mut t.lines, &mut t.metrics) {
+                t.cancel_selection();
+                t.cursor = cursor;
+                true
+            } else {
+                false
+            }
+        })
+    }
+
+    /// Redo the undone modifications made until the `time`, like `:later` command of Vim. This is the reverse
+    /// operation of [`TextArea::undo_until`]. This method returns if the text was modified or not. Like
+    /// [`TextArea::undo_until`], this method does nothing on targets without clock.
+    pub fn redo_until(&mut self, time: Instant) -> bool {
+        self.track_cursor(|t| {
+            if let Some(cursor) = t.history.redo_until(time, &mut t.lines, &mut t.metrics) {
+                t.cancel_selection();
+                t.cursor = cursor;
+                true
+            } else {
+                false
+            }
+        })
+    }
+
+    fn mask_reveal_range(&self, line: &str, row: usize) -> Option<(usize, usize)> {
+        match self.mask_policy {
+            MaskPolicy::All => None,
+            MaskPolicy::AllExceptLast(n) => {
+                let len = line.chars().count();
+                let start = line
+                    .char_indices()
+                    .nth(len.saturating_sub(n))
+                    .map(|(i, _)| i)
+                    .unwrap_or(line.len());
+                Some((start, line.len()))
+            }
+            MaskPolicy::RevealWhile(duration) => {
+                let (at, (r, c)) = self.last_typed?;
+                if r != row || self.cursor != (r, c + 1) || at.elapsed() >= duration {
+                    return None;
+                }
+                let (start, ch) = line.char_indices().nth(c)?;
+                Some((start, start + ch.len_utf8()))
+            }
+        }
+    }
+
+    // Modify states affecting the rendering, such as styles, highlights, and callbacks. Such states must be modified only
+    // through this method so that the next frame is not restored from the render cache
+    fn touch_render<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        self.render_generation = self.render_generation.wrapping_add(1);
+        f(self)
+    }
+
+    // Key of the rendered frame. It changes when anything affecting the rendering is changed. The text is identified by
+    // the number of edits applied to it and other states such as styles, highlights, and callbacks are identified by the
+    // generation bumped by their setters, so computing the key depends on neither the size of the document nor the
+    // number of the states. Only the cursor, the selections, and the reveal state of masked text, which change without
+    // setters, are hashed directly.
+    pub(crate) fn render_key(&self, area: Rect, top_row: u16, top_col: u16) -> u64 {
+        let revealing = match (self.mask_policy, self.last_typed) {
+            (MaskPolicy::RevealWhile(duration), Some((at, _))) => at.elapsed() < duration,
+            _ => false,
+        };
+        let selections =
+            self.selections_in_rows(top_row as usize, top_row as usize + area.height as usize);
+        let mut hasher = DefaultHasher::new();
+        (area, top_row, top_col).hash(&mut hasher);
+        (self.metrics.edits(), self.render_generation).hash(&mut hasher);
+        (
+            self.cursor,
+            self.virtual_cursor,
+            self.selection_start,
+            selections,
+        )
+            .hash(&mut hasher);
+        (revealing, self.last_typed.map(|(_, pos)| pos)).hash(&mut hasher);
+        hasher.finish()
+    }
+
+    // Secondary selections overlapping the rows in `top..bottom`. They are sorted by their positions and don't overlap
+    // each other so the range can be found by binary search without walking all selections.
+    pub(crate) fn selections_in_rows(&self, top: usize, bottom: usize) -> &[(Pos, Pos)] {
+        if self.selection_start.is_none() {
+            return &[];
+        }
+        let selections = self.history.selections();
+        let start = selections.partition_point(|(_, e)| e.row < top);
+        let len = selections[start..].partition_point(|(s, _)| s.row < bottom);
+        &selections[start..start + len]
+    }
+
+    // Start rendering the rows in `top..bottom`. `width` is the width of the lines including the horizontally scrolled
+    // part
+    pub(crate) fn render_pass(
+        &self,
+        top: usize,
+        bottom: usize,
+        width: usize,
+        view_width: usize,
+    ) -> RenderPass<'_> {
+        RenderPass {
+            lnum_len: self.line_number_len(),
+            width,
+            view_width,
+            selection: self.selection_positions(),
+            selections: self.selections_in_rows(top, bottom),
+            scratch: Scratch::default(),
+        }
+    }
+
+    pub(crate) fn line_spans<'b>(
+        &'b self,
+        line: &'b str,
+        row: usize,
+        pass: &mut RenderPass<'_>,
+    ) -> Line<'b> {
+        let (lnum_len, width) = (pass.lnum_len, pass.width);
+        let (cursor_style, cursor_line_style, select_style) = self.rendered_styles();
+        let mut hl = LineHighlighter::new(
+            line,
+            cursor_style,
+            self.indent.width,
+            self.mask,
+            select_style,
+        );
+        hl.reuse(&mut pass.scratch);
+
+        if let Some(style) = self.line_number_style {
+            hl.line_number(row, lnum_len, style);
+        }
+        if !self.diff_marks.is_empty() {
+            let mark = self.diff_marks.get(row);
+            hl.diff_mark(mark.map(|k| (k.sign(), self.diff_marks.style(k))));
+            if let Some(kind) = mark {
+                if self.alignment == Alignment::Left {
+                    hl.fill(width, self.diff_marks.style(kind));
+                }
+            }
+        }
+
+        let padding = self.row_padding(row, pass.view_width);
+        if padding > 0 {
+            hl.padding(padding);
+        }
+
+        hl.merge_styles(self.merge_styles);
+        if let Some(span) = self.eol_annotations.get(&row) {
+            hl.annotation(span.clone());
+        }
+        if !self.cursor_rendered() {
+            hl.hide_cursor();
+        }
+        hl.char_width(&self.char_width);
+        hl.show_whitespace(self.whitespace);
+        hl.control_chars(self.control_chars, self.control_char_style);
+        if let Some(style) = self.trailing_whitespace_style {
+            hl.trailing_whitespace(style);
+        }
+        if let Some(style) = self.link_style {
+            for (start, end) in Links::new(line) {
+                hl.link(start, end, style);
+            }
+        }
+
+        for h in &self.highlights {
+            if h.start.0 <= row && row <= h.end.0 {
+                let start = if h.start.0 == row {
+                    self.line_offset(row, h.start.1)
+                } else {
+                    0
+                };
+                let end = if h.end.0 == row {
+                    self.line_offset(row, h.end.1)
+                } else {
+                    line.len()
+                };
+                hl.custom(start, end, h.style, h.priority);
+            }
+        }
+
+        let cursor_offset = (row == self.cursor.0).then(|| self.line_offset(row, self.cursor.1));
+        for (start, end, replacement) in self.history.conceals().in_row(row, line.len()) {
+            // Reveal the concealed text under the cursor
+            if !matches!(cursor_offset, Some(o) if start <= o && o < end) {
+                hl.conceal(start, end, replacement);
+            }
+        }
+
+        if self.mask.is_some() {
+            if let Some((start, end)) = self.mask_reveal_range(line, row) {
+                hl.mask_reveal(start, end);
+            }
+        }
+
+        if row == self.cursor.0 {
+            if let Some((text, cursor)) = &self.preedit {
+                hl.preedit(
+                    self.cursor.1,
+                    text,
+                    *cursor,
+                    self.preedit_style,
+                    cursor_line_style,
+                );
+            } else if let Some((hint, style)) = &self.inline_hint {
+                // Ghost text is rendered like composing text with the cursor on its head
+                hl.preedit(self.cursor.1, hint, 0, *style, cursor_line_style);
+            } else {
+                hl.cursor_line(self.cursor.1, cursor_line_style);
+                hl.cursor_padding(self.virtual_padding());
+            }
+            if self.cursor_line_fullwidth && self.alignment == Alignment::Left {
+                hl.fill(width, cursor_line_style);
+            }
+        }
+
+        #[cfg(feature = "search")]
+        if let Some(matches) = self.search.matches(line) {
+            hl.search(matches, self.search.style, self.search.current_match(row));
+        }
+
+        if let Some((start, end)) = &pass.selection {
+            hl.selection(row, start.row, start.offset, end.row, end.offset);
+        }
+        // Only secondary selections overlapping the row are checked
+        let start = pass.selections.partition_point(|(_, e)| e.row < row);
+        let len = pass.selections[start..].partition_point(|(s, _)| s.row <= row);
+        for (start, end) in &pass.selections[start..start + len] {
+            hl.selection(row, start.row, start.offset, end.row, end.offset);
+        }
+
+        let line = hl.into_spans_reusing(&mut pass.scratch);
+        match &self.line_postprocessor {
+            Some(f) => (f.0)(row, line),
+            None => line,
+        }
+    }
+
+    /// Build a ratatui (or tui-rs) widget to render the current state of the textarea. The widget instance returned
+    /// from this method can be rendered with [`ratatui::Frame::render_widget`].
+    ///
+    /// This method was deprecated at v0.5.3 and is no longer necessary. Instead you can directly pass `&TextArea`
+    /// reference to the `Frame::render_widget` method call.
+    /// ```no_run
+    /// # use ratatui::layout::Rect;
+    /// # use ratatui::Terminal;
+    /// # use ratatui::widgets::Widget as _;
+    /// # use ratatui::backend::CrosstermBackend;
+    /// # use tui_textarea::TextArea;
+    /// #
+    /// # let backend = CrosstermBackend::new(std::io::stdout());
+    /// # let mut term = Terminal::new(backend).unwrap();
+    /// # let textarea = TextArea::default();
+    /// #
+    /// # #[allow(deprecated)]
+    /// # term.draw(|f| {
+    /// #   let rect = Rect {
+    /// #       x: 0,
+    /// #       y: 0,
+    /// #       width: 24,
+    /// #       height: 8,
+    /// #   };
+    /// // v0.5.2 or earlier
+    /// f.render_widget(textarea.widget(), rect);
+    ///
+    /// // v0.5.3 or later
+    /// f.render_widget(&textarea, rect);
+    /// # }).unwrap();
+    /// ```
+    #[deprecated(
+        since = "0.5.3",
+        note = "calling this method is no longer necessary on rendering a textarea. pass &TextArea reference to `Frame::render_widget` method call directly"
+    )]
+    pub fn widget(&'a self) -> impl Widget + 'a {
+        self
+    }
+
+    /// Set the style of textarea. By default, textarea is not styled.
+    /// ```
+    /// use ratatui::style::{Style, Color};
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    /// let style = Style::default().fg(Color::Red);
+    /// textarea.set_style(style);
+    /// assert_eq!(textarea.style(), style);
+    /// ```
+    pub fn set_style(&mut self, style: Style) {
+        self.touch_render(|t| t.style = style);
+    }
+
+    /// Get the current style of textarea.
+    pub fn style(&self) -> Style {
+        self.style
+    }
+
+    /// Set the block of textarea. By default, no block is set.
+    /// ```
+    /// use tui_textarea::TextArea;
+    /// use ratatui::widgets::{Block, Borders};
+    ///
+    /// let mut textarea = TextArea::default();
+    /// let block = Block::default().borders(Borders::ALL).title("Block Title");
+    /// textarea.set_block(block);
+    /// assert!(textarea.block().is_some());
+    /// ```
+    pub fn set_block(&mut self, block: Block<'a>) {
+        self.touch_render(|t| t.block = Some(block));
+    }
+
+    /// Remove the block of textarea which was set by [`TextArea::set_block`].
+    /// ```
+    /// use tui_textarea::TextArea;
+    /// use ratatui::widgets::{Block, Borders};
+    ///
+    /// let mut textarea = TextArea::default();
+    /// let block = Block::default().borders(Borders::ALL).title("Block Title");
+    /// textarea.set_block(block);
+    /// textarea.remove_block();
+    /// assert!(textarea.block().is_none());
+    /// ```
+    pub fn remove_block(&mut self) {
+        self.touch_render(|t| t.block = None);
+    }
+
+    /// Get the block of textarea if exists.
+    pub fn block<'s>(&'s self) -> Option<&'s Block<'a>> {
+        self.block.as_ref()
+    }
+
+    /// Set the length of tab character. Setting 0 disables tab inputs. This is a shortcut to set the width of
+    /// [`IndentPolicy`] by [`TextArea::set_indent_policy`].
+    /// ```
+    /// use tui_textarea::{TextArea, Input, Key};
+    ///
+    /// let mut textarea = TextArea::default();
+    /// let tab_input = Input { key: Key::Tab, ctrl: false, alt: false, shift: false };
+    ///
+    /// textarea.set_tab_length(8);
+    /// textarea.input(tab_input.clone());
+    /// assert_eq!(textarea.lines(), ["        "]);
+    ///
+    /// textarea.set_tab_length(2);
+    /// textarea.input(tab_input);
+    /// assert_eq!(textarea.lines(), ["          "]);
+    /// ```
+    pub fn set_tab_length(&mut self, len: u8) {
+        self.touch_render(|t| t.indent.width = len as usize);
+    }
+
+    /// Get how many spaces are used for representing tab character. The default value is 4. When the width of
+    /// [`IndentPolicy`] is larger than [`u8::MAX`], this method returns [`u8::MAX`].
+    pub fn tab_length(&self) -> u8 {
+        cmp::min(self.indent.width, u8::MAX as usize) as u8
+    }
+
+    /// Set if a hard tab is used or not for indent. When `true` is set, typing a tab key inserts a hard tab instead of
+    /// spaces. By default, hard tab is disabled.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// textarea.set_hard_tab_indent(true);
+    /// textarea.insert_tab();
+    /// assert_eq!(textarea.lines(), ["\t"]);
+    /// ```
+    pub fn set_hard_tab_indent(&mut self, enabled: bool) {
+        self.indent.hard_tab = enabled;
+    }
+
+    /// Get if a hard tab is used for indent or not.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// assert!(!textarea.hard_tab_indent());
+    /// textarea.set_hard_tab_indent(true);
+    /// assert!(textarea.hard_tab_indent());
+    /// ```
+    pub fn hard_tab_indent(&self) -> bool {
+        self.indent.hard_tab
+    }
+
+    /// Get a string for indent. It consists of spaces by default. When hard tab is enabled, it is a tab character.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// assert_eq!(textarea.indent(), "    ");
+    /// textarea.set_tab_length(2);
+    /// assert_eq!(textarea.indent(), "  ");
+    /// textarea.set_hard_tab_indent(true);
+    /// assert_eq!(textarea.indent(), "\t");
+    /// ```
+    pub fn indent(&self) -> Cow<'static, str> {
+        self.indent.indent()
+    }
+
+    /// Set the policy of indentation. It decides the width of tab stops, whether a tab input inserts a hard tab or
+    /// spaces, and whether the spaces align the cursor to the next tab stop. Unlike [`TextArea::set_tab_length`], the
+    /// width can be larger than 255.
+    /// ```
+    /// use tui_textarea::{IndentPolicy, TextArea};
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// textarea.set_indent_policy(IndentPolicy::hard_tab(8));
+    /// assert_eq!(textarea.indent(), "\t");
+    /// assert_eq!(textarea.tab_length(), 8);
+    ///
+    /// let mut policy = IndentPolicy::spaces(3);
+    /// policy.keep_alignment = false;
+    /// textarea.set_indent_policy(policy);
+    /// textarea.insert_str("a");
+    /// textarea.insert_tab();
+    /// assert_eq!(textarea.lines(), ["a   "]);
+    /// ```
+    pub fn set_indent_policy(&mut self, policy: IndentPolicy) {
+        self.touch_render(|t| t.indent = policy);
+    }
+
+    /// Get the policy of indentation. See [`TextArea::set_indent_policy`].
+    /// ```
+    /// use tui_textarea::{IndentPolicy, TextArea};
+    ///
+    /// let mut textarea = TextArea::default();
+    /// assert_eq!(textarea.indent_policy(), IndentPolicy::spaces(4));
+    ///
+    /// textarea.set_hard_tab_indent(true);
+    /// assert_eq!(textarea.indent_policy(), IndentPolicy::hard_tab(4));
+    /// ```
+    pub fn indent_policy(&self) -> IndentPolicy {
+        self.indent
+    }
+
+    /// Set the measurement of the display width of characters. The measurement is used to expand tabs to the tab
+    /// stops, to mask text, to align spaces inserted by [`TextArea::insert_tab`], and to place the completion popup.
+    /// The default measurement is [`UnicodeWidth`](crate::UnicodeWidth). This is useful when the terminal renders
+    /// some characters in different widths, such as characters of ambiguous width in CJK context. See [`CharWidth`]
+    /// for more details. When the measurement depends on states outside of it, call
+    /// [`TextArea::invalidate_render_cache`] after the states are changed.
+    /// ```
+    /// use tui_textarea::{CursorMove, TextArea, UnicodeWidth};
+    ///
+    /// let mut textarea = TextArea::from(["○"]);
+    /// textarea.move_cursor(CursorMove::End);
+    ///
+    /// // `○` is rendered in 2 cells in CJK context so a tab after it is expanded to 2 cells
+    /// textarea.set_char_width(UnicodeWidth::cjk());
+    /// textarea.insert_tab();
+    /// assert_eq!(textarea.lines(), ["○  "]);
+    ///
+    /// // Functions can also measure the width
+    /// textarea.set_char_width(|c: char| if c == '○' { 3 } else { 1 });
+    /// textarea.insert_tab();
+    /// assert_eq!(textarea.lines(), ["○     "]);
+    /// ```
+    pub fn set_char_width(&mut self, measure: impl CharWidth + 'static) {
+        self.touch_render(|t| t.char_width.measure = Arc::new(measure));
+    }
+
+    /// Get the measurement of the display width of characters set by [`TextArea::set_char_width`].
+    pub fn char_width(&self) -> &dyn CharWidth {
+        &*self.char_width.measure
+    }
+
+    /// Override the display widths of the characters in the ranges. Each item is a pair of a range of characters and
+    /// their width in cells. The overrides take precedence over the measurement set by [`TextArea::set_char_width`]
+    /// and they are applied to all computations of the display width. When ranges overlap, the later one is used.
+    /// Setting overrides replaces the previous ones and an empty iterator removes them.
+    ///
+    /// Terminals disagree on the widths of some characters such as emojis. This is useful to correct the drift of the
+    /// cursor on specific characters reported by users without implementing [`CharWidth`].
+    /// ```
+    /// use tui_textarea::{CursorMove, TextArea};
+    ///
+    /// let mut textarea = TextArea::from(["☺"]);
+    /// textarea.move_cursor(CursorMove::End);
+    ///
+    /// // The terminal renders the emoji range in 2 cells
+    /// textarea.set_width_overrides(['\u{2600}'..='\u{26FF}', '\u{1F300}'..='\u{1FAFF}'].map(|r| (r, 2)));
+    /// textarea.insert_tab();
+    /// assert_eq!(textarea.lines(), ["☺  "]);
+    /// assert_eq!(textarea.width_overrides().len(), 2);
+    ///
+    /// textarea.set_width_overrides([]);
+    /// assert!(textarea.width_overrides().is_empty());
+    /// ```
+    pub fn set_width_overrides(
+        &mut self,
+        overrides: impl IntoIterator<Item = (RangeInclusive<char>, usize)>,
+    ) {
+        self.touch_render(|t| t.char_width.overrides = Arc::new(overrides.into_iter().collect()));
+    }
+
+    /// Get the overrides of the display widths set by [`TextArea::set_width_overrides`].
+    pub fn width_overrides(&self) -> &[(RangeInclusive<char>, usize)] {
+        &self.char_width.overrides
+    }
+
+    /// Set how many modifications are remembered for undo/redo. Setting 0 disables undo/redo.
+    pub fn set_max_histories(&mut self, max: usize) {
+        self.history.set_max_items(max);
+    }
+
+    /// Clear the undo/redo history. The text and whether it is modified since the last save are kept. This is useful
+    /// after loading a new document into the textarea so that the loading cannot be undone.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    /// textarea.insert_str("hello");
+    ///
+    /// textarea.clear_history();
+    /// assert!(!textarea.undo());
+    /// assert_eq!(textarea.lines(), ["hello"]);
+    /// ```
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+    }
+
+    /// Create a snapshot of the text, the cursor, and the text selection. Other states such as styles and the
+    /// history are not included. The text is copied into the snapshot so this takes time proportional to the size of
+    /// the text. The snapshot can be restored by [`TextArea::restore`]. See [`Snapshot`] for more details.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["hello"]);
+    /// textarea.move_cursor(CursorMove::End);
+    ///
+    /// let snapshot = textarea.snapshot();
+    /// assert_eq!(snapshot.lines(), ["hello"]);
+    /// assert_eq!(snapshot.cursor(), (0, 5));
+    /// assert_eq!(snapshot.selection_start(), None);
+    /// ```
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            lines: Arc::new(self.lines.clone()),
+            cursor: self.cursor,
+            selection_start: self.selection_start,
+        }
+    }
+
+    /// Restore the text, the cursor, and the text selection from the snapshot created by [`TextArea::snapshot`]. Like
+    /// [`TextArea::set_lines`], only the range which differs from the current text is edited and the edit is recorded
+    /// as one history entry so the restoration can be undone. This method returns if the text was modified or not.
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["hello"]);
+    /// let snapshot = textarea.snapshot();
+    ///
+    /// // Start editing and cancel it
+    /// textarea.move_cursor(CursorMove::End);
+    /// textarea.insert_str(", world");
+    /// assert!(textarea.restore(snapshot));
+    /// assert_eq!(textarea.lines(), ["hello"]);
+    /// assert_eq!(textarea.cursor(), (0, 0));
+    ///
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["hello, world"]);
+    /// ```
+    pub fn restore(&mut self, snapshot: Snapshot) -> bool {
+        let Snapshot {
+            lines,
+            cursor,
+            selection_start,
+        } = snapshot;
+        let lines = Arc::try_unwrap(lines).unwrap_or_else(|lines| (*lines).clone());
+        self.track_cursor(|t| {
+            let modified = t.set_lines(lines);
+            t.cursor = t.clamp_position(cursor);
+            t.virtual_cursor = None;
+            t.selection_start = selection_start.map(|p| t.clamp_position(p));
+            t.history.set_selections(vec![]);
+            modified
+        })
+    }
+
+    /// Get how many modifications are remembered for undo/redo. The default value is 50.
+    pub fn max_histories(&self) -> usize {
+        self.history.max_items()
+    }
+
+    /// Set the max size in bytes of the memory used by the undo/redo history. The size is approximated with the size
+    /// of the text inserted or deleted by each modification. When the history exceeds the size, the oldest
+    /// modifications are removed from the history. This is useful to avoid consuming much memory when pasting or
+    /// cutting huge text, since [`TextArea::set_max_histories`] limits only the number of modifications. A
+    /// modification larger than the max size is not remembered at all. The default value is [`usize::MAX`], which
+    /// means no limit.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    /// textarea.set_max_history_bytes(1024);
+    /// assert_eq!(textarea.max_history_bytes(), 1024);
+    ///
+    /// textarea.insert_str("small");
+    /// textarea.insert_str("huge".repeat(1024));
+    /// assert!(!textarea.undo()); // Both are removed from the history
+    ///
+    /// textarea.insert_str("small");
+    /// assert!(textarea.undo());
+    /// ```
+    pub fn set_max_history_bytes(&mut self, max: usize) {
+        self.history.set_max_bytes(max);
+    }
+
+    /// Get the max size in bytes of the memory used by the undo/redo history set by
+    /// [`TextArea::set_max_history_bytes`]. The default value is [`usize::MAX`].
+    pub fn max_history_bytes(&self) -> usize {
+        self.history.max_bytes()
+    }
+
+    /// Set the interval to merge modifications into one undo/redo history entry. When a modification is made within
+    /// the interval after the previous one, both are undone or redone together. For example, the characters typed
+    /// without pausing longer than the interval are undone at once. `None` disables the merging, which is the
+    /// default. Modifications are not merged into the entry at the save point or into the undone entries. On targets
+    /// without clock such as `wasm32-unknown-unknown`, modifications are never merged.
+    /// ```
+    /// use std::time::Duration;
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    /// textarea.set_history_merge_interval(Some(Duration::from_secs(60)));
+    /// assert_eq!(textarea.history_merge_interval(), Some(Duration::from_secs(60)));
+    ///
+    /// textarea.insert_str("hello");
+    /// textarea.insert_char(' ');
+    /// textarea.insert_str("world");
+    ///
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), [""]);
+    /// ```
+    pub fn set_history_merge_interval(&mut self, interval: Option<Duration>) {
+        self.history.set_merge_interval(interval);
+    }
+
+    /// Get the interval to merge modifications set by [`TextArea::set_history_merge_interval`].
+    pub fn history_merge_interval(&self) -> Option<Duration> {
+        self.history.merge_interval()
+    }
+
+    /// Run the function with excluding edits in it from the undo/redo history. This is useful for machine-generated
+    /// edits such as appending logs and automatic fixes which should not be undone by users. The edits in the undo
+    /// history are adjusted so that undoing them does not revert the excluded edits, in the same manner as
+    /// [`TextArea::apply_remote_edit`]. When an edit in the history overlaps with an excluded edit, the edit and older
+    /// ones are removed from the history. The redo history is cleared. The excluded edits are still recorded by
+    /// [`TextArea::drain_edits`].
+    /// ```
+    /// use tui_textarea::{TextArea, CursorMove};
+    ///
+    /// let mut textarea = TextArea::from(["hello"]);
+    /// textarea.move_cursor(CursorMove::End);
+    /// textarea.insert_str(", world");
+    ///
+    /// textarea.with_history_disabled(|t| {
+    ///     t.append_lines(["log 1", "log 2"]);
+    ///     t.replace_range((0, 0), (0, 1), "H");
+    /// });
+    /// assert_eq!(textarea.lines(), ["Hello, world", "log 1", "log 2"]);
+    ///
+    /// // Undo reverts only the edit by the user
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["Hello", "log 1", "log 2"]);
+    /// assert!(!textarea.undo());
+    /// ```
+    pub fn with_history_disabled<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        let recording = self.history.set_recording(false);
+        let ret = f(self);
+        self.history.set_recording(recording);
+        ret
+    }
+
+    /// Set the style of line at cursor. By default, the cursor line is styled with underline. To stop styling the
+    /// cursor line, set the default style.
+    /// ```
+    /// use ratatui::style::{Style, Color};
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// let style = Style::default().fg(Color::Red);
+    /// textarea.set_cursor_line_style(style);
+    /// assert_eq!(textarea.cursor_line_style(), style);
+    ///
+    /// // Disable cursor line style
+    /// textarea.set_cursor_line_style(Style::default());
+    /// ```
+    pub fn set_cursor_line_style(&mut self, style: Style) {
+        self.touch_render(|t| t.cursor_line_style = style);
+    }
+
+    /// Get the style of cursor line. By default it is styled with underline.
+    pub fn cursor_line_style(&self) -> Style {
+        self.cursor_line_style
+    }
+
+    /// Set if the cursor line style is applied to the whole width of the textarea. By default, the style is applied
+    /// only to the characters in the cursor line. Enabling this is useful when the cursor line is highlighted with
+    /// background color, like current line highlight of most editors. This setting only takes effect with
+    /// [`Alignment::Left`].
+    /// ```
+    /// use ratatui::style::{Style, Color};
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// textarea.set_cursor_line_style(Style::default().bg(Color::DarkGray));
+    /// textarea.set_cursor_line_fullwidth(true);
+    /// assert!(textarea.cursor_line_fullwidth());
+    /// ```
+    pub fn set_cursor_line_fullwidth(&mut self, enabled: bool) {
+        self.touch_render(|t| t.cursor_line_fullwidth = enabled);
+    }
+
+    /// Get if the cursor line style is applied to the whole width of the textarea.
+    pub fn cursor_line_fullwidth(&self) -> bool {
+        self.cursor_line_fullwidth
+    }
+
+    /// Set the style of line number. By setting the style with this method, line numbers are drawn in textarea, meant
+    /// that line numbers are disabled by default. If you want to show line numbers but don't want to style them, set
+    /// the default style.
+    /// ```
+    /// use ratatui::style::{Style, Color};
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// // Show line numbers in dark gray background
+    /// let style = Style::default().bg(Color::DarkGray);
+    /// textarea.set_line_number_style(style);
+    /// assert_eq!(textarea.line_number_style(), Some(style));
+    /// ```
+    pub fn set_line_number_style(&mut self, style: Style) {
+        self.touch_render(|t| t.line_number_style = Some(style));
+    }
+
+    /// Remove the style of line number which was set by [`TextArea::set_line_number_style`]. After calling this
+    /// method, Line numbers will no longer be shown.
+    /// ```
+    /// use ratatui::style::{Style, Color};
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// textarea.set_line_number_style(Style::default().bg(Color::DarkGray));
+    /// textarea.remove_line_number();
+    /// assert_eq!(textarea.line_number_style(), None);
+    /// ```
+    pub fn remove_line_number(&mut self) {
+        self.touch_render(|t| t.line_number_style = None);
+    }
+
+    /// Get the style of line number if set.
+    pub fn line_number_style(&self) -> Option<Style> {
+        self.line_number_style
+    }
+
+    /// Fix the number of digits of line numbers. By default, the width of line numbers grows with the number of lines,
+    /// which shifts the text horizontally when crossing 9 to 10 lines or 99 to 100 lines. Fixing the width keeps the
+    /// layout stable. Line numbers shorter than the width are padded with spaces on the left and longer ones are
+    /// truncated to their last digits. The width is at least 1. Passing `None` restores the default width.
+    /// ```
+    /// use ratatui::style::Style;
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea: TextArea = (0..12).map(|i| i.to_string()).collect();
+    /// textarea.set_line_number_style(Style::default());
+    /// assert_eq!(textarea.render_to_string(8, 1), "  1 0   ");
+    ///
+    /// textarea.set_line_number_width(Some(3));
+    /// assert_eq!(textarea.line_number_width(), Some(3));
+    /// assert_eq!(textarea.render_to_string(8, 1), "   1 0  ");
+    ///
+    /// // Line numbers wider than the width are truncated
+    /// textarea.set_line_number_width(Some(1));
+    /// textarea.move_cursor(tui_textarea::CursorMove::Bottom);
+    /// assert_eq!(textarea.render_to_string(8, 1), " 2 11   ");
+    /// ```
+    pub fn set_line_number_width(&mut self, width: Option<u8>) {
+        self.touch_render(|t| t.line_number_width = width.map(|w| cmp::max(w, 1)));
+    }
+
+    /// Get the number of digits of line numbers set by [`TextArea::set_line_number_width`]. `None` means the width
+    /// grows with the number of lines.
+    pub fn line_number_width(&self) -> Option<u8> {
+        self.line_number_width
+    }
+
+    /// Set which whitespaces are rendered visibly. Spaces are rendered as `·` and tabs are rendered as `→` padded to
+    /// the tab stop. This only changes how the text is rendered. The text and the cursor position are not affected.
+    /// The default value is [`WhitespaceStyle::Hidden`].
+    /// ```
+    /// use tui_textarea::{TextArea, WhitespaceStyle};
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// textarea.set_show_whitespace(WhitespaceStyle::Trailing);
+    /// assert_eq!(textarea.show_whitespace(), WhitespaceStyle::Trailing);
+    /// ```
+    pub fn set_show_whitespace(&mut self, whitespace: WhitespaceStyle) {
+        self.touch_render(|t| t.whitespace = whitespace);
+    }
+
+    /// Get which whitespaces are rendered visibly.
+    pub fn show_whitespace(&self) -> WhitespaceStyle {
+        self.whitespace
+    }
+
+    /// Set the style of whitespaces at the end of lines. By setting the style with this method, trailing whitespaces
+    /// are highlighted. It is disabled by default.
+    /// ```
+    /// use ratatui::style::{Style, Color};
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// // Highlight trailing whitespaces in red background
+    /// let style = Style::default().bg(Color::Red);
+    /// textarea.set_trailing_whitespace_style(style);
+    /// assert_eq!(textarea.trailing_whitespace_style(), Some(style));
+    /// ```
+    pub fn set_trailing_whitespace_style(&mut self, style: Style) {
+        self.touch_render(|t| t.trailing_whitespace_style = Some(style));
+    }
+
+    /// Remove the style of trailing whitespaces which was set by [`TextArea::set_trailing_whitespace_style`]. After
+    /// calling this method, trailing whitespaces will no longer be highlighted.
+    /// ```
+    /// use ratatui::style::{Style, Color};
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// textarea.set_trailing_whitespace_style(Style::default().bg(Color::Red));
+    /// textarea.remove_trailing_whitespace_style();
+    /// assert_eq!(textarea.trailing_whitespace_style(), None);
+    /// ```
+    pub fn remove_trailing_whitespace_style(&mut self) {
+        self.touch_render(|t| t.trailing_whitespace_style = None);
+    }
+
+    /// Get the style of trailing whitespaces if set.
+    pub fn trailing_whitespace_style(&self) -> Option<Style> {
+        self.trailing_whitespace_style
+    }
+
+    /// Set how control characters such as `\x1b` of ANSI escape sequences are rendered. The default value is
+    /// [`ControlCharStyle::Caret`]. See [`ControlCharStyle`] for more details.
+    /// ```
+    /// use tui_textarea::{TextArea, ControlCharStyle};
+    ///
+    /// let mut textarea = TextArea::from(["\x1b[1mbold"]);
+    /// assert_eq!(textarea.render_to_string(10, 1), "^[[1mbold ");
+    ///
+    /// textarea.set_show_control_chars(ControlCharStyle::Replacement);
+    /// assert_eq!(textarea.show_control_chars(), ControlCharStyle::Replacement);
+    /// assert_eq!(textarea.render_to_string(10, 1), "\u{FFFD}[1mbold  ");
+    /// ```
+    pub fn set_show_control_chars(&mut self, control: ControlCharStyle) {
+        self.touch_render(|t| t.control_chars = control);
+    }
+
+    /// Get how control characters are rendered.
+    pub fn show_control_chars(&self) -> ControlCharStyle {
+        self.control_chars
+    }
+
+    /// Set the style of control characters rendered by [`TextArea::set_show_control_chars`]. The default style is
+    /// blue foreground.
+    /// ```
+    /// use ratatui::style::{Style, Color};
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// let style = Style::default().fg(Color::Red);
+    /// textarea.set_control_char_style(style);
+    /// assert_eq!(textarea.control_char_style(), style);
+    /// ```
+    pub fn set_control_char_style(&mut self, style: Style) {
+        self.touch_render(|t| t.control_char_style = style);
+    }
+
+    /// Get the style of control characters.
+    pub fn control_char_style(&self) -> Style {
+        self.control_char_style
+    }
+
+    /// Set the style of links. By setting the style with this method, URLs in the text such as `https://example.com`
+    /// are detected and highlighted with the style. URLs starting with `https://`, `http://`, `ftp://`, `file://`, or
+    /// `mailto:` are detected. Punctuations at the end of a URL like `.` in `see https://example.com.` are not
+    /// included. It is disabled by default. See also [`TextArea::link_at_cursor`].
+    /// ```
+    /// use ratatui::style::{Color, Modifier, Style};
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// // Highlight links with blue underlined text
+    /// let style = Style::default().fg(Color::Blue).add_modifier(Modifier::UNDERLINED);
+    /// textarea.set_link_style(style);
+    /// assert_eq!(textarea.link_style(), Some(style));
+    /// ```
+    pub fn set_link_style(&mut self, style: Style) {
+        self.touch_render(|t| t.link_style = Some(style));
+    }
+
+    /// Remove the style of links which was set by [`TextArea::set_link_style`]. After calling this method, links will
+    /// no longer be highlighted.
+    /// ```
+    /// use ratatui::style::{Modifier, Style};
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// textarea.set_link_style(Style::default().add_modifier(Modifier::UNDERLINED));
+    /// textarea.remove_link_style();
+    /// assert_eq!(textarea.link_style(), None);
+    /// ```
+    pub fn remove_link_style(&mut self) {
+        self.touch_render(|t| t.link_style = None);
+    }
+
+    /// Get the style of links if set.
+    pub fn link_style(&self) -> Option<Style> {
+        self.link_style
+    }
+
+    /// Get the URL under the cursor. `None` is returned when the cursor is not on a link. Links are detected in the
+    /// same way as [`TextArea::set_link_style`] even if the style is not set. This is useful to open the link under
+    /// the cursor with a browser.
+    /// ```
+    /// use tui_textarea::{CursorMove, TextArea};
+    ///
+    /// let mut textarea = TextArea::from(["see https://example.com."]);
+    /// assert_eq!(textarea.link_at_cursor(), None);
+    ///
+    /// textarea.move_cursor(CursorMove::Jump(0, 10));
+    /// assert_eq!(textarea.link_at_cursor(), Some("https://example.com"));
+    ///
+    /// // The period after the link is not a part of it
+    /// textarea.move_cursor(CursorMove::End);
+    /// textarea.move_cursor(CursorMove::Back);
+    /// assert_eq!(textarea.link_at_cursor(), None);
+    /// ```
+    pub fn link_at_cursor(&self) -> Option<&str> {
+        let (row, col) = self.cursor;
+        let line = &self.lines[row];
+        let offset = self.line_offset(row, col);
+        Links::new(line)
+            .take_while(|&(start, _)| start <= offset)
+            .find(|&(_, end)| offset < end)
+            .map(|(start, end)| &line[start..end])
+    }
+
+    /// Set the placeholder text. The text is set in the textarea when no text is input. Setting a non-empty string `""`
+    /// enables the placeholder. The default value is an empty string so the placeholder is disabled by default.
+    /// To customize the text style, see [`TextArea::set_placeholder_style`].
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    /// assert_eq!(textarea.placeholder_text(), "");
+    /// assert!(textarea.placeholder_style().is_none());
+    ///
+    /// textarea.set_placeholder_text("Hello");
+    /// assert_eq!(textarea.placeholder_text(), "Hello");
+    /// assert!(textarea.placeholder_style().is_some());
+    /// ```
+    pub fn set_placeholder_text(&mut self, placeholder: impl Into<String>) {
+        self.touch_render(|t| t.placeholder = placeholder.into());
+    }
+
+    /// Set the style of the placeholder text. The default style is a dark gray text.
+    /// ```
+    /// use ratatui::style::{Style, Color};
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    /// assert_eq!(textarea.placeholder_style(), None); // When the placeholder is disabled
+    ///
+    /// textarea.set_placeholder_text("Enter your message"); // Enable placeholder by setting non-empty text
+    ///
+    /// let style = Style::default().bg(Color::Blue);
+    /// textarea.set_placeholder_style(style);
+    /// assert_eq!(textarea.placeholder_style(), Some(style));
+    /// ```
+    pub fn set_placeholder_style(&mut self, style: Style) {
+        self.touch_render(|t| t.placeholder_style = style);
+    }
+
+    /// Get the placeholder text. An empty string means the placeholder is disabled. The default value is an empty string.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let textarea = TextArea::default();
+    /// assert_eq!(textarea.placeholder_text(), "");
+    /// ```
+    pub fn placeholder_text(&self) -> &'_ str {
+        self.placeholder.as_str()
+    }
+
+    /// Get the placeholder style. When the placeholder text is empty, it returns `None` since the placeholder is disabled.
+    /// The default style is a dark gray text.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    /// assert_eq!(textarea.placeholder_style(), None);
+    ///
+    /// textarea.set_placeholder_text("hello");
+    /// assert!(textarea.placeholder_style().is_some());
+    /// ```
+    pub fn placeholder_style(&self) -> Option<Style> {
+        if self.placeholder.is_empty() {
+            None
+        } else {
+            Some(self.placeholder_style)
+        }
+    }
+
+    /// Set how the cursor is rendered while the placeholder text is shown. By default, the cursor is rendered as a
+    /// blank cell before the placeholder text. See [`PlaceholderCursor`] for the options. When the cursor is hidden by
+    /// [`TextArea::set_cursor_hidden`], it is not rendered regardless of this option.
+    /// ```
+    /// use tui_textarea::{TextArea, PlaceholderCursor};
+    ///
+    /// let mut textarea = TextArea::default();
+    /// textarea.set_placeholder_text("hello");
+    /// assert_eq!(textarea.render_to_string(7, 1), " hello ");
+    ///
+    /// // The cursor is rendered on 'h' without shifting the placeholder
+    /// textarea.set_placeholder_cursor(PlaceholderCursor::Over);
+    /// assert_eq!(textarea.placeholder_cursor(), PlaceholderCursor::Over);
+    /// assert_eq!(textarea.render_to_string(7, 1), "hello  ");
+    /// ```
+    pub fn set_placeholder_cursor(&mut self, cursor: PlaceholderCursor) {
+        self.touch_render(|t| t.placeholder_cursor = cursor);
+    }
+
+    /// Get how the cursor is rendered while the placeholder text is shown.
+    pub fn placeholder_cursor(&self) -> PlaceholderCursor {
+        self.placeholder_cursor
+    }
+
+    /// Set the alignment of the placeholder text separately from the alignment of the text set by
+    /// [`TextArea::set_alignment`]. `None` means the placeholder text follows the alignment of the text. This is the
+    /// default.
+    /// ```
+    /// use ratatui::layout::Alignment;
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    /// textarea.set_placeholder_text("hi");
+    /// textarea.set_cursor_hidden(true);
+    ///
+    /// textarea.set_placeholder_alignment(Some(Alignment::Right));
+    /// assert_eq!(textarea.placeholder_alignment(), Alignment::Right);
+    /// assert_eq!(textarea.render_to_string(6, 1), "    hi");
+    ///
+    /// // The text is still left-aligned
+    /// assert_eq!(textarea.alignment(), Alignment::Left);
+    /// ```
+    pub fn set_placeholder_alignment(&mut self, alignment: Option<Alignment>) {
+        self.touch_render(|t| t.placeholder_alignment = alignment);
+    }
+
+    /// Get the alignment of the placeholder text. When it is not set by [`TextArea::set_placeholder_alignment`], the
+    /// alignment of the text is returned.
+    pub fn placeholder_alignment(&self) -> Alignment {
+        self.placeholder_alignment.unwrap_or(self.alignment)
+    }
+
+    /// Specify a character masking the text. All characters in the textarea will be replaced by this character.
+    /// This API is useful for making a kind of credentials form such as a password input. The masked text keeps its
+    /// display width: a wide character is replaced with as many mask characters as the cells it occupies, and a wide
+    /// mask character covers consecutive narrow characters. A cell left over by a wide mask character is padded with
+    /// a space.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// textarea.set_mask_char('*');
+    /// assert_eq!(textarea.mask_char(), Some('*'));
+    /// textarea.set_mask_char('●');
+    /// assert_eq!(textarea.mask_char(), Some('●'));
+    /// ```
+    pub fn set_mask_char(&mut self, mask: char) {
+        self.touch_render(|t| t.mask = Some(mask));
     }
 
-    /// Undo the last modification. This method returns if the undo modified text contents or not in the textarea.
+    /// Clear the masking character previously set by [`TextArea::set_mask_char`].
     /// ```
-    /// use tui_textarea::{TextArea, CursorMove};
+    /// use tui_textarea::TextArea;
     ///
-    /// let mut textarea = TextArea::from(["abc def"]);
+    /// let mut textarea = TextArea::default();
     ///
-    /// textarea.delete_next_word();
-    /// assert_eq!(textarea.lines(), [" def"]);
-    /// textarea.undo();
-    /// assert_eq!(textarea.lines(), ["abc def"]);
+    /// textarea.set_mask_char('*');
+    /// assert_eq!(textarea.mask_char(), Some('*'));
+    /// textarea.clear_mask_char();
+    /// assert_eq!(textarea.mask_char(), None);
     /// ```
-    pub fn undo(&mut self) -> bool {
-        if let Some(cursor) = self.history.undo(&mut self.lines) {
-            self.cancel_selection();
-            self.cursor = cursor;
-            true
-        } else {
-            false
-        }
+    pub fn clear_mask_char(&mut self) {
+        self.touch_render(|t| t.mask = None);
     }
 
-    /// Redo the last undo change. This method returns if the redo modified text contents or not in the textarea.
+    /// Get the character to mask text. When no character is set, `None` is returned.
     /// ```
-    /// use tui_textarea::{TextArea, CursorMove};
+    /// use tui_textarea::TextArea;
     ///
-    /// let mut textarea = TextArea::from(["abc def"]);
+    /// let mut textarea = TextArea::default();
     ///
-    /// textarea.delete_next_word();
-    /// assert_eq!(textarea.lines(), [" def"]);
-    /// textarea.undo();
-    /// assert_eq!(textarea.lines(), ["abc def"]);
-    /// textarea.redo();
-    /// assert_eq!(textarea.lines(), [" def"]);
+    /// assert_eq!(textarea.mask_char(), None);
+    /// textarea.set_mask_char('*');
+    /// assert_eq!(textarea.mask_char(), Some('*'));
     /// ```
-    pub fn redo(&mut self) -> bool {
-        if let Some(cursor) = self.history.redo(&mut self.lines) {
-            self.cancel_selection();
-            self.cursor = cursor;
-            true
-        } else {
-            false
-        }
+    pub fn mask_char(&self) -> Option<char> {
+        self.mask
     }
 
-    pub(crate) fn line_spans<'b>(&'b self, line: &'b str, row: usize, lnum_len: u8) -> Line<'b> {
-        let mut hl = LineHighlighter::new(
-            line,
-            self.cursor_style,
-            self.tab_len,
-            self.mask,
-            self.select_style,
-        );
-
-        if let Some(style) = self.line_number_style {
-            hl.line_number(row, lnum_len, style);
-        }
-
-        if row == self.cursor.0 {
-            hl.cursor_line(self.cursor.1, self.cursor_line_style);
-        }
-
-        #[cfg(feature = "search")]
-        if let Some(matches) = self.search.matches(line) {
-            hl.search(matches, self.search.style);
-        }
-
-        if let Some((start, end)) = self.selection_positions() {
-            hl.selection(row, start.row, start.offset, end.row, end.offset);
-        }
-
-        hl.into_spans()
+    /// Set the policy which specifies which part of the text is masked by the character set with
+    /// [`TextArea::set_mask_char`]. The default policy is [`MaskPolicy::All`]. See [`MaskPolicy`] for the details of
+    /// each policy.
+    /// ```
+    /// use std::time::Duration;
+    /// use tui_textarea::{TextArea, MaskPolicy};
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// textarea.set_mask_char('*');
+    ///
+    /// // Show the last 4 characters like a card number field
+    /// textarea.set_mask_policy(MaskPolicy::AllExceptLast(4));
+    /// assert_eq!(textarea.mask_policy(), MaskPolicy::AllExceptLast(4));
+    ///
+    /// // Reveal the last typed character for one second
+    /// textarea.set_mask_policy(MaskPolicy::RevealWhile(Duration::from_secs(1)));
+    /// assert_eq!(textarea.mask_policy(), MaskPolicy::RevealWhile(Duration::from_secs(1)));
+    /// ```
+    pub fn set_mask_policy(&mut self, policy: MaskPolicy) {
+        self.touch_render(|t| t.mask_policy = policy);
     }
 
-    /// Build a ratatui (or tui-rs) widget to render the current state of the textarea. The widget instance returned
-    /// from this method can be rendered with [`ratatui::Frame::render_widget`].
-    ///
-    /// This method was deprecated at v0.5.3 and is no longer necessary. Instead you can directly pass `&TextArea`
-    /// reference to the `Frame::render_widget` method call.
-    /// ```no_run
-    /// # use ratatui::layout::Rect;
-    /// # use ratatui::Terminal;
-    /// # use ratatui::widgets::Widget as _;
-    /// # use ratatui::backend::CrosstermBackend;
-    /// # use tui_textarea::TextArea;
-    /// #
-    /// # let backend = CrosstermBackend::new(std::io::stdout());
-    /// # let mut term = Terminal::new(backend).unwrap();
-    /// # let textarea = TextArea::default();
-    /// #
-    /// # #[allow(deprecated)]
-    /// # term.draw(|f| {
-    /// #   let rect = Rect {
-    /// #       x: 0,
-    /// #       y: 0,
-    /// #       width: 24,
-    /// #       height: 8,
-    /// #   };
-    /// // v0.5.2 or earlier
-    /// f.render_widget(textarea.widget(), rect);
+    /// Get the policy of masking text. The default value is [`MaskPolicy::All`].
+    /// ```
+    /// use tui_textarea::{TextArea, MaskPolicy};
     ///
-    /// // v0.5.3 or later
-    /// f.render_widget(&textarea, rect);
-    /// # }).unwrap();
+    /// let textarea = TextArea::default();
+    /// assert_eq!(textarea.mask_policy(), MaskPolicy::All);
     /// ```
-    #[deprecated(
-        since = "0.5.3",
-        note = "calling this method is no longer necessary on rendering a textarea. pass &TextArea reference to `Frame::render_widget` method call directly"
-    )]
-    pub fn widget(&'a self) -> impl Widget + 'a {
-        self
+    pub fn mask_policy(&self) -> MaskPolicy {
+        self.mask_policy
     }
 
-    /// Set the style of textarea. By default, textarea is not styled.
+    /// Set the style of cursor. By default, a cursor is rendered in the reversed color. To hide the cursor, use
+    /// [`TextArea::set_cursor_hidden`].
     /// ```
     /// use ratatui::style::{Style, Color};
     /// use tui_textarea::TextArea;
     ///
     /// let mut textarea = TextArea::default();
-    /// let style = Style::default().fg(Color::Red);
-    /// textarea.set_style(style);
-    /// assert_eq!(textarea.style(), style);
+    ///
+    /// let style = Style::default().bg(Color::Red);
+    /// textarea.set_cursor_style(style);
+    /// assert_eq!(textarea.cursor_style(), style);
     /// ```
-    pub fn set_style(&mut self, style: Style) {
-        self.style = style;
+    pub fn set_cursor_style(&mut self, style: Style) {
+        self.touch_render(|t| t.cursor_style = style);
     }
 
-    /// Get the current style of textarea.
-    pub fn style(&self) -> Style {
-        self.style
+    /// Get the style of cursor.
+    pub fn cursor_style(&self) -> Style {
+        self.cursor_style
     }
 
-    /// Set the block of textarea. By default, no block is set.
+    /// Set if the cursor is hidden or not. By default, the cursor is rendered as a cell styled with
+    /// [`TextArea::cursor_style`]. Hiding the cursor is useful when the application shows the terminal's cursor at the
+    /// cursor position by itself. The cursor line style is still applied to the cursor line.
     /// ```
     /// use tui_textarea::TextArea;
-    /// use ratatui::widgets::{Block, Borders};
     ///
     /// let mut textarea = TextArea::default();
-    /// let block = Block::default().borders(Borders::ALL).title("Block Title");
-    /// textarea.set_block(block);
-    /// assert!(textarea.block().is_some());
+    ///
+    /// textarea.set_cursor_hidden(true);
+    /// assert!(textarea.cursor_hidden());
     /// ```
-    pub fn set_block(&mut self, block: Block<'a>) {
-        self.block = Some(block);
+    pub fn set_cursor_hidden(&mut self, hidden: bool) {
+        self.touch_render(|t| t.cursor_hidden = hidden);
     }
 
-    /// Remove the block of textarea which was set by [`TextArea::set_block`].
+    /// Get if the cursor is hidden or not.
+    pub fn cursor_hidden(&self) -> bool {
+        self.cursor_hidden
+    }
+
+    /// Set the interval of blinking the cursor. While the cursor is blinking, it is rendered with
+    /// [`TextArea::cursor_style`] and hidden alternately on each interval. The blinking is driven by
+    /// [`TextArea::tick`] so the application needs to call it periodically. When the cursor moves, it is shown and
+    /// the blinking restarts so that the cursor is visible while typing. `None` disables the blinking. By default,
+    /// the cursor does not blink.
     /// ```
+    /// use std::time::Duration;
     /// use tui_textarea::TextArea;
-    /// use ratatui::widgets::{Block, Borders};
     ///
     /// let mut textarea = TextArea::default();
-    /// let block = Block::default().borders(Borders::ALL).title("Block Title");
-    /// textarea.set_block(block);
-    /// textarea.remove_block();
-    /// assert!(textarea.block().is_none());
+    ///
+    /// textarea.set_cursor_blink(Some(Duration::from_millis(500)));
+    /// assert_eq!(textarea.cursor_blink(), Some(Duration::from_millis(500)));
     /// ```
-    pub fn remove_block(&mut self) {
-        self.block = None;
+    pub fn set_cursor_blink(&mut self, interval: Option<Duration>) {
+        self.cursor_blink = interval;
+        self.reset_blink();
     }
 
-    /// Get the block of textarea if exists.
-    pub fn block<'s>(&'s self) -> Option<&'s Block<'a>> {
-        self.block.as_ref()
+    /// Get the interval of blinking the cursor set by [`TextArea::set_cursor_blink`].
+    pub fn cursor_blink(&self) -> Option<Duration> {
+        self.cursor_blink
     }
 
-    /// Set the length of tab character. Setting 0 disables tab inputs.
+    /// Update the time-based state of the textarea to the time `now`. Currently this toggles the blinking cursor set
+    /// by [`TextArea::set_cursor_blink`]. Call this method periodically (e.g. on the tick of the event loop) at a
+    /// shorter period than the blink interval. This method returns if the textarea needs to be rendered again.
     /// ```
-    /// use tui_textarea::{TextArea, Input, Key};
+    /// use std::time::{Duration, Instant};
+    /// use tui_textarea::TextArea;
     ///
     /// let mut textarea = TextArea::default();
-    /// let tab_input = Input { key: Key::Tab, ctrl: false, alt: false, shift: false };
+    /// textarea.set_cursor_blink(Some(Duration::from_millis(500)));
     ///
-    /// textarea.set_tab_length(8);
-    /// textarea.input(tab_input.clone());
-    /// assert_eq!(textarea.lines(), ["        "]);
+    /// let start = Instant::now();
+    /// assert!(!textarea.tick(start)); // The blinking starts
+    /// assert!(textarea.tick(start + Duration::from_millis(600))); // The cursor is hidden
+    /// assert!(!textarea.tick(start + Duration::from_millis(700)));
+    /// assert!(textarea.tick(start + Duration::from_millis(1000))); // The cursor is shown
     ///
-    /// textarea.set_tab_length(2);
-    /// textarea.input(tab_input);
-    /// assert_eq!(textarea.lines(), ["          "]);
+    /// // Typing shows the cursor and restarts the blinking
+    /// textarea.tick(start + Duration::from_millis(1500));
+    /// textarea.insert_char('a');
+    /// assert_eq!(textarea.render_to_string(3, 1), "a  ");
+    /// assert!(!textarea.tick(start + Duration::from_millis(1600)));
     /// ```
-    pub fn set_tab_length(&mut self, len: u8) {
-        self.tab_len = len;
+    pub fn tick(&mut self, now: Instant) -> bool {
+        let hidden = match (self.cursor_blink, self.blink_since) {
+            (Some(interval), Some(since)) if !interval.is_zero() => {
+                let elapsed = now.saturating_duration_since(since);
+                elapsed.as_nanos() / interval.as_nanos() % 2 == 1
+            }
+            (Some(_), None) => {
+                self.blink_since = Some(now);
+                false
+            }
+            _ => false,
+        };
+        let changed = self.blink_hidden != hidden;
+        if changed {
+            self.touch_render(|t| t.blink_hidden = hidden);
+        }
+        changed
     }
 
-    /// Get how many spaces are used for representing tab character. The default value is 4.
-    pub fn tab_length(&self) -> u8 {
-        self.tab_len
+    fn reset_blink(&mut self) {
+        self.blink_since = None;
+        if self.blink_hidden {
+            self.touch_render(|t| t.blink_hidden = false);
+        }
     }
 
-    /// Set if a hard tab is used or not for indent. When `true` is set, typing a tab key inserts a hard tab instead of
-    /// spaces. By default, hard tab is disabled.
+    // If the cursor is rendered or not. It is hidden by the option, by blinking, or while the textarea is unfocused
+    pub(crate) fn cursor_rendered(&self) -> bool {
+        if self.cursor_hidden {
+            false
+        } else if self.focused {
+            !self.blink_hidden
+        } else {
+            self.unfocused_cursor_style.is_some()
+        }
+    }
+
+    // Styles of the cursor, the cursor line, and the text selection for the current focus state
+    pub(crate) fn rendered_styles(&self) -> (Style, Style, Style) {
+        if self.focused {
+            (self.cursor_style, self.cursor_line_style, self.select_style)
+        } else {
+            (
+                self.unfocused_cursor_style.unwrap_or(self.cursor_style),
+                self.unfocused_cursor_line_style,
+                self.unfocused_selection_style,
+            )
+        }
+    }
+
+    /// Set if the textarea is focused or not. While the textarea is unfocused, the cursor, the cursor line, and the
+    /// text selection are rendered with the styles for the unfocused state instead. This is useful to indicate which
+    /// textarea receives inputs when a form has multiple textareas. By default, the textarea is focused. Note that
+    /// this only changes how the textarea is rendered and the textarea still handles inputs passed to it.
+    ///
+    /// By default, the cursor is hidden, the cursor line is not styled, and the text selection is rendered in dark
+    /// gray while the textarea is unfocused. They can be changed with [`TextArea::set_unfocused_cursor_style`],
+    /// [`TextArea::set_unfocused_cursor_line_style`], and [`TextArea::set_unfocused_selection_style`].
     /// ```
+    /// use ratatui::style::{Color, Style};
     /// use tui_textarea::TextArea;
     ///
-    /// let mut textarea = TextArea::default();
+    /// let mut textarea = TextArea::from(["hello"]);
+    /// assert!(textarea.focused());
     ///
-    /// textarea.set_hard_tab_indent(true);
-    /// textarea.insert_tab();
-    /// assert_eq!(textarea.lines(), ["\t"]);
+    /// textarea.set_focused(false);
+    /// assert!(!textarea.focused());
+    ///
+    /// // Show the cursor in gray while unfocused
+    /// textarea.set_unfocused_cursor_style(Some(Style::default().bg(Color::Gray)));
     /// ```
-    pub fn set_hard_tab_indent(&mut self, enabled: bool) {
-        self.hard_tab_indent = enabled;
+    pub fn set_focused(&mut self, focused: bool) {
+        self.touch_render(|t| t.focused = focused);
     }
 
-    /// Get if a hard tab is used for indent or not.
+    /// Get if the textarea is focused or not. See [`TextArea::set_focused`].
+    pub fn focused(&self) -> bool {
+        self.focused
+    }
+
+    /// Set the style of the cursor while the textarea is unfocused by [`TextArea::set_focused`]. `None` hides the
+    /// cursor while unfocused. This is the default.
+    pub fn set_unfocused_cursor_style(&mut self, style: Option<Style>) {
+        self.touch_render(|t| t.unfocused_cursor_style = style);
+    }
+
+    /// Get the style of the cursor while the textarea is unfocused. `None` means the cursor is hidden.
+    pub fn unfocused_cursor_style(&self) -> Option<Style> {
+        self.unfocused_cursor_style
+    }
+
+    /// Set the style of the cursor line while the textarea is unfocused by [`TextArea::set_focused`]. By default, the
+    /// cursor line is not styled while unfocused.
+    pub fn set_unfocused_cursor_line_style(&mut self, style: Style) {
+        self.touch_render(|t| t.unfocused_cursor_line_style = style);
+    }
+
+    /// Get the style of the cursor line while the textarea is unfocused.
+    pub fn unfocused_cursor_line_style(&self) -> Style {
+        self.unfocused_cursor_line_style
+    }
+
+    /// Set the style of the text selection while the textarea is unfocused by [`TextArea::set_focused`]. The default
+    /// style is dark gray so that the selection is dimmed compared to the focused one.
+    pub fn set_unfocused_selection_style(&mut self, style: Style) {
+        self.touch_render(|t| t.unfocused_selection_style = style);
+    }
+
+    /// Get the style of the text selection while the textarea is unfocused.
+    pub fn unfocused_selection_style(&self) -> Style {
+        self.unfocused_selection_style
+    }
+
+    /// Get slice of line texts. This method borrows the content, but not moves. Note that the returned slice will
+    /// never be empty because an empty text means a slice containing one empty line. This is correct since any text
+    /// file must end with a newline.
     /// ```
     /// use tui_textarea::TextArea;
     ///
     /// let mut textarea = TextArea::default();
+    /// assert_eq!(textarea.lines(), [""]);
     ///
-    /// assert!(!textarea.hard_tab_indent());
-    /// textarea.set_hard_tab_indent(true);
-    /// assert!(textarea.hard_tab_indent());
+    /// textarea.insert_char('a');
+    /// assert_eq!(textarea.lines(), ["a"]);
+    ///
+    /// textarea.insert_newline();
+    /// assert_eq!(textarea.lines(), ["a", ""]);
+    ///
+    /// textarea.insert_char('b');
+    /// assert_eq!(textarea.lines(), ["a", "b"]);
     /// ```
-    pub fn hard_tab_indent(&self) -> bool {
-        self.hard_tab_indent
+    pub fn lines(&'a self) -> &'a [String] {
+        &self.lines
     }
 
-    /// Get a string for indent. It consists of spaces by default. When hard tab is enabled, it is a tab character.
+    /// Convert [`TextArea`] instance into line texts.
     /// ```
     /// use tui_textarea::TextArea;
     ///
     /// let mut textarea = TextArea::default();
     ///
-    /// assert_eq!(textarea.indent(), "    ");
-    /// textarea.set_tab_length(2);
-    /// assert_eq!(textarea.indent(), "  ");
-    /// textarea.set_hard_tab_indent(true);
-    /// assert_eq!(textarea.indent(), "\t");
+    /// textarea.insert_char('a');
+    /// textarea.insert_newline();
+    /// textarea.insert_char('b');
+    ///
+    /// assert_eq!(textarea.into_lines(), ["a", "b"]);
     /// ```
-    pub fn indent(&self) -> &'static str {
-        if self.hard_tab_indent {
-            "\t"
-        } else {
-            spaces(self.tab_len)
-        }
-    }
-
-    /// Set how many modifications are remembered for undo/redo. Setting 0 disables undo/redo.
-    pub fn set_max_histories(&mut self, max: usize) {
-        self.history = History::new(max);
+    pub fn into_lines(self) -> Vec<String> {
+        self.lines
     }
 
-    /// Get how many modifications are remembered for undo/redo. The default value is 50.
-    pub fn max_histories(&self) -> usize {
-        self.history.max_items()
+    /// Get the whole text in the textarea. Lines are joined with the line ending set by
+    /// [`TextArea::set_line_ending`] (`\n` by default). No line ending is added at the end of the text.
+    /// ```
+    /// use tui_textarea::{TextArea, LineEnding};
+    ///
+    /// let mut textarea = TextArea::from(["hello", "world"]);
+    /// assert_eq!(textarea.text(), "hello\nworld");
+    ///
+    /// textarea.set_line_ending(LineEnding::CrLf);
+    /// assert_eq!(textarea.text(), "hello\r\nworld");
+    /// ```
+    pub fn text(&self) -> String {
+        self.lines.join(self.line_ending.as_str())
     }
 
-    /// Set the style of line at cursor. By default, the cursor line is styled with underline. To stop styling the
-    /// cursor line, set the default style.
+    /// Set the line ending used for joining lines into one text by [`TextArea::text`] and [`TextArea::yank_text`].
+    /// The default value is [`LineEnding::Lf`]. Text inserted into the textarea is not affected since both `\n` and
+    /// `\r\n` are always recognized as newlines.
     /// ```
-    /// use ratatui::style::{Style, Color};
-    /// use tui_textarea::TextArea;
+    /// use tui_textarea::{TextArea, LineEnding};
     ///
     /// let mut textarea = TextArea::default();
     ///
-    /// let style = Style::default().fg(Color::Red);
-    /// textarea.set_cursor_line_style(style);
-    /// assert_eq!(textarea.cursor_line_style(), style);
-    ///
-    /// // Disable cursor line style
-    /// textarea.set_cursor_line_style(Style::default());
+    /// // CRLF in the inserted text is normalized
+    /// textarea.insert_str("hello\r\nworld");
+    /// assert_eq!(textarea.lines(), ["hello", "world"]);
+    ///
+    /// // Lines are joined with CRLF on export
+    /// textarea.set_line_ending(LineEnding::CrLf);
+    /// assert_eq!(textarea.line_ending(), LineEnding::CrLf);
+    /// assert_eq!(textarea.text(), "hello\r\nworld");
     /// ```
-    pub fn set_cursor_line_style(&mut self, style: Style) {
-        self.cursor_line_style = style;
+    pub fn set_line_ending(&mut self, line_ending: LineEnding) {
+        self.line_ending = line_ending;
     }
 
-    /// Get the style of cursor line. By default it is styled with underline.
-    pub fn cursor_line_style(&self) -> Style {
-        self.cursor_line_style
+    /// Get the line ending used for joining lines.
+    pub fn line_ending(&self) -> LineEnding {
+        self.line_ending
     }
 
-    /// Set the style of line number. By setting the style with this method, line numbers are drawn in textarea, meant
-    /// that line numbers are disabled by default. If you want to show line numbers but don't want to style them, set
-    /// the default style.
+    /// Get the line ending of the first line in the text loaded by [`TextArea::set_text`] or
+    /// [`TextArea::open_file`]. This is useful to show "LF" or "CRLF" in the status bar of editors. `None` is returned
+    /// when no text was loaded or the loaded text had no newline.
     /// ```
-    /// use ratatui::style::{Style, Color};
-    /// use tui_textarea::TextArea;
+    /// use tui_textarea::{TextArea, LineEnding};
     ///
     /// let mut textarea = TextArea::default();
+    /// assert_eq!(textarea.detected_line_ending(), None);
     ///
-    /// // Show line numbers in dark gray background
-    /// let style = Style::default().bg(Color::DarkGray);
-    /// textarea.set_line_number_style(style);
-    /// assert_eq!(textarea.line_number_style(), Some(style));
+    /// textarea.set_text("hello\r\nworld\r\n");
+    /// assert_eq!(textarea.detected_line_ending(), Some(LineEnding::CrLf));
+    /// assert!(!textarea.has_mixed_line_endings());
     /// ```
-    pub fn set_line_number_style(&mut self, style: Style) {
-        self.line_number_style = Some(style);
+    pub fn detected_line_ending(&self) -> Option<LineEnding> {
+        self.detected_line_ending
     }
 
-    /// Remove the style of line number which was set by [`TextArea::set_line_number_style`]. After calling this
-    /// method, Line numbers will no longer be shown.
+    /// Return if the text loaded by [`TextArea::set_text`] or [`TextArea::open_file`] contained both `\n` and `\r\n`
+    /// line endings. Use [`TextArea::convert_line_endings`] to unify them.
     /// ```
-    /// use ratatui::style::{Style, Color};
-    /// use tui_textarea::TextArea;
+    /// use tui_textarea::{TextArea, LineEnding};
     ///
     /// let mut textarea = TextArea::default();
-    ///
-    /// textarea.set_line_number_style(Style::default().bg(Color::DarkGray));
-    /// textarea.remove_line_number();
-    /// assert_eq!(textarea.line_number_style(), None);
+    /// textarea.set_text("hello\r\nworld\ngoodbye");
+    /// assert_eq!(textarea.detected_line_ending(), Some(LineEnding::CrLf));
+    /// assert!(textarea.has_mixed_line_endings());
     /// ```
-    pub fn remove_line_number(&mut self) {
-        self.line_number_style = None;
+    pub fn has_mixed_line_endings(&self) -> bool {
+        self.mixed_line_endings
     }
 
-    /// Get the style of line number if set.
-    pub fn line_number_style(&self) -> Option<Style> {
-        self.line_number_style
+    /// Convert all line endings of the text to the given one. This sets the line ending used by [`TextArea::text`]
+    /// and on saving the text to a file, and resolves the mixed line endings. When the line ending was changed or mixed
+    /// line endings were resolved, the textarea is marked as modified until the next save point and this method
+    /// returns `true`.
+    ///
+    /// Since the lines in the textarea never contain line endings, the conversion does not modify the lines and it is
+    /// not recorded in the undo history.
+    /// ```
+    /// use tui_textarea::{TextArea, LineEnding};
+    ///
+    /// let mut textarea = TextArea::default();
+    /// textarea.set_text("hello\r\nworld\ngoodbye");
+    /// textarea.mark_saved();
+    ///
+    /// assert!(textarea.convert_line_endings(LineEnding::Lf));
+    /// assert_eq!(textarea.text(), "hello\nworld\ngoodbye");
+    /// assert_eq!(textarea.detected_line_ending(), Some(LineEnding::Lf));
+    /// assert!(!textarea.has_mixed_line_endings());
+    /// assert!(textarea.is_modified());
+    ///
+    /// // Nothing changes when the text already uses the line ending
+    /// assert!(!textarea.convert_line_endings(LineEnding::Lf));
+    /// ```
+    pub fn convert_line_endings(&mut self, line_ending: LineEnding) -> bool {
+        let changed = self.mixed_line_endings || self.line_ending != line_ending;
+        self.line_ending = line_ending;
+        self.mixed_line_endings = false;
+        if self.detected_line_ending.is_some() {
+            self.detected_line_ending = Some(line_ending);
+        }
+        if changed {
+            self.history.mark_modified();
+        }
+        changed
     }
 
-    /// Set the placeholder text. The text is set in the textarea when no text is input. Setting a non-empty string `""`
-    /// enables the placeholder. The default value is an empty string so the placeholder is disabled by default.
-    /// To customize the text style, see [`TextArea::set_placeholder_style`].
+    /// Return if the text was modified since the last save point. The save point is initially at the creation of the
+    /// textarea and it is updated by [`TextArea::mark_saved`]. Undoing or redoing edits back to the save point makes
+    /// the text unmodified again, like Vim.
     /// ```
     /// use tui_textarea::TextArea;
     ///
-    /// let mut textarea = TextArea::default();
-    /// assert_eq!(textarea.placeholder_text(), "");
-    /// assert!(textarea.placeholder_style().is_none());
+    /// let mut textarea = TextArea::from(["hello"]);
+    /// assert!(!textarea.is_modified());
     ///
-    /// textarea.set_placeholder_text("Hello");
-    /// assert_eq!(textarea.placeholder_text(), "Hello");
-    /// assert!(textarea.placeholder_style().is_some());
+    /// textarea.insert_char('!');
+    /// assert!(textarea.is_modified());
+    ///
+    /// textarea.undo();
+    /// assert!(!textarea.is_modified());
     /// ```
-    pub fn set_placeholder_text(&mut self, placeholder: impl Into<String>) {
-        self.placeholder = placeholder.into();
+    pub fn is_modified(&self) -> bool {
+        self.history.is_modified()
     }
 
-    /// Set the style of the placeholder text. The default style is a dark gray text.
+    /// Set if edits applied to the text are recorded or not. When enabled, every edit including undo and redo is
+    /// recorded as [`EditRecord`] and the records can be retrieved by [`TextArea::drain_edits`]. This is useful to
+    /// synchronize the text with other places such as collaborative editing, autosave, or incremental parsing. It is
+    /// disabled by default. Disabling it discards the records which have not been retrieved yet.
     /// ```
-    /// use ratatui::style::{Style, Color};
-    /// use tui_textarea::TextArea;
+    /// use tui_textarea::{TextArea, EditRecordKind};
     ///
     /// let mut textarea = TextArea::default();
-    /// assert_eq!(textarea.placeholder_style(), None); // When the placeholder is disabled
+    /// textarea.set_edit_log(true);
+    /// assert!(textarea.edit_log());
     ///
-    /// textarea.set_placeholder_text("Enter your message"); // Enable placeholder by setting non-empty text
+    /// textarea.insert_str("hello\nworld");
+    /// textarea.delete_char();
     ///
-    /// let style = Style::default().bg(Color::Blue);
-    /// textarea.set_placeholder_style(style);
-    /// assert_eq!(textarea.placeholder_style(), Some(style));
+    /// let edits = textarea.drain_edits();
+    /// assert_eq!(edits.len(), 2);
+    /// assert_eq!(edits[0].kind, EditRecordKind::Insert);
+    /// assert_eq!(edits[0].text, "hello\nworld");
+    /// assert_eq!((edits[0].start, edits[0].end), ((0, 0), (1, 5)));
+    /// assert_eq!(edits[1].kind, EditRecordKind::Delete);
+    /// assert_eq!(edits[1].text, "d");
+    /// assert_eq!((edits[1].start, edits[1].end), ((1, 4), (1, 5)));
+    ///
+    /// // Records are removed once they are retrieved
+    /// assert!(textarea.drain_edits().is_empty());
     /// ```
-    pub fn set_placeholder_style(&mut self, style: Style) {
-        self.placeholder_style = style;
+    pub fn set_edit_log(&mut self, enabled: bool) {
+        self.history.set_edit_log(enabled);
     }
 
-    /// Get the placeholder text. An empty string means the placeholder is disabled. The default value is an empty string.
+    /// Get if edits applied to the text are recorded or not.
+    pub fn edit_log(&self) -> bool {
+        self.history.edit_log()
+    }
+
+    /// Take all edits recorded since the last call of this method in the order they were applied. When recording
+    /// edits is not enabled by [`TextArea::set_edit_log`], this method always returns an empty vector.
+    pub fn drain_edits(&mut self) -> Vec<EditRecord> {
+        self.history.drain_edit_log()
+    }
+
+    /// Get the revision of the text. The revision starts from 0 and is incremented on each edit including undo and
+    /// redo. It is incremented even if recording edits is disabled. It is useful to check if the text was changed.
     /// ```
     /// use tui_textarea::TextArea;
     ///
-    /// let textarea = TextArea::default();
-    /// assert_eq!(textarea.placeholder_text(), "");
+    /// let mut textarea = TextArea::default();
+    /// assert_eq!(textarea.revision(), 0);
+    ///
+    /// textarea.insert_char('a');
+    /// assert_eq!(textarea.revision(), 1);
+    /// textarea.undo();
+    /// assert_eq!(textarea.revision(), 2);
     /// ```
-    pub fn placeholder_text(&self) -> &'_ str {
-        self.placeholder.as_str()
+    pub fn revision(&self) -> u64 {
+        self.history.revision()
     }
 
-    /// Get the placeholder style. When the placeholder text is empty, it returns `None` since the placeholder is disabled.
-    /// The default style is a dark gray text.
+    /// Get the last edit in the undo history, which is the edit undone by the next [`TextArea::undo`] call. Unlike
+    /// [`TextArea::drain_edits`], recording edits does not need to be enabled. This is useful to inspect the last
+    /// change such as implementing "repeat last change" or re-parsing the edited range. When the last history item
+    /// consists of multiple edits like [`TextArea::replace_range`], the last one of them is returned. `None` is
+    /// returned when no edit can be undone.
     /// ```
-    /// use tui_textarea::TextArea;
+    /// use tui_textarea::{TextArea, EditRecordKind};
     ///
     /// let mut textarea = TextArea::default();
-    /// assert_eq!(textarea.placeholder_style(), None);
+    /// assert_eq!(textarea.last_edit(), None);
     ///
-    /// textarea.set_placeholder_text("hello");
-    /// assert!(textarea.placeholder_style().is_some());
+    /// textarea.insert_str("hello\nworld");
+    /// textarea.delete_char();
+    ///
+    /// let edit = textarea.last_edit().unwrap();
+    /// assert_eq!(edit.kind, EditRecordKind::Delete);
+    /// assert_eq!(edit.text, "d");
+    /// assert_eq!((edit.start, edit.end), ((1, 4), (1, 5)));
+    /// assert_eq!(edit.revision, textarea.revision());
+    ///
+    /// textarea.undo();
+    /// let edit = textarea.last_edit().unwrap();
+    /// assert_eq!(edit.kind, EditRecordKind::Insert);
+    /// assert_eq!(edit.text, "hello\nworld");
     /// ```
-    pub fn placeholder_style(&self) -> Option<Style> {
-        if self.placeholder.is_empty() {
-            None
-        } else {
-            Some(self.placeholder_style)
-        }
+    pub fn last_edit(&self) -> Option<EditRecord> {
+        self.history.last_edit()
     }
 
-    /// Specify a character masking the text. All characters in the textarea will be replaced by this character.
-    /// This API is useful for making a kind of credentials form such as a password input.
+    /// Get the ranges of rows of lines modified since they were cleared by [`TextArea::clear_damaged_lines`]. The
+    /// ranges are sorted and don't overlap each other. Every edit including undo, redo, and remote edits damages the
+    /// edited lines, and the ranges follow subsequent edits so that they always point to the rows in the current
+    /// text. Lines inserted by an edit are damaged, and a line where lines were deleted is damaged.
+    ///
+    /// This is useful to update external states derived from lines incrementally, such as syntax highlighting of a
+    /// large text. For example, an application can re-highlight only the damaged lines in the viewport on each frame
+    /// and clear the damage of them. The rest of the damaged lines are re-highlighted when they are scrolled into the
+    /// viewport.
     /// ```
-    /// use tui_textarea::TextArea;
+    /// use tui_textarea::{CursorMove, TextArea};
     ///
-    /// let mut textarea = TextArea::default();
+    /// let mut textarea: TextArea = (0..10).map(|i| i.to_string()).collect();
+    /// assert!(textarea.damaged_lines().is_empty());
     ///
-    /// textarea.set_mask_char('*');
-    /// assert_eq!(textarea.mask_char(), Some('*'));
-    /// textarea.set_mask_char('●');
-    /// assert_eq!(textarea.mask_char(), Some('●'));
+    /// textarea.move_cursor(CursorMove::Jump(2, 0));
+    /// textarea.insert_str("a\nb");
+    /// textarea.move_cursor(CursorMove::Jump(8, 0));
+    /// textarea.insert_char('c');
+    /// assert_eq!(textarea.damaged_lines(), [2..4, 8..9]);
+    ///
+    /// // Inserting a line before the damaged lines moves them
+    /// textarea.move_cursor(CursorMove::Top);
+    /// textarea.insert_newline();
+    /// assert_eq!(textarea.damaged_lines(), [0..2, 3..5, 9..10]);
+    ///
+    /// // Only the lines in the viewport were re-highlighted
+    /// textarea.clear_damaged_lines(0..5);
+    /// assert_eq!(textarea.damaged_lines(), [9..10]);
     /// ```
-    pub fn set_mask_char(&mut self, mask: char) {
-        self.mask = Some(mask);
+    pub fn damaged_lines(&self) -> &[Range<usize>] {
+        self.history.damage().ranges()
     }
 
-    /// Clear the masking character previously set by [`TextArea::set_mask_char`].
+    /// Clear the damage of the lines in the range of rows. See [`TextArea::damaged_lines`] for more details. Pass
+    /// `..` to clear all damaged lines.
+    pub fn clear_damaged_lines(&mut self, rows: impl RangeBounds<usize>) {
+        self.history.damage_mut().clear(rows);
+    }
+
+    /// Set the save point to the current state of the text. Call this method after the text was written to a file.
+    /// [`TextArea::is_modified`] returns `false` until the text is modified again.
     /// ```
     /// use tui_textarea::TextArea;
     ///
     /// let mut textarea = TextArea::default();
     ///
-    /// textarea.set_mask_char('*');
-    /// assert_eq!(textarea.mask_char(), Some('*'));
-    /// textarea.clear_mask_char();
-    /// assert_eq!(textarea.mask_char(), None);
+    /// textarea.insert_str("hello");
+    /// textarea.mark_saved();
+    /// assert!(!textarea.is_modified());
+    ///
+    /// textarea.insert_str(", world");
+    /// assert!(textarea.is_modified());
+    /// textarea.undo();
+    /// assert!(!textarea.is_modified());
+    /// textarea.undo();
+    /// assert!(textarea.is_modified()); // Undoing beyond the save point modifies the text
     /// ```
-    pub fn clear_mask_char(&mut self) {
-        self.mask = None;
+    pub fn mark_saved(&mut self) {
+        self.history.mark_saved();
     }
 
-    /// Get the character to mask text. When no character is set, `None` is returned.
-    /// ```
+    /// Create a new [`TextArea`] instance with the text read from the file at the given path. The encoding of the
+    /// file is detected from its byte order mark (BOM) and the file is decoded as UTF-8 when it has no BOM. When the
+    /// first line in the file ends with `\r\n`, the line ending is set to [`LineEnding::CrLf`]. The encoding, the
+    /// line ending, and whether the file ends with a newline are remembered and used by [`TextArea::save_file`]. See
+    /// also [`TextArea::has_mixed_line_endings`].
+    /// ```no_run
     /// use tui_textarea::TextArea;
     ///
-    /// let mut textarea = TextArea::default();
+    /// let mut textarea = TextArea::open_file("README.md").unwrap();
+    /// textarea.insert_str("hello");
+    /// assert!(textarea.is_modified());
     ///
-    /// assert_eq!(textarea.mask_char(), None);
-    /// textarea.set_mask_char('*');
-    /// assert_eq!(textarea.mask_char(), Some('*'));
+    /// textarea.save_file("README.md").unwrap();
+    /// assert!(!textarea.is_modified());
     /// ```
-    pub fn mask_char(&self) -> Option<char> {
-        self.mask
+    #[cfg(feature = "file")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "file")))]
+    pub fn open_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut bytes = vec![];
+        io::BufReader::new(fs::File::open(path)?).read_to_end(&mut bytes)?;
+        let (text, encoding) = file::decode(bytes)?;
+
+        let (detected, mixed) = line_ending::detect(&text);
+        let (text, final_newline) = match text.strip_suffix('\n') {
+            Some(t) => (t.strip_suffix('\r').unwrap_or(t), true),
+            None => (text.as_str(), false),
+        };
+        let lines = text
+            .split('\n')
+            .map(|l| l.strip_suffix('\r').unwrap_or(l).to_string())
+            .collect();
+
+        let mut textarea = Self::new(lines);
+        textarea.line_ending = detected.unwrap_or_default();
+        textarea.detected_line_ending = detected;
+        textarea.mixed_line_endings = mixed;
+        textarea.encoding = encoding;
+        textarea.final_newline = final_newline;
+        Ok(textarea)
+    }
+
+    /// Write the text in the textarea to the file at the given path. Lines are joined with the line ending set by
+    /// [`TextArea::set_line_ending`] and the text is encoded with the encoding set by [`TextArea::set_encoding`].
+    /// When the text was read by [`TextArea::open_file`], a newline is put at the end of the file only when the
+    /// original file ended with a newline. Otherwise a newline is always put at the end. After the file was written
+    /// successfully, [`TextArea::is_modified`] returns `false`. Trailing whitespaces are removed before writing the
+    /// file when [`TextArea::set_trim_trailing_whitespace_on_save`] is enabled.
+    #[cfg(feature = "file")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "file")))]
+    pub fn save_file(&mut self, path: impl AsRef<Path>) -> io::Result<()> {
+        if self.trim_on_save {
+            self.trim_trailing_whitespace();
+        }
+        let mut text = self.text();
+        if self.final_newline {
+            text.push_str(self.line_ending.as_str());
+        }
+        let mut w = io::BufWriter::new(fs::File::create(path)?);
+        file::encode(&mut w, &text, self.encoding)?;
+        w.flush()?;
+        self.mark_saved();
+        Ok(())
     }
 
-    /// Set the style of cursor. By default, a cursor is rendered in the reversed color. Setting the same style as
-    /// cursor line hides a cursor.
+    /// Set the encoding used by [`TextArea::save_file`]. The default value is [`Encoding::Utf8`].
     /// ```
-    /// use ratatui::style::{Style, Color};
-    /// use tui_textarea::TextArea;
+    /// use tui_textarea::{TextArea, Encoding};
     ///
     /// let mut textarea = TextArea::default();
-    ///
-    /// let style = Style::default().bg(Color::Red);
-    /// textarea.set_cursor_style(style);
-    /// assert_eq!(textarea.cursor_style(), style);
+    /// textarea.set_encoding(Encoding::Utf16Le);
+    /// assert_eq!(textarea.encoding(), Encoding::Utf16Le);
     /// ```
-    pub fn set_cursor_style(&mut self, style: Style) {
-        self.cursor_style = style;
+    #[cfg(feature = "file")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "file")))]
+    pub fn set_encoding(&mut self, encoding: Encoding) {
+        self.encoding = encoding;
     }
 
-    /// Get the style of cursor.
-    pub fn cursor_style(&self) -> Style {
-        self.cursor_style
+    /// Get the encoding used by [`TextArea::save_file`].
+    #[cfg(feature = "file")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "file")))]
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
     }
 
-    /// Get slice of line texts. This method borrows the content, but not moves. Note that the returned slice will
-    /// never be empty because an empty text means a slice containing one empty line. This is correct since any text
-    /// file must end with a newline.
+    /// Set if [`TextArea::save_file`] puts a newline at the end of the file. It is enabled by default and
+    /// [`TextArea::open_file`] sets it to whether the opened file ended with a newline.
     /// ```
     /// use tui_textarea::TextArea;
     ///
     /// let mut textarea = TextArea::default();
-    /// assert_eq!(textarea.lines(), [""]);
-    ///
-    /// textarea.insert_char('a');
-    /// assert_eq!(textarea.lines(), ["a"]);
-    ///
-    /// textarea.insert_newline();
-    /// assert_eq!(textarea.lines(), ["a", ""]);
+    /// assert!(textarea.final_newline());
     ///
-    /// textarea.insert_char('b');
-    /// assert_eq!(textarea.lines(), ["a", "b"]);
+    /// textarea.set_final_newline(false);
+    /// assert!(!textarea.final_newline());
     /// ```
-    pub fn lines(&'a self) -> &'a [String] {
-        &self.lines
+    #[cfg(feature = "file")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "file")))]
+    pub fn set_final_newline(&mut self, enabled: bool) {
+        self.final_newline = enabled;
     }
 
-    /// Convert [`TextArea`] instance into line texts.
+    /// Get if [`TextArea::save_file`] puts a newline at the end of the file.
+    #[cfg(feature = "file")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "file")))]
+    pub fn final_newline(&self) -> bool {
+        self.final_newline
+    }
+
+    /// Set if [`TextArea::save_file`] removes trailing whitespaces with [`TextArea::trim_trailing_whitespace`] before
+    /// writing the file. It is disabled by default.
     /// ```
     /// use tui_textarea::TextArea;
     ///
     /// let mut textarea = TextArea::default();
-    ///
-    /// textarea.insert_char('a');
-    /// textarea.insert_newline();
-    /// textarea.insert_char('b');
-    ///
-    /// assert_eq!(textarea.into_lines(), ["a", "b"]);
+    /// textarea.set_trim_trailing_whitespace_on_save(true);
+    /// assert!(textarea.trim_trailing_whitespace_on_save());
     /// ```
-    pub fn into_lines(self) -> Vec<String> {
-        self.lines
+    #[cfg(feature = "file")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "file")))]
+    pub fn set_trim_trailing_whitespace_on_save(&mut self, enabled: bool) {
+        self.trim_on_save = enabled;
     }
 
-    /// Get the current cursor position. 0-base character-wise (row, col) cursor position.
+    /// Get if [`TextArea::save_file`] removes trailing whitespaces before writing the file.
+    #[cfg(feature = "file")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "file")))]
+    pub fn trim_trailing_whitespace_on_save(&self) -> bool {
+        self.trim_on_save
+    }
+
+    /// Get the current cursor position. 0-base character-wise (row, col) cursor position. In virtual edit mode, the
+    /// column may be beyond the end of the line (see [`TextArea::set_virtual_edit`]).
     /// ```
     /// use tui_textarea::TextArea;
     ///
@@ -2037,7 +7095,8 @@ impl<'a> TextArea<'a> {
     /// assert_eq!(textarea.cursor(), (1, 1));
     /// ```
     pub fn cursor(&self) -> (usize, usize) {
-        self.cursor
+        let (row, col) = self.cursor;
+        (row, col + self.virtual_padding())
     }
 
     /// Get the current selection range as a pair of the start position and the end position. The range is bounded
@@ -2096,10 +7155,54 @@ impl<'a> TextArea<'a> {
     /// assert_eq!(textarea.alignment(), Alignment::Center);
     /// ```
     pub fn set_alignment(&mut self, alignment: Alignment) {
-        if let Alignment::Center | Alignment::Right = alignment {
-            self.line_number_style = None;
-        }
-        self.alignment = alignment;
+        self.touch_render(|t| {
+            if let Alignment::Center | Alignment::Right = alignment {
+                t.line_number_style = None;
+            }
+            t.alignment = alignment;
+        });
+    }
+
+    /// Set the alignment of the line at the `row`, such as centering a title line in a left-aligned document. The
+    /// line is shifted by spaces rendered between the gutter and the text so line numbers and diff signs stay in
+    /// place. Setting [`Alignment::Left`] removes the alignment of the row. Row alignments are only respected while
+    /// the alignment of the whole textarea is [`Alignment::Left`] and lines wider than the textarea are not aligned.
+    /// Like [`TextArea::set_eol_annotation`], row alignments are not moved by text edits.
+    /// ```
+    /// use ratatui::layout::Alignment;
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["Title", "body"]);
+    ///
+    /// textarea.set_row_alignment(0, Alignment::Center);
+    /// assert_eq!(textarea.row_alignment(0), Alignment::Center);
+    /// assert_eq!(textarea.row_alignment(1), Alignment::Left);
+    ///
+    /// // One cell at the end of line is reserved for the cursor
+    /// assert_eq!(textarea.render_to_string(10, 2), "  Title   \nbody      ");
+    /// ```
+    pub fn set_row_alignment(&mut self, row: usize, alignment: Alignment) {
+        self.touch_render(|t| {
+            if alignment == Alignment::Left {
+                t.row_alignments.remove(&row);
+            } else {
+                t.row_alignments.insert(row, alignment);
+            }
+        });
+    }
+
+    /// Get the alignment of the line at the `row` set by [`TextArea::set_row_alignment`]. The default alignment of
+    /// rows is [`Alignment::Left`].
+    pub fn row_alignment(&self, row: usize) -> Alignment {
+        self.row_alignments
+            .get(&row)
+            .copied()
+            .unwrap_or(Alignment::Left)
+    }
+
+    /// Remove all row alignments set by [`TextArea::set_row_alignment`].
+    pub fn clear_row_alignments(&mut self) {
+        self.touch_render(|t| t.row_alignments.clear());
     }
 
     /// Get current text alignment. The default alignment is [`Alignment::Left`].
@@ -2115,6 +7218,60 @@ impl<'a> TextArea<'a> {
         self.alignment
     }
 
+    /// Get the number of lines in the textarea.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let textarea = TextArea::from(["hello", "world"]);
+    /// assert_eq!(textarea.line_count(), 2);
+    /// ```
+    pub fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Get the number of characters in the textarea. Newlines between lines are counted as one character. This
+    /// method does not walk the text since the count is updated on each edit, so it is cheap enough to call on every
+    /// frame (e.g. for showing the statistics in a status bar).
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["hello", "世界"]);
+    /// assert_eq!(textarea.char_count(), 8);
+    ///
+    /// textarea.insert_char('!');
+    /// assert_eq!(textarea.char_count(), 9);
+    /// ```
+    pub fn char_count(&self) -> usize {
+        self.metrics.chars()
+    }
+
+    /// Get the number of words in the textarea. Words are separated by whitespaces. Like [`TextArea::char_count`],
+    /// the count is updated on each edit.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["hello, world", "  goodbye"]);
+    /// assert_eq!(textarea.word_count(), 3);
+    ///
+    /// textarea.insert_str("foo ");
+    /// assert_eq!(textarea.word_count(), 4);
+    /// ```
+    pub fn word_count(&self) -> usize {
+        self.metrics.words()
+    }
+
+    /// Get the length of the text in bytes. Newlines between lines are counted as one byte, so this is the same as
+    /// the length of the lines joined with `\n`. Like [`TextArea::char_count`], the length is updated on each edit.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let textarea = TextArea::from(["hello", "世界"]);
+    /// assert_eq!(textarea.len_bytes(), 12);
+    /// ```
+    pub fn len_bytes(&self) -> usize {
+        self.metrics.bytes()
+    }
+
     /// Check if the textarea has a empty content.
     /// ```
     /// use tui_textarea::TextArea;
@@ -2132,7 +7289,7 @@ impl<'a> TextArea<'a> {
     /// Get the yanked text. Text is automatically yanked when deleting strings by [`TextArea::delete_line_by_head`],
     /// [`TextArea::delete_line_by_end`], [`TextArea::delete_word`], [`TextArea::delete_next_word`],
     /// [`TextArea::delete_str`], [`TextArea::copy`], and [`TextArea::cut`]. When multiple lines were yanked, they are
-    /// always joined with `\n`.
+    /// joined with the line ending set by [`TextArea::set_line_ending`] (`\n` by default).
     /// ```
     /// use tui_textarea::TextArea;
     ///
@@ -2146,7 +7303,7 @@ impl<'a> TextArea<'a> {
     /// assert_eq!(textarea.yank_text(), "abc\nd");
     /// ```
     pub fn yank_text(&self) -> String {
-        self.yank.to_string()
+        self.yank.join(self.line_ending)
     }
 
     /// Set a yanked text. The text can be inserted by [`TextArea::paste`]. `\n` and `\r\n` are recognized as newline
@@ -2161,9 +7318,12 @@ impl<'a> TextArea<'a> {
     /// assert_eq!(textarea.lines(), ["hello", "world"]);
     /// ```
     pub fn set_yank_text(&mut self, text: impl Into<String>) {
+        let mut text = text.into();
+        if let Some(f) = &self.paste_filter {
+            text = (f.0)(text);
+        }
         // `str::lines` is not available since it strips a newline at end
         let lines: Vec<_> = text
-            .into()
             .split('\n')
             .map(|s| s.strip_suffix('\r').unwrap_or(s).to_string())
             .collect();
@@ -2175,7 +7335,8 @@ impl<'a> TextArea<'a> {
     /// move. To move the cursor, use [`TextArea::search_forward`] and [`TextArea::search_back`].
     ///
     /// Grammar of regular expression follows [regex crate](https://docs.rs/regex/latest/regex). Patterns don't match
-    /// to newlines so match passes across no newline.
+    /// to newlines so match passes across no newline. The pattern is interpreted as a plain string or a fuzzy query
+    /// instead of a regular expression depending on [`TextArea::set_search_mode`].
     ///
     /// When the pattern is invalid, the search pattern will not be updated and an error will be returned.
     ///
@@ -2201,27 +7362,97 @@ impl<'a> TextArea<'a> {
     /// ```
     #[cfg(feature = "search")]
     #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
-    pub fn set_search_pattern(&mut self, query: impl AsRef<str>) -> Result<(), regex::Error> {
-        self.search.set_pattern(query.as_ref())
+    pub fn set_search_pattern(&mut self, query: impl AsRef<str>) -> Result<(), regex::Error> {
+        self.touch_render(|t| t.search.set_pattern(query.as_ref()))
+    }
+
+    /// Get a regular expression which was set by [`TextArea::set_search_pattern`]. When no text search is ongoing or the
+    /// search mode is not [`SearchMode::Regex`], this method returns `None`. See [`TextArea::search_query`] to get the
+    /// query in any mode.
+    ///
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// assert!(textarea.search_pattern().is_none());
+    /// textarea.set_search_pattern("hello+").unwrap();
+    /// assert!(textarea.search_pattern().is_some());
+    /// assert_eq!(textarea.search_pattern().unwrap().as_str(), "hello+");
+    /// ```
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn search_pattern(&self) -> Option<&regex::Regex> {
+        self.search.regex()
+    }
+
+    /// Get the query which was set by [`TextArea::set_search_pattern`] regardless of the search mode. When no text
+    /// search is ongoing, this method returns `None`.
+    ///
+    /// ```
+    /// use tui_textarea::{SearchMode, TextArea};
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// assert_eq!(textarea.search_query(), None);
+    /// textarea.set_search_mode(SearchMode::Literal).unwrap();
+    /// textarea.set_search_pattern("hello+").unwrap();
+    /// assert_eq!(textarea.search_query(), Some("hello+"));
+    /// assert!(textarea.search_pattern().is_none());
+    /// ```
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn search_query(&self) -> Option<&str> {
+        self.search.is_active().then(|| self.search.query.as_str())
+    }
+
+    /// Set the strategy to match the query of text search. See [`SearchMode`] for the available strategies. The
+    /// default mode is [`SearchMode::Regex`]. When some query is already set by [`TextArea::set_search_pattern`], it
+    /// is matched with the new mode. If the query is not a valid regular expression on switching to
+    /// [`SearchMode::Regex`], the text search stops and the error is returned.
+    ///
+    /// ```
+    /// use tui_textarea::{SearchMode, TextArea};
+    ///
+    /// let mut textarea = TextArea::from(["f(x) = foo(x)"]);
+    ///
+    /// // Parentheses are not interpreted by literal search
+    /// textarea.set_search_mode(SearchMode::Literal).unwrap();
+    /// textarea.set_search_pattern("(x)").unwrap();
+    /// textarea.search_forward(false);
+    /// assert_eq!(textarea.cursor(), (0, 1));
+    ///
+    /// // Fuzzy search matches to characters in the same order
+    /// textarea.set_search_mode(SearchMode::Fuzzy).unwrap();
+    /// textarea.set_search_pattern("fo(").unwrap();
+    /// textarea.search_forward(false);
+    /// assert_eq!(textarea.cursor(), (0, 7));
+    ///
+    /// // "fo(" is not a valid regular expression
+    /// assert!(textarea.set_search_mode(SearchMode::Regex).is_err());
+    /// assert_eq!(textarea.search_mode(), SearchMode::Regex);
+    /// ```
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn set_search_mode(&mut self, mode: SearchMode) -> Result<(), regex::Error> {
+        self.touch_render(|t| t.search.set_mode(mode))
     }
 
-    /// Get a regular expression which was set by [`TextArea::set_search_pattern`]. When no text search is ongoing, this
-    /// method returns `None`.
+    /// Get the strategy to match the query of text search set by [`TextArea::set_search_mode`].
     ///
     /// ```
-    /// use tui_textarea::TextArea;
+    /// use tui_textarea::{SearchMode, TextArea};
     ///
     /// let mut textarea = TextArea::default();
+    /// assert_eq!(textarea.search_mode(), SearchMode::Regex);
     ///
-    /// assert!(textarea.search_pattern().is_none());
-    /// textarea.set_search_pattern("hello+").unwrap();
-    /// assert!(textarea.search_pattern().is_some());
-    /// assert_eq!(textarea.search_pattern().unwrap().as_str(), "hello+");
+    /// textarea.set_search_mode(SearchMode::Fuzzy).unwrap();
+    /// assert_eq!(textarea.search_mode(), SearchMode::Fuzzy);
     /// ```
     #[cfg(feature = "search")]
     #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
-    pub fn search_pattern(&self) -> Option<&regex::Regex> {
-        self.search.pat.as_ref()
+    pub fn search_mode(&self) -> SearchMode {
+        self.search.mode
     }
 
     /// Search the pattern set by [`TextArea::set_search_pattern`] forward and move the cursor to the next match
@@ -2264,7 +7495,16 @@ impl<'a> TextArea<'a> {
     #[cfg(feature = "search")]
     #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
     pub fn search_forward(&mut self, match_cursor: bool) -> bool {
-        if let Some(cursor) = self.search.forward(&self.lines, self.cursor, match_cursor) {
+        self.track_cursor(|t| t.search_forward_inner(match_cursor))
+    }
+
+    #[cfg(feature = "search")]
+    fn search_forward_inner(&mut self, match_cursor: bool) -> bool {
+        let found = self.touch_render(|t| t.search.forward(&t.lines, t.cursor, match_cursor));
+        if let Some(cursor) = found {
+            if cursor.0 != self.cursor.0 {
+                self.push_jump_at(self.cursor);
+            }
             self.cursor = cursor;
             true
         } else {
@@ -2308,7 +7548,16 @@ impl<'a> TextArea<'a> {
     #[cfg(feature = "search")]
     #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
     pub fn search_back(&mut self, match_cursor: bool) -> bool {
-        if let Some(cursor) = self.search.back(&self.lines, self.cursor, match_cursor) {
+        self.track_cursor(|t| t.search_back_inner(match_cursor))
+    }
+
+    #[cfg(feature = "search")]
+    fn search_back_inner(&mut self, match_cursor: bool) -> bool {
+        let found = self.touch_render(|t| t.search.back(&t.lines, t.cursor, match_cursor));
+        if let Some(cursor) = found {
+            if cursor.0 != self.cursor.0 {
+                self.push_jump_at(self.cursor);
+            }
             self.cursor = cursor;
             true
         } else {
@@ -2316,6 +7565,163 @@ impl<'a> TextArea<'a> {
         }
     }
 
+    /// Set the direction of text search used by [`TextArea::search_next`] and [`TextArea::search_prev`]. The default
+    /// direction is [`SearchDirection::Forward`]. [`TextArea::search_forward`] and [`TextArea::search_back`] don't
+    /// change the direction.
+    ///
+    /// ```
+    /// use tui_textarea::{SearchDirection, TextArea};
+    ///
+    /// let mut textarea = TextArea::default();
+    /// assert_eq!(textarea.search_direction(), SearchDirection::Forward);
+    ///
+    /// textarea.set_search_direction(SearchDirection::Backward);
+    /// assert_eq!(textarea.search_direction(), SearchDirection::Backward);
+    /// ```
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn set_search_direction(&mut self, direction: SearchDirection) {
+        self.search.direction = direction;
+    }
+
+    /// Get the direction of text search set by [`TextArea::set_search_direction`].
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn search_direction(&self) -> SearchDirection {
+        self.search.direction
+    }
+
+    /// Move the cursor to the next match of the pattern set by [`TextArea::set_search_pattern`] in the direction set
+    /// by [`TextArea::set_search_direction`]. The match at the cursor position is ignored. This is equivalent to `n`
+    /// of Vim. It returns `true` when some match was found. Otherwise it returns `false`.
+    ///
+    /// ```
+    /// use tui_textarea::{SearchDirection, TextArea};
+    ///
+    /// let mut textarea = TextArea::from(["hello", "hello", "hello"]);
+    /// textarea.set_search_pattern("hello").unwrap();
+    ///
+    /// // `?hello` of Vim
+    /// textarea.set_search_direction(SearchDirection::Backward);
+    /// assert!(textarea.search_next());
+    /// assert_eq!(textarea.cursor(), (2, 0));
+    ///
+    /// // `n` repeats the search backward
+    /// textarea.search_next();
+    /// assert_eq!(textarea.cursor(), (1, 0));
+    ///
+    /// // `N` searches in the opposite direction
+    /// textarea.search_prev();
+    /// assert_eq!(textarea.cursor(), (2, 0));
+    /// ```
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn search_next(&mut self) -> bool {
+        match self.search.direction {
+            SearchDirection::Forward => self.search_forward(false),
+            SearchDirection::Backward => self.search_back(false),
+        }
+    }
+
+    /// Move the cursor to the next match of the pattern set by [`TextArea::set_search_pattern`] in the opposite
+    /// direction to the one set by [`TextArea::set_search_direction`]. The match at the cursor position is ignored.
+    /// This is equivalent to `N` of Vim. It returns `true` when some match was found. Otherwise it returns `false`.
+    /// See [`TextArea::search_next`] for the example.
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn search_prev(&mut self) -> bool {
+        match self.search.direction {
+            SearchDirection::Forward => self.search_back(false),
+            SearchDirection::Backward => self.search_forward(false),
+        }
+    }
+
+    /// Count the matches of the pattern set by [`TextArea::set_search_pattern`] in the text. This returns 0 when no
+    /// pattern is set.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["foo bar", "foo foo"]);
+    /// assert_eq!(textarea.count_matches(), 0);
+    ///
+    /// textarea.set_search_pattern("foo").unwrap();
+    /// assert_eq!(textarea.count_matches(), 3);
+    /// ```
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn count_matches(&self) -> usize {
+        self.lines
+            .iter()
+            .filter_map(|l| self.search.matches(l))
+            .map(Iterator::count)
+            .sum()
+    }
+
+    /// Visit the matches of the pattern set by [`TextArea::set_search_pattern`] from the head of the text and replace
+    /// them as the callback chooses. The callback receives each match with the text around it and returns
+    /// [`MatchAction`]. All the matches are found in the text before the replacements, and the replacements are
+    /// applied as one undo step. This is useful to implement interactive replacement like "replace? y/n/a/q". This
+    /// method returns the number of the replaced matches.
+    /// ```
+    /// use tui_textarea::{TextArea, MatchAction};
+    ///
+    /// let mut textarea = TextArea::from(["foo = 1", "foo = 2", "foo = 3"]);
+    /// textarea.set_search_pattern(r"\d").unwrap();
+    ///
+    /// let replaced = textarea.for_each_match(|m| {
+    ///     if m.line.starts_with("foo = 3") {
+    ///         MatchAction::Stop
+    ///     } else if m.text == "1" {
+    ///         MatchAction::Skip
+    ///     } else {
+    ///         MatchAction::Replace(format!("{}0", m.text))
+    ///     }
+    /// });
+    /// assert_eq!(replaced, 1);
+    /// assert_eq!(textarea.lines(), ["foo = 1", "foo = 20", "foo = 3"]);
+    ///
+    /// textarea.undo();
+    /// assert_eq!(textarea.lines(), ["foo = 1", "foo = 2", "foo = 3"]);
+    /// ```
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn for_each_match(&mut self, mut f: impl FnMut(MatchRef<'_>) -> MatchAction) -> usize {
+        let mut replacements = vec![];
+        let mut head = 0;
+        'lines: for (row, line) in self.lines.iter().enumerate() {
+            if let Some(matches) = self.search.matches(line) {
+                for (s, e) in matches {
+                    let start = line[..s].chars().count();
+                    let m = MatchRef {
+                        row,
+                        start,
+                        end: start + line[s..e].chars().count(),
+                        text: &line[s..e],
+                        line,
+                    };
+                    match f(m) {
+                        MatchAction::Replace(text) => replacements.push((head + s, head + e, text)),
+                        MatchAction::Skip => {}
+                        MatchAction::Stop => break 'lines,
+                    }
+                }
+            }
+            head += line.len() + 1;
+        }
+        if replacements.is_empty() {
+            return 0;
+        }
+        self.track_cursor(|t| {
+            t.with_history_group(|t| {
+                // Replace from the end so that the offsets of the remaining matches are not shifted
+                for (s, e, text) in replacements.iter().rev() {
+                    t.replace_text_range(*s, *e, text);
+                }
+            })
+        });
+        replacements.len()
+    }
+
     /// Get the text style at matches of text search. The default style is colored with blue in background.
     ///
     /// ```
@@ -2348,7 +7754,54 @@ impl<'a> TextArea<'a> {
     #[cfg(feature = "search")]
     #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
     pub fn set_search_style(&mut self, style: Style) {
-        self.search.style = style;
+        self.touch_render(|t| t.search.style = style);
+    }
+
+    /// Set the text style at the match which the cursor last jumped to by [`TextArea::search_forward`] or
+    /// [`TextArea::search_back`]. The other matches are highlighted with [`TextArea::search_style`]. This is similar to
+    /// `CurSearch` highlight group of Neovim. By default, the current match is not distinguished from the others.
+    ///
+    /// ```
+    /// use ratatui::style::{Style, Color};
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    /// assert_eq!(textarea.current_search_style(), None);
+    ///
+    /// let style = Style::default().bg(Color::Yellow);
+    /// textarea.set_current_search_style(style);
+    /// assert_eq!(textarea.current_search_style(), Some(style));
+    /// ```
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn set_current_search_style(&mut self, style: Style) {
+        self.touch_render(|t| t.search.current_style = Some(style));
+    }
+
+    /// Remove the style of the current match which was set by [`TextArea::set_current_search_style`]. After calling
+    /// this method, the current match is highlighted with the same style as the other matches.
+    ///
+    /// ```
+    /// use ratatui::style::{Style, Color};
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::default();
+    ///
+    /// textarea.set_current_search_style(Style::default().bg(Color::Yellow));
+    /// textarea.remove_current_search_style();
+    /// assert_eq!(textarea.current_search_style(), None);
+    /// ```
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn remove_current_search_style(&mut self) {
+        self.touch_render(|t| t.search.current_style = None);
+    }
+
+    /// Get the text style at the current match of text search if set.
+    #[cfg(feature = "search")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+    pub fn current_search_style(&self) -> Option<Style> {
+        self.search.current_style
     }
 
     /// Scroll the textarea. See [`Scrolling`] for the argument.
@@ -2390,6 +7843,10 @@ impl<'a> TextArea<'a> {
     /// assert_eq!(textarea.cursor(), (12, 0));
     /// ```
     pub fn scroll(&mut self, scrolling: impl Into<Scrolling>) {
+        self.track_cursor(|t| t.scroll_inner(scrolling))
+    }
+
+    fn scroll_inner(&mut self, scrolling: impl Into<Scrolling>) {
         self.scroll_with_shift(scrolling.into(), self.selection_start.is_some());
     }
 
@@ -2397,9 +7854,317 @@ impl<'a> TextArea<'a> {
         if shift && self.selection_start.is_none() {
             self.selection_start = Some(self.cursor);
         }
-        scrolling.scroll(&mut self.viewport);
+        scrolling.scroll(&mut self.viewport, &self.lines, self.page_overlap);
         self.move_cursor_with_shift(CursorMove::InViewport, shift);
     }
+
+    /// Set how many rows are kept visible across [`Scrolling::PageDown`] and [`Scrolling::PageUp`], like `-z-{n}`
+    /// option of `less`. Scrolling by one page then moves the viewport by the height minus the overlap so that the
+    /// last rows of the previous page are still shown as context. The viewport moves at least one row even when the
+    /// overlap is larger than the height. This also applies to page-wise scrolling by keys such as `PageDown` in
+    /// [`TextArea::input`]. The default value is 0.
+    ///
+    /// ```
+    /// # use ratatui::buffer::Buffer;
+    /// # use ratatui::layout::Rect;
+    /// # use ratatui::widgets::Widget as _;
+    /// use tui_textarea::{TextArea, Scrolling};
+    ///
+    /// // Let's say terminal height is 8.
+    ///
+    /// // Create textarea with 20 lines "0", "1", "2", "3", ...
+    /// let mut textarea: TextArea = (0..20).into_iter().map(|i| i.to_string()).collect();
+    /// # let r = Rect { x: 0, y: 0, width: 24, height: 8 };
+    /// # let mut b = Buffer::empty(r.clone());
+    /// # textarea.render(r, &mut b);
+    ///
+    /// // Keep 2 rows of context
+    /// textarea.set_page_overlap(2);
+    /// assert_eq!(textarea.page_overlap(), 2);
+    ///
+    /// textarea.scroll(Scrolling::PageDown);
+    /// assert_eq!(textarea.scroll_offset(), (6, 0));
+    /// textarea.scroll(Scrolling::PageUp);
+    /// assert_eq!(textarea.scroll_offset(), (0, 0));
+    /// ```
+    pub fn set_page_overlap(&mut self, rows: u16) {
+        self.page_overlap = rows;
+    }
+
+    /// Get how many rows are kept visible across page-wise scrolling. See [`TextArea::set_page_overlap`].
+    pub fn page_overlap(&self) -> u16 {
+        self.page_overlap
+    }
+
+    /// Set the scroll offset as (row, col) position of the top-left corner of the viewport. While [`TextArea::scroll`]
+    /// scrolls the textarea relatively, this method scrolls it to the absolute position. This is useful to restore the
+    /// exact viewport which was saved by [`TextArea::scroll_offset`]. The offset is saturated at [`u16::MAX`]. Like
+    /// [`TextArea::scroll`], the cursor position is adjusted to stay in the viewport using the same logic as
+    /// [`CursorMove::InViewport`].
+    ///
+    /// ```
+    /// # use ratatui::buffer::Buffer;
+    /// # use ratatui::layout::Rect;
+    /// # use ratatui::widgets::Widget as _;
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea: TextArea = (0..20).into_iter().map(|i| i.to_string()).collect();
+    /// # // Call `render` at least once to populate terminal size
+    /// # let r = Rect { x: 0, y: 0, width: 24, height: 8 };
+    /// # let mut b = Buffer::empty(r.clone());
+    /// # textarea.render(r, &mut b);
+    ///
+    /// textarea.set_scroll_offset(10, 0);
+    /// assert_eq!(textarea.scroll_offset(), (10, 0));
+    /// // The cursor was moved into the viewport
+    /// assert_eq!(textarea.cursor(), (10, 0));
+    /// ```
+    pub fn set_scroll_offset(&mut self, row: usize, col: usize) {
+        let row = cmp::min(row, u16::MAX as usize) as u16;
+        let col = cmp::min(col, u16::MAX as usize) as u16;
+        self.track_cursor(|t| {
+            t.viewport.set_scroll_top(row, col);
+            t.move_cursor_with_shift(CursorMove::InViewport, t.selection_start.is_some());
+        })
+    }
+
+    /// Get the scroll offset as (row, col) position of the top-left corner of the viewport. The offset is updated when
+    /// the textarea is rendered or scrolled. See [`TextArea::set_scroll_offset`] to restore it.
+    ///
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea: TextArea = (0..20).into_iter().map(|i| i.to_string()).collect();
+    /// assert_eq!(textarea.scroll_offset(), (0, 0));
+    ///
+    /// textarea.scroll((5, 2));
+    /// assert_eq!(textarea.scroll_offset(), (5, 2));
+    /// ```
+    pub fn scroll_offset(&self) -> (usize, usize) {
+        let (row, col) = self.viewport.scroll_top();
+        (row as usize, col as usize)
+    }
+
+    /// Get the (width, height) size of the area where the text was rendered last time. The size excludes the borders
+    /// and the padding of the block and the gutter of line numbers and diff signs. This is useful to calculate how many
+    /// rows and columns fit in the textarea for custom page-wise moves. The gutter width is calculated from the
+    /// current text. `None` is returned until the textarea is rendered into a non-empty area.
+    ///
+    /// Note that rendering the textarea as [`ratatui::widgets::StatefulWidget`] does not update the size. Use
+    /// [`TextAreaState::size`](crate::TextAreaState::size) instead.
+    ///
+    /// ```
+    /// # use ratatui::buffer::Buffer;
+    /// # use ratatui::layout::Rect;
+    /// # use ratatui::widgets::Widget as _;
+    /// use ratatui::widgets::{Block, Borders};
+    /// use ratatui::style::Style;
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["hello", "world"]);
+    /// assert_eq!(textarea.last_render_size(), None);
+    ///
+    /// textarea.set_block(Block::default().borders(Borders::ALL));
+    /// textarea.set_line_number_style(Style::default());
+    /// let r = Rect { x: 0, y: 0, width: 24, height: 8 };
+    /// let mut b = Buffer::empty(r.clone());
+    /// textarea.render(r, &mut b);
+    ///
+    /// // 2 cells for the borders and 3 cells for the line numbers are excluded
+    /// assert_eq!(textarea.last_render_size(), Some((19, 6)));
+    /// ```
+    pub fn last_render_size(&self) -> Option<(u16, u16)> {
+        let (_, _, width, height) = self.viewport.rect();
+        if width == 0 && height == 0 {
+            return None;
+        }
+        Some((width.saturating_sub(self.gutter_width()), height))
+    }
+
+    /// Move the cursor to the first non-blank character of the line. The line number `line` is 1-based like `:42`
+    /// command of Vim and is clamped to the range of the text. When `center` is `true`, the viewport is scrolled so
+    /// that the line is at the center of the textarea. Centering has no effect until the textarea is rendered once
+    /// since its height is unknown. The previous cursor position is recorded in the jumplist when the line changes.
+    ///
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["abc", "  def", "ghi"]);
+    ///
+    /// textarea.goto_line(2, false);
+    /// assert_eq!(textarea.cursor(), (1, 2));
+    ///
+    /// // The line number is clamped
+    /// textarea.goto_line(100, false);
+    /// assert_eq!(textarea.cursor(), (2, 0));
+    /// textarea.goto_line(0, false);
+    /// assert_eq!(textarea.cursor(), (0, 0));
+    /// ```
+    pub fn goto_line(&mut self, line: usize, center: bool) {
+        let row = cmp::min(line.saturating_sub(1), self.lines.len() - 1);
+        self.track_cursor(|t| {
+            let col = t.lines[row]
+                .chars()
+                .position(|c| !c.is_whitespace())
+                .unwrap_or(0);
+            if row != t.cursor.0 {
+                t.push_jump_at(t.cursor);
+            }
+            t.cursor = (row, col);
+        });
+        if center {
+            let (_, col, _, height) = self.viewport.rect();
+            let top = row.saturating_sub(height as usize / 2);
+            self.viewport
+                .set_scroll_top(cmp::min(top, u16::MAX as usize) as u16, col);
+        }
+    }
+
+    /// Move the cursor to the line at the percentage of the text like `{count}%` command of Vim. `percent` is clamped
+    /// to 100. See [`TextArea::goto_line`] for the `center` parameter and the cursor column.
+    ///
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let lines: Vec<_> = (1..=200).map(|i| i.to_string()).collect();
+    /// let mut textarea = TextArea::new(lines);
+    ///
+    /// textarea.goto_percentage(50, false);
+    /// assert_eq!(textarea.cursor(), (99, 0));
+    /// textarea.goto_percentage(100, false);
+    /// assert_eq!(textarea.cursor(), (199, 0));
+    /// textarea.goto_percentage(0, false);
+    /// assert_eq!(textarea.cursor(), (0, 0));
+    /// ```
+    pub fn goto_percentage(&mut self, percent: usize, center: bool) {
+        let percent = cmp::min(percent, 100);
+        let line = (percent * self.lines.len() + 99) / 100;
+        self.goto_line(line, center);
+    }
+
+    /// Check the internal invariants of the textarea and return the description of the first broken one as an error.
+    /// This is intended to be used with [`TextArea::apply_random_ops`] in property-based tests and fuzzing. The
+    /// following invariants are checked:
+    ///
+    /// - The text has at least one line and no line contains a newline
+    /// - The cursor, the text selection, the jumplist and anchored ranges point to valid positions in the text
+    /// - Line metadata is associated with lines in the text
+    /// - Undoing all edits in the history and redoing them again restores the current text
+    #[cfg(feature = "arbitrary")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "arbitrary")))]
+    pub fn check_invariants(&self) -> Result<(), String> {
+        if self.lines.is_empty() {
+            return Err("text has no line".to_string());
+        }
+        if let Some(row) = self.lines.iter().position(|l| l.contains('\n')) {
+            return Err(format!(
+                "line {} contains newline: {:?}",
+                row, self.lines[row]
+            ));
+        }
+        let in_text = |(row, col): (usize, usize)| {
+            self.lines
+                .get(row)
+                .map_or(false, |l| col <= l.chars().count())
+        };
+        if !in_text(self.cursor) {
+            return Err(format!("cursor is out of text: {:?}", self.cursor));
+        }
+        if let Some(start) = self.selection_start {
+            if !in_text(start) {
+                return Err(format!("selection start is out of text: {:?}", start));
+            }
+        }
+        let positions = self
+            .history
+            .jumps_positions()
+            .chain(self.history.anchors().positions())
+            .chain(self.history.conceals().positions())
+            .chain(
+                self.history
+                    .last_selection()
+                    .into_iter()
+                    .chain(self.history.selections())
+                    .flat_map(|(s, c)| [s, c]),
+            );
+        for pos in positions {
+            if !in_text((pos.row, pos.col)) || self.line_offset(pos.row, pos.col) != pos.offset {
+                return Err(format!("tracked position is invalid: {:?}", pos));
+            }
+        }
+        if let Some(row) = self
+            .history
+            .line_meta()
+            .rows()
+            .find(|&r| r >= self.lines.len())
+        {
+            return Err(format!("line metadata is out of text: {}", row));
+        }
+        let damaged = self.damaged_lines();
+        let sorted = damaged.windows(2).all(|w| w[0].end < w[1].start);
+        if !sorted
+            || damaged
+                .iter()
+                .any(|r| r.is_empty() || r.end > self.lines.len())
+        {
+            return Err(format!("damaged lines are invalid: {:?}", damaged));
+        }
+        if self.metrics != Metrics::new(&self.lines) {
+            return Err(format!("metrics is inconsistent: {:?}", self.metrics));
+        }
+        self.offsets.check(&self.lines, self.metrics.edits())?;
+
+        let mut t = self.clone();
+        t.on_cursor_change = None;
+        let mut undone = 0;
+        while t.undo() {
+            undone += 1;
+        }
+        for _ in 0..undone {
+            t.redo();
+        }
+        if t.lines != self.lines {
+            return Err(format!(
+                "undo and redo all edits did not restore the text: {:?}",
+                t.lines
+            ));
+        }
+        Ok(())
+    }
+}
+
+// Byte lengths of the open and close delimiters surrounding some text. `open` and `close` iterate characters from the
+// nearest ones to the text.
+fn find_delimiters(
+    mut open: impl Iterator<Item = char>,
+    mut close: impl Iterator<Item = char>,
+) -> Option<(usize, usize)> {
+    const BRACKETS: [(char, char); 4] = [('(', ')'), ('[', ']'), ('{', '}'), ('<', '>')];
+    let (o, c) = (open.next()?, close.next()?);
+    if BRACKETS.contains(&(o, c)) {
+        return Some((1, 1));
+    }
+    if o != c || !o.is_ascii_punctuation() || BRACKETS.iter().any(|&(a, b)| o == a || o == b) {
+        return None;
+    }
+    let len = 1 + cmp::min(
+        open.take_while(|&x| x == o).count(),
+        close.take_while(|&x| x == c).count(),
+    );
+    Some((len, len))
+}
+
+// Convert the byte offset in the text where lines are joined with newlines to the position
+fn pos_at_text_offset(lines: &[String], mut offset: usize) -> Pos {
+    for (row, line) in lines.iter().enumerate() {
+        if offset <= line.len() {
+            return Pos::new(row, line[..offset].chars().count(), offset);
+        }
+        offset -= line.len() + 1;
+    }
+    let row = lines.len() - 1;
+    let line = &lines[row];
+    Pos::new(row, line.chars().count(), line.len())
 }
 
 #[cfg(test)]
@@ -2430,4 +8195,105 @@ mod tests {
         textarea.scroll((-5, 0));
         assert_eq!(textarea.cursor(), (12, 0));
     }
+
+    #[test]
+    fn apply_remote_edit_keeps_scroll_position() {
+        let mut textarea: TextArea = (0..20).map(|i| i.to_string()).collect();
+        textarea.viewport.set_scroll_top(10, 0);
+        textarea.move_cursor(CursorMove::Jump(12, 0));
+
+        textarea.apply_remote_edit((0, 0), (0, 0), "a\nb\n");
+        assert_eq!(textarea.viewport.scroll_top(), (12, 0));
+        assert_eq!(textarea.cursor(), (14, 0));
+
+        textarea.apply_remote_edit((15, 0), (16, 0), "");
+        assert_eq!(textarea.viewport.scroll_top(), (12, 0));
+
+        textarea.apply_remote_edit((10, 0), (13, 0), "");
+        assert_eq!(textarea.viewport.scroll_top(), (10, 0));
+        assert_eq!(textarea.cursor(), (11, 0));
+    }
+
+    #[test]
+    fn render_inline_hint() {
+        use crate::ratatui::buffer::Buffer;
+        use crate::ratatui::layout::Rect;
+        use crate::ratatui::widgets::Widget as _;
+
+        let mut textarea = TextArea::from(["ab", "cd"]);
+        textarea.move_cursor(CursorMove::Forward);
+        let hint_style = Style::default().fg(Color::DarkGray);
+        textarea.set_inline_hint(Some(("xy".to_string(), hint_style)));
+
+        let r = Rect::new(0, 0, 6, 2);
+        let mut b = Buffer::empty(r);
+        textarea.render(r, &mut b);
+
+        let mut expected = Buffer::empty(r);
+        let line_style = textarea.cursor_line_style();
+        expected.set_string(0, 0, "a", line_style);
+        expected.set_string(1, 0, "x", hint_style.patch(textarea.cursor_style()));
+        expected.set_string(2, 0, "y", hint_style);
+        expected.set_string(3, 0, "b", line_style);
+        expected.set_string(0, 1, "cd", Style::default());
+        assert_eq!(b, expected);
+    }
+
+    #[test]
+    fn render_cursor_line_fullwidth() {
+        use crate::ratatui::buffer::Buffer;
+        use crate::ratatui::layout::Rect;
+        use crate::ratatui::widgets::Widget as _;
+
+        let mut textarea = TextArea::from(["ab", "cd"]);
+        let line_style = Style::default().bg(Color::DarkGray);
+        textarea.set_cursor_line_style(line_style);
+        textarea.set_cursor_line_fullwidth(true);
+        textarea.move_cursor(CursorMove::Forward);
+
+        let r = Rect::new(0, 0, 5, 2);
+        let mut b = Buffer::empty(r);
+        textarea.render(r, &mut b);
+
+        let mut expected = Buffer::empty(r);
+        expected.set_string(0, 0, "a", line_style);
+        expected.set_string(1, 0, "b", line_style.patch(textarea.cursor_style()));
+        expected.set_string(2, 0, "   ", line_style);
+        expected.set_string(0, 1, "cd", Style::default());
+        assert_eq!(b, expected);
+    }
+
+    #[test]
+    fn render_multi_line_highlight() {
+        use crate::ratatui::buffer::Buffer;
+        use crate::ratatui::layout::Rect;
+        use crate::ratatui::widgets::Widget as _;
+
+        let mut textarea = TextArea::from(["abc", "def", "ghi"]);
+        textarea.set_cursor_line_style(Style::default());
+        textarea.set_cursor_style(Style::default());
+        let style = Style::default().bg(Color::Red);
+        textarea.add_highlight(0, ((2, 1), (0, 2)), style, 0); // Reversed range is normalized
+
+        let r = Rect::new(0, 0, 3, 3);
+        let mut b = Buffer::empty(r);
+        textarea.render(r, &mut b);
+
+        let mut expected = Buffer::empty(r);
+        expected.set_string(0, 0, "ab", Style::default());
+        expected.set_string(2, 0, "c", style);
+        expected.set_string(0, 1, "def", style);
+        expected.set_string(0, 2, "g", style);
+        expected.set_string(1, 2, "hi", Style::default());
+        assert_eq!(b, expected);
+
+        assert!(textarea.remove_highlight(0));
+        let mut b = Buffer::empty(r);
+        textarea.render(r, &mut b);
+        let mut expected = Buffer::empty(r);
+        for (i, l) in ["abc", "def", "ghi"].iter().enumerate() {
+            expected.set_string(0, i as u16, l, Style::default());
+        }
+        assert_eq!(b, expected);
+    }
 }
@@ -0,0 +1,388 @@
+use crate::input::Input;
+use crate::ratatui::buffer::Buffer;
+use crate::ratatui::layout::Rect;
+use crate::ratatui::style::{Modifier, Style};
+use crate::ratatui::widgets::Widget;
+use crate::textarea::TextArea;
+#[cfg(feature = "file")]
+use std::io;
+use std::path::{Path, PathBuf};
+use unicode_width::UnicodeWidthStr as _;
+
+#[derive(Clone, Debug)]
+struct Entry<'a> {
+    textarea: TextArea<'a>,
+    path: Option<PathBuf>,
+}
+
+impl Entry<'_> {
+    fn label(&self) -> String {
+        let name = self
+            .path
+            .as_ref()
+            .and_then(|p| p.file_name())
+            .map(|n| n.to_string_lossy())
+            .unwrap_or_else(|| "[No Name]".into());
+        if self.textarea.is_modified() {
+            format!(" {} + ", name)
+        } else {
+            format!(" {} ", name)
+        }
+    }
+}
+
+/// A set of [`TextArea`] buffers with one active buffer, like buffers or tabs of text editors. Each buffer can have
+/// the path of the file it edits, and whether the buffer is modified is tracked by [`TextArea::is_modified`]. Rendering
+/// this type renders a tab bar with the buffer names at the top and the active buffer below it.
+/// ```
+/// use tui_textarea::{TextArea, TextAreaSet};
+///
+/// let mut set = TextAreaSet::default();
+/// set.push(TextArea::from(["foo"]), Some("foo.txt".into()));
+/// set.push(TextArea::from(["bar"]), Some("bar.txt".into()));
+/// assert_eq!(set.active_index(), Some(0));
+///
+/// set.switch_next();
+/// assert_eq!(set.active().unwrap().lines(), ["bar"]);
+/// assert_eq!(set.path(1).unwrap().to_str(), Some("bar.txt"));
+///
+/// set.active_mut().unwrap().insert_char('!');
+/// assert!(set.is_modified(1));
+/// assert!(set.any_modified());
+///
+/// // Closing the active buffer activates the next one (or the previous one for the last buffer)
+/// let closed = set.close(1).unwrap();
+/// assert_eq!(closed.lines(), ["!bar"]);
+/// assert_eq!(set.active_index(), Some(0));
+/// ```
+#[derive(Clone, Debug)]
+pub struct TextAreaSet<'a> {
+    entries: Vec<Entry<'a>>,
+    active: usize,
+    tab_bar_style: Option<Style>,
+    active_tab_style: Style,
+}
+
+impl<'a> Default for TextAreaSet<'a> {
+    fn default() -> Self {
+        Self {
+            entries: vec![],
+            active: 0,
+            tab_bar_style: Some(Style::default().add_modifier(Modifier::REVERSED)),
+            active_tab_style: Style::default().add_modifier(Modifier::BOLD),
+        }
+    }
+}
+
+impl<'a> TextAreaSet<'a> {
+    /// Add the textarea as a new buffer at the end. `path` is the path of the file which the buffer edits. This method
+    /// returns the index of the new buffer. The first buffer becomes active. Use [`TextAreaSet::switch_to`] to make
+    /// other buffers active.
+    pub fn push(&mut self, textarea: TextArea<'a>, path: Option<PathBuf>) -> usize {
+        self.entries.push(Entry { textarea, path });
+        self.entries.len() - 1
+    }
+
+    /// Open the file at the path as a new buffer with [`TextArea::open_file`] and make it active. When the file is
+    /// already opened in some buffer, the buffer is made active instead. When the file does not exist, an empty
+    /// buffer is created for the path. This method returns the index of the buffer.
+    #[cfg(feature = "file")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "file")))]
+    pub fn open_file(&mut self, path: impl AsRef<Path>) -> io::Result<usize> {
+        let path = path.as_ref();
+        if let Some(i) = self.position(path) {
+            self.active = i;
+            return Ok(i);
+        }
+        let textarea = if path.exists() {
+            TextArea::open_file(path)?
+        } else {
+            TextArea::default()
+        };
+        self.active = self.push(textarea, Some(path.to_path_buf()));
+        Ok(self.active)
+    }
+
+    /// Write the buffer at the index to its file with [`TextArea::save_file`]. An error is returned when the index is
+    /// out of range or the buffer has no path.
+    #[cfg(feature = "file")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "file")))]
+    pub fn save(&mut self, index: usize) -> io::Result<()> {
+        let entry = match self.entries.get_mut(index) {
+            Some(entry) => entry,
+            None => return Err(io::Error::new(io::ErrorKind::NotFound, "no such buffer")),
+        };
+        match &entry.path {
+            Some(path) => entry.textarea.save_file(path),
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "buffer has no file path",
+            )),
+        }
+    }
+
+    /// Get the index of the buffer which edits the file at the path.
+    pub fn position(&self, path: impl AsRef<Path>) -> Option<usize> {
+        let path = path.as_ref();
+        self.entries
+            .iter()
+            .position(|e| e.path.as_deref() == Some(path))
+    }
+
+    /// Remove the buffer at the index and return its textarea. When the active buffer is closed, the next buffer
+    /// becomes active, or the previous buffer when it was the last one. `None` is returned when the index is out of
+    /// range.
+    pub fn close(&mut self, index: usize) -> Option<TextArea<'a>> {
+        if index >= self.entries.len() {
+            return None;
+        }
+        let entry = self.entries.remove(index);
+        if index < self.active || self.active == self.entries.len() {
+            self.active = self.active.saturating_sub(1);
+        }
+        Some(entry.textarea)
+    }
+
+    /// Get the number of buffers.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Return if this set has no buffer.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Get the index of the active buffer. `None` is returned when this set is empty.
+    pub fn active_index(&self) -> Option<usize> {
+        (!self.entries.is_empty()).then(|| self.active)
+    }
+
+    /// Get the textarea of the active buffer.
+    pub fn active(&self) -> Option<&TextArea<'a>> {
+        self.entries.get(self.active).map(|e| &e.textarea)
+    }
+
+    /// Get the textarea of the active buffer mutably.
+    pub fn active_mut(&mut self) -> Option<&mut TextArea<'a>> {
+        self.entries.get_mut(self.active).map(|e| &mut e.textarea)
+    }
+
+    /// Get the textarea of the buffer at the index.
+    pub fn get(&self, index: usize) -> Option<&TextArea<'a>> {
+        self.entries.get(index).map(|e| &e.textarea)
+    }
+
+    /// Get the textarea of the buffer at the index mutably.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut TextArea<'a>> {
+        self.entries.get_mut(index).map(|e| &mut e.textarea)
+    }
+
+    /// Iterate the textareas of all buffers.
+    pub fn iter(&self) -> impl Iterator<Item = &TextArea<'a>> {
+        self.entries.iter().map(|e| &e.textarea)
+    }
+
+    /// Get the file path of the buffer at the index.
+    pub fn path(&self, index: usize) -> Option<&Path> {
+        self.entries.get(index)?.path.as_deref()
+    }
+
+    /// Set the file path of the buffer at the index. This is useful to implement 'save as'. This method returns
+    /// `false` when the index is out of range.
+    pub fn set_path(&mut self, index: usize, path: impl Into<PathBuf>) -> bool {
+        match self.entries.get_mut(index) {
+            Some(entry) => {
+                entry.path = Some(path.into());
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Return if the buffer at the index has modifications which are not saved yet. See [`TextArea::is_modified`].
+    pub fn is_modified(&self, index: usize) -> bool {
+        self.entries
+            .get(index)
+            .map_or(false, |e| e.textarea.is_modified())
+    }
+
+    /// Return if some buffer has modifications which are not saved yet. This is useful to confirm before quitting.
+    pub fn any_modified(&self) -> bool {
+        self.entries.iter().any(|e| e.textarea.is_modified())
+    }
+
+    /// Make the buffer at the index active. This method returns `false` when the index is out of range.
+    pub fn switch_to(&mut self, index: usize) -> bool {
+        if index >= self.entries.len() {
+            return false;
+        }
+        self.active = index;
+        true
+    }
+
+    /// Make the next buffer active. After the last buffer, the first buffer becomes active. This method returns
+    /// `false` when this set is empty.
+    pub fn switch_next(&mut self) -> bool {
+        if self.entries.is_empty() {
+            return false;
+        }
+        self.active = (self.active + 1) % self.entries.len();
+        true
+    }
+
+    /// Make the previous buffer active. Before the first buffer, the last buffer becomes active. This method returns
+    /// `false` when this set is empty.
+    pub fn switch_prev(&mut self) -> bool {
+        if self.entries.is_empty() {
+            return false;
+        }
+        self.active = self.active.checked_sub(1).unwrap_or(self.entries.len() - 1);
+        true
+    }
+
+    /// Handle a key input with the active buffer. See [`TextArea::input`]. This method returns if the input modified
+    /// the text or not.
+    pub fn input(&mut self, input: impl Into<Input>) -> bool {
+        match self.active_mut() {
+            Some(textarea) => textarea.input(input),
+            None => false,
+        }
+    }
+
+    /// Set the style of the tab bar rendered at the top. The default style is reversed.
+    pub fn set_tab_bar_style(&mut self, style: Style) {
+        self.tab_bar_style = Some(style);
+    }
+
+    /// Hide the tab bar. Only the active buffer is rendered.
+    pub fn remove_tab_bar(&mut self) {
+        self.tab_bar_style = None;
+    }
+
+    /// Get the style of the tab bar. `None` is returned when the tab bar is hidden.
+    pub fn tab_bar_style(&self) -> Option<Style> {
+        self.tab_bar_style
+    }
+
+    /// Set the style of the tab of the active buffer. It is applied on top of the tab bar style. The default style is
+    /// bold.
+    pub fn set_active_tab_style(&mut self, style: Style) {
+        self.active_tab_style = style;
+    }
+
+    /// Get the style of the tab of the active buffer.
+    pub fn active_tab_style(&self) -> Style {
+        self.active_tab_style
+    }
+
+    fn render_tab_bar(&self, area: Rect, style: Style, buf: &mut Buffer) {
+        buf.set_style(area, style);
+        let labels: Vec<_> = self.entries.iter().map(Entry::label).collect();
+        // Skip the leading tabs so that the active tab is visible
+        let mut first = 0;
+        while first < self.active
+            && labels[first..=self.active]
+                .iter()
+                .map(|l| l.width())
+                .sum::<usize>()
+                > area.width as usize
+        {
+            first += 1;
+        }
+        let (mut x, right) = (area.x, area.x + area.width);
+        for (i, label) in labels.iter().enumerate().skip(first) {
+            if x >= right {
+                break;
+            }
+            let style = if i == self.active {
+                style.patch(self.active_tab_style)
+            } else {
+                style
+            };
+            x = buf
+                .set_stringn(x, area.y, label, (right - x) as usize, style)
+                .0;
+        }
+    }
+}
+
+impl Widget for &TextAreaSet<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let textarea = match self.active() {
+            Some(textarea) => textarea,
+            None => return,
+        };
+        let area = match self.tab_bar_style {
+            Some(style) if area.height > 1 => {
+                self.render_tab_bar(Rect { height: 1, ..area }, style, buf);
+                Rect {
+                    y: area.y + 1,
+                    height: area.height - 1,
+                    ..area
+                }
+            }
+            _ => area,
+        };
+        textarea.render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rendered(buf: &Buffer) -> Vec<String> {
+        let area = buf.area;
+        (area.top()..area.bottom())
+            .map(|y| {
+                (area.left()..area.right())
+                    .map(|x| {
+                        #[cfg(feature = "ratatui")]
+                        let symbol = buf[(x, y)].symbol();
+                        #[cfg(feature = "tuirs")]
+                        let symbol = buf.get(x, y).symbol.as_str();
+                        symbol
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn render_tab_bar() {
+        let mut set = TextAreaSet::default();
+        set.push(TextArea::from(["foo"]), Some("dir/a.rs".into()));
+        set.push(TextArea::from(["bar"]), None);
+        set.get_mut(1).unwrap().insert_char('!');
+        set.switch_to(1);
+
+        let area = Rect::new(0, 0, 24, 2);
+        let mut buf = Buffer::empty(area);
+        set.render(area, &mut buf);
+        assert_eq!(
+            rendered(&buf),
+            [" a.rs  [No Name] +      ", "!bar                    "],
+        );
+        #[cfg(feature = "ratatui")]
+        let (tab, active) = (&buf[(0, 0)], &buf[(6, 0)]);
+        #[cfg(feature = "tuirs")]
+        let (tab, active) = (buf.get(0, 0), buf.get(6, 0));
+        assert!(tab.modifier.contains(Modifier::REVERSED));
+        assert!(!tab.modifier.contains(Modifier::BOLD));
+        assert!(active.modifier.contains(Modifier::BOLD));
+
+        // The leading tabs are skipped to show the active tab
+        let area = Rect::new(0, 0, 14, 1);
+        let mut buf = Buffer::empty(area);
+        set.render_tab_bar(area, Style::default(), &mut buf);
+        assert_eq!(rendered(&buf), [" [No Name] +  "]);
+
+        // Without the tab bar
+        set.remove_tab_bar();
+        let area = Rect::new(0, 0, 6, 1);
+        let mut buf = Buffer::empty(area);
+        set.render(area, &mut buf);
+        assert_eq!(rendered(&buf), ["!bar  "]);
+    }
+}
@@ -1,13 +1,33 @@
-pub fn spaces(size: u8) -> &'static str {
+use std::borrow::Cow;
+use std::time::Instant;
+
+// Current time. `Instant::now` panics on wasm32-unknown-unknown since the target has no clock, so `None` is returned on
+// the target instead. Features depending on the time are disabled there.
+pub fn now() -> Option<Instant> {
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    {
+        Some(Instant::now())
+    }
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    {
+        None
+    }
+}
+
+// Spaces of the size. A static string is returned unless the size is very large.
+pub fn spaces(size: usize) -> Cow<'static, str> {
     const SPACES: &str = "                                                                                                                                                                                                                                                                ";
-    &SPACES[..size as usize]
+    match SPACES.get(..size) {
+        Some(s) => Cow::Borrowed(s),
+        None => Cow::Owned(" ".repeat(size)),
+    }
 }
 
 pub fn num_digits(i: usize) -> u8 {
     f64::log10(i as f64) as u8 + 1
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Hash)]
 pub struct Pos {
     pub row: usize,
     pub col: usize,
@@ -19,3 +39,110 @@ impl Pos {
         Self { row, col, offset }
     }
 }
+
+// Cache of the last column converted to a byte offset in a line. Edits at the cursor convert nearby columns of the same
+// line repeatedly, so scanning from the cached position keeps them fast even in a very long line. The cache is bound
+// to the number of edits applied to the text so that any edit invalidates it.
+#[derive(Debug, Clone, Default)]
+pub struct OffsetCache {
+    edits: u64,
+    pos: Option<Pos>,
+}
+
+impl OffsetCache {
+    pub fn set(&mut self, edits: u64, pos: Pos) {
+        self.edits = edits;
+        self.pos = Some(pos);
+    }
+
+    // Byte offset of the character at the column. The end of line is returned when the column is beyond it.
+    pub fn offset(&mut self, line: &str, edits: u64, row: usize, col: usize) -> usize {
+        let (start_col, start_offset) = match &self.pos {
+            Some(p) if self.edits == edits && p.row == row => (p.col, p.offset),
+            _ => (0, 0),
+        };
+
+        let pos = if col < start_col && start_col - col < col {
+            // Scan backward from the cached position when it is closer than the head of line
+            let mut c = start_col;
+            let offset = line[..start_offset]
+                .char_indices()
+                .rev()
+                .find(|_| {
+                    c -= 1;
+                    c == col
+                })
+                .map_or(0, |(i, _)| i);
+            Pos::new(row, col, offset)
+        } else {
+            let (mut c, base) = if col < start_col {
+                (0, 0)
+            } else {
+                (start_col, start_offset)
+            };
+            // When the column is beyond the end of line, the end of line is cached with its actual column
+            let mut offset = line.len();
+            for (i, _) in line[base..].char_indices() {
+                if c == col {
+                    offset = base + i;
+                    break;
+                }
+                c += 1;
+            }
+            Pos::new(row, c, offset)
+        };
+
+        let offset = pos.offset;
+        self.set(edits, pos);
+        offset
+    }
+
+    #[cfg(feature = "arbitrary")]
+    pub fn check(&self, lines: &[String], edits: u64) -> Result<(), String> {
+        match &self.pos {
+            Some(p) if self.edits == edits => {
+                let valid = lines.get(p.row).map_or(false, |l| {
+                    l.char_indices().nth(p.col).map_or(l.len(), |(i, _)| i) == p.offset
+                        && p.col <= l.chars().count()
+                });
+                if valid {
+                    Ok(())
+                } else {
+                    Err(format!("cached offset is invalid: {:?}", p))
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offset_cache() {
+        let line = "aあbいc";
+        let mut cache = OffsetCache::default();
+        let tests = [
+            (0, 0, 0),
+            (0, 3, 5),
+            (0, 4, 8),
+            (0, 1, 1),
+            (0, 5, 9),
+            (0, 9, 9),
+            (0, 2, 4),
+            (1, 2, 4),
+            (0, 0, 0),
+        ];
+        for (edits, col, expected) in tests {
+            assert_eq!(cache.offset(line, edits, 0, col), expected, "{}", col);
+        }
+
+        // The cached position is not used for other lines
+        assert_eq!(cache.offset("abc", 1, 1, 2), 2);
+        // The cached position is not used after edits
+        cache.set(1, Pos::new(0, 5, 9));
+        assert_eq!(cache.offset("あいうえおか", 2, 0, 4), 12);
+    }
+}
@@ -0,0 +1,1099 @@
+use crate::cursor::CursorMove;
+use crate::input::{Input, Key};
+use crate::scroll::Scrolling;
+use crate::textarea::TextArea;
+use crate::word::find_word_range;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::cmp;
+use std::collections::HashMap;
+use std::fmt;
+
+// Maximum length of the text put by `p` or `P` at once. This is the same as the maximum column of Vim
+const MAX_PUT_LEN: usize = i32::MAX as usize;
+
+/// Mode of Vim emulation by [`VimState`].
+///
+/// This type is marked as `#[non_exhaustive]` since more modes may be supported in the future.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum VimMode {
+    /// Normal mode.
+    Normal,
+    /// Insert mode. Keys are handled by [`TextArea::input`] except for `Esc` and `Ctrl+C`.
+    Insert,
+    /// Character-wise visual mode started by `v`.
+    Visual,
+    /// Line-wise visual mode started by `V`.
+    VisualLine,
+    /// Operator-pending mode waiting for a motion or a text object after the operator (`d`, `c`, or `y`).
+    Operator(char),
+}
+
+impl fmt::Display for VimMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Normal => write!(f, "NORMAL"),
+            Self::Insert => write!(f, "INSERT"),
+            Self::Visual => write!(f, "VISUAL"),
+            Self::VisualLine => write!(f, "VISUAL LINE"),
+            Self::Operator(c) => write!(f, "OPERATOR({})", c),
+        }
+    }
+}
+
+// Key sequence waiting for the next key
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Pending {
+    None,
+    G,                // `g` of `gg`
+    Register,         // `"` to select a register
+    Find(char),       // `f`, `F`, `t`, or `T` waiting for the target character
+    Replace,          // `r` waiting for the new character
+    TextObject(bool), // `i` (`true`) or `a` (`false`) waiting for the kind of text object
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Motion {
+    Left,
+    Right,
+    Up,
+    Down,
+    DownFirstNonBlank, // `+`
+    UpFirstNonBlank,   // `-`
+    WordForward,
+    WordEnd,
+    WordBack,
+    WordEndBack,        // `ge`
+    WordSpacingEndBack, // `gE`
+    Head,
+    FirstNonBlank,
+    End,
+    Top,    // `gg`
+    Bottom, // `G`
+    ParagraphForward,
+    ParagraphBack,
+    Find(char, char, bool), // Kind of the find motion, the target character, and if it is repeated by `;` or `,`
+}
+
+impl Motion {
+    fn linewise(self) -> bool {
+        matches!(
+            self,
+            Self::Up
+                | Self::Down
+                | Self::DownFirstNonBlank
+                | Self::UpFirstNonBlank
+                | Self::Top
+                | Self::Bottom
+        )
+    }
+
+    fn inclusive(self) -> bool {
+        matches!(
+            self,
+            Self::WordEnd
+                | Self::WordEndBack
+                | Self::WordSpacingEndBack
+                | Self::End
+                | Self::Find('f' | 't', _, _)
+        )
+    }
+
+    // Move the cursor by the motion `count` times. `count` is `None` when no count was typed
+    fn apply(self, t: &mut TextArea<'_>, count: Option<usize>) {
+        let n = count.unwrap_or(1);
+        let (row, col) = t.cursor();
+        match self {
+            Self::Left => jump(t, row, col.saturating_sub(n)),
+            Self::Right => jump(t, row, cmp::min(col.saturating_add(n), line_len(t, row))),
+            Self::Up => repeat(t, CursorMove::Up, n),
+            Self::Down => repeat(t, CursorMove::Down, n),
+            Self::DownFirstNonBlank => repeat(t, CursorMove::DownToFirstNonSpace, n),
+            Self::UpFirstNonBlank => repeat(t, CursorMove::UpToFirstNonSpace, n),
+            Self::WordForward => repeat(t, CursorMove::WordForward, n),
+            Self::WordEnd => repeat(t, CursorMove::WordEnd, n),
+            Self::WordBack => repeat(t, CursorMove::WordBack, n),
+            Self::WordEndBack => repeat(t, CursorMove::WordEndBack, n),
+            Self::WordSpacingEndBack => repeat(t, CursorMove::WordSpacingEndBack, n),
+            Self::Head => t.move_cursor(CursorMove::Head),
+            Self::FirstNonBlank => jump(t, row, first_non_blank(t, row)),
+            Self::End => {
+                repeat(t, CursorMove::Down, n - 1);
+                let row = t.cursor().0;
+                jump(t, row, line_len(t, row).saturating_sub(1));
+            }
+            Self::Top | Self::Bottom => {
+                let row = match count {
+                    Some(n) => n - 1,
+                    None if self == Self::Top => 0,
+                    None => t.lines().len() - 1,
+                };
+                let row = cmp::min(row, t.lines().len() - 1);
+                if row != t.cursor().0 {
+                    t.push_jump();
+                }
+                jump(t, row, first_non_blank(t, row));
+            }
+            Self::ParagraphForward => repeat(t, CursorMove::ParagraphForward, n),
+            Self::ParagraphBack => repeat(t, CursorMove::ParagraphBack, n),
+            Self::Find(kind, target, repeated) => {
+                if let Some(col) = find_char(&t.lines()[row], col, kind, target, n, repeated) {
+                    jump(t, row, col);
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct Register {
+    text: String,
+    linewise: bool,
+}
+
+fn jump(t: &mut TextArea<'_>, row: usize, col: usize) {
+    t.jump_keeping_jumps(row, col);
+}
+
+// Move the cursor `count` times. Stop when the cursor no longer moves since the count can be very large
+fn repeat(t: &mut TextArea<'_>, m: CursorMove, count: usize) {
+    for _ in 0..count {
+        let cursor = t.cursor();
+        t.move_cursor(m);
+        if t.cursor() == cursor {
+            break;
+        }
+    }
+}
+
+// Repeat the operation `count` times until it fails
+fn repeat_while(count: usize, mut f: impl FnMut() -> bool) {
+    for _ in 0..count {
+        if !f() {
+            break;
+        }
+    }
+}
+
+fn line_len(t: &TextArea<'_>, row: usize) -> usize {
+    t.lines()[row].chars().count()
+}
+
+fn first_non_blank(t: &TextArea<'_>, row: usize) -> usize {
+    t.lines()[row]
+        .chars()
+        .position(|c| !c.is_whitespace())
+        .unwrap_or(0)
+}
+
+// Position next to the given position. The position next to the end of line is the head of the next line
+fn next_pos(t: &TextArea<'_>, (row, col): (usize, usize)) -> (usize, usize) {
+    if col < line_len(t, row) {
+        (row, col + 1)
+    } else if row + 1 < t.lines().len() {
+        (row + 1, 0)
+    } else {
+        (row, col)
+    }
+}
+
+// Column of the `count`-th target character found by `f`, `F`, `t`, or `T` in the line
+fn find_char(
+    line: &str,
+    col: usize,
+    kind: char,
+    target: char,
+    count: usize,
+    repeated: bool,
+) -> Option<usize> {
+    let chars: Vec<char> = line.chars().collect();
+    // Repeated `t` and `T` don't match the character next to the cursor not to get stuck
+    let skip = repeated && (kind == 't' || kind == 'T');
+    let found = match kind {
+        'f' | 't' => {
+            let start = if skip { col + 2 } else { col + 1 };
+            (start..chars.len())
+                .filter(|&i| chars[i] == target)
+                .nth(count - 1)?
+        }
+        _ => {
+            let end = if skip { col.saturating_sub(1) } else { col };
+            (0..end)
+                .rev()
+                .filter(|&i| chars[i] == target)
+                .nth(count - 1)?
+        }
+    };
+    match kind {
+        't' => Some(found - 1),
+        'T' => Some(found + 1),
+        _ => Some(found),
+    }
+}
+
+type Range = ((usize, usize), (usize, usize));
+
+// Range of the brackets pair surrounding the cursor. The end position is exclusive.
+fn bracket_object(t: &TextArea<'_>, open: char, close: char, inner: bool) -> Option<Range> {
+    // Flatten the text into characters with their positions. Newlines are at the end of each line
+    let mut chars = vec![];
+    for (row, line) in t.lines().iter().enumerate() {
+        for (col, c) in line.chars().chain(Some('\n')).enumerate() {
+            chars.push(((row, col), c));
+        }
+    }
+    let cursor = t.cursor();
+    let idx = chars.iter().position(|(p, _)| *p == cursor)?;
+
+    let mut depth = 0;
+    let mut start = None;
+    for i in (0..=idx).rev() {
+        let c = chars[i].1;
+        if c == close && i != idx {
+            depth += 1;
+        } else if c == open {
+            if depth == 0 {
+                start = Some(i);
+                break;
+            }
+            depth -= 1;
+        }
+    }
+    let start = start?;
+
+    let mut depth = 0;
+    let mut end = None;
+    for (i, (_, c)) in chars.iter().enumerate().skip(start + 1) {
+        if *c == open {
+            depth += 1;
+        } else if *c == close {
+            if depth == 0 {
+                end = Some(i);
+                break;
+            }
+            depth -= 1;
+        }
+    }
+    let end = end?;
+
+    if inner {
+        let mut end_pos = chars[end].0;
+        // When the brackets are on their own lines, the inner lines are the range like Vim
+        if chars[start + 1].1 == '\n'
+            && t.lines()[end_pos.0]
+                .chars()
+                .take(end_pos.1)
+                .all(char::is_whitespace)
+        {
+            end_pos.1 = 0;
+            let (row, _) = chars[start].0;
+            return Some(((row + 1, 0), end_pos));
+        }
+        Some((chars[start + 1].0, end_pos))
+    } else {
+        let (row, col) = chars[end].0;
+        Some((chars[start].0, (row, col + 1)))
+    }
+}
+
+// Range of the quoted string at the cursor in the cursor line. The end position is exclusive.
+fn quote_object(t: &TextArea<'_>, quote: char, inner: bool) -> Option<Range> {
+    let (row, col) = t.cursor();
+    let chars: Vec<char> = t.lines()[row].chars().collect();
+    let quotes: Vec<usize> = (0..chars.len())
+        .filter(|&i| chars[i] == quote && (i == 0 || chars[i - 1] != '\\'))
+        .collect();
+    let (start, end) = quotes
+        .chunks_exact(2)
+        .map(|p| (p[0], p[1]))
+        .find(|&(_, e)| col <= e)?;
+    if inner {
+        Some(((row, start + 1), (row, end)))
+    } else {
+        Some(((row, start), (row, end + 1)))
+    }
+}
+
+// Range of the word at the cursor. `aw` also includes the spaces after the word, or the spaces before the word when no
+// space follows it.
+fn word_object(t: &TextArea<'_>, inner: bool) -> Option<Range> {
+    let (row, col) = t.cursor();
+    let line = &t.lines()[row];
+    let (start, end) = find_word_range(line, col, t.words())?;
+    if inner {
+        return Some(((row, start), (row, end)));
+    }
+    let chars: Vec<char> = line.chars().collect();
+    let is_space = |i: usize| chars.get(i).map_or(false, |c| c.is_whitespace());
+    let (mut start, mut end) = (start, end);
+    if is_space(start) {
+        // On spaces, the word after the spaces is included
+        if let Some((_, e)) = find_word_range(line, end, t.words()) {
+            end = e;
+        }
+    } else if is_space(end) {
+        end = find_word_range(line, end, t.words()).map_or(end, |(_, e)| e);
+    } else {
+        while start > 0 && is_space(start - 1) {
+            start -= 1;
+        }
+    }
+    Some(((row, start), (row, end)))
+}
+
+/// State machine of Vim emulation on [`TextArea`]. Keys are handled by [`VimState::input`] like Vim, with modes,
+/// counts, operators, registers, and text objects. This type is available when `vim` feature is enabled.
+///
+/// The following keys are supported:
+///
+/// - Motions: `h`, `j`, `k`, `l`, `+`, `-`, `w`, `b`, `e`, `ge`, `gE`, `0`, `^`, `$`, `gg`, `G`, `{`, `}`, `f`, `F`,
+///   `t`, `T`, `;`, `,`, and arrow keys. They can be prefixed with a count such as `3w`
+/// - Operators: `d`, `c`, `y` followed by a motion or a text object (e.g. `d2w`, `ci(`), and `dd`, `cc`, `yy`
+/// - Text objects: `iw`, `aw`, `i(`, `a(`, `ib`, `ab`, `i[`, `a[`, `i{`, `a{`, `iB`, `aB`, `i<`, `a<`, `i"`, `a"`,
+///   `i'`, `a'`, `` i` ``, `` a` ``
+/// - Editing: `x`, `X`, `D`, `C`, `s`, `S`, `Y`, `p`, `P`, `J`, `r`, `~`, `u`, `Ctrl+R`
+/// - Insert mode: `i`, `a`, `I`, `A`, `o`, `O`, and `Esc` or `Ctrl+C` to go back to normal mode
+/// - Visual mode: `v`, `V`, and `d`, `x`, `c`, `y`, `D`, `Y` on the selection
+/// - Registers: `"` followed by a register name. `a`-`z` are named registers, `A`-`Z` append to the named registers,
+///   `0` is the last yanked text, `_` is the black hole register, and `"` is the unnamed register
+/// - Scrolling: `Ctrl+E`, `Ctrl+Y`, `Ctrl+D`, `Ctrl+U`, `Ctrl+F`, `Ctrl+B`
+/// - Jumplist: `Ctrl+O`, `Ctrl+I` (see [`TextArea::jump_back`])
+///
+/// ```
+/// use tui_textarea::{TextArea, VimState, VimMode, Input, Key};
+///
+/// fn key(c: char) -> Input {
+///     Input { key: Key::Char(c), ..Default::default() }
+/// }
+///
+/// let mut textarea = TextArea::from(["hello world", "goodbye"]);
+/// let mut vim = VimState::new();
+///
+/// // Delete the word with `dw`
+/// for c in ['d', 'w'] {
+///     vim.input(&mut textarea, key(c));
+/// }
+/// assert_eq!(textarea.lines(), ["world", "goodbye"]);
+/// assert_eq!(vim.register('"'), Some("hello "));
+///
+/// // Insert text
+/// vim.input(&mut textarea, key('i'));
+/// assert_eq!(vim.mode(), VimMode::Insert);
+/// vim.input(&mut textarea, key('!'));
+/// vim.input(&mut textarea, Input { key: Key::Esc, ..Default::default() });
+/// assert_eq!(vim.mode(), VimMode::Normal);
+/// assert_eq!(textarea.lines(), ["!world", "goodbye"]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct VimState {
+    mode: VimMode,
+    pending: Pending,
+    count: Option<usize>,
+    op_count: Option<usize>, // Count typed before the operator
+    register: Option<char>,
+    registers: HashMap<char, Register>,
+    last_find: Option<(char, char)>,
+    visual_anchor: usize,      // The row where line-wise visual mode started
+    selection_inclusive: bool, // Setting of the textarea before entering visual mode
+}
+
+impl Default for VimState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VimState {
+    /// Create a new [`VimState`] instance in normal mode.
+    pub fn new() -> Self {
+        Self {
+            mode: VimMode::Normal,
+            pending: Pending::None,
+            count: None,
+            op_count: None,
+            register: None,
+            registers: HashMap::new(),
+            last_find: None,
+            visual_anchor: 0,
+            selection_inclusive: false,
+        }
+    }
+
+    /// Get the current mode.
+    pub fn mode(&self) -> VimMode {
+        self.mode
+    }
+
+    /// Get if a key sequence such as a count, a register name, or `g` of `gg` is being typed.
+    /// ```
+    /// use tui_textarea::{TextArea, VimState, Input, Key};
+    ///
+    /// let mut textarea = TextArea::default();
+    /// let mut vim = VimState::new();
+    ///
+    /// vim.input(&mut textarea, Input { key: Key::Char('2'), ..Default::default() });
+    /// assert!(vim.is_pending());
+    /// ```
+    pub fn is_pending(&self) -> bool {
+        self.pending != Pending::None || self.count.is_some() || self.register.is_some()
+    }
+
+    /// Get the text in the register. Line-wise text does not end with a newline.
+    pub fn register(&self, name: char) -> Option<&str> {
+        let name = if name.is_ascii_uppercase() {
+            name.to_ascii_lowercase()
+        } else {
+            name
+        };
+        self.registers.get(&name).map(|r| r.text.as_str())
+    }
+
+    /// Set the character-wise text to the register.
+    /// ```
+    /// use tui_textarea::{TextArea, VimState, Input, Key};
+    ///
+    /// let mut textarea = TextArea::default();
+    /// let mut vim = VimState::new();
+    ///
+    /// vim.set_register('a', "hello");
+    /// for c in ['"', 'a', 'P'] {
+    ///     vim.input(&mut textarea, Input { key: Key::Char(c), ..Default::default() });
+    /// }
+    /// assert_eq!(textarea.lines(), ["hello"]);
+    /// ```
+    pub fn set_register(&mut self, name: char, text: impl Into<String>) {
+        let text = text.into();
+        self.registers.insert(
+            name.to_ascii_lowercase(),
+            Register {
+                text,
+                linewise: false,
+            },
+        );
+    }
+
+    /// Handle a key input on the textarea. This method returns if the text was modified or not.
+    pub fn input(&mut self, textarea: &mut TextArea<'_>, input: impl Into<Input>) -> bool {
+        let revision = textarea.revision();
+        self.handle_input(textarea, input.into());
+        textarea.revision() != revision
+    }
+
+    fn handle_input(&mut self, t: &mut TextArea<'_>, input: Input) {
+        if input.key == Key::Null {
+            return;
+        }
+
+        if self.mode == VimMode::Insert {
+            match input {
+                Input { key: Key::Esc, .. }
+                | Input {
+                    key: Key::Char('c'),
+                    ctrl: true,
+                    ..
+                } => {
+                    t.move_cursor(CursorMove::Back);
+                    self.set_mode(t, VimMode::Normal);
+                }
+                input => {
+                    t.input(input);
+                }
+            }
+            return;
+        }
+
+        if input.key == Key::Esc {
+            self.reset();
+            self.set_mode(t, VimMode::Normal);
+            return;
+        }
+
+        let pending = std::mem::replace(&mut self.pending, Pending::None);
+        if pending != Pending::None {
+            if let Input {
+                key: Key::Char(c),
+                ctrl: false,
+                alt: false,
+                ..
+            } = input
+            {
+                self.handle_pending(t, pending, c);
+            } else {
+                self.reset();
+            }
+            return;
+        }
+
+        match input {
+            Input {
+                key: Key::Char(c),
+                ctrl: true,
+                alt: false,
+                ..
+            } => self.handle_ctrl(t, c),
+            Input {
+                key: Key::Char(c),
+                ctrl: false,
+                alt: false,
+                ..
+            } => self.handle_char(t, c),
+            Input { key: Key::Left, .. } => self.motion(t, Motion::Left),
+            Input {
+                key: Key::Right, ..
+            } => self.motion(t, Motion::Right),
+            Input { key: Key::Up, .. } => self.motion(t, Motion::Up),
+            Input { key: Key::Down, .. } => self.motion(t, Motion::Down),
+            Input { key: Key::Home, .. } => self.motion(t, Motion::Head),
+            Input { key: Key::Tab, .. } => self.handle_ctrl(t, 'i'), // Terminals send `Ctrl+I` as `Tab`
+            Input { key: Key::End, .. } => self.motion(t, Motion::End),
+            _ => self.reset(),
+        }
+    }
+
+    fn handle_pending(&mut self, t: &mut TextArea<'_>, pending: Pending, c: char) {
+        match pending {
+            Pending::None => {}
+            Pending::G if c == 'g' => self.motion(t, Motion::Top),
+            Pending::G if c == 'e' => self.motion(t, Motion::WordEndBack),
+            Pending::G if c == 'E' => self.motion(t, Motion::WordSpacingEndBack),
+            Pending::G => self.reset(),
+            Pending::Register => {
+                if c.is_ascii_alphanumeric() || c == '"' || c == '_' {
+                    self.register = Some(c);
+                } else {
+                    self.reset();
+                }
+            }
+            Pending::Find(kind) => {
+                self.last_find = Some((kind, c));
+                self.motion(t, Motion::Find(kind, c, false));
+            }
+            Pending::Replace => {
+                let n = self.take_count().unwrap_or(1);
+                let (row, col) = t.cursor();
+                if n <= line_len(t, row).saturating_sub(col) {
+                    let text: String = std::iter::repeat(c).take(n).collect();
+                    t.replace_range((row, col), (row, col + n), text);
+                    jump(t, row, col + n - 1);
+                }
+                self.reset();
+            }
+            Pending::TextObject(inner) => {
+                let range = match c {
+                    'w' => word_object(t, inner),
+                    '(' | ')' | 'b' => bracket_object(t, '(', ')', inner),
+                    '[' | ']' => bracket_object(t, '[', ']', inner),
+                    '{' | '}' | 'B' => bracket_object(t, '{', '}', inner),
+                    '<' | '>' => bracket_object(t, '<', '>', inner),
+                    '"' | '\'' | '`' => quote_object(t, c, inner),
+                    _ => None,
+                };
+                match (range, self.mode) {
+                    (Some((start, end)), VimMode::Operator(op)) => {
+                        self.take_count();
+                        self.apply_operator(t, op, start, end);
+                    }
+                    (Some((start, end)), VimMode::Visual) if start != end => {
+                        t.cancel_selection();
+                        jump(t, start.0, start.1);
+                        t.start_selection();
+                        // The selection is inclusive in visual mode
+                        if end.1 > 0 {
+                            jump(t, end.0, end.1 - 1);
+                        } else {
+                            jump(t, end.0 - 1, line_len(t, end.0 - 1));
+                        }
+                        self.reset();
+                    }
+                    _ => {
+                        self.reset();
+                        if matches!(self.mode, VimMode::Operator(_)) {
+                            self.set_mode(t, VimMode::Normal);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn handle_ctrl(&mut self, t: &mut TextArea<'_>, c: char) {
+        match c {
+            'r' => repeat_while(self.take_count().unwrap_or(1), || t.redo()),
+            'o' => repeat_while(self.take_count().unwrap_or(1), || t.jump_back()),
+            'i' => repeat_while(self.take_count().unwrap_or(1), || t.jump_forward()),
+            'e' => t.scroll((1, 0)),
+            'y' => t.scroll((-1, 0)),
+            'd' => t.scroll(Scrolling::HalfPageDown),
+            'u' => t.scroll(Scrolling::HalfPageUp),
+            'f' => t.scroll(Scrolling::PageDown),
+            'b' => t.scroll(Scrolling::PageUp),
+            _ => {}
+        }
+        self.reset();
+    }
+
+    fn handle_char(&mut self, t: &mut TextArea<'_>, c: char) {
+        match c {
+            '1'..='9' | '0' if c != '0' || self.count.is_some() => {
+                let d = c as usize - '0' as usize;
+                self.count = Some(self.count.unwrap_or(0).saturating_mul(10).saturating_add(d));
+            }
+            '"' => self.pending = Pending::Register,
+            'g' => self.pending = Pending::G,
+            'f' | 'F' | 't' | 'T' => self.pending = Pending::Find(c),
+            ';' | ',' => {
+                if let Some((kind, target)) = self.last_find {
+                    let kind = if c == ',' {
+                        match kind {
+                            'f' => 'F',
+                            'F' => 'f',
+                            't' => 'T',
+                            _ => 't',
+                        }
+                    } else {
+                        kind
+                    };
+                    self.motion(t, Motion::Find(kind, target, true));
+                }
+            }
+            'i' | 'a' if matches!(self.mode, VimMode::Operator(_) | VimMode::Visual) => {
+                self.pending = Pending::TextObject(c == 'i')
+            }
+            'h' => self.motion(t, Motion::Left),
+            'l' | ' ' => self.motion(t, Motion::Right),
+            'j' => self.motion(t, Motion::Down),
+            'k' => self.motion(t, Motion::Up),
+            '+' => self.motion(t, Motion::DownFirstNonBlank),
+            '-' => self.motion(t, Motion::UpFirstNonBlank),
+            'w' => {
+                // `cw` works like `ce` on a non-blank character
+                let (row, col) = t.cursor();
+                let on_word = t.lines()[row]
+                    .chars()
+                    .nth(col)
+                    .map_or(false, |c| !c.is_whitespace());
+                if self.mode == VimMode::Operator('c') && on_word {
+                    self.motion(t, Motion::WordEnd);
+                } else {
+                    self.motion(t, Motion::WordForward);
+                }
+            }
+            'e' => self.motion(t, Motion::WordEnd),
+            'b' => self.motion(t, Motion::WordBack),
+            '0' => self.motion(t, Motion::Head),
+            '^' => self.motion(t, Motion::FirstNonBlank),
+            '$' => self.motion(t, Motion::End),
+            'G' => self.motion(t, Motion::Bottom),
+            '}' => self.motion(t, Motion::ParagraphForward),
+            '{' => self.motion(t, Motion::ParagraphBack),
+            _ => match self.mode {
+                VimMode::Normal => self.handle_normal(t, c),
+                VimMode::Visual | VimMode::VisualLine => self.handle_visual(t, c),
+                VimMode::Operator(op) if op == c => {
+                    // `dd`, `cc`, `yy`
+                    let n = self.take_count().unwrap_or(1);
+                    let row = t.cursor().0;
+                    let end = cmp::min(row.saturating_add(n - 1), t.lines().len() - 1);
+                    self.apply_line_operator(t, op, row, end);
+                }
+                _ => {
+                    self.reset();
+                    self.set_mode(t, VimMode::Normal);
+                }
+            },
+        }
+    }
+
+    fn handle_normal(&mut self, t: &mut TextArea<'_>, c: char) {
+        let (row, col) = t.cursor();
+        match c {
+            'd' | 'c' | 'y' => {
+                self.op_count = self.count.take();
+                self.mode = VimMode::Operator(c);
+                return;
+            }
+            'x' | 'X' | 's' => {
+                let n = self.take_count().unwrap_or(1);
+                let len = line_len(t, row);
+                let (start, end) = if c == 'X' {
+                    (col.saturating_sub(n), col)
+                } else {
+                    (col, cmp::min(col.saturating_add(n), len))
+                };
+                let op = if c == 's' { 'c' } else { 'd' };
+                self.apply_operator(t, op, (row, start), (row, end));
+                return;
+            }
+            'D' | 'C' => {
+                let n = self.take_count().unwrap_or(1);
+                let end = cmp::min(row.saturating_add(n - 1), t.lines().len() - 1);
+                let op = if c == 'C' { 'c' } else { 'd' };
+                self.apply_operator(t, op, (row, col), (end, line_len(t, end)));
+                return;
+            }
+            'S' | 'Y' => {
+                let n = self.take_count().unwrap_or(1);
+                let end = cmp::min(row.saturating_add(n - 1), t.lines().len() - 1);
+                let op = if c == 'S' { 'c' } else { 'y' };
+                self.apply_line_operator(t, op, row, end);
+                return;
+            }
+            'p' | 'P' => {
+                let n = self.take_count().unwrap_or(1);
+                self.put(t, c == 'p', n);
+            }
+            'J' => {
+                let n = cmp::max(self.take_count().unwrap_or(2), 2);
+                for _ in 0..n - 1 {
+                    if row + 1 >= t.lines().len() {
+                        break;
+                    }
+                    let len = line_len(t, row);
+                    let next = &t.lines()[row + 1];
+                    let indent = next.chars().take_while(|c| c.is_whitespace()).count();
+                    let sep = if len == 0 || next.trim_start().is_empty() {
+                        ""
+                    } else {
+                        " "
+                    };
+                    t.replace_range((row, len), (row + 1, indent), sep);
+                    jump(t, row, len);
+                }
+            }
+            'r' => {
+                self.pending = Pending::Replace;
+                return;
+            }
+            '~' => {
+                let n = self.take_count().unwrap_or(1);
+                let end = cmp::min(col.saturating_add(n), line_len(t, row));
+                let text: String = t
+                    .text_in_range((row, col), (row, end))
+                    .chars()
+                    .map(|c| {
+                        if c.is_uppercase() {
+                            c.to_lowercase().next().unwrap_or(c)
+                        } else {
+                            c.to_uppercase().next().unwrap_or(c)
+                        }
+                    })
+                    .collect();
+                if !text.is_empty() {
+                    t.replace_range((row, col), (row, end), text);
+                }
+                jump(t, row, cmp::min(end, line_len(t, row).saturating_sub(1)));
+            }
+            'u' => repeat_while(self.take_count().unwrap_or(1), || t.undo()),
+            'i' => self.set_mode(t, VimMode::Insert),
+            'a' => {
+                t.move_cursor(CursorMove::Forward);
+                self.set_mode(t, VimMode::Insert);
+            }
+            'A' => {
+                t.move_cursor(CursorMove::End);
+                self.set_mode(t, VimMode::Insert);
+            }
+            'I' => {
+                jump(t, row, first_non_blank(t, row));
+                self.set_mode(t, VimMode::Insert);
+            }
+            'o' => {
+                t.move_cursor(CursorMove::End);
+                t.insert_newline();
+                self.set_mode(t, VimMode::Insert);
+            }
+            'O' => {
+                t.move_cursor(CursorMove::Head);
+                t.insert_newline();
+                t.move_cursor(CursorMove::Up);
+                self.set_mode(t, VimMode::Insert);
+            }
+            'v' => {
+                t.start_selection();
+                self.set_mode(t, VimMode::Visual);
+            }
+            'V' => {
+                self.visual_anchor = row;
+                self.set_mode(t, VimMode::VisualLine);
+                self.select_lines(t);
+            }
+            _ => {}
+        }
+        self.reset();
+    }
+
+    fn handle_visual(&mut self, t: &mut TextArea<'_>, c: char) {
+        let ((start_row, _), (end_row, _)) = self.visual_range(t);
+        match c {
+            'v' if self.mode == VimMode::Visual => self.set_mode(t, VimMode::Normal),
+            'V' if self.mode == VimMode::VisualLine => self.set_mode(t, VimMode::Normal),
+            'v' => {
+                // Switch to character-wise selection from the anchor line
+                let (row, col) = t.cursor();
+                t.cancel_selection();
+                jump(t, self.visual_anchor, 0);
+                t.start_selection();
+                jump(t, row, col);
+                self.mode = VimMode::Visual;
+            }
+            'V' => {
+                self.visual_anchor =
+                    t.selection_range().map_or(
+                        start_row,
+                        |(s, e)| {
+                            if t.cursor() == s {
+                                e.0
+                            } else {
+                                s.0
+                            }
+                        },
+                    );
+                self.mode = VimMode::VisualLine;
+                self.select_lines(t);
+            }
+            'd' | 'x' | 'c' | 'y' if self.mode == VimMode::VisualLine => {
+                let op = if c == 'x' { 'd' } else { c };
+                self.apply_line_operator(t, op, start_row, end_row);
+            }
+            'd' | 'x' | 'c' | 'y' => {
+                let op = if c == 'x' { 'd' } else { c };
+                let (start, end) = self.visual_range(t);
+                let end = next_pos(t, end);
+                self.apply_operator(t, op, start, end);
+            }
+            'D' | 'X' | 'Y' => {
+                let op = if c == 'Y' { 'y' } else { 'd' };
+                self.apply_line_operator(t, op, start_row, end_row);
+            }
+            _ => {}
+        }
+        self.reset();
+    }
+
+    // Range of the visual selection. Both ends are inclusive
+    fn visual_range(&self, t: &TextArea<'_>) -> Range {
+        let cursor = t.cursor();
+        t.selection_range().unwrap_or((cursor, cursor))
+    }
+
+    fn motion(&mut self, t: &mut TextArea<'_>, motion: Motion) {
+        let count = match (self.op_count.take(), self.count.take()) {
+            (Some(a), Some(b)) => Some(a.saturating_mul(b)),
+            (a, b) => a.or(b),
+        };
+        match self.mode {
+            VimMode::Operator(op) => {
+                let start = t.cursor();
+                motion.apply(t, count);
+                let end = t.cursor();
+                let (start, mut end) = (cmp::min(start, end), cmp::max(start, end));
+                if motion.linewise() {
+                    self.apply_line_operator(t, op, start.0, end.0);
+                    return;
+                }
+                if motion.inclusive() {
+                    end.1 = cmp::min(end.1 + 1, line_len(t, end.0));
+                } else if end.1 == 0 && end.0 > start.0 {
+                    // An exclusive motion which ends at the head of line does not include the newline before it
+                    end = (end.0 - 1, line_len(t, end.0 - 1));
+                }
+                self.apply_operator(t, op, start, end);
+            }
+            VimMode::VisualLine => {
+                motion.apply(t, count);
+                self.select_lines(t);
+            }
+            VimMode::Normal => {
+                motion.apply(t, count);
+                // The cursor is on the last character at most in normal mode
+                let (row, col) = t.cursor();
+                let len = line_len(t, row);
+                if len > 0 && col >= len {
+                    jump(t, row, len - 1);
+                }
+            }
+            _ => motion.apply(t, count),
+        }
+        self.reset();
+    }
+
+    // Select whole lines between the anchor row and the cursor row in line-wise visual mode
+    fn select_lines(&mut self, t: &mut TextArea<'_>) {
+        let row = t.cursor().0;
+        let anchor = self.visual_anchor;
+        t.cancel_selection();
+        if row < anchor {
+            jump(t, anchor, line_len(t, anchor));
+            t.start_selection();
+            jump(t, row, 0);
+        } else {
+            jump(t, anchor, 0);
+            t.start_selection();
+            jump(t, row, line_len(t, row));
+        }
+    }
+
+    // Apply the operator to the text between `start` and `end` (exclusive)
+    fn apply_operator(
+        &mut self,
+        t: &mut TextArea<'_>,
+        op: char,
+        start: (usize, usize),
+        end: (usize, usize),
+    ) {
+        t.cancel_selection();
+        let text = t.text_in_range(start, end);
+        self.store(t, op, text, false);
+        match op {
+            'y' => jump(t, start.0, start.1),
+            _ => {
+                t.replace_range(start, end, "");
+            }
+        }
+        let mode = if op == 'c' {
+            VimMode::Insert
+        } else {
+            VimMode::Normal
+        };
+        self.reset();
+        self.set_mode(t, mode);
+    }
+
+    // Apply the operator to the lines from `start` to `end` (inclusive)
+    fn apply_line_operator(&mut self, t: &mut TextArea<'_>, op: char, start: usize, end: usize) {
+        t.cancel_selection();
+        let text = t.lines()[start..=end].join("\n");
+        self.store(t, op, text, true);
+        let last = t.lines().len() - 1;
+        match op {
+            'y' => jump(t, start, cmp::min(t.cursor().1, line_len(t, start))),
+            'c' => {
+                t.replace_range((start, 0), (end, line_len(t, end)), "");
+            }
+            _ => {
+                if end < last {
+                    t.replace_range((start, 0), (end + 1, 0), "");
+                } else if start > 0 {
+                    t.replace_range(
+                        (start - 1, line_len(t, start - 1)),
+                        (end, line_len(t, end)),
+                        "",
+                    );
+                } else {
+                    t.replace_range((0, 0), (end, line_len(t, end)), "");
+                }
+                let row = cmp::min(start, t.lines().len() - 1);
+                jump(t, row, first_non_blank(t, row));
+            }
+        }
+        let mode = if op == 'c' {
+            VimMode::Insert
+        } else {
+            VimMode::Normal
+        };
+        self.reset();
+        self.set_mode(t, mode);
+    }
+
+    // Store the yanked or deleted text to the registers
+    fn store(&mut self, t: &mut TextArea<'_>, op: char, text: String, linewise: bool) {
+        let mut reg = Register { text, linewise };
+        match self.register {
+            Some('_') => return,
+            Some(c) if c.is_ascii_uppercase() => {
+                let name = c.to_ascii_lowercase();
+                if let Some(prev) = self.registers.get(&name) {
+                    let sep = if prev.linewise || linewise { "\n" } else { "" };
+                    reg = Register {
+                        text: format!("{}{}{}", prev.text, sep, reg.text),
+                        linewise: prev.linewise || linewise,
+                    };
+                }
+                self.registers.insert(name, reg.clone());
+            }
+            Some(c) if c != '"' => {
+                self.registers.insert(c, reg.clone());
+            }
+            _ => {}
+        }
+        if op == 'y' && matches!(self.register, None | Some('"')) {
+            self.registers.insert('0', reg.clone());
+        }
+        let yank = if reg.linewise {
+            format!("{}\n", reg.text)
+        } else {
+            reg.text.clone()
+        };
+        t.set_yank_text(yank);
+        self.registers.insert('"', reg);
+    }
+
+    fn put(&mut self, t: &mut TextArea<'_>, after: bool, count: usize) {
+        let name = self.register.unwrap_or('"').to_ascii_lowercase();
+        let reg = match self.registers.get(&name) {
+            Some(reg) => reg.clone(),
+            None => return,
+        };
+        // Like Vim, text which would be too long is not put
+        let len = if reg.linewise {
+            reg.text.len() + 1
+        } else {
+            reg.text.len()
+        };
+        if len.checked_mul(count).map_or(true, |len| len > MAX_PUT_LEN) {
+            return;
+        }
+        let (row, col) = t.cursor();
+        if reg.linewise {
+            let text = vec![reg.text.as_str(); count].join("\n");
+            let target = if after {
+                jump(t, row, line_len(t, row));
+                t.insert_str(format!("\n{}", text));
+                row + 1
+            } else {
+                jump(t, row, 0);
+                t.insert_str(format!("{}\n", text));
+                row
+            };
+            jump(t, target, first_non_blank(t, target));
+        } else {
+            if after && line_len(t, row) > 0 {
+                jump(t, row, col + 1);
+            }
+            t.insert_str(reg.text.repeat(count));
+            t.move_cursor(CursorMove::Back);
+        }
+    }
+
+    fn take_count(&mut self) -> Option<usize> {
+        match (self.op_count.take(), self.count.take()) {
+            (Some(a), Some(b)) => Some(a.saturating_mul(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    fn reset(&mut self) {
+        self.pending = Pending::None;
+        self.count = None;
+        self.op_count = None;
+        self.register = None;
+    }
+
+    fn set_mode(&mut self, t: &mut TextArea<'_>, mode: VimMode) {
+        let is_visual = |m| matches!(m, VimMode::Visual | VimMode::VisualLine);
+        if is_visual(self.mode) && !is_visual(mode) {
+            t.cancel_selection();
+            t.set_selection_inclusive(self.selection_inclusive);
+        } else if !is_visual(self.mode) && is_visual(mode) {
+            // Selection in visual mode includes the character under the cursor
+            self.selection_inclusive = t.selection_inclusive();
+            t.set_selection_inclusive(true);
+        }
+        if mode == VimMode::Insert {
+            t.cancel_selection();
+        }
+        self.mode = mode;
+    }
+}
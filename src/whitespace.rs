@@ -0,0 +1,27 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Specify which whitespaces are rendered visibly. Visible spaces are rendered as `·` and visible tabs are rendered as
+/// `→` padded to the tab stop. The width of the text on screen does not change.
+///
+/// This type is marked as `#[non_exhaustive]` since more variations may be supported in the future. See also:
+/// [`TextArea::set_show_whitespace`]
+///
+/// [`TextArea::set_show_whitespace`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.set_show_whitespace
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum WhitespaceStyle {
+    /// Render whitespaces as they are. This is the default.
+    Hidden,
+    /// Render all spaces and tabs visibly.
+    All,
+    /// Render only spaces and tabs at the end of lines visibly.
+    Trailing,
+}
+
+impl Default for WhitespaceStyle {
+    fn default() -> Self {
+        Self::Hidden
+    }
+}
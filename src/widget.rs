@@ -1,15 +1,17 @@
-use crate::ratatui::buffer::Buffer;
+use crate::placeholder::PlaceholderCursor;
+use crate::ratatui::buffer::{Buffer, Cell};
 use crate::ratatui::layout::Rect;
 use crate::ratatui::text::{Span, Text};
-use crate::ratatui::widgets::{Paragraph, Widget};
+use crate::ratatui::widgets::{Paragraph, StatefulWidget, Widget};
 use crate::textarea::TextArea;
-use crate::util::num_digits;
 #[cfg(feature = "ratatui")]
 use ratatui::text::Line;
 use std::cmp;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
 #[cfg(feature = "tuirs")]
 use tui::text::Spans as Line;
+use unicode_width::UnicodeWidthStr as _;
 
 // &mut 'a (u16, u16, u16, u16) is not available since `render` method takes immutable reference of TextArea
 // instance. In the case, the TextArea instance cannot be accessed from any other objects since it is mutablly
@@ -64,19 +66,110 @@ impl Viewport {
         self.0.store(u, Ordering::Relaxed);
     }
 
-    pub fn scroll(&mut self, rows: i16, cols: i16) {
-        fn apply_scroll(pos: u16, delta: i16) -> u16 {
-            if delta >= 0 {
-                pos.saturating_add(delta as u16)
-            } else {
-                pos.saturating_sub(-delta as u16)
+    pub fn set_scroll_top(&mut self, row: u16, col: u16) {
+        let u = self.0.get_mut();
+        *u = (*u & 0xffff_ffff_0000_0000) | ((row as u64) << 16) | (col as u64);
+    }
+}
+
+// Key of the last frame with its cells. When nothing was changed since the last frame, the cells are copied to the
+// buffer instead of building the lines again. The cells are saved only when the render cache is enabled. It is wrapped
+// with `Mutex` for the same reason as `Viewport`. Computing the key is not free so it is computed only when the render
+// cache is enabled or `TextArea::is_render_dirty` has been called.
+#[derive(Default, Debug)]
+pub struct RenderCache {
+    frame: Mutex<Option<CachedFrame>>,
+    tracked: AtomicBool,
+}
+
+#[derive(Debug)]
+struct CachedFrame {
+    key: u64,
+    area: Rect,
+    clip: Rect, // Part of the area inside the buffer
+    cells: Vec<Cell>,
+}
+
+impl Clone for RenderCache {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl RenderCache {
+    fn track(&self) {
+        self.tracked.store(true, Ordering::Relaxed);
+    }
+
+    fn tracked(&self) -> bool {
+        self.tracked.load(Ordering::Relaxed)
+    }
+
+    fn key(&self) -> Option<(u64, Rect)> {
+        let cache = self.frame.lock().ok()?;
+        cache.as_ref().map(|f| (f.key, f.area))
+    }
+
+    fn restore(&self, key: u64, area: Rect, buf: &mut Buffer) -> bool {
+        let cache = match self.frame.lock() {
+            Ok(cache) => cache,
+            Err(_) => return false,
+        };
+        let clip = area.intersection(buf.area);
+        let frame = match &*cache {
+            Some(f)
+                if f.key == key
+                    && f.area == area
+                    && f.clip == clip
+                    && f.cells.len() == clip.area() as usize =>
+            {
+                f
+            }
+            _ => return false,
+        };
+        let mut cells = frame.cells.iter();
+        for y in clip.top()..clip.bottom() {
+            for x in clip.left()..clip.right() {
+                let i = buf.index_of(x, y);
+                if let Some(cell) = cells.next() {
+                    buf.content[i].clone_from(cell);
+                }
             }
         }
+        true
+    }
 
-        let u = self.0.get_mut();
-        let row = apply_scroll((*u >> 16) as u16, rows);
-        let col = apply_scroll(*u as u16, cols);
-        *u = (*u & 0xffff_ffff_0000_0000) | ((row as u64) << 16) | (col as u64);
+    fn save(&self, key: u64, area: Rect, buf: &Buffer, with_cells: bool) {
+        let mut cache = match self.frame.lock() {
+            Ok(cache) => cache,
+            Err(_) => return,
+        };
+        // Reuse the allocation of the previous frame
+        let mut cells = match cache.take() {
+            Some(f) => f.cells,
+            None => vec![],
+        };
+        cells.clear();
+        let clip = area.intersection(buf.area);
+        if with_cells {
+            for y in clip.top()..clip.bottom() {
+                for x in clip.left()..clip.right() {
+                    cells.push(buf.content[buf.index_of(x, y)].clone());
+                }
+            }
+        }
+        *cache = Some(CachedFrame {
+            key,
+            area,
+            clip,
+            cells,
+        });
+    }
+
+    fn clear(&self) {
+        if let Ok(mut cache) = self.frame.lock() {
+            *cache = None;
+        }
     }
 }
 
@@ -92,21 +185,47 @@ fn next_scroll_top(prev_top: u16, cursor: u16, len: u16) -> u16 {
 }
 
 impl<'a> TextArea<'a> {
-    fn text_widget(&'a self, top_row: usize, height: usize) -> Text<'a> {
+    // `view_width` is the width of the viewport and `top_col` is the width of the horizontally scrolled part
+    fn text_widget(
+        &'a self,
+        top_row: usize,
+        height: usize,
+        view_width: usize,
+        top_col: usize,
+    ) -> Text<'a> {
         let lines_len = self.lines().len();
-        let lnum_len = num_digits(lines_len);
         let bottom_row = cmp::min(top_row + height, lines_len);
+        let mut pass = self.render_pass(top_row, bottom_row, view_width + top_col, view_width);
         let mut lines = Vec::with_capacity(bottom_row - top_row);
         for (i, line) in self.lines()[top_row..bottom_row].iter().enumerate() {
-            lines.push(self.line_spans(line.as_str(), top_row + i, lnum_len));
+            lines.push(self.line_spans(line.as_str(), top_row + i, &mut pass));
         }
         Text::from(lines)
     }
 
     fn placeholder_widget(&'a self) -> Text<'a> {
-        let cursor = Span::styled(" ", self.cursor_style);
-        let text = Span::raw(self.placeholder.as_str());
-        Text::from(Line::from(vec![cursor, text]))
+        let text = self.placeholder.as_str();
+        let cursor = if !self.cursor_rendered() {
+            PlaceholderCursor::Hidden
+        } else {
+            self.placeholder_cursor()
+        };
+        let (cursor_style, _, _) = self.rendered_styles();
+        let spans = match cursor {
+            PlaceholderCursor::Before => {
+                vec![Span::styled(" ", cursor_style), Span::raw(text)]
+            }
+            PlaceholderCursor::Over => {
+                // The placeholder is not empty while it is rendered
+                let len = text.chars().next().map_or(0, char::len_utf8);
+                vec![
+                    Span::styled(&text[..len], cursor_style),
+                    Span::raw(&text[len..]),
+                ]
+            }
+            PlaceholderCursor::Hidden => vec![Span::raw(text)],
+        };
+        Text::from(Line::from(spans))
     }
 
     fn scroll_top_row(&self, prev_top: u16, height: u16) -> u16 {
@@ -115,58 +234,1021 @@ impl<'a> TextArea<'a> {
 
     fn scroll_top_col(&self, prev_top: u16, width: u16) -> u16 {
         let mut cursor = self.cursor().1 as u16;
-        // Adjust the cursor position due to the width of line number.
-        if self.line_number_style().is_some() {
-            let lnum = num_digits(self.lines().len()) as u16 + 2; // `+ 2` for margins
-            if cursor <= lnum {
-                cursor *= 2; // Smoothly slide the line number into the screen on scrolling left
+        // Adjust the cursor position due to the width of line number and diff signs.
+        let gutter = self.gutter_width();
+        if gutter > 0 {
+            if cursor <= gutter {
+                cursor *= 2; // Smoothly slide the gutter into the screen on scrolling left
             } else {
-                cursor += lnum; // The cursor position is shifted by the line number part
+                cursor += gutter; // The cursor position is shifted by the gutter
             };
         }
+        let (row, _) = self.cursor();
+        cursor += self.row_padding(row, width as usize) as u16;
         next_scroll_top(prev_top, cursor, width)
     }
 }
 
-impl Widget for &TextArea<'_> {
-    fn render(self, area: Rect, buf: &mut Buffer) {
-        let Rect { width, height, .. } = if let Some(b) = self.block() {
-            b.inner(area)
-        } else {
-            area
-        };
+impl TextArea<'_> {
+    /// Render the textarea into a string as if the widget were rendered in an area of the given size. Rows of the
+    /// area are joined with `\n` and each row is padded with spaces to the width. Everything the widget draws such
+    /// as the block, line numbers, masked text, and placeholder is included, but styles including the cursor are not.
+    /// This is useful to write golden tests of applications without setting up a terminal backend. Like rendering the
+    /// widget, this updates the scroll position to keep the cursor in the area. Note that the tui-rs backend renders
+    /// an area of more than 65535 cells in a smaller area keeping its aspect ratio, so the returned string has fewer
+    /// rows and columns than the given size in the case.
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["hello", "world"]);
+    /// textarea.set_line_number_style(Default::default());
+    ///
+    /// assert_eq!(textarea.render_to_string(10, 3), " 1 hello  \n 2 world  \n          ");
+    ///
+    /// // The textarea is scrolled to show the cursor
+    /// textarea.move_cursor(tui_textarea::CursorMove::Bottom);
+    /// assert_eq!(textarea.render_to_string(10, 1), " 2 world  ");
+    /// ```
+    pub fn render_to_string(&self, width: u16, height: u16) -> String {
+        let area = Rect::new(0, 0, width, height);
+        let mut buf = Buffer::empty(area);
+        Widget::render(self, area, &mut buf);
 
-        let (top_row, top_col) = self.viewport.scroll_top();
-        let top_row = self.scroll_top_row(top_row, height);
-        let top_col = self.scroll_top_col(top_col, width);
+        // The area may be smaller than the requested size. tui-rs shrinks an area keeping its aspect ratio when it
+        // has more than `u16::MAX` cells
+        let area = buf.area;
+        let mut rendered = String::new();
+        for y in area.top()..area.bottom() {
+            if y > area.top() {
+                rendered.push('\n');
+            }
+            let mut x = area.left();
+            while x < area.right() {
+                #[cfg(feature = "ratatui")]
+                let symbol = buf[(x, y)].symbol();
+                #[cfg(feature = "tuirs")]
+                let symbol = buf.get(x, y).symbol.as_str();
+                rendered.push_str(symbol);
+                // Cells covered by a wide character are skipped
+                x += cmp::max(symbol.width(), 1) as u16;
+            }
+        }
+        rendered
+    }
+}
 
-        let (text, style) = if !self.placeholder.is_empty() && self.is_empty() {
-            (self.placeholder_widget(), self.placeholder_style)
+impl TextArea<'_> {
+    // Area inside the block and the scroll position to render the textarea in the area
+    fn render_layout(&self, area: Rect, (top_row, top_col): (u16, u16)) -> (Rect, u16, u16) {
+        let inner = if let Some(b) = self.block() {
+            b.inner(area)
         } else {
-            (self.text_widget(top_row as _, height as _), self.style())
+            area
         };
+        let top_row = self.scroll_top_row(top_row, inner.height);
+        let top_col = self.scroll_top_col(top_col, inner.width);
+        (inner, top_row, top_col)
+    }
 
+    fn render_text(&self, area: Rect, inner: Rect, top_row: u16, top_col: u16, buf: &mut Buffer) {
         // To get fine control over the text color and the surrrounding block they have to be rendered separately
         // see https://github.com/ratatui/ratatui/issues/144
-        let mut text_area = area;
-        let mut inner = Paragraph::new(text)
-            .style(style)
-            .alignment(self.alignment());
         if let Some(b) = self.block() {
-            text_area = b.inner(area);
             // ratatui does not need `clone()` call because `Block` implements `WidgetRef` and `&T` implements `Widget`
             // where `T: WidgetRef`. So `b.render` internally calls `b.render_ref` and it doesn't move out `self`.
             #[cfg(feature = "tuirs")]
             let b = b.clone();
             b.render(area, buf)
         }
-        if top_col != 0 {
-            inner = inner.scroll((0, top_col));
+        self.render_inner(inner, top_row as usize, top_col, 0, buf);
+    }
+
+    // Render the text in `rows`, which are the rows of the area inside the block starting from the `skip`-th row
+    fn render_inner(&self, rows: Rect, top_row: usize, top_col: u16, skip: u16, buf: &mut Buffer) {
+        let (text, style, alignment, skip) = if !self.placeholder.is_empty() && self.is_empty() {
+            (
+                self.placeholder_widget(),
+                self.placeholder_style,
+                self.placeholder_alignment(),
+                skip,
+            )
+        } else {
+            // Rows below the last line are empty
+            let top_row = cmp::min(top_row + skip as usize, self.lines().len());
+            (
+                self.text_widget(top_row, rows.height as _, rows.width as _, top_col as _),
+                self.style(),
+                self.alignment(),
+                0,
+            )
+        };
+
+        let mut inner_widget = Paragraph::new(text).style(style).alignment(alignment);
+        if skip != 0 || top_col != 0 {
+            inner_widget = inner_widget.scroll((skip, top_col));
+        }
+        inner_widget.render(rows, buf);
+    }
+
+    /// Enable or disable the render cache. When it is enabled and nothing was changed since the last frame, rendering
+    /// the textarea copies the cells of the last frame to the buffer without building the lines. The render cache is
+    /// disabled by default.
+    ///
+    /// The cached cells include the cells in the area which the textarea does not paint, such as cells after the end
+    /// of lines. Enable the cache only when nothing is rendered under the textarea or what is rendered under it does
+    /// not change between frames. Otherwise the cells under the textarea in the last frame are restored.
+    /// ```
+    /// use ratatui::buffer::Buffer;
+    /// use ratatui::layout::Rect;
+    /// use ratatui::widgets::Widget;
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["hello"]);
+    /// textarea.set_render_cache(true);
+    /// assert!(textarea.render_cache());
+    ///
+    /// let area = Rect::new(0, 0, 8, 1);
+    /// let mut first = Buffer::empty(area);
+    /// (&textarea).render(area, &mut first);
+    ///
+    /// // The cells of the last frame are copied
+    /// let mut second = Buffer::empty(area);
+    /// (&textarea).render(area, &mut second);
+    /// assert_eq!(first, second);
+    /// ```
+    pub fn set_render_cache(&mut self, enabled: bool) {
+        self.render_cache = enabled;
+        if !enabled {
+            self.last_frame.clear();
+        }
+    }
+
+    /// Return if the render cache is enabled by [`TextArea::set_render_cache`].
+    pub fn render_cache(&self) -> bool {
+        self.render_cache
+    }
+
+    /// Discard the last frame so that the next rendering builds the lines again and [`TextArea::is_render_dirty`]
+    /// returns `true`. The textarea detects changes made through its methods, but it cannot detect changes of states
+    /// outside of it. Call this method when such states used by the function set by
+    /// [`TextArea::set_line_postprocessor`] or the measurement set by [`TextArea::set_char_width`] were changed.
+    /// ```
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    /// use std::sync::Arc;
+    /// use tui_textarea::TextArea;
+    ///
+    /// let upper = Arc::new(AtomicBool::new(false));
+    /// let mut textarea = TextArea::from(["hello"]);
+    /// textarea.set_render_cache(true);
+    /// let flag = upper.clone();
+    /// textarea.set_line_postprocessor(move |_, line| {
+    ///     if flag.load(Ordering::Relaxed) {
+    ///         line.spans.into_iter().map(|s| s.content.to_uppercase()).collect::<String>().into()
+    ///     } else {
+    ///         line
+    ///     }
+    /// });
+    ///
+    /// textarea.render_to_string(8, 1);
+    /// upper.store(true, Ordering::Relaxed);
+    /// assert!(!textarea.is_render_dirty());
+    ///
+    /// textarea.invalidate_render_cache();
+    /// assert!(textarea.is_render_dirty());
+    /// ```
+    pub fn invalidate_render_cache(&self) {
+        self.last_frame.clear();
+    }
+
+    /// Check if rendering the textarea would produce a different frame from the last rendered one. Applications
+    /// which render only the textarea can skip drawing the frame entirely when this method returns `false`. This
+    /// method returns `true` when the textarea has never been rendered. Frames are tracked after this method is called
+    /// first or the render cache is enabled by [`TextArea::set_render_cache`], so this method also returns `true` when
+    /// the last frame was rendered before that. Note that changes of states outside of the textarea are not detected.
+    /// See [`TextArea::invalidate_render_cache`].
+    /// ```
+    /// use tui_textarea::TextArea;
+    ///
+    /// let mut textarea = TextArea::from(["hello"]);
+    /// assert!(textarea.is_render_dirty());
+    ///
+    /// textarea.render_to_string(10, 3);
+    /// assert!(!textarea.is_render_dirty());
+    ///
+    /// textarea.insert_char('!');
+    /// assert!(textarea.is_render_dirty());
+    /// ```
+    pub fn is_render_dirty(&self) -> bool {
+        self.last_frame.track();
+        match self.last_frame.key() {
+            Some((key, area)) => {
+                let (_, top_row, top_col) = self.render_layout(area, self.viewport.scroll_top());
+                self.render_key(area, top_row, top_col) != key
+            }
+            None => true,
+        }
+    }
+}
+
+// Intersection of the two rectangles. `None` is returned when they don't overlap
+fn intersect(a: Rect, b: Rect) -> Option<Rect> {
+    let left = cmp::max(a.left(), b.left());
+    let right = cmp::min(a.right(), b.right());
+    let top = cmp::max(a.top(), b.top());
+    let bottom = cmp::min(a.bottom(), b.bottom());
+    (left < right && top < bottom).then(|| Rect {
+        x: left,
+        y: top,
+        width: right - left,
+        height: bottom - top,
+    })
+}
+
+// Copy the cells inside `clip` from one buffer to another. Cells outside either buffer are not copied.
+fn copy_cells(from: &Buffer, to: &mut Buffer, clip: Rect) {
+    let left = cmp::max(clip.left(), cmp::max(from.area.left(), to.area.left()));
+    let right = cmp::min(clip.right(), cmp::min(from.area.right(), to.area.right()));
+    let top = cmp::max(clip.top(), cmp::max(from.area.top(), to.area.top()));
+    let bottom = cmp::min(
+        clip.bottom(),
+        cmp::min(from.area.bottom(), to.area.bottom()),
+    );
+    for y in top..bottom {
+        for x in left..right {
+            let i = to.index_of(x, y);
+            to.content[i].clone_from(&from.content[from.index_of(x, y)]);
+        }
+    }
+}
+
+impl TextArea<'_> {
+    /// Render the textarea in the area but modify only the cells inside the `clip` rectangle of the buffer. The
+    /// scroll position is calculated for the whole area, not for the visible part of it. This is useful to embed the
+    /// textarea in a widget which shows only a part of its children such as a scroll view or a popup partially
+    /// covered by another widget. Rendering with [`ratatui::widgets::Widget`] would scroll the textarea to keep the
+    /// cursor in the clipped area instead.
+    ///
+    /// The area may be outside the buffer. Only the rows of the area inside the clip rectangle are rendered into a
+    /// temporary buffer, so the cost does not depend on the size of the whole area but this is slower than rendering
+    /// the textarea directly.
+    /// ```
+    /// use ratatui::buffer::Buffer;
+    /// use ratatui::layout::Rect;
+    /// use tui_textarea::{CursorMove, TextArea};
+    ///
+    /// let mut textarea = TextArea::from(["a", "b", "c", "d"]);
+    /// textarea.move_cursor(CursorMove::Bottom);
+    ///
+    /// // Only the first row of the 4 rows area is visible
+    /// let area = Rect::new(0, 0, 3, 4);
+    /// let visible = Rect::new(0, 0, 3, 1);
+    /// let mut buf = Buffer::empty(visible);
+    /// textarea.render_clipped(area, &mut buf, visible);
+    ///
+    /// // The textarea was not scrolled since the cursor is in the area
+    /// assert_eq!(textarea.scroll_offset(), (0, 0));
+    /// assert_eq!(buf, Buffer::with_lines(["a  "]));
+    /// ```
+    pub fn render_clipped(&self, area: Rect, buf: &mut Buffer, clip: Rect) {
+        let (inner, top_row, top_col) = self.render_layout(area, self.viewport.scroll_top());
+        if let Some(visible) = intersect(area, clip).and_then(|r| intersect(r, buf.area)) {
+            // Render the visible rows in chunks so that a temporary buffer does not exceed `u16::MAX` cells, which is
+            // the limit of tui-rs
+            let chunk = cmp::max(u16::MAX / area.width, 1);
+            let mut y = visible.top();
+            while y < visible.bottom() {
+                let height = cmp::min(chunk, visible.bottom() - y);
+                let mut scratch = Buffer::empty(Rect { y, height, ..area });
+                // Keep the cells under the textarea so that rendering it in the clipped area is the same as rendering
+                // it directly
+                copy_cells(buf, &mut scratch, visible);
+                self.render_rows(area, (inner, top_row, top_col), &mut scratch);
+                copy_cells(&scratch, buf, visible);
+                y += height;
+            }
+        }
+        self.viewport
+            .store(top_row, top_col, inner.width, inner.height);
+    }
+
+    // Render the rows of the area which are inside the buffer
+    fn render_rows(
+        &self,
+        area: Rect,
+        (inner, top_row, top_col): (Rect, u16, u16),
+        buf: &mut Buffer,
+    ) {
+        if let Some(b) = self.block() {
+            // Rows between the top and bottom rows of the block look the same. Render the block in 3 rows at most
+            // and copy the rows to the buffer
+            let height = cmp::min(area.height, 3);
+            let mut block = Buffer::empty(Rect { height, ..area });
+            #[cfg(feature = "tuirs")]
+            let b = b.clone();
+            b.render(block.area, &mut block);
+            for y in buf.area.top()..buf.area.bottom() {
+                let from = if y == area.top() {
+                    area.top()
+                } else if y + 1 == area.bottom() {
+                    area.top() + height - 1
+                } else {
+                    area.top() + 1
+                };
+                for x in area.left()..area.right() {
+                    let i = buf.index_of(x, y);
+                    buf.content[i].clone_from(&block.content[block.index_of(x, from)]);
+                }
+            }
+        }
+
+        if let Some(rows) = intersect(inner, buf.area) {
+            self.render_inner(rows, top_row as usize, top_col, rows.y - inner.y, buf);
+        }
+        self.render_completion(inner, top_row, top_col, buf);
+    }
+}
+
+impl TextArea<'_> {
+    // Render the textarea scrolled from the previous scroll position. The area inside the block and the new scroll
+    // position are returned.
+    fn render_view(
+        &self,
+        area: Rect,
+        buf: &mut Buffer,
+        scroll_top: (u16, u16),
+    ) -> (Rect, u16, u16) {
+        let (inner, top_row, top_col) = self.render_layout(area, scroll_top);
+
+        // Reuse the last frame when nothing was changed
+        if self.render_cache || self.last_frame.tracked() {
+            let key = self.render_key(area, top_row, top_col);
+            if !(self.render_cache && self.last_frame.restore(key, area, buf)) {
+                self.render_text(area, inner, top_row, top_col, buf);
+                self.last_frame.save(key, area, buf, self.render_cache);
+            }
+        } else {
+            self.render_text(area, inner, top_row, top_col, buf);
         }
 
+        self.render_completion(inner, top_row, top_col, buf);
+        (inner, top_row, top_col)
+    }
+}
+
+impl Widget for &TextArea<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let (inner, top_row, top_col) = self.render_view(area, buf, self.viewport.scroll_top());
         // Store scroll top position for rendering on the next tick
-        self.viewport.store(top_row, top_col, width, height);
+        self.viewport
+            .store(top_row, top_col, inner.width, inner.height);
+    }
+}
+
+/// State of a view of [`TextArea`] rendered with [`ratatui::widgets::StatefulWidget`]. While rendering a textarea
+/// with [`ratatui::widgets::Widget`] stores the scroll position in the textarea itself, rendering it as a stateful
+/// widget stores the scroll position in this state instead. This allows rendering the same textarea in multiple views
+/// with different scroll positions such as split windows. Each view is scrolled to keep the cursor in it.
+///
+/// Note that methods of the textarea which depend on the viewport such as [`TextArea::scroll`] and
+/// [`CursorMove::InViewport`] use the viewport of the textarea, which is not updated by rendering it as a stateful
+/// widget.
+///
+/// This type is marked as `#[non_exhaustive]` since more fields may be added in the future. Use
+/// [`TextAreaState::default`] to create an instance.
+/// ```
+/// use ratatui::buffer::Buffer;
+/// use ratatui::layout::Rect;
+/// use ratatui::widgets::StatefulWidget;
+/// use tui_textarea::{CursorMove, TextArea, TextAreaState};
+///
+/// let mut textarea = TextArea::from(["a", "b", "c", "d"]);
+/// let (mut upper, mut lower) = (TextAreaState::default(), TextAreaState::default());
+///
+/// let area = Rect::new(0, 0, 3, 2);
+/// let mut buf = Buffer::empty(area);
+/// StatefulWidget::render(&textarea, area, &mut buf, &mut upper);
+///
+/// // Only the lower view is scrolled to the cursor at the last line
+/// textarea.move_cursor(CursorMove::Bottom);
+/// lower.set_scroll_offset(2, 0);
+/// StatefulWidget::render(&textarea, area, &mut buf, &mut lower);
+///
+/// assert_eq!(upper.scroll_offset(), (0, 0));
+/// assert_eq!(lower.scroll_offset(), (2, 0));
+/// assert_eq!(lower.size(), (3, 2));
+/// // The scroll position of the textarea itself is not changed
+/// assert_eq!(textarea.scroll_offset(), (0, 0));
+/// ```
+///
+/// [`TextArea`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html
+/// [`TextArea::scroll`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.scroll
+/// [`CursorMove::InViewport`]: https://docs.rs/tui-textarea/latest/tui_textarea/enum.CursorMove.html#variant.InViewport
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TextAreaState {
+    scroll_top: (u16, u16),
+    size: (u16, u16),
+}
+
+impl TextAreaState {
+    /// Get the (row, col) position of the top-left corner of the view. It is updated when the textarea is rendered.
+    pub fn scroll_offset(&self) -> (u16, u16) {
+        self.scroll_top
+    }
+
+    /// Set the (row, col) position of the top-left corner of the view. The view is scrolled from the position to
+    /// keep the cursor in it on the next rendering.
+    pub fn set_scroll_offset(&mut self, row: u16, col: u16) {
+        self.scroll_top = (row, col);
+    }
+
+    /// Get the (width, height) size of the view excluding the block. It is `(0, 0)` until the textarea is rendered.
+    pub fn size(&self) -> (u16, u16) {
+        self.size
+    }
+}
+
+impl StatefulWidget for &TextArea<'_> {
+    type State = TextAreaState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let (inner, top_row, top_col) = self.render_view(area, buf, state.scroll_top);
+        state.scroll_top = (top_row, top_col);
+        state.size = (inner.width, inner.height);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ratatui::layout::Alignment;
+    use crate::ratatui::style::{Color, Modifier, Style};
+    use crate::CursorMove;
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    fn rendered(buf: &Buffer) -> Vec<String> {
+        let area = buf.area;
+        (area.top()..area.bottom())
+            .map(|y| {
+                (area.left()..area.right())
+                    .map(|x| {
+                        #[cfg(feature = "ratatui")]
+                        let symbol = buf[(x, y)].symbol();
+                        #[cfg(feature = "tuirs")]
+                        let symbol = buf.get(x, y).symbol.as_str();
+                        symbol
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn render(t: &TextArea<'_>) -> Buffer {
+        let area = Rect::new(0, 0, 12, 3);
+        let mut buf = Buffer::empty(area);
+        Widget::render(t, area, &mut buf);
+        buf
+    }
+
+    #[test]
+    fn render_cache() {
+        let steps: &[fn(&mut TextArea<'_>)] = &[
+            |t| t.insert_char('!'),
+            |t| t.move_cursor(CursorMove::Down),
+            |t| t.set_cursor_line_style(Style::default().fg(Color::Red)),
+            |t| t.start_selection(),
+            |t| t.move_cursor(CursorMove::Up),
+            |t| t.set_line_number_style(Style::default()),
+            |t| t.scroll((1, 0)),
+            |t| {
+                t.undo();
+            },
+            |t| t.set_eol_annotation(0, Span::raw("!")),
+            |t| t.select_all(),
+            |t| {
+                t.split_selection_on_newlines();
+            },
+            |t| {
+                t.cut();
+            },
+            |t| t.set_placeholder_text("empty"),
+            |t| {
+                t.insert_str("hel");
+            },
+            |t| t.set_completion_items(vec!["hello".to_string()]),
+            |t| {
+                t.select_next_completion();
+            },
+            |t| t.add_highlight(1, ((0, 0), (0, 1)), Style::default().fg(Color::Red), 0),
+            |t| t.set_line_postprocessor(|_, line| line),
+            |t| {
+                t.conceal_range((0, 0), (0, 1), "x");
+            },
+            |t| t.set_row_alignment(0, Alignment::Right),
+            |t| t.set_style(Style::default().fg(Color::Blue)),
+            |t| t.set_tab_length(2),
+            |t| t.set_preedit(Some(("x".to_string(), 1))),
+            |t| t.set_inline_hint(Some(("hint".to_string(), Style::default()))),
+            |t| t.set_mask_char('*'),
+            |t| t.set_focused(false),
+        ];
+
+        let mut t = TextArea::from(["hello", "world", "goodbye", "world"]);
+        t.set_render_cache(true);
+        assert!(t.is_render_dirty());
+        for (i, step) in steps.iter().enumerate() {
+            let rendered = render(&t);
+            assert!(!t.is_render_dirty(), "step {}", i);
+            assert_eq!(render(&t), rendered, "step {}", i);
+
+            step(&mut t);
+            assert!(t.is_render_dirty(), "step {}", i);
+            // Rendering the clone does not use the cache
+            assert_eq!(render(&t), render(&t.clone()), "step {}", i);
+        }
+    }
+
+    #[test]
+    fn render_dirty_without_cache() {
+        let mut t = TextArea::from(["hello"]);
+        // Frames are not tracked until the dirtiness is checked
+        render(&t);
+        assert!(t.last_frame.key().is_none());
+        assert!(t.is_render_dirty());
+
+        render(&t);
+        assert!(!t.is_render_dirty());
+        t.add_highlight(1, ((0, 0), (0, 1)), Style::default().fg(Color::Red), 0);
+        assert!(t.is_render_dirty());
+        render(&t);
+        assert!(!t.is_render_dirty());
+    }
+
+    #[test]
+    fn render_cache_cells_under_textarea() {
+        let fill = |c: &str| {
+            let mut buf = Buffer::empty(Rect::new(0, 0, 8, 1));
+            for x in 0..8 {
+                #[cfg(feature = "ratatui")]
+                buf[(x, 0)].set_symbol(c);
+                #[cfg(feature = "tuirs")]
+                buf.get_mut(x, 0).set_symbol(c);
+            }
+            buf
+        };
+        let mut t = TextArea::from(["hi"]);
+        t.move_cursor(CursorMove::End);
+        assert!(t.is_render_dirty());
+
+        // Cells after the end of the line are not painted by the textarea
+        let area = Rect::new(0, 0, 8, 1);
+        for c in ["X", "Y"] {
+            let mut buf = fill(c);
+            Widget::render(&t, area, &mut buf);
+            assert_eq!(rendered(&buf), [format!("hi {}", c.repeat(5))]);
+        }
+        assert!(!t.is_render_dirty());
+
+        // The cached frame includes the cells under the textarea
+        t.set_render_cache(true);
+        let mut buf = fill("X");
+        Widget::render(&t, area, &mut buf);
+        let mut buf = fill("Y");
+        Widget::render(&t, area, &mut buf);
+        assert_eq!(rendered(&buf), ["hi XXXXX"]);
+    }
+
+    #[test]
+    fn invalidate_render_cache() {
+        let upper = Arc::new(AtomicBool::new(false));
+        let mut t = TextArea::from(["hello"]);
+        t.set_render_cache(true);
+        let flag = upper.clone();
+        t.set_line_postprocessor(move |_, line| {
+            if flag.load(Ordering::Relaxed) {
+                Line::from("HELLO")
+            } else {
+                line
+            }
+        });
+
+        assert_eq!(rendered(&render(&t))[0], "hello       ");
+        upper.store(true, Ordering::Relaxed);
+        assert!(!t.is_render_dirty());
+        assert_eq!(rendered(&render(&t))[0], "hello       ");
+
+        t.invalidate_render_cache();
+        assert!(t.is_render_dirty());
+        assert_eq!(rendered(&render(&t))[0], "HELLO       ");
+        assert!(!t.is_render_dirty());
+    }
+
+    #[test]
+    fn render_secondary_selections() {
+        let mut t = TextArea::from(["abc", "def", "ghi"]);
+        t.set_selection_style(Style::default().bg(Color::Red));
+        t.select_all();
+        t.split_selection_on_newlines();
+
+        let buf = render(&t);
+        for y in 0..3 {
+            for x in 0..3 {
+                #[cfg(feature = "ratatui")]
+                let bg = buf[(x, y)].bg;
+                #[cfg(feature = "tuirs")]
+                let bg = buf.get(x, y).bg;
+                assert_eq!(bg, Color::Red, "({}, {})", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn render_secondary_selections_scrolled() {
+        let mut t: TextArea = (0..10).map(|_| "abc").collect();
+        t.set_selection_style(Style::default().bg(Color::Red));
+        render(&t);
+        t.set_scroll_offset(1, 0);
+        t.move_cursor(CursorMove::Jump(7, 2));
+        t.start_selection();
+        t.move_cursor(CursorMove::Jump(2, 1));
+        t.split_selection_on_newlines();
+
+        // Rows 1 to 3 are rendered
+        let buf = render(&t);
+        assert_eq!(t.scroll_offset(), (1, 0));
+        for (x, y, selected) in [(0, 0, false), (2, 0, false), (0, 1, false), (2, 1, true)]
+            .into_iter()
+            .chain((0..3).map(|x| (x, 2, true)))
+        {
+            #[cfg(feature = "ratatui")]
+            let bg = buf[(x, y)].bg;
+            #[cfg(feature = "tuirs")]
+            let bg = buf.get(x, y).bg;
+            assert_eq!(bg == Color::Red, selected, "({}, {})", x, y);
+        }
+    }
+
+    #[test]
+    fn render_placeholder() {
+        let mut t = TextArea::default();
+        t.set_placeholder_text("abc");
+        t.set_placeholder_style(Style::default().fg(Color::Gray));
+        t.set_cursor_style(Style::default().bg(Color::Red));
+
+        let tests = [
+            (PlaceholderCursor::Before, None, " abc        ", Some(0)),
+            (PlaceholderCursor::Over, None, "abc         ", Some(0)),
+            (PlaceholderCursor::Hidden, None, "abc         ", None),
+            (
+                PlaceholderCursor::Over,
+                Some(Alignment::Right),
+                "         abc",
+                Some(9),
+            ),
+            (
+                PlaceholderCursor::Before,
+                Some(Alignment::Center),
+                "     abc    ",
+                Some(4),
+            ),
+        ];
+        for (cursor, alignment, want, cursor_x) in tests {
+            t.set_placeholder_cursor(cursor);
+            t.set_placeholder_alignment(alignment);
+            let buf = render(&t);
+            let row = &rendered(&buf)[0];
+            assert_eq!(row, want, "{:?} {:?}", cursor, alignment);
+            for x in 0..12 {
+                #[cfg(feature = "ratatui")]
+                let bg = buf[(x, 0)].bg;
+                #[cfg(feature = "tuirs")]
+                let bg = buf.get(x, 0).bg;
+                let is_cursor = cursor_x == Some(x);
+                assert_eq!(
+                    bg == Color::Red,
+                    is_cursor,
+                    "{:?} {:?} {}",
+                    cursor,
+                    alignment,
+                    x
+                );
+            }
+        }
+
+        // The cursor hidden by the option is not rendered
+        t.set_placeholder_cursor(PlaceholderCursor::Before);
+        t.set_placeholder_alignment(None);
+        t.set_cursor_hidden(true);
+        assert_eq!(t.render_to_string(5, 1), "abc  ");
+    }
+
+    #[test]
+    fn render_cursor_blink() {
+        let mut t = TextArea::from(["abc"]);
+        t.set_cursor_style(Style::default().bg(Color::Red));
+        t.set_cursor_blink(Some(Duration::from_millis(100)));
+        let cursor_bg = |t: &TextArea<'_>| {
+            let buf = render(t);
+            #[cfg(feature = "ratatui")]
+            let bg = buf[(0, 0)].bg;
+            #[cfg(feature = "tuirs")]
+            let bg = buf.get(0, 0).bg;
+            bg
+        };
+
+        let start = Instant::now();
+        assert!(!t.tick(start));
+        assert_eq!(cursor_bg(&t), Color::Red);
+        assert!(t.tick(start + Duration::from_millis(150)));
+        assert_ne!(cursor_bg(&t), Color::Red);
+        assert!(t.tick(start + Duration::from_millis(250)));
+        assert_eq!(cursor_bg(&t), Color::Red);
+
+        // Moving the cursor shows it
+        t.tick(start + Duration::from_millis(350));
+        assert_ne!(cursor_bg(&t), Color::Red);
+        t.move_cursor(CursorMove::End);
+        t.move_cursor(CursorMove::Head);
+        assert_eq!(cursor_bg(&t), Color::Red);
+
+        // Disabling the blink shows the cursor
+        t.tick(start + Duration::from_millis(350));
+        t.tick(start + Duration::from_millis(450));
+        assert_ne!(cursor_bg(&t), Color::Red);
+        t.set_cursor_blink(None);
+        assert!(!t.tick(start + Duration::from_millis(550)));
+        assert_eq!(cursor_bg(&t), Color::Red);
+    }
+
+    #[test]
+    fn render_focus() {
+        let mut t = TextArea::from(["abc"]);
+        t.set_cursor_style(Style::default().bg(Color::Red));
+        t.set_selection_style(Style::default().bg(Color::Blue));
+        t.move_cursor(CursorMove::End);
+        t.start_selection();
+        t.move_cursor(CursorMove::Head);
+        let cells = |t: &TextArea<'_>| {
+            let buf = render(t);
+            (0..4)
+                .map(|x| {
+                    #[cfg(feature = "ratatui")]
+                    let cell = &buf[(x, 0)];
+                    #[cfg(feature = "tuirs")]
+                    let cell = buf.get(x, 0);
+                    (cell.bg, cell.modifier.contains(Modifier::UNDERLINED))
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let focused = [
+            (Color::Red, true),
+            (Color::Blue, true),
+            (Color::Blue, true),
+            (Color::Reset, false),
+        ];
+        assert_eq!(cells(&t), focused);
+
+        t.set_focused(false);
+        assert_eq!(
+            cells(&t),
+            [
+                (Color::DarkGray, false),
+                (Color::DarkGray, false),
+                (Color::DarkGray, false),
+                (Color::Reset, false),
+            ],
+        );
+
+        t.set_unfocused_cursor_style(Some(Style::default().bg(Color::Gray)));
+        t.set_unfocused_cursor_line_style(Style::default().add_modifier(Modifier::UNDERLINED));
+        t.set_unfocused_selection_style(Style::default().bg(Color::Green));
+        assert_eq!(
+            cells(&t),
+            [
+                (Color::Gray, true),
+                (Color::Green, true),
+                (Color::Green, true),
+                (Color::Reset, false),
+            ],
+        );
+
+        t.set_focused(true);
+        assert_eq!(cells(&t), focused);
+    }
+
+    #[test]
+    fn render_row_alignment() {
+        let mut t = TextArea::from(["ab", "cd", "ef"]);
+        t.set_row_alignment(0, Alignment::Center);
+        t.set_row_alignment(1, Alignment::Right);
+        assert_eq!(
+            t.render_to_string(12, 3),
+            "    ab      \n         cd \nef          "
+        );
+
+        // Line numbers stay in the gutter
+        t.set_line_number_style(Style::default());
+        assert_eq!(
+            t.render_to_string(12, 3),
+            " 1    ab    \n 2       cd \n 3 ef       "
+        );
+
+        // Row alignments are ignored while the whole textarea is aligned
+        t.set_alignment(Alignment::Right);
+        assert_eq!(
+            t.render_to_string(12, 3),
+            "          ab\n          cd\n          ef"
+        );
+
+        t.set_alignment(Alignment::Left);
+        t.clear_row_alignments();
+        assert_eq!(t.render_to_string(12, 1), "ab          ");
+    }
+
+    #[test]
+    fn row_alignment_cursor() {
+        let mut t = TextArea::from(["abcdef"]);
+        t.set_row_alignment(0, Alignment::Right);
+        t.move_cursor(CursorMove::End);
+        // The line fits in the area so it is not scrolled horizontally
+        assert_eq!(t.render_to_string(8, 1), " abcdef ");
+
+        // Lines wider than the area are not aligned
+        assert_eq!(t.render_to_string(4, 1), "def ");
+    }
+
+    #[test]
+    fn row_alignment_horizontal_scroll() {
+        let long = "x".repeat(60);
+        let mut t = TextArea::from(["title", long.as_str()]);
+        t.set_row_alignment(0, Alignment::Center);
+        t.move_cursor(CursorMove::Jump(1, 60));
+        assert_eq!(
+            t.render_to_string(20, 2),
+            format!("{}\n{} ", " ".repeat(20), "x".repeat(19))
+        );
+
+        // The padding does not depend on the scroll position so rendering again does not scroll the textarea
+        t.move_cursor(CursorMove::Up);
+        let area = Rect::new(0, 0, 20, 2);
+        let mut prev = None;
+        for _ in 0..3 {
+            let mut buf = Buffer::empty(area);
+            Widget::render(&t, area, &mut buf);
+            assert_eq!(rendered(&buf), [" ".repeat(20), "x".repeat(20)]);
+            // The cursor after the centered title is at the left edge
+            #[cfg(feature = "ratatui")]
+            let style = buf[(0, 0)].style();
+            #[cfg(feature = "tuirs")]
+            let style = buf.get(0, 0).style();
+            assert!(style.add_modifier.contains(Modifier::REVERSED));
+            if let Some(prev) = prev {
+                assert_eq!(buf, prev);
+            }
+            prev = Some(buf);
+        }
+        assert_eq!(t.scroll_offset(), (0, 12));
+    }
+
+    #[test]
+    fn last_render_size() {
+        let mut t = TextArea::from(["abc", "def"]);
+        assert_eq!(t.last_render_size(), None);
+
+        render(&t);
+        assert_eq!(t.last_render_size(), Some((12, 3)));
+
+        t.set_line_number_style(Style::default());
+        t.set_diff_marks([(0, crate::DiffKind::Added)]);
+        assert_eq!(t.last_render_size(), Some((7, 3)));
+
+        // Rendering as a stateful widget does not update the size
+        let mut state = TextAreaState::default();
+        let area = Rect::new(0, 0, 5, 1);
+        StatefulWidget::render(&t, area, &mut Buffer::empty(area), &mut state);
+        assert_eq!(t.last_render_size(), Some((7, 3)));
+    }
+
+    #[test]
+    fn render_clipped() {
+        let mut t = TextArea::from(["abc", "def", "ghi", "jkl"]);
+        t.move_cursor(CursorMove::Jump(2, 0));
+
+        let mut buf = Buffer::empty(Rect::new(0, 0, 5, 3));
+        for cell in buf.content.iter_mut() {
+            cell.set_symbol("x");
+        }
+
+        // Only the cells in the clip are modified
+        t.render_clipped(Rect::new(1, 0, 4, 4), &mut buf, Rect::new(0, 1, 5, 1));
+        assert_eq!(rendered(&buf), ["xxxxx", "xdefx", "xxxxx"]);
+        assert_eq!(t.scroll_offset(), (0, 0));
+
+        // The area can be partially outside the buffer
+        let visible = Rect::new(0, 0, 5, 2);
+        let mut buf = Buffer::empty(visible);
+        t.render_clipped(Rect::new(2, 0, 4, 4), &mut buf, visible);
+        assert_eq!(rendered(&buf), ["  abc", "  def"]);
+        assert_eq!(t.scroll_offset(), (0, 0));
+
+        // The viewport is still tracked when the area is not visible at all
+        t.move_cursor(CursorMove::Bottom);
+        t.render_clipped(Rect::new(0, 0, 4, 2), &mut buf, Rect::new(0, 0, 0, 0));
+        assert_eq!(rendered(&buf), ["  abc", "  def"]);
+        assert_eq!(t.scroll_offset(), (2, 0));
+    }
+
+    #[test]
+    fn render_clipped_rows() {
+        use crate::ratatui::widgets::{Block, Borders};
+
+        // Each row rendered in the clip is the same as the row of the textarea rendered directly
+        let mut t = TextArea::from(["abc", "def", "ghi", "jkl", "mno"]);
+        t.move_cursor(CursorMove::Jump(2, 1));
+        t.set_completion_items(vec!["hello".to_string()]);
+        let area = Rect::new(0, 0, 6, 7);
+        for mut t in [t, TextArea::from(["abc"]), TextArea::default()] {
+            t.set_block(Block::default().borders(Borders::ALL).title("t"));
+            t.set_placeholder_text("empty");
+            let mut expected = Buffer::empty(area);
+            Widget::render(&t, area, &mut expected);
+            let expected = rendered(&expected);
+            for y in 0..area.height {
+                let clip = Rect::new(0, y, area.width, 1);
+                let mut buf = Buffer::empty(clip);
+                t.render_clipped(area, &mut buf, clip);
+                assert_eq!(rendered(&buf)[0], expected[y as usize], "row {y}");
+            }
+        }
+
+        // The area can be larger than the limit of the buffer size of tui-rs. Note that `Rect::new` of tui-rs shrinks
+        // such a large area
+        let area = Rect {
+            x: 0,
+            y: 0,
+            width: 80,
+            height: 1000,
+        };
+        let mut t = TextArea::new((0..1000).map(|i| i.to_string()).collect());
+        t.set_block(Block::default().borders(Borders::ALL));
+        t.move_cursor(CursorMove::Jump(600, 0));
+        let visible = Rect::new(0, 0, 80, 3);
+        let mut buf = Buffer::empty(visible);
+        t.render_clipped(area, &mut buf, visible);
+        assert_eq!(t.scroll_offset(), (0, 0));
+        let rows = rendered(&buf);
+        assert!(rows[1].starts_with("│0 "), "{rows:?}");
+        assert!(rows[2].starts_with("│1 "), "{rows:?}");
+
+        // The textarea is scrolled for the whole area and the rows below the visible rows are rendered
+        let visible = Rect::new(0, 998, 80, 2);
+        let mut buf = Buffer::empty(visible);
+        t.render_clipped(area, &mut buf, visible);
+        let rows = rendered(&buf);
+        assert!(rows[0].starts_with("│997 "), "{rows:?}");
+        assert!(rows[1].starts_with("└──"), "{rows:?}");
+
+        // Many visible rows are rendered in chunks
+        let mut buf = Buffer::empty(Rect::new(0, 100, 80, 800));
+        t.render_clipped(area, &mut buf, area);
+        let rows = rendered(&buf);
+        assert!(rows[0].starts_with("│99 "), "{rows:?}");
+        assert!(rows[799].starts_with("│898 "), "{rows:?}");
+    }
+
+    #[test]
+    fn render_stateful() {
+        let mut t = TextArea::from(["abc", "def", "ghi", "jkl"]);
+        let area = Rect::new(0, 0, 4, 2);
+        let mut left = TextAreaState::default();
+        let mut right = TextAreaState::default();
+
+        t.move_cursor(CursorMove::Bottom);
+        let mut buf = Buffer::empty(area);
+        StatefulWidget::render(&t, area, &mut buf, &mut left);
+        assert_eq!(rendered(&buf), ["ghi ", "jkl "]);
+        assert_eq!(left.scroll_offset(), (2, 0));
+        assert_eq!(left.size(), (4, 2));
+        assert_eq!(t.scroll_offset(), (0, 0));
+
+        // Each view scrolls from its own position
+        t.move_cursor(CursorMove::Up);
+        let mut buf = Buffer::empty(area);
+        StatefulWidget::render(&t, area, &mut buf, &mut right);
+        assert_eq!(rendered(&buf), ["def ", "ghi "]);
+        assert_eq!(right.scroll_offset(), (1, 0));
+        let mut buf = Buffer::empty(area);
+        StatefulWidget::render(&t, area, &mut buf, &mut left);
+        assert_eq!(rendered(&buf), ["ghi ", "jkl "]);
+        assert_eq!(left.scroll_offset(), (2, 0));
 
-        inner.render(text_area, buf);
+        // Rendering as a stateless widget uses the viewport of the textarea
+        let mut buf = Buffer::empty(area);
+        Widget::render(&t, area, &mut buf);
+        assert_eq!(rendered(&buf), ["def ", "ghi "]);
+        assert_eq!(t.scroll_offset(), (1, 0));
     }
 }
@@ -0,0 +1,173 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+use unicode_width::UnicodeWidthChar as _;
+
+/// Measurement of the display width of characters in cells. The measurement is used to expand tabs to the tab stops,
+/// to mask text, to align spaces inserted by [`TextArea::insert_tab`], and to place the completion popup. Implement
+/// this trait to match the width which the terminal actually renders, for example when the font renders some
+/// characters wider than [Unicode Standard Annex #11][uax11] defines. Functions of `Fn(char) -> usize` also
+/// implement this trait. The default measurement is [`UnicodeWidth`]. See also: [`TextArea::set_char_width`]
+///
+/// Note that the cells of the text in the buffer are laid out by ratatui (or tui-rs) with the `unicode-width` crate.
+///
+/// ```
+/// use tui_textarea::{CharWidth, UnicodeWidth};
+///
+/// assert_eq!(UnicodeWidth::default().width('a'), 1);
+/// assert_eq!(UnicodeWidth::default().width('あ'), 2);
+///
+/// // Ambiguous width characters are wide in CJK context
+/// assert_eq!(UnicodeWidth::default().width('○'), 1);
+/// assert_eq!(UnicodeWidth::cjk().width('○'), 2);
+///
+/// // Functions can be used as measurement
+/// let w = |c: char| if c == '○' { 2 } else { 1 };
+/// assert_eq!(w.str_width("a○"), 3);
+/// ```
+///
+/// [`TextArea::insert_tab`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.insert_tab
+/// [`TextArea::set_char_width`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.set_char_width
+/// [uax11]: https://www.unicode.org/reports/tr11/
+pub trait CharWidth: Send + Sync {
+    /// Number of cells the character occupies. Control characters should occupy 0 cells.
+    fn width(&self, c: char) -> usize;
+
+    /// Number of cells the string occupies. The default implementation sums the widths of the characters.
+    fn str_width(&self, s: &str) -> usize {
+        s.chars().map(|c| self.width(c)).sum()
+    }
+}
+
+impl<F> CharWidth for F
+where
+    F: Fn(char) -> usize + Send + Sync,
+{
+    fn width(&self, c: char) -> usize {
+        self(c)
+    }
+}
+
+/// Measurement of the display width with the [`unicode-width`][unicode-width] crate. This is the default measurement
+/// of [`TextArea`].
+///
+/// This type is marked as `#[non_exhaustive]` since more fields may be added in the future. Use
+/// [`UnicodeWidth::default`] or [`UnicodeWidth::cjk`] to create an instance.
+///
+/// [unicode-width]: https://crates.io/crates/unicode-width
+/// [`TextArea`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct UnicodeWidth {
+    /// Whether characters of ambiguous width are 2 cells wide as in CJK context. The default value is `false`.
+    pub cjk: bool,
+}
+
+impl UnicodeWidth {
+    /// Create a measurement where characters of ambiguous width such as `○` and `α` are 2 cells wide. This is
+    /// suitable for terminals configured to render the characters wide for CJK languages.
+    /// ```
+    /// use tui_textarea::UnicodeWidth;
+    ///
+    /// assert!(UnicodeWidth::cjk().cjk);
+    /// ```
+    pub fn cjk() -> Self {
+        Self { cjk: true }
+    }
+}
+
+impl CharWidth for UnicodeWidth {
+    fn width(&self, c: char) -> usize {
+        let w = if self.cjk { c.width_cjk() } else { c.width() };
+        w.unwrap_or(0)
+    }
+}
+
+// Measurement of character widths set to the textarea. The overrides of the widths set by
+// `TextArea::set_width_overrides` take precedence over the measurement.
+#[derive(Clone)]
+pub struct SharedCharWidth {
+    pub measure: Arc<dyn CharWidth>,
+    pub overrides: Arc<Vec<(RangeInclusive<char>, usize)>>,
+}
+
+impl Default for SharedCharWidth {
+    fn default() -> Self {
+        Self {
+            measure: Arc::new(UnicodeWidth::default()),
+            overrides: Arc::default(),
+        }
+    }
+}
+
+impl CharWidth for SharedCharWidth {
+    fn width(&self, c: char) -> usize {
+        // Later overrides take precedence over earlier ones
+        match self.overrides.iter().rev().find(|(r, _)| r.contains(&c)) {
+            Some(&(_, width)) => width,
+            None => self.measure.width(c),
+        }
+    }
+}
+
+impl fmt::Debug for SharedCharWidth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The address identifies the measurement in the key of the render cache
+        write!(
+            f,
+            "CharWidth({:p}, {:?})",
+            Arc::as_ptr(&self.measure) as *const (),
+            self.overrides,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn emoji_width() {
+        let measure = UnicodeWidth::default();
+        let tests = [
+            ("😀", 2),                     // Emoji presentation
+            ("☺", 1),                      // Text presentation by default
+            ("☺\u{FE0F}", 1),              // Variation selector is zero width
+            ("👍🏽", 4),                     // Skin tone modifier is measured separately
+            ("👨\u{200D}👩\u{200D}👧", 6), // ZWJ is zero width and the joined emojis are measured separately
+            ("🇯🇵", 2),                     // Regional indicators
+        ];
+        for (s, want) in tests {
+            assert_eq!(measure.str_width(s), want, "{:?}", s);
+        }
+    }
+
+    #[test]
+    fn width_overrides() {
+        let mut w = SharedCharWidth {
+            overrides: Arc::new(vec![
+                ('\u{2600}'..='\u{26FF}', 2),
+                ('\u{1F3FB}'..='\u{1F3FF}', 0), // Skin tone modifiers are rendered with the previous emoji
+                ('☂'..='☂', 1),                 // Later override takes precedence
+            ]),
+            ..Default::default()
+        };
+        let tests = [
+            ("a", 1),
+            ("☺", 2),
+            ("☂", 1),
+            ("👍🏽", 2),
+            ("👨\u{200D}👩\u{200D}👧", 6),
+        ];
+        for (s, want) in tests {
+            assert_eq!(w.str_width(s), want, "{:?}", s);
+        }
+
+        // Overrides are applied on top of the measurement
+        w.measure = Arc::new(|_| 3);
+        assert_eq!(w.str_width("a☺"), 5);
+    }
+}
@@ -1,3 +1,58 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::iter;
+use std::str;
+use std::sync::Arc;
+
+/// Definition of word boundaries used by word-wise cursor moves such as [`CursorMove::WordForward`] and word-wise
+/// deletions such as [`TextArea::delete_word`].
+///
+/// This type is marked as `#[non_exhaustive]` since more variations may be supported in the future. See also:
+/// [`TextArea::set_word_boundary_mode`]
+///
+/// [`CursorMove::WordForward`]: crate::CursorMove::WordForward
+/// [`TextArea::delete_word`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.delete_word
+/// [`TextArea::set_word_boundary_mode`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.set_word_boundary_mode
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum WordBoundary {
+    /// Word boundaries appear at spaces, punctuations, and others. For example `fn foo_bar(a)` consists of words
+    /// `fn`, `foo`, `_`, `bar`, `(`, `a`, `)`. This is the default.
+    Default,
+    /// In addition to [`WordBoundary::Default`], camelCase and PascalCase segments are separate words like modern
+    /// IDEs. For example `parseHTTPRequest2` consists of words `parse`, `HTTP`, `Request2`.
+    SubWord,
+    /// Word boundaries appear only at spaces. For example `fn foo(a)` consists of words `fn` and `foo(a)`.
+    WhitespaceOnly,
+}
+
+impl Default for WordBoundary {
+    fn default() -> Self {
+        Self::Default
+    }
+}
+
+// Predicate to decide word characters.
+type WordCharsFn = dyn Fn(char) -> bool + Send + Sync;
+
+// Definition of words shared by the functions to find word boundaries
+#[derive(Clone, Default)]
+pub struct Words {
+    pub boundary: WordBoundary,
+    pub chars: Option<Arc<WordCharsFn>>, // `None` means alphanumeric and non-ASCII characters
+}
+
+impl fmt::Debug for Words {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Words")
+            .field("boundary", &self.boundary)
+            .field("chars", &self.chars.as_ref().map(|_| "WordCharsFn"))
+            .finish()
+    }
+}
+
 #[derive(PartialEq, Eq, Clone, Copy)]
 enum CharKind {
     Space,
@@ -6,76 +61,499 @@ enum CharKind {
 }
 
 impl CharKind {
-    fn new(c: char) -> Self {
+    fn new(c: char, words: &Words) -> Self {
         if c.is_whitespace() {
-            Self::Space
-        } else if c.is_ascii_punctuation() {
-            Self::Punct
-        } else {
+            return Self::Space;
+        }
+        if words.boundary == WordBoundary::WhitespaceOnly {
+            return Self::Other;
+        }
+        let is_word = match &words.chars {
+            Some(f) => f(c),
+            None => !c.is_ascii_punctuation(),
+        };
+        if is_word {
             Self::Other
+        } else {
+            Self::Punct
         }
     }
 }
 
-pub fn find_word_start_forward(line: &str, start_col: usize) -> Option<usize> {
-    let mut it = line.chars().enumerate().skip(start_col);
-    let mut prev = CharKind::new(it.next()?.1);
-    for (col, c) in it {
-        let cur = CharKind::new(c);
-        if cur != CharKind::Space && prev != cur {
-            return Some(col);
+// Character with its kind to find word boundaries
+#[derive(Clone, Copy)]
+struct Char {
+    c: char,
+    kind: CharKind,
+}
+
+impl Char {
+    fn new(c: char, words: &Words) -> Self {
+        let kind = CharKind::new(c, words);
+        Self { c, kind }
+    }
+
+    fn is_space(self) -> bool {
+        self.kind == CharKind::Space
+    }
+}
+
+// Edge between the characters at `col - 1` and `col` of a line
+struct Edge {
+    col: usize,
+    prev: Char,
+    cur: Char,
+    splits: bool, // Whether the characters belong to different words
+}
+
+impl Edge {
+    // `next` is the character after `cur`, which is necessary to split `HTTPRequest` into `HTTP` and `Request`
+    fn new(col: usize, prev: Char, cur: Char, next: Option<char>, boundary: WordBoundary) -> Self {
+        let splits = if prev.kind != cur.kind {
+            true
+        } else if boundary != WordBoundary::SubWord || cur.kind != CharKind::Other {
+            false
+        } else {
+            let (p, c) = (prev.c, cur.c);
+            // `fooBar` or `foo2Bar`
+            c.is_uppercase() && !p.is_uppercase() && p.is_alphanumeric()
+                // `HTTPRequest`
+                || c.is_uppercase() && p.is_uppercase() && next.map_or(false, char::is_lowercase)
+        };
+        Self {
+            col,
+            prev,
+            cur,
+            splits,
         }
-        prev = cur;
     }
-    None
 }
 
-pub fn find_word_exclusive_end_forward(line: &str, start_col: usize) -> Option<usize> {
-    let mut it = line.chars().enumerate().skip(start_col);
-    let mut prev = CharKind::new(it.next()?.1);
-    for (col, c) in it {
-        let cur = CharKind::new(c);
-        if prev != CharKind::Space && prev != cur {
-            return Some(col);
+// Iterator of the edges after the column in the line from left to right. The line is scanned lazily from the column
+struct Forward<'a> {
+    chars: iter::Peekable<iter::Skip<str::Chars<'a>>>,
+    col: usize,
+    prev: Char, // Character at `col`
+    words: &'a Words,
+}
+
+impl<'a> Forward<'a> {
+    // `None` is returned when the column is at or after the end of line
+    fn new(line: &'a str, col: usize, words: &'a Words) -> Option<Self> {
+        let mut chars = line.chars().skip(col).peekable();
+        let prev = Char::new(chars.next()?, words);
+        Some(Self {
+            chars,
+            col,
+            prev,
+            words,
+        })
+    }
+}
+
+impl Iterator for Forward<'_> {
+    type Item = Edge;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let cur = Char::new(self.chars.next()?, self.words);
+        let next = self.chars.peek().copied();
+        self.col += 1;
+        let edge = Edge::new(self.col, self.prev, cur, next, self.words.boundary);
+        self.prev = cur;
+        Some(edge)
+    }
+}
+
+// Iterator of the edges before the column in the line from right to left. The first edge is between the characters at
+// `col - 1` and `col`. The line is scanned lazily from the column
+struct Backward<'a> {
+    chars: iter::Rev<str::Chars<'a>>,
+    col: usize,
+    cur: Char, // Character at `col`
+    next: Option<char>,
+    words: &'a Words,
+}
+
+impl<'a> Backward<'a> {
+    // `None` is returned when the column is at or after the end of line
+    fn new(line: &'a str, col: usize, words: &'a Words) -> Option<Self> {
+        let mut it = line.char_indices().skip(col);
+        let (i, c) = it.next()?;
+        let next = it.next().map(|(_, c)| c);
+        Some(Self {
+            chars: line[..i].chars().rev(),
+            col,
+            cur: Char::new(c, words),
+            next,
+            words,
+        })
+    }
+
+    // Start from the last character of the line. `None` is returned when the line is empty
+    fn from_end(line: &'a str, words: &'a Words) -> Option<Self> {
+        let (i, c) = line.char_indices().next_back()?;
+        let chars = line[..i].chars().rev();
+        Some(Self {
+            col: chars.clone().count(),
+            chars,
+            cur: Char::new(c, words),
+            next: None,
+            words,
+        })
+    }
+}
+
+impl Iterator for Backward<'_> {
+    type Item = Edge;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let prev = Char::new(self.chars.next()?, self.words);
+        let edge = Edge::new(self.col, prev, self.cur, self.next, self.words.boundary);
+        self.col -= 1;
+        self.next = Some(self.cur.c);
+        self.cur = prev;
+        Some(edge)
+    }
+}
+
+pub fn find_word_start_forward(line: &str, start_col: usize, words: &Words) -> Option<usize> {
+    Forward::new(line, start_col, words)?
+        .find(|e| !e.cur.is_space() && e.splits)
+        .map(|e| e.col)
+}
+
+pub fn find_word_exclusive_end_forward(
+    line: &str,
+    start_col: usize,
+    words: &Words,
+) -> Option<usize> {
+    Forward::new(line, start_col, words)?
+        .find(|e| !e.prev.is_space() && e.splits)
+        .map(|e| e.col)
+}
+
+pub fn find_word_inclusive_end_forward(
+    line: &str,
+    start_col: usize,
+    words: &Words,
+) -> Option<usize> {
+    let mut it = Forward::new(line, start_col, words)?;
+    if let Some(e) = it.by_ref().find(|e| !e.prev.is_space() && e.splits) {
+        return Some(e.col - 1);
+    }
+    // The last character of the line is the end of the last word
+    (!it.prev.is_space()).then(|| it.col)
+}
+
+pub fn find_word_start_backward(line: &str, start_col: usize, words: &Words) -> Option<usize> {
+    let mut it = match Backward::new(line, start_col.checked_sub(1)?, words) {
+        Some(it) => it,
+        None => Backward::from_end(line, words)?,
+    };
+    if let Some(e) = it.by_ref().find(|e| !e.cur.is_space() && e.splits) {
+        return Some(e.col);
+    }
+    // The head of the line is the start of the first word
+    (!it.cur.is_space()).then(|| 0)
+}
+
+// Find the last character of the word which ends before the column
+pub fn find_word_inclusive_end_backward(
+    line: &str,
+    start_col: usize,
+    words: &Words,
+) -> Option<usize> {
+    let mut it = match Backward::new(line, start_col, words) {
+        Some(it) => it,
+        None => {
+            // The last character of the line is the end of the last word
+            let it = Backward::from_end(line, words)?;
+            if !it.cur.is_space() {
+                return Some(it.col);
+            }
+            it
+        }
+    };
+    it.find(|e| !e.prev.is_space() && e.splits)
+        .map(|e| e.col - 1)
+}
+
+// Range of the word at the column as (start, exclusive end). Consecutive spaces are also treated as one word.
+pub fn find_word_range(line: &str, col: usize, words: &Words) -> Option<(usize, usize)> {
+    let start = Backward::new(line, col, words)?
+        .find(|e| e.splits)
+        .map_or(0, |e| e.col);
+    let mut it = Forward::new(line, col, words)?;
+    let end = match it.by_ref().find(|e| e.splits) {
+        Some(e) => e.col,
+        None => it.col + 1,
+    };
+    Some((start, end))
+}
+
+/// Scanner to find word boundaries in a line with the same rules as word-wise cursor moves and deletions of
+/// [`TextArea`]. This is useful to implement custom motions or to decide when to trigger completion. Columns are
+/// counted in characters (not bytes nor display width) as the cursor positions of [`TextArea`]. Spaces are not
+/// words, but a sequence of punctuations is one word in [`WordBoundary::Default`].
+///
+/// Use [`TextArea::word_scanner`] to scan a line of the textarea with its word definition.
+///
+/// ```
+/// use tui_textarea::{WordBoundary, WordScanner};
+///
+/// let mut scanner = WordScanner::new("let café = parseHTTP(x);");
+///
+/// assert_eq!(scanner.next_word_start(0), Some(4));
+/// assert_eq!(scanner.next_word_end(4), Some(7));
+/// assert_eq!(scanner.prev_word_start(9), Some(4));
+/// assert_eq!(scanner.word_at(12), Some((11, 20)));
+///
+/// scanner.set_boundary(WordBoundary::SubWord);
+/// assert_eq!(scanner.word_at(12), Some((11, 16)));
+/// ```
+///
+/// [`TextArea`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html
+/// [`TextArea::word_scanner`]: https://docs.rs/tui-textarea/latest/tui_textarea/struct.TextArea.html#method.word_scanner
+#[derive(Clone, Debug)]
+pub struct WordScanner<'a> {
+    line: &'a str,
+    words: Words,
+}
+
+impl<'a> WordScanner<'a> {
+    /// Create a scanner of the line with [`WordBoundary::Default`] and the default word characters.
+    pub fn new(line: &'a str) -> Self {
+        Self {
+            line,
+            words: Words::default(),
         }
-        prev = cur;
     }
-    None
+
+    pub(crate) fn with_words(line: &'a str, words: Words) -> Self {
+        Self { line, words }
+    }
+
+    /// Set the definition of word boundaries. See [`WordBoundary`] for the variations.
+    pub fn set_boundary(&mut self, boundary: WordBoundary) {
+        self.words.boundary = boundary;
+    }
+
+    /// Set the predicate to decide which characters are word characters. By default, all characters except for
+    /// spaces and ASCII punctuations are word characters.
+    /// ```
+    /// use tui_textarea::WordScanner;
+    ///
+    /// let mut scanner = WordScanner::new("cd ~/foo-bar");
+    /// assert_eq!(scanner.word_at(6), Some((5, 8)));
+    ///
+    /// scanner.set_word_chars(|c| c.is_alphanumeric() || c == '-');
+    /// assert_eq!(scanner.word_at(6), Some((5, 12)));
+    /// ```
+    pub fn set_word_chars(&mut self, is_word_char: impl Fn(char) -> bool + Send + Sync + 'static) {
+        self.words.chars = Some(Arc::new(is_word_char));
+    }
+
+    /// Get the line scanned by this scanner.
+    pub fn line(&self) -> &'a str {
+        self.line
+    }
+
+    /// Find the column of the start of the next word after the column, like `w` of Vim. `None` is returned when no
+    /// word starts after the column in the line.
+    /// ```
+    /// use tui_textarea::WordScanner;
+    ///
+    /// let scanner = WordScanner::new("foo.bar  baz");
+    /// assert_eq!(scanner.next_word_start(0), Some(3));
+    /// assert_eq!(scanner.next_word_start(4), Some(9));
+    /// assert_eq!(scanner.next_word_start(9), None);
+    /// ```
+    pub fn next_word_start(&self, col: usize) -> Option<usize> {
+        find_word_start_forward(self.line, col, &self.words)
+    }
+
+    /// Find the column of the last character of the word which ends after the column, like `e` of Vim. `None` is
+    /// returned when no word ends after the column in the line.
+    /// ```
+    /// use tui_textarea::WordScanner;
+    ///
+    /// let scanner = WordScanner::new("foo.bar  baz");
+    /// assert_eq!(scanner.next_word_end(0), Some(2));
+    /// assert_eq!(scanner.next_word_end(2), Some(3));
+    /// assert_eq!(scanner.next_word_end(6), Some(11));
+    /// assert_eq!(scanner.next_word_end(11), None);
+    /// ```
+    pub fn next_word_end(&self, col: usize) -> Option<usize> {
+        find_word_inclusive_end_forward(self.line, col.saturating_add(1), &self.words)
+    }
+
+    /// Find the column of the start of the word before the column, like `b` of Vim. `None` is returned when no word
+    /// starts before the column in the line.
+    /// ```
+    /// use tui_textarea::WordScanner;
+    ///
+    /// let scanner = WordScanner::new("foo.bar  baz");
+    /// assert_eq!(scanner.prev_word_start(12), Some(9));
+    /// assert_eq!(scanner.prev_word_start(9), Some(4));
+    /// assert_eq!(scanner.prev_word_start(0), None);
+    /// ```
+    pub fn prev_word_start(&self, col: usize) -> Option<usize> {
+        find_word_start_backward(self.line, col, &self.words)
+    }
+
+    /// Find the range of the word at the column as a pair of the start column and the exclusive end column.
+    /// Consecutive spaces are treated as one word. `None` is returned when the column is at or after the end of line.
+    /// ```
+    /// use tui_textarea::WordScanner;
+    ///
+    /// let scanner = WordScanner::new("foo.bar  baz");
+    /// assert_eq!(scanner.word_at(1), Some((0, 3)));
+    /// assert_eq!(scanner.word_at(8), Some((7, 9)));
+    /// assert_eq!(scanner.word_at(12), None);
+    /// ```
+    pub fn word_at(&self, col: usize) -> Option<(usize, usize)> {
+        find_word_range(self.line, col, &self.words)
+    }
 }
 
-pub fn find_word_inclusive_end_forward(line: &str, start_col: usize) -> Option<usize> {
-    let mut it = line.chars().enumerate().skip(start_col);
-    let (mut last_col, c) = it.next()?;
-    let mut prev = CharKind::new(c);
-    for (col, c) in it {
-        let cur = CharKind::new(c);
-        if prev != CharKind::Space && cur != prev {
-            return Some(col.saturating_sub(1));
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with(boundary: WordBoundary) -> Words {
+        Words {
+            boundary,
+            chars: None,
+        }
+    }
+
+    fn words(line: &str, words: &Words) -> Vec<String> {
+        let chars: Vec<char> = line.chars().collect();
+        let mut ret = vec![];
+        let mut col = if chars.first().map_or(true, |c| c.is_whitespace()) {
+            find_word_start_forward(line, 0, words)
+        } else {
+            Some(0)
+        };
+        while let Some(start) = col {
+            let end = find_word_inclusive_end_forward(line, start, words).unwrap();
+            ret.push(chars[start..=end].iter().collect());
+            col = find_word_start_forward(line, start, words);
         }
-        prev = cur;
-        last_col = col;
-    }
-    if prev != CharKind::Space {
-        Some(last_col)
-    } else {
-        None
-    }
-}
-
-pub fn find_word_start_backward(line: &str, start_col: usize) -> Option<usize> {
-    let idx = line
-        .char_indices()
-        .nth(start_col)
-        .map(|(i, _)| i)
-        .unwrap_or(line.len());
-    let mut it = line[..idx].chars().rev().enumerate();
-    let mut cur = CharKind::new(it.next()?.1);
-    for (i, c) in it {
-        let next = CharKind::new(c);
-        if cur != CharKind::Space && next != cur {
-            return Some(start_col - i);
+        ret
+    }
+
+    #[test]
+    fn word_scanner_unicode() {
+        let tests: &[(&str, &[(usize, usize)])] = &[
+            ("日本語 テキスト", &[(0, 3), (4, 8)]),
+            ("héllo, wörld", &[(0, 5), (5, 6), (7, 12)]),
+            ("a→b", &[(0, 3)]),
+            ("x 🐶🐱 y", &[(0, 1), (2, 4), (5, 6)]),
+            ("\tα.β", &[(1, 2), (2, 3), (3, 4)]),
+            ("", &[]),
+        ];
+        for (line, expected) in tests {
+            let scanner = WordScanner::new(line);
+            let mut col = match scanner.word_at(0) {
+                Some((0, _)) if !line.starts_with(char::is_whitespace) => Some(0),
+                _ => scanner.next_word_start(0),
+            };
+            let mut ranges = vec![];
+            while let Some(start) = col {
+                ranges.push(scanner.word_at(start).unwrap());
+                col = scanner.next_word_start(start);
+            }
+            assert_eq!(&ranges, expected, "{:?}", line);
+
+            // Scanning backward finds the same starts
+            let count = line.chars().count();
+            let mut starts = vec![];
+            let mut col = count;
+            while let Some(start) = scanner.prev_word_start(col) {
+                starts.push(start);
+                col = start;
+            }
+            starts.reverse();
+            let want: Vec<_> = expected.iter().map(|r| r.0).collect();
+            assert_eq!(starts, want, "{:?}", line);
+
+            // Ends of the words are the last characters
+            for &(s, e) in expected.iter().filter(|(s, e)| e - s > 1) {
+                assert_eq!(scanner.next_word_end(s), Some(e - 1), "{:?} at {}", line, s);
+            }
         }
-        cur = next;
     }
-    (cur != CharKind::Space).then(|| 0)
+
+    #[test]
+    fn word_boundary() {
+        let line = "fn parseHTTPRequest2(foo_bar, x.y)";
+        assert_eq!(
+            words(line, &with(WordBoundary::Default)),
+            [
+                "fn",
+                "parseHTTPRequest2",
+                "(",
+                "foo",
+                "_",
+                "bar",
+                ",",
+                "x",
+                ".",
+                "y",
+                ")"
+            ],
+        );
+        assert_eq!(
+            words(line, &with(WordBoundary::SubWord)),
+            ["fn", "parse", "HTTP", "Request2", "(", "foo", "_", "bar", ",", "x", ".", "y", ")"],
+        );
+        assert_eq!(
+            words(line, &with(WordBoundary::WhitespaceOnly)),
+            ["fn", "parseHTTPRequest2(foo_bar,", "x.y)"],
+        );
+    }
+
+    #[test]
+    fn word_chars() {
+        let path = "cd ~/.config/foo-bar";
+        let mut w = with(WordBoundary::Default);
+        assert_eq!(
+            words(path, &w),
+            ["cd", "~/.", "config", "/", "foo", "-", "bar"]
+        );
+        w.chars = Some(Arc::new(|c| c.is_alphanumeric() || c == '-' || c == '.'));
+        assert_eq!(words(path, &w), ["cd", "~/", ".config", "/", "foo-bar"]);
+        w.boundary = WordBoundary::WhitespaceOnly;
+        assert_eq!(words(path, &w), ["cd", "~/.config/foo-bar"]);
+    }
+
+    #[test]
+    fn backward() {
+        let line = "fooBar baz";
+        assert_eq!(
+            find_word_start_backward(line, 10, &with(WordBoundary::Default)),
+            Some(7)
+        );
+        assert_eq!(
+            find_word_start_backward(line, 7, &with(WordBoundary::Default)),
+            Some(0)
+        );
+        assert_eq!(
+            find_word_start_backward(line, 7, &with(WordBoundary::SubWord)),
+            Some(3)
+        );
+        assert_eq!(
+            find_word_start_backward(line, 3, &with(WordBoundary::SubWord)),
+            Some(0)
+        );
+        assert_eq!(
+            find_word_start_backward(line, 0, &with(WordBoundary::SubWord)),
+            None
+        );
+        assert_eq!(
+            find_word_start_backward("  a", 1, &with(WordBoundary::Default)),
+            None
+        );
+    }
 }
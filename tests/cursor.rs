@@ -16,9 +16,15 @@ fn empty_textarea() {
         End,
         Top,
         Bottom,
+        DownToFirstNonSpace,
+        UpToFirstNonSpace,
+        LineStartNonSpaceOf(0),
+        LineStartNonSpaceOf(u16::MAX),
         WordForward,
         WordEnd,
         WordBack,
+        WordEndBack,
+        WordSpacingEndBack,
         ParagraphForward,
         ParagraphBack,
         Jump(0, 0),
@@ -273,6 +279,33 @@ fn bottom_trim() {
     }
 }
 
+#[test]
+fn first_non_space() {
+    let mut t = TextArea::from(["  aaa", "\t\tbbb", "   ", "ccc"]);
+    t.move_cursor(CursorMove::End);
+    for pos in [(1, 2), (2, 0), (3, 0)] {
+        t.move_cursor(CursorMove::DownToFirstNonSpace);
+        assert_eq!(t.cursor(), pos);
+    }
+    t.move_cursor(CursorMove::DownToFirstNonSpace);
+    assert_eq!(t.cursor(), (3, 0));
+
+    t.move_cursor(CursorMove::End);
+    for pos in [(2, 0), (1, 2), (0, 2)] {
+        t.move_cursor(CursorMove::UpToFirstNonSpace);
+        assert_eq!(t.cursor(), pos);
+    }
+    t.move_cursor(CursorMove::UpToFirstNonSpace);
+    assert_eq!(t.cursor(), (0, 2));
+
+    for (row, pos) in [(1, (1, 2)), (3, (3, 0)), (100, (3, 0)), (0, (0, 2))] {
+        t.move_cursor(CursorMove::LineStartNonSpaceOf(row));
+        assert_eq!(t.cursor(), pos);
+    }
+    assert!(t.jump_back());
+    assert_eq!(t.cursor(), (3, 0));
+}
+
 #[test]
 fn word_end() {
     for (lines, positions) in [
@@ -326,3 +359,93 @@ fn word_end() {
         }
     }
 }
+
+#[test]
+fn word_end_back() {
+    for (lines, positions) in [
+        (
+            &[
+                "aaa !!! bbb", // Consecutive punctuations are a word
+            ][..],
+            &[(0, 6), (0, 2), (0, 0)][..],
+        ),
+        (
+            &[
+                "aaa!!!bbb", // Word boundaries without spaces
+            ][..],
+            &[(0, 5), (0, 2), (0, 0)][..],
+        ),
+        (
+            &[
+                "aaa", "", "", "bbb", // Go across multiple empty lines
+            ][..],
+            &[(0, 2), (0, 0)][..],
+        ),
+        (
+            &[
+                "aaa   ", "   ",
+                "  bbb", // Ignore blank lines and the spaces at the end of line
+            ][..],
+            &[(0, 2), (0, 0)][..],
+        ),
+    ] {
+        let mut t: TextArea = lines.iter().cloned().collect();
+        t.move_cursor(CursorMove::Bottom);
+        t.move_cursor(CursorMove::End);
+        t.move_cursor(CursorMove::Back);
+        for pos in positions {
+            t.move_cursor(CursorMove::WordEndBack);
+            assert_eq!(t.cursor(), *pos, "{:?}", t.lines());
+        }
+    }
+}
+
+#[test]
+fn word_spacing_end_back() {
+    let mut t = TextArea::from(["foo(a)  bar.baz", "x+y"]);
+    t.move_cursor(CursorMove::Bottom);
+    t.move_cursor(CursorMove::End);
+    for pos in [(1, 2), (0, 14), (0, 5), (0, 0)] {
+        t.move_cursor(CursorMove::WordSpacingEndBack);
+        assert_eq!(t.cursor(), pos);
+    }
+}
+
+#[test]
+fn range_for_motion() {
+    let t = TextArea::from(["abc def", "ghi"]);
+    for (from, motion, count, expected) in [
+        ((0, 0), CursorMove::Forward, 2, ((0, 0), (0, 2))),
+        ((0, 0), CursorMove::WordForward, 1, ((0, 0), (0, 4))),
+        ((0, 0), CursorMove::WordForward, 100, ((0, 0), (1, 3))),
+        ((0, 4), CursorMove::WordEnd, 1, ((0, 4), (0, 7))),
+        ((0, 0), CursorMove::WordEnd, 1, ((0, 0), (0, 3))),
+        ((0, 0), CursorMove::WordEnd, 2, ((0, 0), (0, 7))),
+        ((1, 2), CursorMove::WordEndBack, 1, ((0, 6), (1, 3))),
+        ((1, 2), CursorMove::WordBack, 1, ((1, 0), (1, 2))),
+        ((1, 2), CursorMove::Up, 1, ((0, 0), (1, 3))),
+        ((0, 3), CursorMove::Down, 1, ((0, 0), (1, 3))),
+        ((1, 0), CursorMove::Down, 1, ((1, 0), (1, 0))),
+        ((0, 3), CursorMove::End, 1, ((0, 3), (0, 7))),
+        ((0, 3), CursorMove::Forward, 0, ((0, 3), (0, 3))),
+        ((9, 9), CursorMove::Head, 1, ((1, 0), (1, 3))),
+    ] {
+        let range = t.range_for_motion(from, motion, count);
+        assert_eq!(range, expected, "{:?} from {:?}", motion, from);
+    }
+    assert_eq!(t.cursor(), (0, 0));
+
+    // Removing the range of line-wise motions removes the whole lines
+    for (from, motion, count, expected) in [
+        ((0, 1), CursorMove::Down, 1, &["c", "d"][..]),
+        ((1, 1), CursorMove::Down, 2, &["a"][..]),
+        ((2, 0), CursorMove::Up, 1, &["a", "d"][..]),
+        ((0, 0), CursorMove::Bottom, 1, &[""][..]),
+        ((3, 0), CursorMove::Top, 1, &[""][..]),
+    ] {
+        let mut t = TextArea::from(["a", "b", "c", "d"]);
+        let (start, end) = t.range_for_motion(from, motion, count);
+        t.replace_range(start, end, "");
+        assert_eq!(t.lines(), expected, "{:?} from {:?}", motion, from);
+    }
+}
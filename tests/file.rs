@@ -0,0 +1,116 @@
+#![cfg(feature = "file")]
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use tui_textarea::{Encoding, LineEnding, TextArea};
+
+type FileTest<'a> = (&'a str, &'a [u8], &'a [&'a str], Encoding, LineEnding);
+
+fn temp_file(name: &str, content: &[u8]) -> PathBuf {
+    let path = env::temp_dir().join(format!("tui-textarea-{}-{}", std::process::id(), name));
+    fs::write(&path, content).unwrap();
+    path
+}
+
+#[test]
+fn test_open_save_file() {
+    #[rustfmt::skip]
+    let tests: &[FileTest] = &[
+        ("lf", b"abc\ndef\n", &["abc", "def"], Encoding::Utf8, LineEnding::Lf),
+        ("crlf", b"abc\r\ndef\r\n", &["abc", "def"], Encoding::Utf8, LineEnding::CrLf),
+        ("no-final-newline", b"abc\ndef", &["abc", "def"], Encoding::Utf8, LineEnding::Lf),
+        ("empty", b"", &[""], Encoding::Utf8, LineEnding::Lf),
+        ("utf8-bom", b"\xef\xbb\xbfabc\n", &["abc"], Encoding::Utf8Bom, LineEnding::Lf),
+        ("utf16le", b"\xff\xfea\x00\r\x00\n\x00b\x00", &["a", "b"], Encoding::Utf16Le, LineEnding::CrLf),
+        ("utf16be", b"\xfe\xff\x00a\x00\n", &["a"], Encoding::Utf16Be, LineEnding::Lf),
+    ];
+
+    for (name, content, lines, encoding, line_ending) in tests {
+        let path = temp_file(name, content);
+
+        let mut t = TextArea::open_file(&path).unwrap();
+        assert_eq!(t.lines(), *lines, "{}", name);
+        assert_eq!(t.encoding(), *encoding, "{}", name);
+        assert_eq!(t.line_ending(), *line_ending, "{}", name);
+        assert!(!t.is_modified(), "{}", name);
+
+        t.save_file(&path).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), *content, "{}", name);
+
+        fs::remove_file(&path).unwrap();
+    }
+}
+
+#[test]
+fn test_open_file_mixed_line_endings() {
+    let path = temp_file("mixed", b"abc\r\ndef\nghi\r\n");
+
+    let mut t = TextArea::open_file(&path).unwrap();
+    assert_eq!(t.lines(), ["abc", "def", "ghi"]);
+    assert_eq!(t.line_ending(), LineEnding::CrLf);
+    assert_eq!(t.detected_line_ending(), Some(LineEnding::CrLf));
+    assert!(t.has_mixed_line_endings());
+    assert!(!t.is_modified());
+
+    assert!(t.convert_line_endings(LineEnding::CrLf));
+    assert!(t.is_modified());
+    t.save_file(&path).unwrap();
+    assert_eq!(fs::read(&path).unwrap(), b"abc\r\ndef\r\nghi\r\n");
+    assert!(!t.is_modified());
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_trim_trailing_whitespace_on_save() {
+    let path = temp_file("trim", b"abc \ndef\t\n");
+
+    let mut t = TextArea::open_file(&path).unwrap();
+    t.save_file(&path).unwrap();
+    assert_eq!(fs::read(&path).unwrap(), b"abc \ndef\t\n");
+
+    t.set_trim_trailing_whitespace_on_save(true);
+    t.save_file(&path).unwrap();
+    assert_eq!(fs::read(&path).unwrap(), b"abc\ndef\n");
+    assert_eq!(t.lines(), ["abc", "def"]);
+    assert!(!t.is_modified());
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_set_final_newline() {
+    let path = temp_file("final-newline", b"abc");
+
+    let mut t = TextArea::open_file(&path).unwrap();
+    assert!(!t.final_newline());
+    t.set_final_newline(true);
+    t.save_file(&path).unwrap();
+    assert_eq!(fs::read(&path).unwrap(), b"abc\n");
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_save_file_resets_modified() {
+    let path = temp_file("modified", b"hello\n");
+
+    let mut t = TextArea::open_file(&path).unwrap();
+    t.insert_str(", world");
+    assert!(t.is_modified());
+
+    t.save_file(&path).unwrap();
+    assert!(!t.is_modified());
+    assert_eq!(fs::read(&path).unwrap(), b", worldhello\n");
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_open_file_invalid_data() {
+    let path = temp_file("invalid", b"\xff\xff\xff");
+    let err = TextArea::open_file(&path).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,223 @@
+use tui_textarea::{Form, Input, Key, TextArea};
+
+fn key(key: Key) -> Input {
+    Input {
+        key,
+        ..Default::default()
+    }
+}
+
+fn shift_tab() -> Input {
+    Input {
+        key: Key::Tab,
+        shift: true,
+        ..Default::default()
+    }
+}
+
+fn type_str(form: &mut Form<'_>, s: &str) {
+    for c in s.chars() {
+        assert!(form.input(key(Key::Char(c))), "{c:?}");
+    }
+}
+
+// Form with "name", "email", and "age" fields where only the name is filled
+fn signup_form() -> Form<'static> {
+    let mut form = Form::default();
+    form.push(TextArea::from(["rhysd"]));
+    form.push(TextArea::default());
+    form.push(TextArea::default());
+    form
+}
+
+// Only the focused field should be rendered as focused
+fn assert_focus(form: &Form<'_>, index: usize) {
+    assert_eq!(form.focused_index(), Some(index));
+    for (i, textarea) in form.iter().enumerate() {
+        assert_eq!(
+            textarea.focused(),
+            i == index,
+            "field {i} while {index} is focused"
+        );
+    }
+}
+
+// The cursor is rendered only in the focused field since the cursor is hidden while unfocused by default
+#[cfg(feature = "ratatui")]
+fn cursor_rendered(textarea: &TextArea<'_>) -> bool {
+    use ratatui::buffer::Buffer;
+    use ratatui::layout::Rect;
+    use ratatui::style::Modifier;
+    use ratatui::widgets::Widget;
+
+    let area = Rect::new(0, 0, 8, 1);
+    let mut buf = Buffer::empty(area);
+    Widget::render(textarea, area, &mut buf);
+    let (row, col) = textarea.cursor();
+    buf[(col as u16, row as u16)]
+        .modifier
+        .contains(Modifier::REVERSED)
+}
+
+#[test]
+fn test_tab_traversal() {
+    let mut form = signup_form();
+    assert_focus(&form, 0);
+
+    // Tab moves the focus forward and wraps around at the last field
+    for expected in [1, 2, 0, 1] {
+        assert!(!form.input(key(Key::Tab)));
+        assert_focus(&form, expected);
+    }
+
+    // Shift+Tab moves the focus backward and wraps around at the first field
+    for expected in [0, 2, 1] {
+        assert!(!form.input(shift_tab()));
+        assert_focus(&form, expected);
+    }
+
+    // Tab is never inserted into the fields
+    let lines: Vec<_> = form.iter().map(|t| t.lines().join("\n")).collect();
+    assert_eq!(lines, ["rhysd", "", ""]);
+
+    // Other inputs go to the focused field
+    type_str(&mut form, "a@b");
+    assert_eq!(form.get(1).unwrap().lines(), ["a@b"]);
+
+    // Tab with Ctrl or Alt is not a key to move the focus
+    for input in [
+        Input {
+            key: Key::Tab,
+            ctrl: true,
+            ..Default::default()
+        },
+        Input {
+            key: Key::Tab,
+            alt: true,
+            ..Default::default()
+        },
+    ] {
+        form.input(input);
+        assert_focus(&form, 1);
+    }
+
+    let mut empty = Form::default();
+    assert!(!empty.input(key(Key::Tab)));
+    assert!(!empty.input(shift_tab()));
+    assert!(!empty.input(key(Key::Char('a'))));
+    assert_eq!(empty.focused_index(), None);
+}
+
+#[cfg(feature = "crossterm")]
+#[test]
+fn test_back_tab_key_event() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    let mut form = signup_form();
+    // Terminals report Shift+Tab as `BackTab` with or without the Shift modifier
+    form.input(KeyEvent::new(KeyCode::BackTab, KeyModifiers::NONE));
+    assert_focus(&form, 2);
+    form.input(KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT));
+    assert_focus(&form, 1);
+    form.input(KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE));
+    assert_focus(&form, 2);
+}
+
+#[test]
+fn test_validate_after_input() {
+    let mut form = signup_form();
+    assert!(form.set_validator(1, |t| {
+        if t.lines()[0].contains('@') {
+            Ok(())
+        } else {
+            Err("email must contain '@'".to_string())
+        }
+    }));
+    assert!(form.set_validator(2, |t| match t.lines()[0].parse::<u8>() {
+        Ok(_) => Ok(()),
+        Err(_) => Err("age must be a number".to_string()),
+    }));
+    assert!(!form.set_validator(3, |_| Ok(())));
+
+    // Fields are validated with their current text
+    assert_eq!(form.first_invalid(), Some(1));
+    assert!(!form.is_valid());
+
+    form.input(key(Key::Tab));
+    type_str(&mut form, "rhysd");
+    assert_eq!(form.validate(1), Err("email must contain '@'".to_string()));
+    type_str(&mut form, "@example.com");
+    assert_eq!(form.validate(1), Ok(()));
+    assert_eq!(form.first_invalid(), Some(2));
+
+    form.input(key(Key::Tab));
+    type_str(&mut form, "4x");
+    assert_eq!(form.validate(2), Err("age must be a number".to_string()));
+    assert!(form.input(key(Key::Backspace)));
+    assert_eq!(form.validate(2), Ok(()));
+    assert!(form.is_valid());
+
+    // Validators don't prevent moving the focus. Invalid input is kept and the application decides what to do
+    type_str(&mut form, "99999");
+    form.input(key(Key::Tab));
+    assert_focus(&form, 0);
+    assert_eq!(form.first_invalid(), Some(2));
+    assert!(form.focus(form.first_invalid().unwrap()));
+    assert_focus(&form, 2);
+
+    // Replacing and removing validators take effect immediately
+    assert!(form.set_validator(2, |_| Ok(())));
+    assert!(form.is_valid());
+    assert!(form.remove_validator(1));
+    assert!(!form.remove_validator(1));
+    form.focus(1);
+    while form.input(key(Key::Backspace)) {}
+    assert_eq!(form.validate(1), Ok(()));
+    assert_eq!(form.validate(5), Ok(()));
+}
+
+#[test]
+fn test_focus_after_remove() {
+    let mut form = Form::default();
+    for s in ["a", "b", "c", "d"] {
+        form.push(TextArea::from([s]));
+    }
+    form.set_validator(3, |_| Err("invalid".to_string()));
+    assert!(form.focus(2));
+
+    // Removing a field before the focused one keeps the focus on the same field
+    assert_eq!(form.remove(0).unwrap().lines(), ["a"]);
+    assert_focus(&form, 1);
+    assert_eq!(form.focused().unwrap().lines(), ["c"]);
+
+    // Removing the focused field moves the focus to the next field, which is now rendered as focused
+    assert_eq!(form.remove(1).unwrap().lines(), ["c"]);
+    assert_focus(&form, 1);
+    assert_eq!(form.focused().unwrap().lines(), ["d"]);
+    // The validator moves with its field
+    assert_eq!(form.first_invalid(), Some(1));
+    #[cfg(feature = "ratatui")]
+    {
+        assert!(cursor_rendered(form.get(1).unwrap()));
+        assert!(!cursor_rendered(form.get(0).unwrap()));
+    }
+
+    // Removing the focused last field moves the focus to the previous field
+    assert!(form.remove(1).is_some());
+    assert_focus(&form, 0);
+    assert!(form.is_valid());
+    #[cfg(feature = "ratatui")]
+    assert!(cursor_rendered(form.get(0).unwrap()));
+
+    assert!(form.remove(1).is_none());
+    assert_focus(&form, 0);
+
+    assert_eq!(form.remove(0).unwrap().lines(), ["b"]);
+    assert!(form.is_empty());
+    assert_eq!(form.focused_index(), None);
+    assert!(!form.input(key(Key::Char('a'))));
+
+    // A field pushed to the emptied form gets the focus
+    form.push(TextArea::default());
+    assert_focus(&form, 0);
+}
@@ -0,0 +1,40 @@
+#![cfg(feature = "arbitrary")]
+
+use arbitrary::Unstructured;
+use tui_textarea::TextArea;
+
+// Simple xorshift generator to make the test deterministic without depending on a random number crate
+fn random_bytes(seed: u64, len: usize) -> Vec<u8> {
+    let mut x = seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1;
+    (0..len)
+        .map(|_| {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            (x >> 32) as u8
+        })
+        .collect()
+}
+
+#[test]
+fn test_random_ops_keep_invariants() {
+    for seed in 0..200 {
+        let data = random_bytes(seed, 512);
+        let mut u = Unstructured::new(&data);
+        let mut t = TextArea::from(["hello, world", "  fn foo(a, b) {}", "", "あいうえお"]);
+        for i in 0.. {
+            if t.apply_random_op(&mut u).is_err() || u.is_empty() {
+                break;
+            }
+            // Checking invariants on every operation is too slow
+            if i % 8 == 0 {
+                if let Err(err) = t.check_invariants() {
+                    panic!("seed {}: {} ({:?})", seed, err, t.lines());
+                }
+            }
+        }
+        if let Err(err) = t.check_invariants() {
+            panic!("seed {}: {} ({:?})", seed, err, t.lines());
+        }
+    }
+}
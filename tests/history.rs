@@ -1,4 +1,6 @@
-use tui_textarea::TextArea;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+use tui_textarea::{CursorMove, EditRecordKind, TextArea};
 
 // Regression test for #4
 #[test]
@@ -8,3 +10,112 @@ fn disable_history() {
     assert!(t.insert_str("hello"));
     assert_eq!(t.lines(), ["hello"]);
 }
+
+#[test]
+fn undo_redo_until() {
+    let tick = || sleep(Duration::from_millis(1));
+    let mut t = TextArea::default();
+    let start = Instant::now();
+    tick();
+    t.insert_str("a");
+    tick();
+    let t1 = Instant::now();
+    tick();
+    t.insert_str("b");
+    tick();
+    let t2 = Instant::now();
+    tick();
+    t.insert_str("c");
+
+    // Nothing to undo
+    assert!(!t.undo_until(Instant::now()));
+    assert!(t.undo_until(t2));
+    assert_eq!(t.lines(), ["ab"]);
+    assert_eq!(t.cursor(), (0, 2));
+    assert!(t.undo_until(start));
+    assert_eq!(t.lines(), [""]);
+    assert!(!t.undo_until(start));
+
+    assert!(t.redo_until(t1));
+    assert_eq!(t.lines(), ["a"]);
+    assert!(!t.redo_until(t1));
+    assert!(t.redo_until(Instant::now()));
+    assert_eq!(t.lines(), ["abc"]);
+    assert!(!t.redo_until(Instant::now()));
+
+    // Only the edits made after the time are undone
+    t.move_cursor(CursorMove::Head);
+    t.insert_str("x");
+    tick();
+    let t3 = Instant::now();
+    tick();
+    t.insert_str("y");
+    assert!(t.undo_until(t3));
+    assert_eq!(t.lines(), ["xabc"]);
+}
+
+#[test]
+fn with_history_disabled() {
+    let mut t = TextArea::from(["abc"]);
+    t.move_cursor(CursorMove::End);
+    t.insert_char('d');
+    t.insert_newline();
+    t.insert_str("efg");
+    t.undo(); // Redo history is cleared by the following edits
+    assert_eq!(t.lines(), ["abcd", ""]);
+
+    let modified = t.with_history_disabled(|t| {
+        t.move_cursor(CursorMove::Top);
+        t.insert_str(">> ");
+        t.append_lines(["log"]);
+        t.replace_range((0, 3), (0, 4), "A")
+    });
+    assert!(modified);
+    assert_eq!(t.lines(), [">> Abcd", "", "log"]);
+    assert!(!t.redo());
+
+    // Excluded edits are not undone
+    assert!(t.undo());
+    assert_eq!(t.lines(), [">> Abcd", "log"]);
+    assert!(t.undo());
+    assert_eq!(t.lines(), [">> Abc", "log"]);
+    assert!(!t.undo());
+    assert!(t.is_modified());
+
+    // Edits in the history which overlap with the excluded edit are discarded
+    t.insert_str("xyz");
+    assert_eq!(t.lines(), [">> Abcxyz", "log"]);
+    t.with_history_disabled(|t| t.replace_range((0, 5), (0, 7), ""));
+    assert_eq!(t.lines(), [">> Abyz", "log"]);
+    assert!(!t.undo());
+
+    // History is recorded again after the function
+    t.insert_char('!');
+    assert!(t.undo());
+}
+
+#[test]
+fn last_edit() {
+    let mut t = TextArea::from(["foo bar"]);
+    assert_eq!(t.last_edit(), None);
+
+    t.replace_range((0, 4), (0, 7), "baz");
+    let edit = t.last_edit().unwrap();
+    assert_eq!(edit.kind, EditRecordKind::Insert);
+    assert_eq!(edit.text, "baz");
+    assert_eq!((edit.start, edit.end), ((0, 4), (0, 7)));
+    assert_eq!(edit.revision, 2);
+
+    // Positions follow the edit excluded from the history
+    t.with_history_disabled(|t| t.replace_range((0, 0), (0, 0), "\n"));
+    let edit = t.last_edit().unwrap();
+    assert_eq!((edit.start, edit.end), ((1, 4), (1, 7)));
+
+    // Revision is updated by redo
+    t.undo();
+    assert_eq!(t.last_edit(), None);
+    t.redo();
+    let edit = t.last_edit().unwrap();
+    assert_eq!(edit.revision, t.revision());
+    assert!(!edit.remote);
+}
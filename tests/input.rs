@@ -1,4 +1,5 @@
-use tui_textarea::{Input, Key, TextArea};
+use std::time::Duration;
+use tui_textarea::{CursorMove, Input, Key, Keymap, TextArea};
 
 // Sanity test for checking textarea does not crash against all combination of inputs
 #[test]
@@ -80,3 +81,186 @@ fn test_insert_multi_code_unit_emoji() {
     }
     assert_eq!(t.lines(), ["👨‍👩‍👧‍👦"]);
 }
+
+#[test]
+fn test_repeat_last_change() {
+    fn key(key: Key) -> Input {
+        Input {
+            key,
+            ..Default::default()
+        }
+    }
+    fn ctrl(c: char) -> Input {
+        Input {
+            key: Key::Char(c),
+            ctrl: true,
+            ..Default::default()
+        }
+    }
+
+    let mut t = TextArea::from(["", "", ""]);
+    assert!(!t.repeat_last_change());
+
+    // Typing with backspace is one change
+    for k in [
+        Key::Char('a'),
+        Key::Char('x'),
+        Key::Backspace,
+        Key::Char('b'),
+    ] {
+        t.input(key(k));
+    }
+    assert_eq!(t.lines(), ["ab", "", ""]);
+    t.input(key(Key::Down));
+    assert!(t.repeat_last_change());
+    assert_eq!(t.lines(), ["ab", "ab", ""]);
+    t.input(key(Key::Down));
+    assert!(t.repeat_last_change());
+    assert_eq!(t.lines(), ["ab", "ab", "ab"]);
+
+    // Moving the cursor ends the change
+    t.input(key(Key::Char('c')));
+    t.input(key(Key::Left));
+    t.input(key(Key::Char('d')));
+    assert_eq!(t.lines(), ["ab", "ab", "abdc"]);
+    t.input(key(Key::Up));
+    t.repeat_last_change();
+    assert_eq!(t.lines(), ["ab", "abd", "abdc"]);
+
+    // Undo is not a change and the repeated change is undone at once
+    t.input(ctrl('u'));
+    assert_eq!(t.lines(), ["ab", "ab", "abdc"]);
+    t.input(key(Key::Up));
+    t.input(key(Key::End));
+    t.repeat_last_change();
+    assert_eq!(t.lines(), ["abd", "ab", "abdc"]);
+
+    // Deleting a word
+    t.input(ctrl('w'));
+    assert_eq!(t.lines(), ["", "ab", "abdc"]);
+    t.input(key(Key::Down));
+    t.input(key(Key::End));
+    t.repeat_last_change();
+    assert_eq!(t.lines(), ["", "", "abdc"]);
+
+    // Input which modified nothing does not clear the last change
+    t.input(key(Key::Up));
+    assert!(!t.input(key(Key::Backspace)));
+    t.input(key(Key::Down));
+    t.input(key(Key::Down));
+    t.input(key(Key::End));
+    t.repeat_last_change();
+    assert_eq!(t.lines(), ["", "", ""]);
+}
+
+#[test]
+fn test_queue_input() {
+    fn key(key: Key) -> Input {
+        Input {
+            key,
+            ..Default::default()
+        }
+    }
+
+    let mut t = TextArea::default();
+    assert_eq!(t.queued_inputs(), 0);
+    assert!(!t.process_queued(10));
+
+    for c in "ab\ncd".chars() {
+        let k = if c == '\n' { Key::Enter } else { Key::Char(c) };
+        assert!(t.queue_input(key(k)));
+    }
+    assert!(t.queue_input(key(Key::Left)));
+    assert_eq!(t.queued_inputs(), 6);
+
+    // Inputs are handled in order and the rest are kept in the queue
+    assert!(!t.process_queued(0));
+    assert!(t.process_queued(2));
+    assert_eq!(t.lines(), ["ab"]);
+    assert_eq!(t.queued_inputs(), 4);
+    assert!(t.process_queued(3));
+    assert_eq!(t.lines(), ["ab", "cd"]);
+
+    // Moving the cursor does not modify the text
+    assert!(!t.process_queued(usize::MAX));
+    assert_eq!(t.cursor(), (1, 1));
+    assert_eq!(t.queued_inputs(), 0);
+
+    // Each input is a separate edit like `TextArea::input`
+    t.undo();
+    assert_eq!(t.lines(), ["ab", "c"]);
+
+    t.queue_input(key(Key::Char('x')));
+    t.clear_queued_inputs();
+    assert_eq!(t.queued_inputs(), 0);
+    assert!(!t.process_queued(1));
+    assert_eq!(t.lines(), ["ab", "c"]);
+}
+
+#[test]
+fn test_keymap() {
+    fn key(c: char) -> Input {
+        Input {
+            key: Key::Char(c),
+            ..Default::default()
+        }
+    }
+
+    let mut keymap = Keymap::new();
+    keymap.set_timeout(None);
+    keymap.bind([key('g'), key('g')], |t| {
+        t.move_cursor(CursorMove::Top);
+        false
+    });
+    keymap.bind([key('g')], |t| t.insert_str("G"));
+    keymap.bind([key('j'), key('k')], |t| t.input(key('!')));
+    assert!(keymap.is_bound([key('g')]));
+
+    let mut t = TextArea::from(["a", "b"]);
+    t.move_cursor(CursorMove::Bottom);
+    t.set_keymap(keymap);
+
+    // `g g` is resolved by the second key
+    assert!(!t.input(key('g')));
+    assert_eq!(t.pending_keys(), [key('g')]);
+    assert!(!t.input(key('g')));
+    assert!(t.pending_keys().is_empty());
+    assert_eq!(t.cursor(), (0, 0));
+    assert_eq!(t.lines(), ["a", "b"]);
+
+    // `g` is resolved by the next key which is not a part of `g g`. `j` is kept pending for `j k`
+    t.input(key('g'));
+    assert!(t.input(key('j')));
+    assert_eq!(t.lines(), ["Ga", "b"]);
+    assert_eq!(t.pending_keys(), [key('j')]);
+
+    // The action handles the input without the keymap
+    assert!(t.input(key('k')));
+    assert_eq!(t.lines(), ["G!a", "b"]);
+
+    // Keys which are not bound are handled as usual
+    t.input(key('j'));
+    assert!(t.input(key('x')));
+    assert_eq!(t.lines(), ["G!jxa", "b"]);
+
+    // The pending keys are not resolved until the timeout
+    t.input(key('j'));
+    assert!(!t.process_pending_keys());
+    assert_eq!(t.pending_keys(), [key('j')]);
+    assert!(t.keymap().is_some());
+    let mut keymap = t.remove_keymap().unwrap();
+    assert_eq!(t.lines(), ["G!jxja", "b"]);
+    assert!(t.pending_keys().is_empty());
+
+    keymap.set_timeout(Some(Duration::ZERO));
+    t.set_keymap(keymap);
+    t.input(key('g'));
+    assert!(t.process_pending_keys());
+    assert!(t.pending_keys().is_empty());
+    assert_eq!(t.lines(), ["G!jxjGa", "b"]);
+
+    t.input(key('j'));
+    t.clear_pending_keys();
+    assert!(!t.process_pending_keys());
+    assert_eq!(t.lines(), ["G!jxjGa", "b"]);
+}
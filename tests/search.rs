@@ -1,6 +1,6 @@
 #![cfg(feature = "search")]
 
-use tui_textarea::{CursorMove, TextArea};
+use tui_textarea::{CursorMove, MatchAction, SearchDirection, SearchMode, TextArea};
 
 #[test]
 fn search_lines_forward() {
@@ -130,3 +130,158 @@ fn set_search_pattern() {
     assert!(!textarea.search_back(true));
     assert!(!textarea.search_back(false));
 }
+
+#[test]
+fn search_modes() {
+    #[rustfmt::skip]
+    let mut textarea = TextArea::from([
+        "foo.bar(x)",
+        "foo_bar(x)",
+        "FooBar(x)",
+    ]);
+
+    textarea.set_search_pattern("foo.bar").unwrap();
+    assert!(textarea.search_forward(false));
+    assert_eq!(textarea.cursor(), (1, 0));
+
+    // "." matches only to "." in literal mode
+    textarea.set_search_mode(SearchMode::Literal).unwrap();
+    assert_eq!(textarea.search_query(), Some("foo.bar"));
+    assert!(textarea.search_forward(false));
+    assert_eq!(textarea.cursor(), (0, 0));
+    assert!(textarea.search_back(false));
+    assert_eq!(textarea.cursor(), (0, 0));
+
+    // Smart case in fuzzy mode
+    textarea.set_search_mode(SearchMode::Fuzzy).unwrap();
+    textarea.set_search_pattern("fb(").unwrap();
+    let expected = [(1, 0), (2, 0), (0, 0)];
+    for (i, pos) in expected.into_iter().enumerate() {
+        assert!(textarea.search_forward(false), "{}th move", i + 1);
+        assert_eq!(textarea.cursor(), pos, "{}th position", i + 1);
+    }
+    textarea.set_search_pattern("FB").unwrap();
+    assert!(textarea.search_forward(false));
+    assert_eq!(textarea.cursor(), (2, 0));
+    textarea.set_search_pattern("fbz").unwrap();
+    assert!(!textarea.search_forward(false));
+}
+
+#[test]
+fn search_next_prev() {
+    #[rustfmt::skip]
+    let mut textarea = TextArea::from([
+        "foo",
+        "bar foo",
+        "foo bar",
+    ]);
+    textarea.move_cursor(CursorMove::Jump(1, 0));
+
+    // No pattern is set
+    assert!(!textarea.search_next());
+    assert!(!textarea.search_prev());
+
+    textarea.set_search_pattern("foo").unwrap();
+    assert!(textarea.search_next());
+    assert_eq!(textarea.cursor(), (1, 4));
+    assert!(textarea.search_prev());
+    assert_eq!(textarea.cursor(), (0, 0));
+
+    textarea.set_search_direction(SearchDirection::Backward);
+    let expected = [(2, 0), (1, 4), (0, 0)];
+    for (i, pos) in expected.into_iter().enumerate() {
+        assert!(textarea.search_next(), "{}th move", i + 1);
+        assert_eq!(textarea.cursor(), pos, "{}th position", i + 1);
+    }
+    assert!(textarea.search_prev());
+    assert_eq!(textarea.cursor(), (1, 4));
+
+    // Searching in the explicit direction does not change the direction
+    assert!(textarea.search_forward(false));
+    assert_eq!(textarea.search_direction(), SearchDirection::Backward);
+}
+
+#[test]
+fn select_all_matches() {
+    let mut t = TextArea::from(["foo bar foo", "baz foo"]);
+    assert!(t.select_all_matches("(").is_err());
+    assert_eq!(t.select_all_matches("qux").unwrap(), 0);
+    assert!(t.selections().is_empty());
+
+    // Matches in the entire text are selected when selection is not ongoing
+    assert_eq!(t.select_all_matches("foo").unwrap(), 3);
+    assert_eq!(
+        t.selections(),
+        [((0, 0), (0, 3)), ((0, 8), (0, 11)), ((1, 4), (1, 7))],
+    );
+    assert_eq!(t.primary_selection_index(), Some(0));
+
+    // Matches are searched in the selections
+    assert_eq!(t.select_all_matches("o").unwrap(), 6);
+    assert_eq!(t.select_all_matches("^o").unwrap(), 0);
+    assert_eq!(t.selections().len(), 6);
+
+    // Empty matches are not selected
+    t.cancel_selection();
+    assert_eq!(t.select_all_matches("x*").unwrap(), 0);
+
+    // The first match in the primary selection becomes the primary selection
+    t.move_cursor(CursorMove::Jump(1, 0));
+    t.start_selection();
+    t.move_cursor(CursorMove::End);
+    assert_eq!(t.select_all_matches("a").unwrap(), 1);
+    assert_eq!(t.selection_range(), Some(((1, 1), (1, 2))));
+
+    // Selections in the same line are not aligned
+    t.cancel_selection();
+    assert_eq!(t.select_all_matches("o").unwrap(), 6);
+    assert!(!t.align_selections());
+
+    // Matches can span multiple lines
+    t.cancel_selection();
+    assert_eq!(t.select_all_matches(r"foo\nbaz").unwrap(), 1);
+    assert_eq!(t.selections(), [((0, 8), (1, 3))]);
+}
+
+#[test]
+fn for_each_match() {
+    let mut t = TextArea::from(["aaa", "xa", "", "a"]);
+    assert_eq!(t.for_each_match(|_| unreachable!()), 0);
+
+    t.set_search_pattern("a+").unwrap();
+    assert_eq!(t.count_matches(), 3);
+
+    let mut visited = vec![];
+    let replaced = t.for_each_match(|m| {
+        visited.push((m.row, m.start, m.end, m.text.to_string()));
+        match m.row {
+            0 => MatchAction::Replace("b\nb".to_string()),
+            1 => MatchAction::Skip,
+            _ => MatchAction::Replace(String::new()),
+        }
+    });
+    assert_eq!(replaced, 2);
+    assert_eq!(
+        visited,
+        [
+            (0, 0, 3, "aaa".to_string()),
+            (1, 1, 2, "a".to_string()),
+            (3, 0, 1, "a".to_string()),
+        ],
+    );
+    assert_eq!(t.lines(), ["b", "b", "xa", "", ""]);
+    assert_eq!(t.count_matches(), 1);
+
+    // All replacements are undone at once
+    t.undo();
+    assert_eq!(t.lines(), ["aaa", "xa", "", "a"]);
+
+    // Matches after stopping are not visited
+    let mut count = 0;
+    t.for_each_match(|_| {
+        count += 1;
+        MatchAction::Stop
+    });
+    assert_eq!(count, 1);
+    assert_eq!(t.lines(), ["aaa", "xa", "", "a"]);
+}
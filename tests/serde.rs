@@ -1,6 +1,9 @@
 #![cfg(feature = "serde")]
 
-use tui_textarea::{CursorMove, Input, Key, Scrolling};
+use tui_textarea::{
+    CursorMove, DiffKind, Input, Key, MaskPolicy, PersistentState, Scrolling, TextArea,
+    WhitespaceStyle,
+};
 
 #[test]
 fn test_serde_key() {
@@ -45,3 +48,52 @@ fn test_serde_cursor_move() {
     let d: CursorMove = serde_json::from_str(&s).unwrap();
     assert_eq!(d, c);
 }
+
+#[test]
+fn test_serde_mask_policy() {
+    let p = MaskPolicy::AllExceptLast(4);
+    let s = serde_json::to_string(&p).unwrap();
+    assert_eq!(s, r#"{"AllExceptLast":4}"#);
+    let d: MaskPolicy = serde_json::from_str(&s).unwrap();
+    assert_eq!(d, p);
+}
+
+#[test]
+fn test_serde_whitespace_style() {
+    let w = WhitespaceStyle::Trailing;
+    let s = serde_json::to_string(&w).unwrap();
+    assert_eq!(s, r#""Trailing""#);
+    let d: WhitespaceStyle = serde_json::from_str(&s).unwrap();
+    assert_eq!(d, w);
+}
+
+#[test]
+fn test_serde_diff_kind() {
+    let k = DiffKind::Removed;
+    let s = serde_json::to_string(&k).unwrap();
+    assert_eq!(s, r#""Removed""#);
+    let d: DiffKind = serde_json::from_str(&s).unwrap();
+    assert_eq!(d, k);
+}
+
+#[test]
+fn test_serde_persistent_state() {
+    let mut t = TextArea::from(["abc", "def"]);
+    t.move_cursor(CursorMove::Bottom);
+    t.start_selection();
+    t.move_cursor(CursorMove::End);
+    t.set_mask_char('*');
+    t.set_placeholder_text("placeholder");
+
+    let state = t.to_state();
+    let s = serde_json::to_string(&state).unwrap();
+    let d: PersistentState = serde_json::from_str(&s).unwrap();
+    assert_eq!(d, state);
+
+    let restored = TextArea::from_state(d);
+    assert_eq!(restored.lines(), t.lines());
+    assert_eq!(restored.cursor(), (1, 3));
+    assert_eq!(restored.selection_range(), Some(((1, 0), (1, 3))));
+    assert_eq!(restored.mask_char(), Some('*'));
+    assert_eq!(restored.placeholder_text(), "placeholder");
+}
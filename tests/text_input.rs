@@ -0,0 +1,128 @@
+use tui_textarea::{Input, Key, TextInput};
+
+fn key(key: Key) -> Input {
+    Input {
+        key,
+        ..Default::default()
+    }
+}
+
+fn ctrl(c: char) -> Input {
+    Input {
+        key: Key::Char(c),
+        ctrl: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_no_newline() {
+    let mut t = TextInput::new("abc");
+    for input in [key(Key::Enter), ctrl('m'), key(Key::Char('\n'))] {
+        assert!(!t.input(input.clone()), "{:?}", input);
+        assert_eq!(t.text(), "abc", "{:?}", input);
+    }
+
+    t.textarea_mut().set_yank_text("d\ne\r\nf");
+    assert!(t.input(key(Key::Paste)));
+    assert_eq!(t.text(), "abcd e f");
+    assert!(t.input(ctrl('y')));
+    assert_eq!(t.text(), "abcd e fd e f");
+
+    assert!(t.set_text("x\ny"));
+    assert_eq!(t.text(), "x y");
+    assert_eq!(t.textarea().lines().len(), 1);
+}
+
+#[test]
+fn test_history_recall() {
+    let mut t = TextInput::default();
+    assert!(!t.input(key(Key::Up)));
+
+    for s in ["a", "b", "", "b", "c"] {
+        t.set_text(s);
+        assert_eq!(t.submit(), s);
+        assert_eq!(t.text(), "");
+    }
+    assert_eq!(t.history(), ["a", "b", "c"]);
+
+    t.input(key(Key::Char('x')));
+    assert!(!t.input(key(Key::Down)));
+
+    let tests = [
+        (key(Key::Up), true, "c"),
+        (ctrl('p'), true, "b"),
+        (key(Key::Up), true, "a"),
+        (key(Key::Up), false, "a"),
+        (key(Key::Down), true, "b"),
+        (ctrl('n'), true, "c"),
+        (key(Key::Down), true, "x"),
+        (key(Key::Down), false, "x"),
+    ];
+    for (input, modified, text) in tests {
+        assert_eq!(t.input(input.clone()), modified, "{:?}", input);
+        assert_eq!(t.text(), text, "{:?}", input);
+        assert_eq!(t.cursor(), text.chars().count(), "{:?}", input);
+    }
+
+    // Edit the recalled entry and submit it
+    t.input(key(Key::Up));
+    t.input(key(Key::Char('!')));
+    assert_eq!(t.submit(), "c!");
+    assert_eq!(t.history(), ["a", "b", "c", "c!"]);
+    t.input(key(Key::Up));
+    assert_eq!(t.text(), "c!");
+}
+
+#[test]
+fn test_complete() {
+    let mut t = TextInput::new("");
+    assert!(!t.complete());
+
+    let words = ["foo", "foobar", "foobaz", "qux"];
+    t.set_completer(move |text| {
+        words
+            .iter()
+            .filter(|w| w.starts_with(text))
+            .map(|w| w.to_string())
+            .collect()
+    });
+
+    t.set_text("q");
+    assert!(t.input(key(Key::Tab)));
+    assert_eq!(t.text(), "qux");
+    assert_eq!(t.completion_candidates(), ["qux"]);
+
+    t.set_text("f");
+    assert!(t.complete());
+    assert_eq!(t.text(), "foo");
+    assert!(!t.complete());
+    assert_eq!(t.completion_candidates(), ["foo", "foobar", "foobaz"]);
+
+    t.input(key(Key::Char('b')));
+    assert!(t.complete());
+    assert_eq!(t.text(), "fooba");
+    assert_eq!(t.cursor(), 5);
+
+    // Only the text before the cursor is completed
+    t.set_text("q rest");
+    t.input(key(Key::Home));
+    t.input(key(Key::Right));
+    assert!(t.complete());
+    assert_eq!(t.text(), "qux rest");
+    assert_eq!(t.cursor(), 3);
+
+    t.set_text("zzz");
+    assert!(!t.complete());
+    assert!(t.completion_candidates().is_empty());
+}
+
+#[test]
+fn test_mask() {
+    let mut t = TextInput::new("secret");
+    assert_eq!(t.mask_char(), None);
+    t.set_mask_char('*');
+    assert_eq!(t.mask_char(), Some('*'));
+    t.clear_mask_char();
+    assert_eq!(t.mask_char(), None);
+}
@@ -1,6 +1,6 @@
 use std::cmp;
 use std::fmt::Debug;
-use tui_textarea::{CursorMove, TextArea};
+use tui_textarea::{AnchorGrowth, CursorMove, DiffKind, IndentPolicy, Input, Key, TextArea};
 
 fn assert_undo_redo<T: Debug>(
     before_pos: (usize, usize),
@@ -941,6 +941,36 @@ fn test_delete_str_multiple_lines() {
     }
 }
 
+// Regression test for deleting characters past the end of the text. The deleted range ended at the line after the last
+// line, so undoing the deletion put the cursor outside the text
+#[test]
+fn test_delete_str_past_end_of_text() {
+    for (cursor, after, deleted) in [
+        ((0, 0), &[""][..], "a\nb"),
+        ((0, 1), &["a"][..], "\nb"),
+        ((1, 0), &["a", ""][..], "b"),
+    ] {
+        let mut t = TextArea::from(["a", "b"]);
+        t.move_cursor(CursorMove::Jump(cursor.0, cursor.1));
+
+        assert!(t.delete_str(10), "{cursor:?}");
+        assert_eq!(t.lines(), after, "{cursor:?}");
+        assert_eq!(
+            t.cursor(),
+            (cursor.0 as usize, cursor.1 as usize),
+            "{cursor:?}"
+        );
+        assert_eq!(t.yank_text(), deleted, "{cursor:?}");
+
+        // The deleted range ends at the end of the last line
+        assert!(t.undo(), "{cursor:?}");
+        assert_eq!(t.lines(), ["a", "b"], "{cursor:?}");
+        assert_eq!(t.cursor(), (1, 1), "{cursor:?}");
+        t.insert_char('!');
+        assert_eq!(t.lines(), ["a", "b!"], "{cursor:?}");
+    }
+}
+
 #[test]
 fn test_copy_single_line() {
     for i in 0..="abc".len() {
@@ -1619,6 +1649,55 @@ fn test_delete_next_char() {
     t.test((1, 1), (1, 1, t.0, ""));
 }
 
+#[test]
+fn test_replace_char() {
+    let tests = [
+        ((0, 0), 'x', (0, 0), &["xあc", "d"][..]),
+        ((0, 1), 'b', (0, 1), &["abc", "d"][..]),
+        ((0, 1), '😄', (0, 1), &["a😄c", "d"][..]),
+        ((0, 2), 'あ', (0, 2), &["aああ", "d"][..]),
+        ((1, 0), 'e', (1, 0), &["aあc", "e"][..]),
+    ];
+    for test in tests {
+        let (pos, c, after_pos, after_buf) = test;
+        let before_buf = ["aあc", "d"];
+        let mut t = TextArea::from(before_buf);
+        t.move_cursor(CursorMove::Jump(pos.0 as _, pos.1 as _));
+        assert!(t.replace_char(c), "{:?}", test);
+        assert_eq!(t.lines(), after_buf, "{:?}", test);
+        assert_eq!(t.cursor(), after_pos, "{:?}", test);
+        assert!(t.undo(), "{:?}", test);
+        assert_eq!(t.lines(), before_buf, "{:?}", test);
+        assert_eq!(t.cursor(), pos, "{:?}", test);
+        assert!(t.redo(), "{:?}", test);
+        assert_eq!(t.lines(), after_buf, "{:?}", test);
+    }
+
+    let mut t = TextArea::from(["ab"]);
+    for (pos, c) in [((0, 2), 'x'), ((0, 0), 'a'), ((0, 0), '\n')] {
+        t.move_cursor(CursorMove::Jump(pos.0 as _, pos.1 as _));
+        assert!(!t.replace_char(c), "{:?}", (pos, c));
+        assert_eq!(t.lines(), ["ab"]);
+        assert_eq!(t.cursor(), pos);
+        assert_no_undo_redo(&mut t, (pos, c));
+    }
+
+    // Replaced character is not yanked
+    t.set_yank_text("y");
+    t.move_cursor(CursorMove::Head);
+    assert!(t.replace_char('c'));
+    assert_eq!(t.yank_text(), "y");
+    t.undo();
+
+    // Selection is cancelled
+    t.move_cursor(CursorMove::Head);
+    t.start_selection();
+    t.move_cursor(CursorMove::Forward);
+    assert!(t.replace_char('x'));
+    assert_eq!(t.lines(), ["ax"]);
+    assert_eq!(t.selection_range(), None);
+}
+
 #[test]
 fn test_delete_line_by_end() {
     let t = DeleteTester(&["aaa bbb", "d"], |t| t.delete_line_by_end());
@@ -1669,3 +1748,1847 @@ fn test_delete_next_word() {
     t.test((1, 0), (1, 0, &["word  ことば 🐶", ""], " x"));
     t.test((1, 2), (1, 2, t.0, ""));
 }
+
+#[test]
+fn test_commit_preedit() {
+    let mut t = TextArea::from(["ab"]);
+    t.move_cursor(CursorMove::Forward);
+    assert!(!t.commit_preedit());
+
+    t.set_preedit(Some(("にほん".to_string(), 1)));
+    assert_eq!(t.lines(), ["ab"]);
+    assert_eq!(t.cursor(), (0, 1));
+
+    assert!(t.commit_preedit());
+    assert_eq!(t.preedit(), None);
+    assert_eq!(t.lines(), ["aにほんb"]);
+    assert_eq!(t.cursor(), (0, 4));
+    assert_undo_redo((0, 1), &["ab"], &["aにほんb"], &mut t, "");
+
+    t.set_preedit(Some((String::new(), 0)));
+    assert!(!t.commit_preedit());
+    assert_eq!(t.lines(), ["aにほんb"]);
+}
+
+#[test]
+fn test_input_with_count() {
+    use tui_textarea::{Input, Key};
+
+    let input = |key| Input {
+        key,
+        ctrl: false,
+        alt: false,
+        shift: false,
+    };
+
+    let mut t = TextArea::from(["abcdef", "ghi"]);
+    t.move_cursor(CursorMove::End);
+    assert!(t.input_with_count(input(Key::Backspace), 3));
+    assert_eq!(t.lines(), ["abc", "ghi"]);
+    assert_eq!(t.cursor(), (0, 3));
+    assert_undo_redo((0, 6), &["abcdef", "ghi"], &["abc", "ghi"], &mut t, "");
+
+    // Cursor moves don't modify text
+    assert!(!t.input_with_count(input(Key::Left), 2));
+    assert_eq!(t.cursor(), (0, 1));
+
+    let mut t = TextArea::default();
+    assert!(t.input_with_count(input(Key::Char('a')), 5));
+    assert!(t.input_with_count(input(Key::Enter), 2));
+    assert_eq!(t.lines(), ["aaaaa", "", ""]);
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["aaaaa"]);
+    assert!(t.undo());
+    assert_eq!(t.lines(), [""]);
+    assert!(!t.undo());
+
+    // Zero count does nothing
+    assert!(!t.input_with_count(input(Key::Char('a')), 0));
+    assert_eq!(t.lines(), [""]);
+}
+
+#[test]
+fn test_set_lines() {
+    type Pos = (usize, usize);
+    // (before, after, cursor before, cursor after)
+    let tests: &[(&[&str], &[&str], Pos, Pos)] = &[
+        (&["abc"], &["abc"], (0, 1), (0, 1)),
+        (&["abc"], &["aXc"], (0, 3), (0, 3)),
+        (&["abc"], &["aXYc"], (0, 3), (0, 4)),
+        (&["abc"], &["ac"], (0, 2), (0, 1)),
+        (&["abc"], &[""], (0, 2), (0, 0)),
+        (&["ab", "cd"], &["ab", "xy", "cd"], (1, 1), (2, 1)),
+        (&["ab", "xy", "cd"], &["ab", "cd"], (2, 2), (1, 2)),
+        (&["ab", "cd"], &["ab", "cd", "ef"], (1, 2), (1, 2)),
+        (&["ab", "cd", "ef"], &["ab"], (0, 1), (0, 1)),
+        (&["ab", "cd", "ef"], &["ab"], (1, 1), (0, 2)),
+        (&["🐱🐶", "🐰"], &["🐱🐮🐶", "🐰"], (0, 1), (0, 1)),
+        (&["🐱🐶", "🐰"], &["🐱🐮🐶", "🐰"], (1, 1), (1, 1)),
+        (&["あいう"], &["あえう"], (0, 3), (0, 3)),
+    ];
+
+    for (before, after, cursor_before, cursor_after) in tests {
+        let mut t = TextArea::from(before.iter().copied());
+        t.move_cursor(CursorMove::Jump(
+            cursor_before.0 as u16,
+            cursor_before.1 as u16,
+        ));
+        let modified = t.set_lines(after.iter().map(|s| s.to_string()).collect());
+        assert_eq!(modified, before != after, "{:?} -> {:?}", before, after);
+        assert_eq!(t.lines(), *after, "{:?} -> {:?}", before, after);
+        assert_eq!(t.cursor(), *cursor_after, "{:?} -> {:?}", before, after);
+
+        if modified {
+            assert!(t.undo(), "{:?} -> {:?}", before, after);
+            assert_eq!(t.lines(), *before, "{:?} -> {:?}", before, after);
+            assert!(t.redo(), "{:?} -> {:?}", before, after);
+            assert_eq!(t.lines(), *after, "{:?} -> {:?}", before, after);
+        }
+    }
+
+    // Lines containing newlines are split
+    let mut t = TextArea::from(["O"]);
+    assert!(t.set_lines(vec!["O\nab".to_string(), "c".to_string()]));
+    assert_eq!(t.lines(), ["O", "ab", "c"]);
+}
+
+#[test]
+fn test_set_text_preserves_state() {
+    let mut t = TextArea::default();
+    t.insert_str("hello");
+    t.move_cursor(CursorMove::Jump(0, 1));
+    t.start_selection();
+    t.move_cursor(CursorMove::Jump(0, 3));
+
+    assert!(t.set_text("# title\r\nhello"));
+    assert_eq!(t.lines(), ["# title", "hello"]);
+    assert_eq!(t.selection_range(), Some(((1, 1), (1, 3))));
+
+    // History before the replacement is preserved
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["hello"]);
+    assert!(t.undo());
+    assert_eq!(t.lines(), [""]);
+    assert!(!t.undo());
+}
+
+#[test]
+fn test_replace_range() {
+    let mut t = TextArea::from(["abc", "def", "ghi"]);
+    t.move_cursor(CursorMove::Jump(2, 2));
+
+    assert!(t.replace_range((0, 1), (1, 2), "XY\nZ"));
+    assert_eq!(t.lines(), ["aXY", "Zf", "ghi"]);
+    assert_eq!(t.cursor(), (2, 2));
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["abc", "def", "ghi"]);
+    assert!(t.redo());
+    assert_eq!(t.lines(), ["aXY", "Zf", "ghi"]);
+
+    // Reversed and out-of-bounds positions
+    let mut t = TextArea::from(["abc", "def"]);
+    assert!(t.replace_range((5, 5), (1, 1), "\r\n!"));
+    assert_eq!(t.lines(), ["abc", "d", "!"]);
+
+    // Cursor inside the replaced range moves to the end of the inserted text
+    let mut t = TextArea::from(["abcdef"]);
+    t.move_cursor(CursorMove::Jump(0, 3));
+    assert!(t.replace_range((0, 1), (0, 5), "🐱"));
+    assert_eq!(t.lines(), ["a🐱f"]);
+    assert_eq!(t.cursor(), (0, 2));
+
+    // Empty edit does nothing
+    let mut t = TextArea::from(["abc"]);
+    assert!(!t.replace_range((0, 1), (0, 1), ""));
+    assert!(!t.undo());
+}
+
+#[test]
+fn test_insert_lines_at() {
+    let mut t = TextArea::from(["abc", "def"]);
+    t.move_cursor(CursorMove::Jump(0, 2));
+    t.start_selection();
+    t.move_cursor(CursorMove::Jump(1, 1));
+
+    // Insert at the head shifts the cursor and the selection down
+    assert!(t.insert_lines_at(0, ["x", "y\r\nz"]));
+    assert_eq!(t.lines(), ["x", "y", "z", "abc", "def"]);
+    assert_eq!(t.cursor(), (4, 1));
+    assert_eq!(t.selection_range(), Some(((3, 2), (4, 1))));
+
+    // Insert in the middle
+    assert!(t.insert_lines_at(4, [""]));
+    assert_eq!(t.lines(), ["x", "y", "z", "abc", "", "def"]);
+    assert_eq!(t.cursor(), (5, 1));
+
+    // Insert after the last line
+    assert!(t.insert_lines_at(100, ["end"]));
+    assert_eq!(t.lines(), ["x", "y", "z", "abc", "", "def", "end"]);
+    assert_eq!(t.cursor(), (5, 1));
+
+    // Nothing to insert
+    assert!(!t.insert_lines_at(0, Vec::<String>::new()));
+
+    // Each insertion is one history entry
+    assert!(t.undo());
+    assert!(t.undo());
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["abc", "def"]);
+}
+
+#[test]
+fn test_delete_lines() {
+    let mut t = TextArea::from(["a", "b", "c", "d", "e"]);
+    t.move_cursor(CursorMove::Jump(3, 1));
+
+    assert_eq!(t.delete_lines(0..=1), ["a", "b"]);
+    assert_eq!(t.lines(), ["c", "d", "e"]);
+    assert_eq!(t.cursor(), (1, 1));
+
+    // Cursor on the deleted line moves to the head of the next line
+    assert_eq!(t.delete_lines(1..2), ["d"]);
+    assert_eq!(t.lines(), ["c", "e"]);
+    assert_eq!(t.cursor(), (1, 0));
+
+    // Cursor on the deleted last line moves to the end of the previous line
+    assert_eq!(t.delete_lines(1..), ["e"]);
+    assert_eq!(t.lines(), ["c"]);
+    assert_eq!(t.cursor(), (0, 1));
+
+    // Empty and out-of-bounds ranges
+    assert!(t.delete_lines(1..).is_empty());
+    #[allow(clippy::reversed_empty_ranges)]
+    let deleted = t.delete_lines(1..0);
+    assert!(deleted.is_empty());
+
+    // Deleting all lines leaves an empty line
+    assert_eq!(t.delete_lines(..), ["c"]);
+    assert_eq!(t.lines(), [""]);
+    assert_eq!(t.cursor(), (0, 0));
+
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["c"]);
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["c", "e"]);
+}
+
+#[test]
+fn test_line_meta() {
+    let mut t = TextArea::from(["a", "b", "c"]);
+    assert!(t.set_line_meta(0, "first"));
+    assert!(t.set_line_meta(1, 1u32));
+    assert!(t.set_line_meta(2, 2u32));
+    assert!(!t.set_line_meta(3, 3u32));
+
+    // Split the line at its middle
+    t.move_cursor(CursorMove::Jump(0, 1));
+    t.insert_newline();
+    assert_eq!(t.lines(), ["a", "", "b", "c"]);
+    assert_eq!(t.line_meta::<&str>(0), Some(&"first"));
+    assert_eq!(t.line_meta_rows().collect::<Vec<_>>(), [0, 2, 3]);
+
+    // Join the lines
+    t.move_cursor(CursorMove::Jump(3, 0));
+    t.delete_char();
+    assert_eq!(t.lines(), ["a", "", "bc"]);
+    assert_eq!(t.line_meta::<u32>(2), Some(&1));
+    assert_eq!(t.line_meta_rows().collect::<Vec<_>>(), [0, 2]);
+
+    // Delete and insert whole lines
+    t.delete_lines(0..1);
+    assert_eq!(t.line_meta_rows().collect::<Vec<_>>(), [1]);
+    t.insert_lines_at(0, ["x", "y"]);
+    assert_eq!(t.line_meta::<u32>(3), Some(&1));
+
+    // Replace the data
+    assert!(t.set_line_meta(3, vec!["diagnostic"]));
+    assert_eq!(t.line_meta::<u32>(3), None);
+    assert_eq!(t.line_meta::<Vec<&str>>(3), Some(&vec!["diagnostic"]));
+
+    assert!(t.remove_line_meta(3));
+    assert!(!t.remove_line_meta(3));
+    t.set_line_meta(0, ());
+    t.clear_line_meta();
+    assert_eq!(t.line_meta_rows().count(), 0);
+}
+
+#[test]
+fn test_text_in_range() {
+    let t = TextArea::from(["abc", "", "🐱🐶🐰"]);
+    let tests = [
+        ((0, 0), (0, 3), "abc"),
+        ((0, 1), (0, 1), ""),
+        ((0, 2), (2, 1), "c\n\n🐱"),
+        ((2, 3), (0, 3), "\n\n🐱🐶🐰"),
+        ((2, 1), (9, 9), "🐶🐰"),
+    ];
+    for (start, end, want) in tests {
+        assert_eq!(t.text_in_range(start, end), want, "{:?}, {:?}", start, end);
+    }
+}
+
+#[test]
+fn test_metrics_follow_edits() {
+    fn assert_metrics(t: &TextArea<'_>, context: &str) {
+        let text = t.lines().join("\n");
+        assert_eq!(t.line_count(), t.lines().len(), "{context}");
+        assert_eq!(t.char_count(), text.chars().count(), "{context}");
+        assert_eq!(t.len_bytes(), text.len(), "{context}");
+        assert_eq!(t.word_count(), text.split_whitespace().count(), "{context}");
+    }
+
+    let mut t = TextArea::from(["fn main() {", "    println!(\"こんにちは\");", "}"]);
+    assert_metrics(&t, "initial");
+
+    t.move_cursor(CursorMove::Jump(1, 4));
+    t.insert_str("let x = 1;\n    ");
+    assert_metrics(&t, "insert_str");
+    t.insert_char(' ');
+    assert_metrics(&t, "insert_char");
+    t.insert_newline();
+    assert_metrics(&t, "insert_newline");
+    t.delete_newline();
+    assert_metrics(&t, "delete_newline");
+    t.delete_word();
+    assert_metrics(&t, "delete_word");
+    t.delete_next_word();
+    assert_metrics(&t, "delete_next_word");
+    t.delete_char();
+    assert_metrics(&t, "delete_char");
+    t.delete_str(20);
+    assert_metrics(&t, "delete_str");
+    t.move_cursor(CursorMove::Top);
+    t.start_selection();
+    t.move_cursor(CursorMove::Bottom);
+    t.cut();
+    assert_metrics(&t, "cut");
+    t.paste();
+    assert_metrics(&t, "paste");
+    t.set_text("hello world\ngoodbye");
+    assert_metrics(&t, "set_text");
+
+    while t.undo() {
+        assert_metrics(&t, "undo");
+    }
+    while t.redo() {
+        assert_metrics(&t, "redo");
+    }
+}
+
+#[test]
+fn test_on_cursor_change() {
+    use std::sync::{Arc, Mutex};
+    use tui_textarea::{CursorChange, Input, Key};
+
+    let changes = Arc::new(Mutex::new(vec![]));
+    let mut t = TextArea::from(["abc", "def"]);
+    {
+        let changes = changes.clone();
+        t.set_on_cursor_change(move |c: &CursorChange| changes.lock().unwrap().push(*c));
+    }
+    let take = || std::mem::take(&mut *changes.lock().unwrap());
+
+    // Input which moves the cursor several times internally reports only one change
+    t.input(Input {
+        key: Key::Down,
+        shift: true,
+        ..Default::default()
+    });
+    let c = take();
+    assert_eq!(c.len(), 1, "{:?}", c);
+    assert_eq!(c[0].old_cursor, (0, 0));
+    assert_eq!(c[0].new_cursor, (1, 0));
+    assert_eq!(c[0].old_selection, None);
+    assert_eq!(c[0].new_selection, Some(((0, 0), (1, 0))));
+
+    t.cut();
+    let c = take();
+    assert_eq!(c.len(), 1, "{:?}", c);
+    assert_eq!(c[0].new_cursor, (0, 0));
+    assert_eq!(c[0].new_selection, None);
+
+    // Edits which don't move the cursor are not reported
+    t.delete_next_char();
+    assert_eq!(take(), []);
+
+    t.insert_str("xy");
+    t.undo();
+    let c = take();
+    assert_eq!(c.len(), 2, "{:?}", c);
+    assert_eq!(c[0].new_cursor, (0, 2));
+    assert_eq!(c[1].new_cursor, (0, 0));
+
+    t.remove_on_cursor_change();
+    t.move_cursor(CursorMove::End);
+    assert_eq!(take(), []);
+}
+
+#[test]
+fn test_completion_keys() {
+    use tui_textarea::{Input, Key};
+
+    let key = |key| Input {
+        key,
+        ..Default::default()
+    };
+    let items = || vec!["foo".to_string(), "foobar".to_string(), "bar".to_string()];
+
+    let mut t = TextArea::default();
+    t.set_completion_items(items());
+    t.input(key(Key::Char('f')));
+    assert_eq!(t.selected_completion(), Some("foo"));
+    assert!(!t.input(key(Key::Down)));
+    assert_eq!(t.selected_completion(), Some("foobar"));
+    assert_eq!(t.cursor(), (0, 1)); // Cursor does not move
+    assert!(t.input(key(Key::Tab)));
+    assert_eq!(t.lines(), ["foobar"]);
+    assert_eq!(t.selected_completion(), None);
+
+    // Tab inserts a tab when no candidate matches
+    assert!(t.input(key(Key::Tab)));
+    assert_eq!(t.lines(), ["foobar  "]);
+
+    // Esc closes the popup
+    let mut t = TextArea::from(["b"]);
+    t.move_cursor(CursorMove::End);
+    t.set_completion_items(items());
+    assert_eq!(t.selected_completion(), Some("bar"));
+    assert!(!t.input(key(Key::Esc)));
+    assert_eq!(t.selected_completion(), None);
+    assert!(t.completion_items().is_empty());
+}
+
+#[test]
+fn test_completion_empty_prefix() {
+    use tui_textarea::{Input, Key};
+
+    let key = |key| Input {
+        key,
+        ..Default::default()
+    };
+    let items = || vec!["foo".to_string(), "format".to_string()];
+
+    // No word before the cursor at the head of the line
+    let mut t = TextArea::from(["foo ", "bar"]);
+    t.set_completion_items(items());
+    assert_eq!(t.selected_completion(), None);
+    t.input(key(Key::Down));
+    assert_eq!(t.cursor(), (1, 0));
+    t.move_cursor(CursorMove::Top);
+    assert!(t.input(key(Key::Tab)));
+    assert_eq!(t.lines(), ["    foo ", "bar"]);
+
+    // No word before the cursor after whitespace
+    let mut t = TextArea::from(["foo "]);
+    t.move_cursor(CursorMove::End);
+    t.set_completion_items(items());
+    assert_eq!(t.selected_completion(), None);
+    assert!(t.input(key(Key::Tab)));
+    assert_eq!(t.lines(), ["foo     "]);
+    assert_eq!(t.completion_items().len(), 2); // Candidates are kept while typing
+}
+
+#[test]
+fn test_line_ending() {
+    use tui_textarea::LineEnding;
+
+    let mut t = TextArea::from(["abc", "def"]);
+    t.set_line_ending(LineEnding::CrLf);
+    assert_eq!(t.text(), "abc\r\ndef");
+
+    t.move_cursor(CursorMove::Jump(0, 1));
+    t.delete_str(4);
+    assert_eq!(t.lines(), ["aef"]);
+    assert_eq!(t.yank_text(), "bc\r\nd");
+
+    // Yanked text is inserted without carriage return
+    t.paste();
+    assert_eq!(t.lines(), ["abc", "def"]);
+
+    t.set_yank_text("x\r\ny");
+    t.set_line_ending(LineEnding::Lf);
+    assert_eq!(t.yank_text(), "x\ny");
+    assert_eq!(t.text(), "abc\ndef");
+}
+
+#[test]
+fn test_trim_trailing_whitespace() {
+    let mut t = TextArea::from(["a b ", "  ", "c", "d\t\t", "あ  "]);
+    t.move_cursor(CursorMove::Jump(4, 2));
+    t.start_selection();
+    t.move_cursor(CursorMove::Jump(0, 1));
+    t.set_yank_text("yank");
+
+    assert_eq!(t.trim_trailing_whitespace(), 4);
+    assert_eq!(t.lines(), ["a b", "", "c", "d", "あ"]);
+    assert_eq!(t.cursor(), (0, 1));
+    assert_eq!(t.selection_range(), Some(((0, 1), (4, 1))));
+    assert_eq!(t.yank_text(), "yank");
+
+    // Nothing to trim
+    assert_eq!(t.trim_trailing_whitespace(), 0);
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["a b ", "  ", "c", "d\t\t", "あ  "]);
+    assert!(!t.undo());
+    assert!(t.redo());
+    assert_eq!(t.lines(), ["a b", "", "c", "d", "あ"]);
+}
+
+#[test]
+fn test_ensure_trailing_newline() {
+    let mut t = TextArea::default();
+    assert!(!t.ensure_trailing_newline());
+    assert_eq!(t.lines(), [""]);
+
+    t.insert_str("abc");
+    assert!(t.ensure_trailing_newline());
+    assert_eq!(t.lines(), ["abc", ""]);
+    assert_eq!(t.cursor(), (0, 3));
+    assert!(!t.ensure_trailing_newline());
+
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["abc"]);
+}
+
+#[test]
+fn test_collapse_multiple_blank_lines() {
+    let mut t = TextArea::from(["", "", "a", "", "", "", "b", "\t", ""]);
+    t.move_cursor(CursorMove::Jump(6, 1));
+    t.start_selection();
+    t.move_cursor(CursorMove::Jump(5, 0));
+
+    assert_eq!(t.collapse_multiple_blank_lines(2), 1);
+    assert_eq!(t.lines(), ["", "", "a", "", "", "b", "\t", ""]);
+    // The cursor in the removed lines moves to the next line
+    assert_eq!(t.cursor(), (5, 0));
+    assert_eq!(t.selection_range(), Some(((5, 0), (5, 1))));
+    assert_eq!(t.collapse_multiple_blank_lines(2), 0);
+
+    t.cancel_selection();
+    t.move_cursor(CursorMove::Jump(7, 0));
+    assert_eq!(t.collapse_multiple_blank_lines(0), 6);
+    assert_eq!(t.lines(), ["a", "b"]);
+    assert_eq!(t.cursor(), (1, 1));
+
+    // All removals are undone at once
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["", "", "a", "", "", "b", "\t", ""]);
+
+    // The text which consists of only blank lines
+    let mut t = TextArea::from(["", " ", ""]);
+    assert_eq!(t.collapse_multiple_blank_lines(0), 2);
+    assert_eq!(t.lines(), [""]);
+    assert_eq!(t.collapse_multiple_blank_lines(0), 0);
+}
+
+#[test]
+fn test_convert_line_endings() {
+    use tui_textarea::LineEnding;
+
+    let mut t = TextArea::from(["abc", "def"]);
+    assert_eq!(t.detected_line_ending(), None);
+    assert!(!t.has_mixed_line_endings());
+
+    assert!(t.set_text("a\r\nb\nc\r\n"));
+    assert_eq!(t.lines(), ["a", "b", "c", ""]);
+    assert_eq!(t.detected_line_ending(), Some(LineEnding::CrLf));
+    assert!(t.has_mixed_line_endings());
+    assert_eq!(t.line_ending(), LineEnding::Lf); // Not changed by loading the text
+    t.mark_saved();
+
+    // Resolving mixed line endings modifies the text even if the line ending is the same
+    assert!(t.convert_line_endings(LineEnding::Lf));
+    assert_eq!(t.detected_line_ending(), Some(LineEnding::Lf));
+    assert!(!t.has_mixed_line_endings());
+    assert!(t.is_modified());
+    assert!(!t.convert_line_endings(LineEnding::Lf));
+
+    // The conversion is not undone since lines are not changed
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["abc", "def"]);
+    assert!(t.redo());
+    assert_eq!(t.line_ending(), LineEnding::Lf);
+
+    t.mark_saved();
+    assert!(t.convert_line_endings(LineEnding::CrLf));
+    assert_eq!(t.text(), "a\r\nb\r\nc\r\n");
+    assert!(t.is_modified());
+
+    // Text without newlines has no line ending
+    t.set_text("abc");
+    assert_eq!(t.detected_line_ending(), None);
+    assert!(t.convert_line_endings(LineEnding::Lf));
+    assert_eq!(t.detected_line_ending(), None);
+}
+
+#[test]
+fn test_modified_state() {
+    let mut t = TextArea::from(["abc"]);
+    assert!(!t.is_modified());
+
+    // Moving the cursor does not modify the text
+    t.move_cursor(CursorMove::End);
+    assert!(!t.is_modified());
+
+    t.insert_char('d');
+    assert!(t.is_modified());
+    t.mark_saved();
+    assert!(!t.is_modified());
+
+    t.delete_char();
+    assert!(t.is_modified());
+    t.undo();
+    assert!(!t.is_modified());
+    t.undo();
+    assert!(t.is_modified());
+    t.redo();
+    assert!(!t.is_modified());
+
+    // Changing max histories keeps the modified state
+    t.insert_char('e');
+    t.set_max_histories(10);
+    assert!(t.is_modified());
+    t.mark_saved();
+    t.set_max_histories(0);
+    assert!(!t.is_modified());
+    t.insert_char('f');
+    assert!(t.is_modified());
+}
+
+#[test]
+fn test_selection_inclusive() {
+    let mut t = TextArea::from(["abc", "def"]);
+    t.set_selection_inclusive(true);
+
+    // Selecting without moving the cursor selects the character under the cursor
+    t.start_selection();
+    t.copy();
+    assert_eq!(t.yank_text(), "a");
+
+    // Selection backward includes the character at the selection start
+    t.move_cursor(CursorMove::Jump(0, 2));
+    t.start_selection();
+    t.move_cursor(CursorMove::Head);
+    t.copy();
+    assert_eq!(t.yank_text(), "abc");
+
+    // Newline is included at the end of line
+    t.move_cursor(CursorMove::Jump(0, 1));
+    t.start_selection();
+    t.move_cursor(CursorMove::End);
+    t.copy();
+    assert_eq!(t.yank_text(), "bc\n");
+
+    t.move_cursor(CursorMove::Jump(0, 1));
+    t.start_selection();
+    t.move_cursor(CursorMove::Jump(1, 0));
+    assert!(t.cut());
+    assert_eq!(t.yank_text(), "bc\nd");
+    assert_eq!(t.lines(), ["aef"]);
+    assert_eq!(t.cursor(), (0, 1));
+
+    // Nothing is selected at the end of text
+    t.move_cursor(CursorMove::End);
+    t.start_selection();
+    assert!(!t.cut());
+    assert_eq!(t.lines(), ["aef"]);
+
+    // Typing replaces the selected text
+    t.move_cursor(CursorMove::Head);
+    t.start_selection();
+    t.move_cursor(CursorMove::Forward);
+    t.insert_char('x');
+    assert_eq!(t.lines(), ["xf"]);
+}
+
+#[test]
+fn test_edit_log() {
+    use tui_textarea::EditRecordKind;
+
+    let mut t = TextArea::from(["abc", "def"]);
+    let mut mirror = t.clone();
+    t.set_edit_log(true);
+
+    t.insert_str("x\ny");
+    t.insert_newline();
+    t.move_cursor(CursorMove::Jump(0, 1));
+    t.delete_next_char();
+    t.delete_line_by_end();
+    t.move_cursor(CursorMove::Bottom);
+    t.delete_char();
+    t.undo();
+    t.undo();
+    t.redo();
+    t.select_all();
+    t.cut();
+    t.undo();
+    t.paste();
+
+    let edits = t.drain_edits();
+    assert!(!edits.is_empty());
+    let mut revision = 0;
+    for edit in edits {
+        assert_eq!(edit.revision, revision + 1, "{:?}", edit);
+        revision = edit.revision;
+        match edit.kind {
+            EditRecordKind::Insert => {
+                mirror.replace_range(edit.start, edit.start, &edit.text);
+                assert_eq!(mirror.text_in_range(edit.start, edit.end), edit.text);
+            }
+            EditRecordKind::Delete => {
+                assert_eq!(mirror.text_in_range(edit.start, edit.end), edit.text);
+                mirror.replace_range(edit.start, edit.end, "");
+            }
+            _ => unreachable!(),
+        }
+    }
+    assert_eq!(mirror.lines(), t.lines());
+    assert_eq!(t.revision(), revision);
+
+    // Edits are not recorded after disabling the log
+    t.set_edit_log(false);
+    t.insert_char('z');
+    t.set_edit_log(true);
+    assert!(t.drain_edits().is_empty());
+    assert_eq!(t.revision(), revision + 1);
+}
+
+#[test]
+fn test_apply_remote_edit() {
+    let mut t = TextArea::from(["abc", "def", "ghi"]);
+    t.set_edit_log(true);
+    t.move_cursor(CursorMove::Jump(2, 3));
+    t.insert_char('X');
+    t.move_cursor(CursorMove::Jump(0, 0));
+    t.insert_str("Y\n");
+    t.move_cursor(CursorMove::Jump(2, 2));
+    t.start_selection();
+    t.move_cursor(CursorMove::Jump(3, 1));
+    assert_eq!(t.lines(), ["Y", "abc", "def", "ghiX"]);
+    t.drain_edits();
+
+    // Replace "e" with "EE" while "f\ng" is selected
+    assert!(t.apply_remote_edit((2, 1), (2, 2), "EE"));
+    assert_eq!(t.lines(), ["Y", "abc", "dEEf", "ghiX"]);
+    assert_eq!(t.selection_range(), Some(((2, 3), (3, 1))));
+    assert!(t.is_modified());
+
+    let edits = t.drain_edits();
+    assert_eq!(edits.len(), 2);
+    assert!(edits.iter().all(|e| e.remote));
+
+    // Local edits are undone without reverting the remote edit
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["abc", "dEEf", "ghiX"]);
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["abc", "dEEf", "ghi"]);
+    assert!(!t.undo());
+
+    // Remote edits are not undone
+    assert!(t.redo());
+    assert!(t.redo());
+    assert!(t.apply_remote_edit((0, 0), (0, 0), "Z"));
+    assert!(!t.redo());
+    assert_eq!(t.lines(), ["ZY", "abc", "dEEf", "ghiX"]);
+
+    assert!(t.apply_remote_edit((2, 3), (3, 2), ""));
+    assert_eq!(t.lines(), ["ZY", "abc", "dEEiX"]);
+
+    // Local edits which conflict with the remote edit are removed from the history
+    assert!(t.apply_remote_edit((0, 1), (1, 1), ""));
+    assert_eq!(t.lines(), ["Zbc", "dEEiX"]);
+    assert!(!t.undo());
+    assert_eq!(t.lines(), ["Zbc", "dEEiX"]);
+
+    assert!(!t.apply_remote_edit((1, 1), (1, 1), ""));
+}
+
+#[test]
+fn test_apply_remote_edit_random() {
+    // Simple LCG to make the test deterministic
+    let mut seed = 42u64;
+    let mut rand = move |n: usize| {
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+        (seed >> 33) as usize % n
+    };
+
+    for _ in 0..50 {
+        let mut t = TextArea::from(["hello world", "foo bar", "", "baz"]);
+        for _ in 0..30 {
+            let pos = |rand: &mut dyn FnMut(usize) -> usize| (rand(6), rand(14));
+            match rand(5) {
+                0 => {
+                    let (r, c) = pos(&mut rand);
+                    t.move_cursor(CursorMove::Jump(r as u16, c as u16));
+                    let x = ["x", "yy\nz", "\n"][rand(3)];
+                    t.insert_str(x);
+                }
+                1 => {
+                    t.delete_str(rand(5));
+                }
+                2 => {
+                    let (s, e) = (pos(&mut rand), pos(&mut rand));
+                    let x = ["", "R", "r\nr"][rand(3)];
+                    t.apply_remote_edit(s, e, x);
+                }
+                3 => {
+                    t.undo();
+                }
+                _ => {
+                    t.redo();
+                }
+            }
+            assert_eq!(t.char_count(), t.text().chars().count());
+            let (r, c) = t.cursor();
+            assert!(r < t.lines().len() && c <= t.lines()[r].chars().count());
+        }
+        while t.undo() {
+            assert_eq!(t.char_count(), t.text().chars().count());
+        }
+    }
+}
+
+#[test]
+fn test_prompt_history() {
+    let up = Input {
+        key: Key::Up,
+        ..Default::default()
+    };
+    let down = Input {
+        key: Key::Down,
+        ..Default::default()
+    };
+
+    let mut t = TextArea::from(["ab", "cd"]);
+    t.push_prompt_history("foo\nbar");
+    t.push_prompt_history("baz");
+    t.move_cursor(CursorMove::Bottom);
+
+    // Cursor moves as usual when the prompt mode is disabled
+    assert!(!t.input(up.clone()));
+    assert_eq!(t.cursor(), (0, 0));
+    assert!(!t.input(up.clone()));
+    assert_eq!(t.lines(), ["ab", "cd"]);
+
+    t.set_prompt_mode(true);
+    t.move_cursor(CursorMove::Bottom);
+    // Recall the history only at the first line
+    assert!(!t.input(up.clone()));
+    assert_eq!(t.cursor(), (0, 0));
+    assert!(t.input(up.clone()));
+    assert_eq!(t.lines(), ["baz"]);
+    assert_eq!(t.cursor(), (0, 3));
+    assert!(t.input(up.clone()));
+    assert_eq!(t.lines(), ["foo", "bar"]);
+    assert_eq!(t.cursor(), (1, 3));
+    assert!(!t.input(up.clone()));
+
+    // Recall the next entry only at the last line
+    t.move_cursor(CursorMove::Top);
+    assert!(!t.input(down.clone()));
+    assert_eq!(t.cursor(), (1, 3));
+    assert!(t.input(down.clone()));
+    assert_eq!(t.lines(), ["baz"]);
+    assert!(t.input(down.clone()));
+    assert_eq!(t.lines(), ["ab", "cd"]);
+    assert_eq!(t.cursor(), (1, 2));
+    assert!(!t.input(down.clone()));
+
+    // Selecting text with Shift does not recall the history
+    t.move_cursor(CursorMove::Top);
+    t.input(Input {
+        key: Key::Up,
+        shift: true,
+        ..Default::default()
+    });
+    assert_eq!(t.lines(), ["ab", "cd"]);
+
+    // Recalling the history can be undone
+    t.cancel_selection();
+    assert!(t.prev_prompt_history());
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["ab", "cd"]);
+
+    t.clear_prompt_history();
+    assert!(t.prompt_history().is_empty());
+}
+
+#[test]
+fn test_word_boundary_mode() {
+    use tui_textarea::WordBoundary;
+
+    for (boundary, forward, back) in [
+        (
+            WordBoundary::Default,
+            vec![8, 18, 19],
+            vec!["getHttpUrl(", "getHttpUrl", ""],
+        ),
+        (
+            WordBoundary::SubWord,
+            vec![8, 11, 15, 18],
+            vec!["getHttpUrl(", "getHttpUrl", "getHttp"],
+        ),
+        (WordBoundary::WhitespaceOnly, vec![8, 22], vec![""]),
+    ] {
+        let mut t = TextArea::from(["let x = getHttpUrl(a);"]);
+        t.set_word_boundary_mode(boundary);
+        t.move_cursor(CursorMove::Jump(0, 6));
+        for col in forward {
+            t.move_cursor(CursorMove::WordForward);
+            assert_eq!(t.cursor(), (0, col), "{:?}", boundary);
+        }
+
+        let mut t = TextArea::from(["getHttpUrl(a"]);
+        t.set_word_boundary_mode(boundary);
+        t.move_cursor(CursorMove::End);
+        for line in back {
+            t.delete_word();
+            assert_eq!(t.lines(), [line], "{:?}", boundary);
+        }
+    }
+}
+
+#[test]
+fn test_word_chars() {
+    use tui_textarea::WordBoundary;
+
+    let line = "cd ~/.config/foo-bar.txt x";
+    let word_char = |c: char| c.is_alphanumeric() || c == '-' || c == '.';
+
+    // Word moves follow the custom word characters
+    let mut t = TextArea::from([line]);
+    t.set_word_chars(word_char);
+    for col in [3, 5, 12, 13, 25] {
+        t.move_cursor(CursorMove::WordForward);
+        assert_eq!(t.cursor(), (0, col));
+    }
+    for col in [13, 12, 5, 3, 0] {
+        t.move_cursor(CursorMove::WordBack);
+        assert_eq!(t.cursor(), (0, col));
+    }
+    for col in [1, 4, 11, 12, 23, 25] {
+        t.move_cursor(CursorMove::WordEnd);
+        assert_eq!(t.cursor(), (0, col));
+    }
+
+    // Clearing the word characters restores the default words
+    t.clear_word_chars();
+    t.move_cursor(CursorMove::Jump(0, 13));
+    for col in [16, 17, 20, 21, 25] {
+        t.move_cursor(CursorMove::WordForward);
+        assert_eq!(t.cursor(), (0, col));
+    }
+
+    // Word-wise deletions follow the custom word characters
+    let mut t = TextArea::from([line]);
+    t.set_word_chars(word_char);
+    t.move_cursor(CursorMove::Jump(0, 24));
+    for (expected, yank) in [
+        ("cd ~/.config/ x", "foo-bar.txt"),
+        ("cd ~/.config x", "/"),
+        ("cd ~/ x", ".config"),
+        ("cd  x", "~/"),
+    ] {
+        assert!(t.delete_word());
+        assert_eq!(t.lines(), [expected]);
+        assert_eq!(t.yank_text(), yank);
+    }
+    assert_undo_redo((0, 5), &["cd ~/ x"], &["cd  x"], &mut t, "delete_word");
+
+    let mut t = TextArea::from([line]);
+    t.set_word_chars(word_char);
+    t.move_cursor(CursorMove::Jump(0, 13));
+    assert!(t.delete_next_word());
+    assert_eq!(t.lines(), ["cd ~/.config/ x"]);
+    assert_eq!(t.yank_text(), "foo-bar.txt");
+    t.move_cursor(CursorMove::Jump(0, 3));
+    assert!(t.delete_next_word());
+    assert_eq!(t.lines(), ["cd .config/ x"]);
+    assert_eq!(t.yank_text(), "~/");
+
+    t.clear_word_chars();
+    assert!(t.delete_next_word());
+    assert_eq!(t.lines(), ["cd config/ x"]);
+    assert_eq!(t.yank_text(), ".");
+
+    // The word characters are ignored when only spaces separate words
+    let mut t = TextArea::from([line]);
+    t.set_word_chars(word_char);
+    t.set_word_boundary_mode(WordBoundary::WhitespaceOnly);
+    t.move_cursor(CursorMove::Jump(0, 24));
+    assert!(t.delete_word());
+    assert_eq!(t.lines(), ["cd  x"]);
+}
+
+#[test]
+fn test_surround_selection() {
+    // Backward selection over "bar" across the cursor direction
+    let mut t = TextArea::from(["foo bar", "baz"]);
+    t.move_cursor(CursorMove::Jump(0, 7));
+    t.start_selection();
+    t.move_cursor(CursorMove::Jump(0, 4));
+    assert!(t.surround_selection("(", ")"));
+    assert_eq!(t.lines(), ["foo (bar)", "baz"]);
+    assert_eq!(t.cursor(), (0, 5));
+    assert_eq!(t.selection_range(), Some(((0, 5), (0, 8))));
+
+    // Surround again and remove the delimiters one by one
+    assert!(t.surround_selection("**", "**"));
+    assert_eq!(t.lines(), ["foo (**bar**)", "baz"]);
+    assert!(t.unsurround_selection());
+    assert_eq!(t.lines(), ["foo (bar)", "baz"]);
+    assert!(t.unsurround_selection());
+    assert_eq!(t.lines(), ["foo bar", "baz"]);
+    assert_eq!(t.selection_range(), Some(((0, 4), (0, 7))));
+    assert!(!t.unsurround_selection());
+
+    // Each operation is one history entry
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["foo (bar)", "baz"]);
+    assert!(t.undo());
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["foo (bar)", "baz"]);
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["foo bar", "baz"]);
+
+    // Across lines with inclusive selection
+    let mut t = TextArea::from(["foo bar", "baz"]);
+    t.set_selection_inclusive(true);
+    t.move_cursor(CursorMove::Jump(0, 4));
+    t.start_selection();
+    t.move_cursor(CursorMove::Jump(1, 2));
+    assert!(t.surround_selection("`", "`"));
+    assert_eq!(t.lines(), ["foo `bar", "baz`"]);
+    assert_eq!(t.selection_range(), Some(((0, 5), (1, 2))));
+    assert!(t.unsurround_selection());
+    assert_eq!(t.lines(), ["foo bar", "baz"]);
+    assert_eq!(t.selection_range(), Some(((0, 4), (1, 2))));
+
+    // Delimiters inside the selection
+    let mut t = TextArea::from(["'a'"]);
+    t.start_selection();
+    t.move_cursor(CursorMove::End);
+    assert!(t.unsurround_selection());
+    assert_eq!(t.lines(), ["a"]);
+    assert_eq!(t.selection_range(), Some(((0, 0), (0, 1))));
+
+    // Nothing is selected
+    let mut t = TextArea::from(["(a)"]);
+    assert!(!t.surround_selection("(", ")"));
+    assert!(!t.unsurround_selection());
+    assert_eq!(t.lines(), ["(a)"]);
+}
+
+#[test]
+fn test_virtual_edit() {
+    // `JumpVirtual` is the same as `Jump` when virtual edit is disabled
+    let mut t = TextArea::from(["abc", "", "abcdef"]);
+    t.move_cursor(CursorMove::JumpVirtual(0, 5));
+    assert_eq!(t.cursor(), (0, 3));
+
+    t.set_virtual_edit(true);
+    t.move_cursor(CursorMove::JumpVirtual(0, 5));
+    assert_eq!(t.cursor(), (0, 5));
+    t.move_cursor(CursorMove::Forward);
+    assert_eq!(t.cursor(), (0, 6));
+    t.move_cursor(CursorMove::Back);
+    assert_eq!(t.cursor(), (0, 5));
+
+    // The column is kept across lines
+    t.move_cursor(CursorMove::Down);
+    assert_eq!(t.cursor(), (1, 5));
+    t.move_cursor(CursorMove::Down);
+    assert_eq!(t.cursor(), (2, 5));
+    t.move_cursor(CursorMove::Up);
+    assert_eq!(t.cursor(), (1, 5));
+
+    // Deleting characters only moves the cursor back
+    assert!(!t.delete_char());
+    assert_eq!(t.cursor(), (1, 4));
+    assert!(!t.delete_next_char());
+    assert_eq!(t.lines(), ["abc", "", "abcdef"]);
+
+    // Spaces are inserted before the inserted text
+    t.insert_char('x');
+    assert_eq!(t.lines(), ["abc", "    x", "abcdef"]);
+    assert_eq!(t.cursor(), (1, 5));
+    assert!(t.undo());
+    assert_eq!(t.lines(), ["abc", "", "abcdef"]);
+
+    // Other moves reset the cursor beyond the end of line
+    t.move_cursor(CursorMove::JumpVirtual(0, 8));
+    t.move_cursor(CursorMove::Head);
+    t.move_cursor(CursorMove::End);
+    assert_eq!(t.cursor(), (0, 3));
+
+    // The cursor at the end of line moves beyond it instead of moving to the next line
+    t.move_cursor(CursorMove::Forward);
+    assert_eq!(t.cursor(), (0, 4));
+    assert!(t.insert_str("yz"));
+    assert_eq!(t.lines(), ["abc yz", "", "abcdef"]);
+
+    t.move_cursor(CursorMove::JumpVirtual(1, 2));
+    t.set_virtual_edit(false);
+    assert_eq!(t.cursor(), (1, 0));
+}
+
+#[test]
+fn test_jumplist() {
+    let mut t = TextArea::from(["a", "b", "c", "d", "e"]);
+    assert!(!t.jump_back());
+    assert!(!t.jump_forward());
+
+    // Moves in the same line and non-jump moves are not recorded
+    t.move_cursor(CursorMove::Jump(0, 1));
+    t.move_cursor(CursorMove::Down);
+    assert!(!t.jump_back());
+
+    t.move_cursor(CursorMove::Bottom);
+    t.push_jump();
+    t.move_cursor(CursorMove::Jump(2, 0));
+    assert!(t.jump_back());
+    assert_eq!(t.cursor(), (4, 1));
+    assert!(t.jump_back());
+    assert_eq!(t.cursor(), (1, 1));
+    assert!(t.jump_forward());
+    assert!(t.jump_forward());
+    assert_eq!(t.cursor(), (2, 0));
+    assert!(!t.jump_forward());
+
+    // Positions follow local and remote edits
+    t.move_cursor(CursorMove::Top);
+    t.delete_line_by_end();
+    t.delete_next_char();
+    assert_eq!(t.lines(), ["b", "c", "d", "e"]);
+    t.apply_remote_edit((2, 0), (2, 0), "x\n");
+    assert_eq!(t.lines(), ["b", "c", "x", "d", "e"]);
+    assert!(t.jump_back());
+    assert_eq!(t.cursor(), (1, 0));
+    assert!(t.jump_back());
+    assert_eq!(t.cursor(), (4, 1));
+    assert!(!t.jump_back());
+
+    // Undo also moves the positions
+    t.undo();
+    assert_eq!(t.lines(), ["", "b", "c", "x", "d", "e"]);
+    assert!(t.jump_forward());
+    assert_eq!(t.cursor(), (2, 0));
+}
+
+#[test]
+fn test_anchor() {
+    let mut t = TextArea::from(["abc", "def", "ghi"]);
+    let a = t.create_anchor((2, 1), (1, 1), AnchorGrowth::Neither);
+    let b = t.create_anchor((10, 10), (2, 10), AnchorGrowth::End);
+    assert_eq!(t.resolve_anchor(a), Some(((1, 1), (2, 1))));
+    assert_eq!(t.resolve_anchor(b), Some(((2, 3), (2, 3))));
+
+    // Joining lines
+    t.move_cursor(CursorMove::Jump(1, 0));
+    t.delete_char();
+    assert_eq!(t.resolve_anchor(a), Some(((0, 4), (1, 1))));
+
+    // Deleting the whole range
+    t.move_cursor(CursorMove::Jump(0, 4));
+    t.delete_str(4);
+    assert_eq!(t.lines(), ["abcdhi"]);
+    assert_eq!(t.resolve_anchor(a), Some(((0, 4), (0, 4))));
+    assert_eq!(t.resolve_anchor(b), Some(((0, 6), (0, 6))));
+
+    // Typing at the end of the range extends it
+    t.move_cursor(CursorMove::Bottom);
+    t.move_cursor(CursorMove::End);
+    t.insert_str("jk");
+    assert_eq!(t.resolve_anchor(b), Some(((0, 6), (0, 8))));
+
+    // Remote edits and undo
+    t.apply_remote_edit((0, 0), (0, 0), "x\n");
+    assert_eq!(t.resolve_anchor(b), Some(((1, 6), (1, 8))));
+    t.undo();
+    assert_eq!(t.lines(), ["x", "abcdhi"]);
+    assert_eq!(t.resolve_anchor(b), Some(((1, 6), (1, 6))));
+
+    assert!(t.remove_anchor(a));
+    assert!(!t.remove_anchor(a));
+    assert_eq!(t.resolve_anchor(a), None);
+    t.clear_anchors();
+    assert_eq!(t.resolve_anchor(b), None);
+}
+
+#[test]
+fn test_render_to_string() {
+    let t = TextArea::from(["あいう", "a\tb"]);
+    assert_eq!(t.render_to_string(8, 2), "あいう  \na   b   ");
+
+    // Horizontally scrolled to show the cursor
+    let mut t = TextArea::from(["abcdefgh"]);
+    t.move_cursor(CursorMove::End);
+    assert_eq!(t.render_to_string(4, 1), "fgh ");
+
+    let mut t = TextArea::from(["secret"]);
+    t.set_mask_char('*');
+    assert_eq!(t.render_to_string(8, 1), "******  ");
+
+    let mut t = TextArea::default();
+    t.set_placeholder_text("type here");
+    assert_eq!(t.render_to_string(10, 2), " type here\n          ");
+
+    assert_eq!(t.render_to_string(0, 0), "");
+
+    // Area larger than `u16::MAX` cells
+    let t = TextArea::from(["hello"]);
+    let s = t.render_to_string(300, 300);
+    let rows: Vec<_> = s.split('\n').collect();
+    assert!(rows[0].starts_with("hello "), "{:?}", rows[0]);
+    assert!(rows.iter().all(|r| r.len() == rows[0].len()));
+    assert!(rows[1..].iter().all(|r| r.trim().is_empty()));
+
+    // Composing text is masked as well as the text
+    let mut t = TextArea::from(["ab"]);
+    t.set_mask_char('*');
+    t.set_preedit(Some(("xyz".to_string(), 3)));
+    assert_eq!(t.render_to_string(8, 1), "*****   ");
+}
+
+#[test]
+fn test_render_completion() {
+    // The popup does not fit below or above the cursor so it overlaps the line
+    let mut t = TextArea::from(["pri"]);
+    t.move_cursor(CursorMove::End);
+    t.set_completion_items(vec!["print".to_string(), "println".to_string()]);
+    assert_eq!(t.render_to_string(20, 1), " print              ");
+
+    // The popup for the cursor beyond the `u16::MAX`th row or column is clamped to the viewport
+    let mut lines = vec![String::new(); 65537];
+    lines.push("pri".to_string());
+    let mut t = TextArea::new(lines);
+    t.move_cursor(CursorMove::Bottom);
+    t.move_cursor(CursorMove::End);
+    t.set_completion_items(vec!["print".to_string()]);
+    let s = t.render_to_string(20, 3);
+    assert_eq!(s.split('\n').nth(1), Some(" print              "), "{s:?}");
+
+    let mut t = TextArea::from([format!("{}pri", " ".repeat(65536))]);
+    t.move_cursor(CursorMove::End);
+    t.set_completion_items(vec!["print".to_string()]);
+    let s = t.render_to_string(20, 3);
+    assert_eq!(s.split('\n').nth(1), Some("              print "), "{s:?}");
+}
+
+#[test]
+fn test_goto_line() {
+    let lines: Vec<_> = (1..=100).map(|i| format!("  {}", i)).collect();
+    let mut t = TextArea::new(lines);
+
+    t.goto_line(50, false);
+    assert_eq!(t.cursor(), (49, 2));
+    assert!(t.jump_back());
+    assert_eq!(t.cursor(), (0, 0));
+
+    // Viewport height is known after rendering
+    t.render_to_string(5, 10);
+    let top_line = |t: &TextArea| {
+        t.render_to_string(5, 10)
+            .lines()
+            .next()
+            .unwrap()
+            .to_string()
+    };
+    t.goto_line(50, true);
+    assert_eq!(top_line(&t), "  45 ");
+    t.goto_percentage(100, true);
+    assert_eq!(t.cursor(), (99, 2));
+    assert_eq!(top_line(&t), "  95 ");
+    t.goto_line(1, true);
+    assert_eq!(top_line(&t), "  1  ");
+
+    // The scroll position is clamped instead of wrapping around
+    let mut t = TextArea::new(vec![String::new(); 70000]);
+    t.goto_line(69000, true);
+    assert_eq!(t.cursor(), (68999, 0));
+    assert_eq!(t.scroll_offset(), (u16::MAX as usize, 0));
+}
+
+#[test]
+fn test_scroll_offset() {
+    let lines: Vec<_> = (0..20).map(|i| i.to_string()).collect();
+    let mut t = TextArea::new(lines);
+    t.render_to_string(4, 5);
+
+    t.set_scroll_offset(8, 0);
+    assert_eq!(t.scroll_offset(), (8, 0));
+    assert_eq!(t.cursor(), (8, 0));
+    assert_eq!(t.render_to_string(4, 5), "8   \n9   \n10  \n11  \n12  ");
+
+    // Restore the viewport on another textarea which has the same size
+    let offset = t.scroll_offset();
+    let mut t2 = t.clone();
+    t2.scroll((-8, 0));
+    assert_eq!(t2.scroll_offset(), (0, 0));
+    t2.set_scroll_offset(offset.0, offset.1);
+    assert_eq!(t2.render_to_string(4, 5), t.render_to_string(4, 5));
+
+    t.set_scroll_offset(usize::MAX, 0);
+    assert_eq!(t.scroll_offset(), (u16::MAX as usize, 0));
+    assert_eq!(t.cursor(), (19, 0));
+}
+
+#[test]
+fn test_keep_viewport_on_edit_above() {
+    let lines: Vec<_> = (0..20).map(|i| i.to_string()).collect();
+    let mut t = TextArea::new(lines);
+    t.render_to_string(4, 3);
+    t.set_scroll_offset(10, 0);
+    let screen = t.render_to_string(4, 3);
+    assert_eq!(screen, "10  \n11  \n12  ");
+
+    // Insert lines above the viewport
+    t.replace_range((0, 0), (0, 0), "a\nb\n");
+    assert_eq!(t.scroll_offset(), (12, 0));
+    assert_eq!(t.render_to_string(4, 3), screen);
+
+    // Delete lines above the viewport
+    t.replace_range((0, 0), (5, 0), "");
+    assert_eq!(t.scroll_offset(), (7, 0));
+    assert_eq!(t.render_to_string(4, 3), screen);
+
+    // Edits below the viewport don't change the scroll position
+    t.replace_range((15, 0), (15, 0), "x\ny\n");
+    assert_eq!(t.scroll_offset(), (7, 0));
+    assert_eq!(t.render_to_string(4, 3), screen);
+}
+
+#[test]
+fn test_append_lines() {
+    let mut t = TextArea::default();
+    assert!(!t.append_lines(Vec::<String>::new()));
+    assert!(!t.append_lines([""]));
+    assert!(t.append_lines(["abc"]));
+    assert_eq!(t.lines(), ["abc"]);
+
+    t.move_cursor(CursorMove::End);
+    t.start_selection();
+    t.move_cursor(CursorMove::Back);
+    let anchor = t.create_anchor((0, 0), (0, 3), AnchorGrowth::Neither);
+
+    assert!(t.append_lines(["def\r\nghi", ""]));
+    assert_eq!(t.lines(), ["abc", "def", "ghi", ""]);
+    assert_eq!(t.cursor(), (0, 2));
+    assert_eq!(t.selection_range(), Some(((0, 2), (0, 3))));
+    assert_eq!(t.resolve_anchor(anchor), Some(((0, 0), (0, 3))));
+
+    t.cancel_selection();
+    t.undo();
+    assert_eq!(t.lines(), ["abc"]);
+    t.redo();
+    assert_eq!(t.lines(), ["abc", "def", "ghi", ""]);
+    t.undo();
+    t.undo();
+    assert_eq!(t.lines(), [""]);
+}
+
+#[test]
+fn test_indent_policy() {
+    let mut t = TextArea::from(["ab"]);
+    t.move_cursor(CursorMove::End);
+
+    t.set_indent_policy(IndentPolicy::spaces(300));
+    assert_eq!(t.tab_length(), 255);
+    assert_eq!(t.indent().len(), 300);
+    assert!(t.insert_tab());
+    assert_eq!(t.lines()[0].len(), 300);
+    assert_eq!(t.cursor(), (0, 300));
+
+    let mut policy = IndentPolicy::spaces(4);
+    policy.keep_alignment = false;
+    t.set_indent_policy(policy);
+    t.set_text("ab");
+    t.move_cursor(CursorMove::End);
+    assert!(t.insert_tab());
+    assert_eq!(t.lines(), ["ab    "]);
+
+    t.set_indent_policy(IndentPolicy::hard_tab(300));
+    assert!(t.insert_tab());
+    assert_eq!(t.lines(), ["ab    \t"]);
+    assert_eq!(t.indent(), "\t");
+    // The hard tab is rendered up to the next tab stop
+    assert_eq!(t.render_to_string(400, 1).trim_end(), "ab");
+    t.insert_char('c');
+    assert_eq!(
+        t.render_to_string(400, 1).trim_end(),
+        format!("ab{}c", " ".repeat(298))
+    );
+
+    t.set_indent_policy(IndentPolicy::spaces(0));
+    assert!(!t.insert_tab());
+}
+
+#[test]
+fn test_link_at_cursor() {
+    let mut t = TextArea::from([
+        "see https://example.com/a_(b) and (http://x.org).",
+        "no link",
+    ]);
+
+    let tests = [
+        ((0, 0), None),
+        ((0, 3), None),
+        ((0, 4), Some("https://example.com/a_(b)")),
+        ((0, 28), Some("https://example.com/a_(b)")),
+        ((0, 29), None),
+        ((0, 34), None),
+        ((0, 35), Some("http://x.org")),
+        ((0, 46), Some("http://x.org")),
+        ((0, 47), None),
+        ((1, 3), None),
+    ];
+    for ((row, col), expected) in tests {
+        t.move_cursor(CursorMove::Jump(row, col));
+        assert_eq!(t.link_at_cursor(), expected, "cursor={:?}", (row, col));
+    }
+
+    // Editing a link changes the URL
+    t.move_cursor(CursorMove::Jump(0, 42));
+    t.insert_str("y");
+    assert_eq!(t.link_at_cursor(), Some("http://yx.org"));
+    t.insert_char(' ');
+    assert_eq!(t.link_at_cursor(), None);
+    t.move_cursor(CursorMove::Jump(0, 35));
+    assert_eq!(t.link_at_cursor(), Some("http://y"));
+}
+
+#[test]
+fn test_diff_marks_with_line_number() {
+    let mut t = TextArea::from(["aaa", "bbb", "ccc"]);
+    t.set_line_number_style(Default::default());
+    t.set_diff_marks([(0, DiffKind::Changed), (2, DiffKind::Added)]);
+    assert_eq!(
+        t.render_to_string(10, 3),
+        " 1 ~ aaa  \n 2   bbb  \n 3 + ccc  ",
+    );
+
+    // The gutter is considered on scrolling horizontally
+    t.insert_str("xxxxxxxx");
+    assert_eq!(t.render_to_string(10, 1), " xxxxxxxxa");
+
+    t.clear_diff_marks();
+    assert_eq!(t.diff_mark(0), None);
+    t.move_cursor(CursorMove::Head);
+    assert_eq!(t.render_to_string(10, 1), " 1 xxxxxxx");
+}
+
+#[test]
+fn test_conceal_range() {
+    let mut t = TextArea::from(["a :smile: b", "foo", "bar baz"]);
+    let smile = t.conceal_range((0, 2), (0, 9), "😄");
+    assert_eq!(t.render_to_string(8, 1), "a 😄 b  ");
+
+    // Cursor moves over the concealed text and reveals it
+    t.move_cursor(CursorMove::Jump(0, 4));
+    assert_eq!(t.render_to_string(12, 1), "a :smile: b ");
+    t.move_cursor(CursorMove::Jump(0, 9));
+    assert_eq!(t.render_to_string(12, 1), "a 😄 b      ");
+    t.move_cursor(CursorMove::Head);
+
+    // Range across lines conceals the part in each line and the replacement is rendered in the first line
+    let multi = t.conceal_range((2, 3), (1, 1), "…");
+    assert_eq!(t.render_to_string(8, 3), "a 😄 b  \nf…      \n baz    ");
+    assert!(t.remove_conceal(multi));
+    assert!(!t.remove_conceal(multi));
+
+    // Text inserted at the boundaries is not concealed
+    t.move_cursor(CursorMove::Jump(0, 2));
+    t.insert_str("x");
+    t.move_cursor(CursorMove::Jump(0, 10));
+    t.insert_str("y");
+    t.move_cursor(CursorMove::Head);
+    assert_eq!(t.render_to_string(8, 1), "a x😄y b");
+
+    // Deleting the concealed text removes the replacement
+    t.move_cursor(CursorMove::Jump(0, 3));
+    t.delete_str(7);
+    assert_eq!(t.lines()[0], "a xy b");
+    assert_eq!(t.render_to_string(8, 1), "a xy b  ");
+
+    assert!(t.remove_conceal(smile));
+    t.conceal_range((0, 0), (0, 1), "A");
+    t.move_cursor(CursorMove::Jump(1, 0));
+    assert_eq!(t.render_to_string(8, 1), "foo     ");
+    t.move_cursor(CursorMove::Top);
+    assert_eq!(t.render_to_string(8, 1), "a xy b  ");
+    t.move_cursor(CursorMove::Forward);
+    assert_eq!(t.render_to_string(8, 1), "A xy b  ");
+    t.clear_conceals();
+    assert_eq!(t.render_to_string(8, 1), "a xy b  ");
+}
+
+#[test]
+fn test_damaged_lines() {
+    fn damaged(t: &TextArea<'_>) -> Vec<(usize, usize)> {
+        t.damaged_lines().iter().map(|r| (r.start, r.end)).collect()
+    }
+
+    let mut t = TextArea::from(["a", "b", "c", "d"]);
+    assert!(t.damaged_lines().is_empty());
+
+    t.move_cursor(CursorMove::Jump(1, 1));
+    t.insert_newline();
+    assert_eq!(damaged(&t), [(1, 3)]);
+    t.clear_damaged_lines(..);
+    assert!(t.damaged_lines().is_empty());
+
+    // Undo and redo damage lines
+    t.undo();
+    assert_eq!(t.lines(), ["a", "b", "c", "d"]);
+    assert_eq!(damaged(&t), [(1, 2)]);
+    t.clear_damaged_lines(..);
+    t.redo();
+    assert_eq!(damaged(&t), [(1, 3)]);
+    t.clear_damaged_lines(..);
+
+    // Deleting lines damages the line where they were deleted and moves the following ranges
+    t.move_cursor(CursorMove::Jump(4, 0));
+    t.insert_char('x');
+    t.replace_range((0, 1), (2, 0), "");
+    assert_eq!(t.lines(), ["a", "c", "xd"]);
+    assert_eq!(damaged(&t), [(0, 1), (2, 3)]);
+
+    // Remote edits also damage lines
+    t.clear_damaged_lines(..);
+    t.apply_remote_edit((1, 0), (1, 0), "y\nz");
+    assert_eq!(damaged(&t), [(1, 3)]);
+}
+
+#[test]
+fn test_reselect_last() {
+    let mut t = TextArea::from(["abc", "def"]);
+    assert!(!t.reselect_last());
+
+    // Select "bc\nd" backward
+    t.move_cursor(CursorMove::Jump(1, 1));
+    t.start_selection();
+    t.move_cursor(CursorMove::Jump(0, 1));
+    t.cancel_selection();
+    assert_eq!(t.selection_range(), None);
+    assert_eq!(t.last_selection_range(), Some(((0, 1), (1, 1))));
+
+    // The last selection follows the edits including undo and redo
+    t.move_cursor(CursorMove::Top);
+    t.move_cursor(CursorMove::Head);
+    t.insert_newline();
+    assert_eq!(t.last_selection_range(), Some(((1, 1), (2, 1))));
+    t.undo();
+    assert_eq!(t.last_selection_range(), Some(((0, 1), (1, 1))));
+    t.redo();
+    assert_eq!(t.last_selection_range(), Some(((1, 1), (2, 1))));
+
+    // The cursor is placed at the start since the selection was made backward
+    assert!(t.reselect_last());
+    assert_eq!(t.selection_range(), Some(((1, 1), (2, 1))));
+    assert_eq!(t.cursor(), (1, 1));
+
+    // Deleting the selection remembers it before the deletion
+    t.cut();
+    assert_eq!(t.lines(), ["", "aef"]);
+    assert_eq!(t.last_selection_range(), Some(((1, 1), (1, 1))));
+
+    // The ongoing selection is swapped with the last one
+    t.move_cursor(CursorMove::Head);
+    t.start_selection();
+    t.move_cursor(CursorMove::End);
+    assert!(t.reselect_last());
+    assert_eq!(t.selection_range(), Some(((1, 1), (1, 1))));
+    assert_eq!(t.last_selection_range(), Some(((1, 0), (1, 3))));
+}
+
+#[test]
+fn test_expand_shrink_selection() {
+    let mut t = TextArea::from(["fn f() {", "    g(a, b);", "}"]);
+    assert!(!t.shrink_selection());
+
+    t.move_cursor(CursorMove::Jump(1, 6));
+    let expected = [
+        ((1, 6), (1, 7)),
+        ((1, 6), (1, 8)),
+        ((1, 6), (1, 10)),
+        ((1, 5), (1, 11)),
+        ((1, 4), (1, 12)),
+        ((1, 0), (1, 12)),
+        ((0, 8), (2, 0)),
+        ((0, 7), (2, 1)),
+        ((0, 0), (2, 1)),
+    ];
+    for range in expected {
+        assert!(t.expand_selection());
+        assert_eq!(t.selection_range(), Some(range));
+        assert_eq!(t.cursor(), range.1);
+    }
+    assert!(!t.expand_selection());
+
+    for range in expected.iter().rev().skip(1) {
+        assert!(t.shrink_selection());
+        assert_eq!(t.selection_range(), Some(*range));
+    }
+    assert!(t.shrink_selection());
+    assert_eq!(t.selection_range(), None);
+    assert_eq!(t.cursor(), (1, 6));
+
+    // Expansions are forgotten when the selection is changed
+    t.expand_selection();
+    t.expand_selection();
+    t.move_cursor(CursorMove::Back);
+    assert!(!t.shrink_selection());
+    assert_eq!(t.selection_range(), Some(((1, 6), (1, 7))));
+
+    // Expanding the changed selection starts new expansions
+    assert!(t.expand_selection());
+    assert_eq!(t.selection_range(), Some(((1, 6), (1, 8))));
+    assert!(t.shrink_selection());
+    assert_eq!(t.selection_range(), Some(((1, 6), (1, 7))));
+    assert!(!t.shrink_selection());
+}
+
+#[test]
+fn test_multiple_selections() {
+    let mut t = TextArea::from(["abc", "", "def ghi", "jkl"]);
+    assert_eq!(t.split_selection_on_newlines(), 0);
+    assert!(!t.replace_selections("x"));
+
+    // Split a selection from the middle of the first line to the middle of the last line
+    t.move_cursor(CursorMove::Forward);
+    t.start_selection();
+    t.move_cursor(CursorMove::Jump(3, 2));
+    assert_eq!(t.split_selection_on_newlines(), 3);
+    assert_eq!(
+        t.selections(),
+        [((0, 1), (0, 3)), ((2, 0), (2, 7)), ((3, 0), (3, 2))],
+    );
+    assert_eq!(t.primary_selection_index(), Some(0));
+    assert_eq!(t.selection_range(), Some(((0, 1), (0, 3))));
+
+    // Secondary selections follow edits
+    t.apply_remote_edit((0, 0), (0, 0), "\n");
+    assert_eq!(
+        t.selections(),
+        [((1, 1), (1, 3)), ((3, 0), (3, 7)), ((4, 0), (4, 2))],
+    );
+    t.apply_remote_edit((0, 0), (1, 0), "");
+    assert_eq!(t.lines(), ["abc", "", "def ghi", "jkl"]);
+    assert_eq!(
+        t.selections(),
+        [((0, 1), (0, 3)), ((2, 0), (2, 7)), ((3, 0), (3, 2))],
+    );
+
+    // Each selection is replaced in one undo step
+    t.select_all();
+    t.split_selection_on_newlines();
+    assert!(t.replace_selections("xy"));
+    assert_eq!(t.lines(), ["xy", "", "xy", "xy"]);
+    assert_eq!(
+        t.selections(),
+        [((0, 0), (0, 2)), ((2, 0), (2, 2)), ((3, 0), (3, 2))],
+    );
+    assert_eq!(t.primary_selection_index(), Some(0));
+    t.undo();
+    assert_eq!(t.lines(), ["abc", "", "def ghi", "jkl"]);
+
+    // Edits are applied to all selections in one undo step
+    t.select_all();
+    t.split_selection_on_newlines();
+    t.insert_char('X');
+    assert_eq!(t.lines(), ["X", "", "X", "X"]);
+    assert_eq!(
+        t.selections(),
+        [((0, 1), (0, 1)), ((2, 1), (2, 1)), ((3, 1), (3, 1))],
+    );
+    assert!(t.insert_str("yz"));
+    assert_eq!(t.lines(), ["Xyz", "", "Xyz", "Xyz"]);
+    assert!(t.delete_char());
+    assert_eq!(t.lines(), ["Xy", "", "Xy", "Xy"]);
+    t.insert_char('\n');
+    assert_eq!(t.lines(), ["Xy", "", "", "Xy", "", "Xy", ""]);
+    assert!(t.delete_char());
+    assert_eq!(t.lines(), ["Xy", "", "Xy", "Xy"]);
+    assert!(t.delete_next_char());
+    assert_eq!(t.lines(), ["Xy", "XyXy"]);
+    t.undo();
+    assert_eq!(t.lines(), ["Xy", "", "Xy", "Xy"]);
+    for _ in 0..5 {
+        t.undo();
+    }
+    assert_eq!(t.lines(), ["abc", "", "def ghi", "jkl"]);
+
+    // Copy and cut yank the texts of all selections
+    t.select_all();
+    t.split_selection_on_newlines();
+    t.copy();
+    assert_eq!(t.yank_text(), "abc\ndef ghi\njkl");
+    assert!(t.selections().is_empty());
+    assert_eq!(t.lines(), ["abc", "", "def ghi", "jkl"]);
+    t.select_all();
+    t.split_selection_on_newlines();
+    assert!(t.cut());
+    assert_eq!(t.yank_text(), "abc\ndef ghi\njkl");
+    assert_eq!(t.lines(), ["", "", "", ""]);
+    assert_eq!(
+        t.selections(),
+        [((0, 0), (0, 0)), ((2, 0), (2, 0)), ((3, 0), (3, 0))],
+    );
+    t.undo();
+    assert_eq!(t.lines(), ["abc", "", "def ghi", "jkl"]);
+
+    // Only the primary selection remains
+    t.select_all();
+    t.split_selection_on_newlines();
+    assert!(t.keep_primary_selection());
+    assert!(!t.keep_primary_selection());
+    assert_eq!(t.selections(), [((0, 0), (0, 3))]);
+
+    // Cancelling the selection clears the secondary selections
+    t.select_all();
+    t.split_selection_on_newlines();
+    t.cancel_selection();
+    assert!(t.selections().is_empty());
+    assert_eq!(t.primary_selection_index(), None);
+    t.start_selection();
+    assert_eq!(t.selections().len(), 1);
+
+    // Selections in inclusive mode contain the last characters
+    let mut t = TextArea::from(["abc", "def"]);
+    t.set_selection_inclusive(true);
+    t.start_selection();
+    t.move_cursor(CursorMove::Jump(1, 1));
+    assert_eq!(t.split_selection_on_newlines(), 2);
+    assert_eq!(t.selections(), [((0, 0), (0, 3)), ((1, 0), (1, 2))]);
+    assert_eq!(t.selection_range(), Some(((0, 0), (0, 2))));
+}
+
+#[test]
+fn test_rotate_and_align_selections() {
+    let mut t = TextArea::from(["a", "bb", "", "ccc"]);
+    assert!(!t.rotate_selection_contents());
+    assert!(!t.align_selections());
+
+    // One selection is not rotated
+    t.start_selection();
+    t.move_cursor(CursorMove::End);
+    assert!(!t.rotate_selection_contents());
+
+    t.select_all();
+    t.split_selection_on_newlines();
+    assert!(t.rotate_selection_contents());
+    assert_eq!(t.lines(), ["ccc", "a", "", "bb"]);
+    assert_eq!(
+        t.selections(),
+        [((0, 0), (0, 3)), ((1, 0), (1, 1)), ((3, 0), (3, 2))],
+    );
+    assert_eq!(t.primary_selection_index(), Some(0));
+    assert!(t.rotate_selection_contents());
+    assert_eq!(t.lines(), ["bb", "ccc", "", "a"]);
+    t.undo();
+    assert_eq!(t.lines(), ["ccc", "a", "", "bb"]);
+    t.undo();
+    assert_eq!(t.lines(), ["a", "bb", "", "ccc"]);
+
+    // Starts of the selections are aligned by the display width
+    let mut t = TextArea::from(["あい:1", "x", "\ty", "z"]);
+    t.set_tab_length(4);
+    t.move_cursor(CursorMove::Jump(0, 2));
+    t.start_selection();
+    t.move_cursor(CursorMove::Bottom);
+    t.move_cursor(CursorMove::End);
+    assert_eq!(t.split_selection_on_newlines(), 4);
+    assert!(t.align_selections());
+    assert_eq!(t.lines(), ["あい:1", "    x", "    \ty", "    z"]);
+    assert_eq!(
+        t.selections(),
+        [
+            ((0, 2), (0, 4)),
+            ((1, 4), (1, 5)),
+            ((2, 4), (2, 6)),
+            ((3, 4), (3, 5))
+        ],
+    );
+    assert!(!t.align_selections());
+    t.undo();
+    assert_eq!(t.lines(), ["あい:1", "x", "\ty", "z"]);
+}
+
+#[test]
+fn test_paste_filter() {
+    let mut t = TextArea::default();
+    t.set_paste_filter(|s| s.to_uppercase());
+
+    assert!(t.insert_str("ab\ncd"));
+    assert_eq!(t.lines(), ["AB", "CD"]);
+
+    // Typed keys are not filtered
+    t.insert_char('e');
+    assert_eq!(t.lines(), ["AB", "CDe"]);
+
+    // Yanked text set from outside is filtered but text cut in the textarea is not
+    t.set_yank_text("xy");
+    assert_eq!(t.yank_text(), "XY");
+    t.delete_char();
+    assert_eq!(t.yank_text(), "XY");
+    t.delete_line_by_head();
+    assert_eq!(t.yank_text(), "CD");
+    t.paste();
+    assert_eq!(t.lines(), ["AB", "CD"]);
+
+    // The filtered text is recorded in the history
+    t.undo();
+    assert_eq!(t.lines(), ["AB", ""]);
+    t.redo();
+    assert_eq!(t.lines(), ["AB", "CD"]);
+
+    // Rejected text does not delete the selection
+    t.set_paste_filter(|_| String::new());
+    t.select_all();
+    assert!(!t.insert_str("zz"));
+    assert_eq!(t.lines(), ["AB", "CD"]);
+    assert!(t.is_selecting());
+
+    t.remove_paste_filter();
+    t.cancel_selection();
+    assert!(t.insert_str("zz"));
+    assert_eq!(t.lines(), ["AB", "CDzz"]);
+}
+
+#[test]
+fn test_snapshot_restore() {
+    let mut t = TextArea::from(["abc", "def"]);
+    t.move_cursor(CursorMove::Jump(1, 1));
+    t.start_selection();
+    t.move_cursor(CursorMove::End);
+    let snapshot = t.snapshot();
+
+    t.cancel_selection();
+    t.insert_str("\nghi");
+    t.move_cursor(CursorMove::Top);
+    assert!(t.restore(snapshot.clone()));
+    assert_eq!(t.lines(), ["abc", "def"]);
+    assert_eq!(t.cursor(), (1, 3));
+    assert_eq!(t.selection_range(), Some(((1, 1), (1, 3))));
+
+    // Restoring the same text only restores the cursor and the selection
+    t.cancel_selection();
+    t.move_cursor(CursorMove::Top);
+    assert!(!t.restore(snapshot.clone()));
+    assert_eq!(t.cursor(), (1, 3));
+    assert!(t.is_selecting());
+
+    // The restoration is one undo step
+    t.undo();
+    assert_eq!(t.lines(), ["abc", "def", "ghi"]);
+
+    // Edits before clearing the history cannot be undone
+    t.set_lines(vec!["x".to_string()]);
+    t.clear_history();
+    assert!(!t.undo());
+    assert_eq!(t.lines(), ["x"]);
+    t.restore(snapshot);
+    assert_eq!(t.lines(), ["abc", "def"]);
+    t.undo();
+    assert_eq!(t.lines(), ["x"]);
+}
@@ -0,0 +1,123 @@
+use tui_textarea::{Input, Key, TextArea, TextAreaSet};
+
+fn set_of(n: usize) -> TextAreaSet<'static> {
+    let mut set = TextAreaSet::default();
+    for i in 0..n {
+        set.push(TextArea::from([i.to_string()]), None);
+    }
+    set
+}
+
+fn active_text(set: &TextAreaSet<'_>) -> String {
+    set.active().unwrap().lines()[0].clone()
+}
+
+#[test]
+fn test_switch() {
+    let mut set = set_of(0);
+    assert!(set.is_empty());
+    assert_eq!(set.active_index(), None);
+    assert!(set.active().is_none());
+    assert!(!set.switch_next());
+    assert!(!set.switch_prev());
+    assert!(!set.input(Input {
+        key: Key::Char('a'),
+        ..Default::default()
+    }));
+
+    let mut set = set_of(3);
+    assert_eq!(set.len(), 3);
+    assert_eq!(set.active_index(), Some(0));
+    assert!(set.switch_prev());
+    assert_eq!(active_text(&set), "2");
+    assert!(set.switch_next());
+    assert_eq!(active_text(&set), "0");
+    assert!(set.switch_next());
+    assert_eq!(active_text(&set), "1");
+    assert!(set.switch_to(2));
+    assert!(!set.switch_to(3));
+    assert_eq!(active_text(&set), "2");
+
+    assert!(set.input(Input {
+        key: Key::Char('a'),
+        ..Default::default()
+    }));
+    assert_eq!(active_text(&set), "a2");
+    assert!(set.is_modified(2));
+    assert!(!set.is_modified(0));
+    assert!(!set.is_modified(3));
+    assert_eq!(
+        set.iter()
+            .map(|t| t.lines()[0].as_str())
+            .collect::<Vec<_>>(),
+        ["0", "1", "a2"],
+    );
+}
+
+#[test]
+fn test_close() {
+    let mut set = set_of(4);
+    set.switch_to(1);
+
+    // Closing the buffer before the active one
+    assert_eq!(set.close(0).unwrap().lines(), ["0"]);
+    assert_eq!(set.active_index(), Some(0));
+    assert_eq!(active_text(&set), "1");
+
+    // Closing the buffer after the active one
+    set.close(2);
+    assert_eq!(active_text(&set), "1");
+
+    // Closing the active one
+    set.close(0);
+    assert_eq!(active_text(&set), "2");
+    assert!(set.close(1).is_none());
+    set.close(0);
+    assert!(set.is_empty());
+    assert_eq!(set.active_index(), None);
+}
+
+#[test]
+fn test_path() {
+    let mut set = set_of(2);
+    assert_eq!(set.path(0), None);
+    assert!(set.set_path(1, "foo.txt"));
+    assert!(!set.set_path(2, "bar.txt"));
+    assert_eq!(set.path(1).unwrap().to_str(), Some("foo.txt"));
+    assert_eq!(set.position("foo.txt"), Some(1));
+    assert_eq!(set.position("bar.txt"), None);
+}
+
+#[cfg(feature = "file")]
+#[test]
+fn test_open_save_file() {
+    use std::env;
+    use std::fs;
+
+    let dir = env::temp_dir();
+    let path = dir.join(format!("tui-textarea-{}-set", std::process::id()));
+    fs::write(&path, "hello\n").unwrap();
+    let new_path = dir.join(format!("tui-textarea-{}-set-new", std::process::id()));
+    let _ = fs::remove_file(&new_path);
+
+    let mut set = set_of(1);
+    assert_eq!(set.open_file(&path).unwrap(), 1);
+    assert_eq!(active_text(&set), "hello");
+    assert_eq!(set.open_file(&new_path).unwrap(), 2);
+    assert_eq!(set.active().unwrap().lines(), [""]);
+
+    // Opening the same file again switches to the buffer
+    assert_eq!(set.open_file(&path).unwrap(), 1);
+    assert_eq!(set.len(), 3);
+
+    set.active_mut().unwrap().insert_str("hi, ");
+    assert!(set.any_modified());
+    set.save(1).unwrap();
+    assert!(!set.any_modified());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "hi, hello\n");
+
+    assert!(set.save(0).is_err()); // No path
+    assert!(set.save(3).is_err()); // Out of range
+
+    fs::remove_file(&path).unwrap();
+}
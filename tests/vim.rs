@@ -0,0 +1,312 @@
+#![cfg(feature = "vim")]
+
+use tui_textarea::{CursorMove, Input, Key, TextArea, VimMode, VimState};
+
+fn keys(vim: &mut VimState, t: &mut TextArea<'_>, keys: &str) {
+    for c in keys.chars() {
+        let input = match c {
+            '\x1b' => Input {
+                key: Key::Esc,
+                ..Default::default()
+            },
+            c => Input {
+                key: Key::Char(c),
+                ..Default::default()
+            },
+        };
+        vim.input(t, input);
+    }
+}
+
+fn run(lines: &[&str], cursor: (u16, u16), input: &str) -> (TextArea<'static>, VimState) {
+    let mut t = TextArea::from(lines.iter().map(|l| l.to_string()));
+    t.move_cursor(CursorMove::Jump(cursor.0, cursor.1));
+    let mut vim = VimState::new();
+    keys(&mut vim, &mut t, input);
+    (t, vim)
+}
+
+#[test]
+fn test_motions() {
+    let tests = [
+        ("l", (0, 1)),
+        ("3l", (0, 3)),
+        ("100l", (0, 11)),
+        ("w", (0, 4)),
+        ("2w", (0, 8)),
+        ("e", (0, 2)),
+        ("$", (0, 11)),
+        ("$0", (0, 0)),
+        ("j", (1, 0)),
+        ("G", (2, 0)),
+        ("Ggg", (0, 0)),
+        ("2G", (1, 0)),
+        ("fr", (0, 6)),
+        ("2fo", (0, 2)),
+        ("tr", (0, 5)),
+        ("fo;", (0, 2)),
+        ("2fo,", (0, 1)),
+        ("$Fo", (0, 2)),
+        ("$To", (0, 3)),
+        ("to;", (0, 1)),
+        ("$ge", (0, 10)),
+        ("$2ge", (0, 6)),
+        ("jge", (0, 11)),
+        ("$gE", (0, 6)),
+        ("$+", (1, 0)),
+        ("2+", (2, 0)),
+        ("G$-", (1, 0)),
+    ];
+    for (input, expected) in tests {
+        let (t, vim) = run(&["foo bar baz!", "x", "y"], (0, 0), input);
+        assert_eq!(t.cursor(), expected, "input: {:?}", input);
+        assert_eq!(vim.mode(), VimMode::Normal, "input: {:?}", input);
+        assert!(!vim.is_pending(), "input: {:?}", input);
+    }
+}
+
+#[test]
+fn test_operators() {
+    let tests = [
+        ("dw", vec!["bar baz", "qux"], "foo "),
+        ("d2w", vec!["baz", "qux"], "foo bar "),
+        ("2dw", vec!["baz", "qux"], "foo bar "),
+        ("de", vec![" bar baz", "qux"], "foo"),
+        ("d$", vec!["", "qux"], "foo bar baz"),
+        ("D", vec!["", "qux"], "foo bar baz"),
+        ("x", vec!["oo bar baz", "qux"], "f"),
+        ("3x", vec![" bar baz", "qux"], "foo"),
+        ("dfa", vec!["r baz", "qux"], "foo ba"),
+        ("dta", vec!["ar baz", "qux"], "foo b"),
+        ("dd", vec!["qux"], "foo bar baz"),
+        ("jdd", vec!["foo bar baz"], "qux"),
+        ("dj", vec![""], "foo bar baz\nqux"),
+        (
+            "yyjp",
+            vec!["foo bar baz", "qux", "foo bar baz"],
+            "foo bar baz",
+        ),
+        ("ywP", vec!["foo foo bar baz", "qux"], "foo "),
+        ("wdw", vec!["foo baz", "qux"], "bar "),
+        ("ciwxyz\x1b", vec!["xyz bar baz", "qux"], "foo"),
+        ("cwxyz\x1b", vec!["xyz bar baz", "qux"], "foo"),
+        ("ccxyz\x1b", vec!["xyz", "qux"], "foo bar baz"),
+        ("wdaw", vec!["foo baz", "qux"], "bar "),
+        ("ddu", vec!["foo bar baz", "qux"], "foo bar baz"),
+        ("wdge", vec!["foar baz", "qux"], "o b"),
+        ("d+", vec![""], "foo bar baz\nqux"),
+        ("jd-", vec![""], "foo bar baz\nqux"),
+    ];
+    for (input, expected, register) in tests {
+        let (t, vim) = run(&["foo bar baz", "qux"], (0, 0), input);
+        assert_eq!(t.lines(), expected, "input: {:?}", input);
+        assert_eq!(vim.register('"'), Some(register), "input: {:?}", input);
+        assert_eq!(vim.mode(), VimMode::Normal, "input: {:?}", input);
+    }
+}
+
+#[test]
+fn test_text_objects() {
+    let tests = [
+        ("di(", vec!["f((), x)"]),
+        ("da(", vec!["f(, x)"]),
+        ("dib", vec!["f((), x)"]),
+        ("di)", vec!["f((), x)"]),
+        ("0ldi(", vec!["f()"]),
+        ("di\"", vec!["f((\"\", [1]), x)"]),
+        ("da\"", vec!["f((, [1]), x)"]),
+        ("f1di[", vec!["f((\"a b\", []), x)"]),
+        ("f1di]", vec!["f((\"a b\", []), x)"]),
+    ];
+    for (input, expected) in tests {
+        let (t, _) = run(&["f((\"a b\", [1]), x)"], (0, 5), input);
+        assert_eq!(t.lines(), expected, "input: {:?}", input);
+    }
+
+    // Brackets across lines
+    let (t, _) = run(&["if x {", "    a;", "    b;", "}"], (1, 4), "di{");
+    assert_eq!(t.lines(), ["if x {", "}"]);
+}
+
+#[test]
+fn test_registers() {
+    let (t, vim) = run(&["foo bar"], (0, 0), "\"adw\"ap0\"Ayw");
+    assert_eq!(t.lines(), ["bfoo ar"]);
+    assert_eq!(vim.register('a'), Some("foo bfoo "));
+    assert_eq!(vim.register('A'), Some("foo bfoo "));
+    assert_eq!(vim.register('0'), None);
+
+    // Black hole register does not overwrite the unnamed register
+    let (t, vim) = run(&["foo bar"], (0, 0), "yw\"_dwP");
+    assert_eq!(t.lines(), ["foo bar"]);
+    assert_eq!(vim.register('"'), Some("foo "));
+
+    let (t, _) = run(&["a"], (0, 0), "yy3p");
+    assert_eq!(t.lines(), ["a", "a", "a", "a"]);
+    assert_eq!(t.cursor(), (1, 0));
+}
+
+#[test]
+fn test_visual() {
+    let (t, vim) = run(&["foo bar baz"], (0, 0), "vey");
+    assert_eq!(t.lines(), ["foo bar baz"]);
+    assert_eq!(vim.register('"'), Some("foo"));
+    assert_eq!(t.selection_range(), None);
+    assert!(!t.selection_inclusive());
+
+    let (t, vim) = run(&["foo bar baz"], (0, 4), "vlld");
+    assert_eq!(t.lines(), ["foo  baz"]);
+    assert_eq!(vim.register('"'), Some("bar"));
+
+    let (t, vim) = run(&["foo bar baz"], (0, 5), "viwc!\x1b");
+    assert_eq!(t.lines(), ["foo ! baz"]);
+    assert_eq!(vim.register('"'), Some("bar"));
+
+    let (t, vim) = run(&["a", "b", "c", "d"], (1, 0), "Vjd");
+    assert_eq!(t.lines(), ["a", "d"]);
+    assert_eq!(vim.register('"'), Some("b\nc"));
+
+    let (mut t, mut vim) = run(&["a", "b"], (0, 0), "v");
+    assert_eq!(vim.mode(), VimMode::Visual);
+    assert!(t.selection_inclusive());
+    keys(&mut vim, &mut t, "V");
+    assert_eq!(vim.mode(), VimMode::VisualLine);
+    keys(&mut vim, &mut t, "\x1b");
+    assert_eq!(vim.mode(), VimMode::Normal);
+    assert_eq!(t.selection_range(), None);
+}
+
+#[test]
+fn test_insert() {
+    let tests = [
+        ("ix\x1b", vec!["xab", "cd"], (0, 0)),
+        ("ax\x1b", vec!["axb", "cd"], (0, 1)),
+        ("Ax\x1b", vec!["abx", "cd"], (0, 2)),
+        ("lIx\x1b", vec!["xab", "cd"], (0, 0)),
+        ("ox\x1b", vec!["ab", "x", "cd"], (1, 0)),
+        ("Ox\x1b", vec!["x", "ab", "cd"], (0, 0)),
+        ("sx\x1b", vec!["xb", "cd"], (0, 0)),
+        ("J", vec!["ab cd"], (0, 2)),
+        ("rx", vec!["xb", "cd"], (0, 0)),
+        ("~", vec!["Ab", "cd"], (0, 1)),
+    ];
+    for (input, expected, cursor) in tests {
+        let (t, vim) = run(&["ab", "cd"], (0, 0), input);
+        assert_eq!(t.lines(), expected, "input: {:?}", input);
+        assert_eq!(t.cursor(), cursor, "input: {:?}", input);
+        assert_eq!(vim.mode(), VimMode::Normal, "input: {:?}", input);
+    }
+}
+
+#[test]
+fn test_modified() {
+    let mut t = TextArea::from(["abc"]);
+    let mut vim = VimState::new();
+    let k = |c| Input {
+        key: Key::Char(c),
+        ..Default::default()
+    };
+    assert!(!vim.input(&mut t, k('l')));
+    assert!(vim.input(&mut t, k('x')));
+    assert!(!vim.input(&mut t, k('y')));
+    assert!(!vim.input(&mut t, k('y')));
+    assert!(vim.input(&mut t, k('p')));
+    assert_eq!(t.lines(), ["ac", "ac"]);
+}
+
+#[test]
+fn test_jumplist() {
+    let ctrl = |c| Input {
+        key: Key::Char(c),
+        ctrl: true,
+        ..Default::default()
+    };
+    let (mut t, mut vim) = run(&["a", "b", "c", "d"], (1, 0), "G$gg");
+    assert_eq!(t.cursor(), (0, 0));
+    vim.input(&mut t, ctrl('o'));
+    assert_eq!(t.cursor(), (3, 0));
+    vim.input(&mut t, ctrl('o'));
+    assert_eq!(t.cursor(), (1, 0));
+    vim.input(&mut t, ctrl('i'));
+    assert_eq!(t.cursor(), (3, 0));
+    vim.input(
+        &mut t,
+        Input {
+            key: Key::Tab,
+            ..Default::default()
+        },
+    );
+    assert_eq!(t.cursor(), (0, 0));
+}
+
+#[test]
+fn test_large_count() {
+    // A count which overflows `usize` saturates and it is clamped to the text
+    const N: &str = "99999999999999999999";
+    let tests = [
+        (format!("{N}l"), &["foo bar", "baz"][..], (0, 6)),
+        (format!("{N}j"), &["foo bar", "baz"][..], (1, 0)),
+        (format!("{N}w"), &["foo bar", "baz"][..], (1, 2)),
+        (format!("{N}G"), &["foo bar", "baz"][..], (1, 0)),
+        (format!("{N}x"), &["", "baz"][..], (0, 0)),
+        (format!("{N}s\x1b"), &["", "baz"][..], (0, 0)),
+        (format!("{N}~"), &["FOO BAR", "baz"][..], (0, 6)),
+        (format!("{N}dd"), &[""][..], (0, 0)),
+        (format!("{N}D"), &[""][..], (0, 0)),
+        (format!("{N}C\x1b"), &[""][..], (0, 0)),
+        (format!("{N}S\x1b"), &[""][..], (0, 0)),
+        (format!("c{N}c\x1b"), &[""][..], (0, 0)),
+        (format!("{N}yy"), &["foo bar", "baz"][..], (0, 0)),
+        (format!("{N}Y"), &["foo bar", "baz"][..], (0, 0)),
+        (format!("{N}rx"), &["foo bar", "baz"][..], (0, 0)),
+        (format!("{N}J"), &["foo bar baz"][..], (0, 7)),
+        (format!("x{N}u"), &["foo bar", "baz"][..], (0, 1)),
+        (format!("yw{N}p"), &["foo bar", "baz"][..], (0, 0)),
+        (format!("yy{N}P"), &["foo bar", "baz"][..], (0, 0)),
+    ];
+    for (input, expected, cursor) in tests {
+        let (t, vim) = run(&["foo bar", "baz"], (0, 0), &input);
+        assert_eq!(t.lines(), expected, "input: {:?}", input);
+        assert_eq!(t.cursor(), cursor, "input: {:?}", input);
+        assert!(!vim.is_pending(), "input: {:?}", input);
+    }
+
+    // Undo, redo, and jumps stop when nothing is left
+    let ctrl = |c| Input {
+        key: Key::Char(c),
+        ctrl: true,
+        ..Default::default()
+    };
+    let mut results = vec![];
+    for count in ["9", N] {
+        let (mut t, mut vim) = run(&["a", "b", "c"], (0, 0), "GggxjX");
+        let mut states = vec![];
+        for c in ['u', 'r', 'o', 'i'] {
+            keys(&mut vim, &mut t, count);
+            if c == 'u' {
+                keys(&mut vim, &mut t, "u");
+            } else {
+                vim.input(&mut t, ctrl(c));
+            }
+            states.push((t.lines().to_vec(), t.cursor()));
+        }
+        results.push(states);
+    }
+    assert_eq!(results[0], results[1]);
+}
+
+#[test]
+fn test_jump_to_far_line() {
+    // Lines beyond `u16::MAX` are reachable
+    let lines: Vec<_> = (0..70000).map(|i| i.to_string()).collect();
+    let mut t = TextArea::new(lines);
+    let mut vim = VimState::new();
+    keys(&mut vim, &mut t, "G");
+    assert_eq!(t.cursor(), (69999, 0));
+    keys(&mut vim, &mut t, "66000G");
+    assert_eq!(t.cursor(), (65999, 0));
+    keys(&mut vim, &mut t, "3j$");
+    assert_eq!(t.cursor(), (66002, 4));
+    keys(&mut vim, &mut t, "gg");
+    assert_eq!(t.cursor(), (0, 0));
+}